@@ -1,40 +0,0 @@
--- Create collections table
-CREATE TABLE IF NOT EXISTS collections (
-    id TEXT PRIMARY KEY,
-    workspace_id TEXT NOT NULL,
-    name TEXT NOT NULL,
-    description TEXT,
-    folder_path TEXT,
-    git_branch TEXT,
-    is_active BOOLEAN NOT NULL DEFAULT 0,
-    created_at TEXT NOT NULL,
-    updated_at TEXT NOT NULL,
-    FOREIGN KEY (workspace_id) REFERENCES workspaces (id) ON DELETE CASCADE
-);
-
--- Create requests table
-CREATE TABLE IF NOT EXISTS requests (
-    id TEXT PRIMARY KEY,
-    collection_id TEXT NOT NULL,
-    name TEXT NOT NULL,
-    description TEXT,
-    method TEXT NOT NULL,
-    url TEXT NOT NULL,
-    headers TEXT NOT NULL DEFAULT '{}',
-    body TEXT,
-    body_type TEXT NOT NULL DEFAULT 'json',
-    auth_type TEXT,
-    auth_config TEXT,
-    follow_redirects BOOLEAN NOT NULL DEFAULT 1,
-    timeout_ms INTEGER NOT NULL DEFAULT 30000,
-    order_index INTEGER NOT NULL DEFAULT 0,
-    created_at TEXT NOT NULL,
-    updated_at TEXT NOT NULL,
-    FOREIGN KEY (collection_id) REFERENCES collections (id) ON DELETE CASCADE
-);
-
--- Create indexes for better performance
-CREATE INDEX IF NOT EXISTS idx_collections_workspace_id ON collections(workspace_id);
-CREATE INDEX IF NOT EXISTS idx_collections_is_active ON collections(is_active);
-CREATE INDEX IF NOT EXISTS idx_requests_collection_id ON requests(collection_id);
-CREATE INDEX IF NOT EXISTS idx_requests_order_index ON requests(order_index);
\ No newline at end of file
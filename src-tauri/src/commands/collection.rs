@@ -1,24 +1,47 @@
 use crate::models::collection::{
     Collection, Request, CreateCollectionRequest, UpdateCollectionRequest,
     CreateRequestRequest, UpdateRequestRequest, CollectionSummary,
+    RequestExample, CreateRequestExampleRequest, CurlImportResult, Extraction, Assertion,
+    PreRequestStep, RequestSearchResult,
 };
+use crate::models::collection_run::{CollectionRunResult, RequestRunResult, RunPacing, RunStatus};
+use crate::commands::http::{apply_workspace_settings, record_execution};
+use crate::models::environment::VariableScope;
+use crate::models::http::{ExecuteOptions, HttpMethod, HttpResponse};
+use crate::models::response_diff::{ResponseDiff, ResponseSnapshot, SnapshotDiff};
+use crate::services::code_export_service::CodeExportService;
+use crate::services::collection_run_service::CollectionRunService;
 use crate::services::collection_service::CollectionService;
 use crate::services::database_service::DatabaseService;
+use crate::services::environment_service::EnvironmentService;
+use crate::services::file_sync_service::FileSyncService;
+use crate::services::har_service::HarService;
+use crate::services::http_service::HttpService;
+use crate::services::last_response_service::LastResponseService;
+use crate::services::response_diff_service::ResponseDiffService;
+use crate::services::response_snapshot_service::{ResponseSnapshotService, DEFAULT_SNAPSHOT_HEADERS};
+use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
 use tauri::State;
 
-// Helper macro to get database service and create collection service
-macro_rules! get_collection_service {
+// Helper macro to get a handle to the database service
+macro_rules! get_database_service {
     ($db_service:expr) => {{
         let db_state = $db_service
             .lock()
             .map_err(|e| format!("Database service lock error: {}", e))?;
         
-        let db_service = db_state
+        db_state
             .as_ref()
             .ok_or("Database not initialized")?
-            .clone();
-            
+            .clone()
+    }};
+}
+
+// Helper macro to get database service and create collection service
+macro_rules! get_collection_service {
+    ($db_service:expr) => {{
+        let db_service = get_database_service!($db_service);
         let pool = db_service.get_pool();
             
         CollectionService::new(pool)
@@ -66,6 +89,27 @@ pub async fn delete_collection(
         .map_err(|e| e.to_string())
 }
 
+#[tauri::command]
+pub async fn restore_collection(
+    id: String,
+    db_service: State<'_, Mutex<Option<Arc<DatabaseService>>>>,
+) -> Result<Collection, String> {
+    let service = get_collection_service!(db_service);
+    service.restore_collection(&id).await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn purge_deleted(
+    workspace_id: String,
+    older_than_days: u32,
+    db_service: State<'_, Mutex<Option<Arc<DatabaseService>>>>,
+) -> Result<u64, String> {
+    let service = get_collection_service!(db_service);
+    service.purge_deleted(&workspace_id, older_than_days).await
+        .map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 pub async fn list_collections(
     workspace_id: String,
@@ -76,6 +120,73 @@ pub async fn list_collections(
         .map_err(|e| e.to_string())
 }
 
+#[tauri::command]
+pub async fn list_collections_by_branch(
+    workspace_id: String,
+    branch: String,
+    db_service: State<'_, Mutex<Option<Arc<DatabaseService>>>>,
+) -> Result<Vec<Collection>, String> {
+    let service = get_collection_service!(db_service);
+    service.list_collections_by_branch(&workspace_id, &branch).await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn sync_workspace_from_disk(
+    workspace_id: String,
+    db_service: State<'_, Mutex<Option<Arc<DatabaseService>>>>,
+) -> Result<Vec<Collection>, String> {
+    let service = get_collection_service!(db_service);
+    service.sync_workspace_from_disk(&workspace_id).await
+        .map_err(|e| e.to_string())
+}
+
+/// Collection and environment files left with merge conflict markers
+#[tauri::command]
+pub async fn list_conflicted_files(
+    workspace_id: String,
+    db_service: State<'_, Mutex<Option<Arc<DatabaseService>>>>,
+) -> Result<Vec<String>, String> {
+    let db_service = get_database_service!(db_service);
+    FileSyncService::new(db_service)
+        .list_conflicted_files(&workspace_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn set_collection_branch(
+    collection_id: String,
+    branch_name: Option<String>,
+    db_service: State<'_, Mutex<Option<Arc<DatabaseService>>>>,
+) -> Result<Collection, String> {
+    let service = get_collection_service!(db_service);
+    service.set_collection_branch(&collection_id, branch_name.as_deref()).await
+        .map_err(|e| e.to_string())
+}
+
+/// Nest a collection under another; no parent moves it back to the top level
+#[tauri::command]
+pub async fn set_collection_parent(
+    collection_id: String,
+    parent_id: Option<String>,
+    db_service: State<'_, Mutex<Option<Arc<DatabaseService>>>>,
+) -> Result<Collection, String> {
+    let service = get_collection_service!(db_service);
+    service.set_collection_parent(&collection_id, parent_id.as_deref()).await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn list_child_collections(
+    parent_id: String,
+    db_service: State<'_, Mutex<Option<Arc<DatabaseService>>>>,
+) -> Result<Vec<Collection>, String> {
+    let service = get_collection_service!(db_service);
+    service.list_child_collections(&parent_id).await
+        .map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 pub async fn get_collection_summaries(
     workspace_id: String,
@@ -127,6 +238,16 @@ pub async fn delete_request(
         .map_err(|e| e.to_string())
 }
 
+#[tauri::command]
+pub async fn restore_request(
+    id: String,
+    db_service: State<'_, Mutex<Option<Arc<DatabaseService>>>>,
+) -> Result<Request, String> {
+    let service = get_collection_service!(db_service);
+    service.restore_request(&id).await
+        .map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 pub async fn list_requests(
     collection_id: String,
@@ -137,14 +258,72 @@ pub async fn list_requests(
         .map_err(|e| e.to_string())
 }
 
+#[tauri::command]
+pub async fn search_requests(
+    workspace_id: String,
+    query: String,
+    db_service: State<'_, Mutex<Option<Arc<DatabaseService>>>>,
+) -> Result<Vec<RequestSearchResult>, String> {
+    let service = get_collection_service!(db_service);
+    service.search_requests(&workspace_id, &query).await
+        .map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 pub async fn duplicate_request(
     id: String,
-    new_name: String,
+    new_name: Option<String>,
+    db_service: State<'_, Mutex<Option<Arc<DatabaseService>>>>,
+) -> Result<Request, String> {
+    let service = get_collection_service!(db_service);
+    service.duplicate_request(&id, new_name.as_deref()).await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn move_request(
+    request_id: String,
+    target_collection_id: String,
+    new_order_index: usize,
     db_service: State<'_, Mutex<Option<Arc<DatabaseService>>>>,
 ) -> Result<Request, String> {
     let service = get_collection_service!(db_service);
-    service.duplicate_request(&id, &new_name).await
+    service.move_request(&request_id, &target_collection_id, new_order_index).await
+        .map_err(|e| e.to_string())
+}
+
+/// Copy a collection with all its requests, into `target_workspace_id` if given
+#[tauri::command]
+pub async fn duplicate_collection(
+    id: String,
+    new_name: Option<String>,
+    target_workspace_id: Option<String>,
+    db_service: State<'_, Mutex<Option<Arc<DatabaseService>>>>,
+) -> Result<Collection, String> {
+    let service = get_collection_service!(db_service);
+    service.duplicate_collection(&id, new_name.as_deref(), target_workspace_id.as_deref()).await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn suggest_duplicate_name(
+    collection_id: String,
+    base_name: String,
+    db_service: State<'_, Mutex<Option<Arc<DatabaseService>>>>,
+) -> Result<String, String> {
+    let service = get_collection_service!(db_service);
+    service.suggest_duplicate_request_name(&collection_id, &base_name).await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn suggest_duplicate_collection_name(
+    workspace_id: String,
+    base_name: String,
+    db_service: State<'_, Mutex<Option<Arc<DatabaseService>>>>,
+) -> Result<String, String> {
+    let service = get_collection_service!(db_service);
+    service.suggest_duplicate_collection_name(&workspace_id, &base_name).await
         .map_err(|e| e.to_string())
 }
 
@@ -157,4 +336,364 @@ pub async fn reorder_requests(
     let service = get_collection_service!(db_service);
     service.reorder_requests(&collection_id, request_orders).await
         .map_err(|e| e.to_string())
-}
\ No newline at end of file
+}
+
+#[tauri::command]
+pub async fn export_collection_as_curl_script(
+    collection_id: String,
+    environment_id: Option<String>,
+    db_service: State<'_, Mutex<Option<Arc<DatabaseService>>>>,
+) -> Result<String, String> {
+    let db = get_database_service!(db_service);
+    let service = CollectionService::new(db.get_pool());
+
+    let collection = service.get_collection(&collection_id).await
+        .map_err(|e| e.to_string())?
+        .ok_or("Collection not found")?;
+    let requests = service.list_requests(&collection_id).await
+        .map_err(|e| e.to_string())?;
+
+    let environment = match environment_id {
+        Some(id) => EnvironmentService::new(db).get_resolved_environment(&id).await
+            .map_err(|e| e.to_string())?,
+        None => None,
+    };
+
+    Ok(CodeExportService::new().collection_to_curl_script(&collection, &requests, environment.as_ref()))
+}
+
+#[tauri::command]
+pub async fn set_request_run_inclusion(
+    request_id: String,
+    included: bool,
+    db_service: State<'_, Mutex<Option<Arc<DatabaseService>>>>,
+) -> Result<Request, String> {
+    let service = get_collection_service!(db_service);
+    service.set_request_run_inclusion(&request_id, included).await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn set_request_connect_timeout(
+    request_id: String,
+    connect_timeout_ms: Option<u32>,
+    db_service: State<'_, Mutex<Option<Arc<DatabaseService>>>>,
+) -> Result<Request, String> {
+    let service = get_collection_service!(db_service);
+    service.set_request_connect_timeout(&request_id, connect_timeout_ms).await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn set_request_run_condition(
+    request_id: String,
+    condition: Option<String>,
+    db_service: State<'_, Mutex<Option<Arc<DatabaseService>>>>,
+) -> Result<Request, String> {
+    let service = get_collection_service!(db_service);
+    service.set_request_run_condition(&request_id, condition.as_deref()).await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn set_request_pre_request(
+    request_id: String,
+    steps: Vec<PreRequestStep>,
+    db_service: State<'_, Mutex<Option<Arc<DatabaseService>>>>,
+) -> Result<Request, String> {
+    let service = get_collection_service!(db_service);
+    service.set_request_pre_request(&request_id, &steps).await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn set_request_raw_stream(
+    request_id: String,
+    raw_stream: bool,
+    db_service: State<'_, Mutex<Option<Arc<DatabaseService>>>>,
+) -> Result<Request, String> {
+    let service = get_collection_service!(db_service);
+    service.set_request_raw_stream(&request_id, raw_stream).await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn set_request_extractions(
+    request_id: String,
+    extractions: Vec<Extraction>,
+    db_service: State<'_, Mutex<Option<Arc<DatabaseService>>>>,
+) -> Result<Request, String> {
+    let service = get_collection_service!(db_service);
+    service.set_request_extractions(&request_id, &extractions).await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn set_request_assertions(
+    request_id: String,
+    assertions: Vec<Assertion>,
+    db_service: State<'_, Mutex<Option<Arc<DatabaseService>>>>,
+) -> Result<Request, String> {
+    let service = get_collection_service!(db_service);
+    service.set_request_assertions(&request_id, &assertions).await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn import_curls(
+    collection_id: String,
+    curl_commands: Vec<String>,
+    db_service: State<'_, Mutex<Option<Arc<DatabaseService>>>>,
+) -> Result<CurlImportResult, String> {
+    let service = get_collection_service!(db_service);
+    service.import_curl_commands(&collection_id, &curl_commands).await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn import_har(
+    collection_id: String,
+    har_json: String,
+    db_service: State<'_, Mutex<Option<Arc<DatabaseService>>>>,
+) -> Result<Vec<Request>, String> {
+    let service = get_collection_service!(db_service);
+    service.import_har(&collection_id, &har_json).await
+        .map_err(|e| e.to_string())
+}
+
+/// A HAR document pairing each saved request with its last response
+#[tauri::command]
+pub async fn export_har(
+    request_ids: Vec<String>,
+    db_service: State<'_, Mutex<Option<Arc<DatabaseService>>>>,
+) -> Result<String, String> {
+    let db = get_database_service!(db_service);
+    let collections = CollectionService::new(db.get_pool());
+    let last_responses = LastResponseService::new(db.get_pool());
+
+    let mut exchanges = Vec::new();
+    for request_id in &request_ids {
+        let request = collections.get_request(request_id).await
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| format!("Request {} not found", request_id))?;
+        let response = last_responses.get(request_id).await
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| format!("Request '{}' has no response to export", request.name))?;
+        exchanges.push((request.to_http_request(), response));
+    }
+
+    serde_json::to_string_pretty(&HarService::new().export(&exchanges))
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn create_request_example(
+    request: CreateRequestExampleRequest,
+    db_service: State<'_, Mutex<Option<Arc<DatabaseService>>>>,
+) -> Result<RequestExample, String> {
+    let service = get_collection_service!(db_service);
+    service.create_request_example(request).await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn compare_request_to_example(
+    request_id: String,
+    example_id: String,
+    live_response: HttpResponse,
+    db_service: State<'_, Mutex<Option<Arc<DatabaseService>>>>,
+) -> Result<ResponseDiff, String> {
+    let service = get_collection_service!(db_service);
+    let example = service.get_request_example(&example_id).await
+        .map_err(|e| e.to_string())?
+        .ok_or("Example not found")?;
+
+    if example.request_id != request_id {
+        return Err(format!("Example {} does not belong to request {}", example_id, request_id));
+    }
+
+    Ok(ResponseDiffService::new().compare_to_example(&example, &live_response))
+}
+
+/// Keep a response as the request's baseline. Without header names only Content-Type is kept.
+#[tauri::command]
+pub async fn save_response_snapshot(
+    request_id: String,
+    response: HttpResponse,
+    headers: Option<Vec<String>>,
+    db_service: State<'_, Mutex<Option<Arc<DatabaseService>>>>,
+) -> Result<ResponseSnapshot, String> {
+    let db = get_database_service!(db_service);
+    let headers = headers.unwrap_or_else(|| DEFAULT_SNAPSHOT_HEADERS.iter().map(|name| name.to_string()).collect());
+    ResponseSnapshotService::new(db.get_pool())
+        .save(&request_id, &response, &headers).await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn diff_against_snapshot(
+    request_id: String,
+    response: HttpResponse,
+    db_service: State<'_, Mutex<Option<Arc<DatabaseService>>>>,
+) -> Result<SnapshotDiff, String> {
+    let db = get_database_service!(db_service);
+    ResponseSnapshotService::new(db.get_pool())
+        .diff(&request_id, &response).await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn preview_auth_header(
+    request_id: String,
+    environment_id: Option<String>,
+    db_service: State<'_, Mutex<Option<Arc<DatabaseService>>>>,
+) -> Result<Option<(String, String)>, String> {
+    let db = get_database_service!(db_service);
+    let request = CollectionService::new(db.get_pool())
+        .get_request(&request_id).await
+        .map_err(|e| e.to_string())?
+        .ok_or("Request not found")?
+        .to_http_request();
+
+    let environment_variables = match environment_id {
+        Some(id) => EnvironmentService::new(db).get_resolved_environment(&id).await
+            .map_err(|e| e.to_string())?
+            .map(|env| {
+                env.variables.into_values()
+                    .map(|v| (v.key, v.value))
+                    .collect()
+            }),
+        None => None,
+    };
+
+    Ok(HttpService::preview_auth_header(&request.auth_type, &request.auth_config, &environment_variables))
+}
+
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn run_collection(
+    collection_id: String,
+    environment_id: Option<String>,
+    continue_on_error: Option<bool>,
+    pacing: Option<RunPacing>,
+    concurrency: Option<usize>,
+    confirm: Option<bool>,
+    db_service: State<'_, Mutex<Option<Arc<DatabaseService>>>>,
+    http_service: State<'_, Arc<Mutex<HttpService>>>,
+) -> Result<CollectionRunResult, String> {
+    let db = get_database_service!(db_service);
+    let http_service = http_service
+        .lock()
+        .map_err(|e| format!("HTTP service lock error: {}", e))?
+        .clone();
+
+    let collection_service = CollectionService::new(db.get_pool());
+    let collection = collection_service.get_collection(&collection_id).await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "Collection not found".to_string())?;
+    let requests = collection_service
+        .list_requests(&collection_id).await
+        .map_err(|e| e.to_string())?;
+
+    let mut options = ExecuteOptions { workspace_id: Some(collection.workspace_id.clone()), ..Default::default() };
+    apply_workspace_settings(&db, &http_service, &mut options).await;
+
+    // Without an explicit environment, run with the workspace's active one (if any)
+    let environment_service = EnvironmentService::new(db.clone());
+    let environment = match environment_id {
+        Some(id) => environment_service.get_environment(&id).await
+            .map_err(|e| e.to_string())?,
+        None => environment_service.list_environments(&collection.workspace_id).await
+            .map_err(|e| e.to_string())?
+            .into_iter()
+            .find(|env| env.is_active),
+    };
+
+    // Hold back a run that would send mutating requests to a protected environment until the user confirms
+    if !confirm.unwrap_or(false) {
+        let unconfirmed = CollectionRunService::requests_needing_confirmation(&requests, environment.as_ref());
+        if let (Some(environment), false) = (&environment, unconfirmed.is_empty()) {
+            return Err(format!(
+                "Environment '{}' is protected; confirm the run to send {}",
+                environment.name,
+                unconfirmed.join(", ")
+            ));
+        }
+    }
+    let environment_id = environment.map(|env| env.id);
+    let environment_variables = environment_service
+        .resolve_variables(&collection.workspace_id, Some(&collection_id), None, environment_id.as_deref()).await
+        .map_err(|e| e.to_string())?;
+    let mut request_variables = HashMap::new();
+    for request in &requests {
+        let variables = environment_service.get_scoped_variables(VariableScope::Request, &request.id).await
+            .map_err(|e| e.to_string())?;
+        if !variables.is_empty() {
+            request_variables.insert(request.id.clone(), variables);
+        }
+    }
+
+    let result = CollectionRunService::new(http_service)
+        .with_request_variables(request_variables)
+        .run(
+            &collection_id,
+            &requests,
+            Some(environment_variables),
+            continue_on_error.unwrap_or(true),
+            &pacing.unwrap_or_default(),
+            concurrency.unwrap_or(1),
+            &options,
+        )
+        .await;
+    record_run_results(&db, &collection.workspace_id, &requests, &result.results).await;
+    Ok(result)
+}
+
+/// Log each request a run sent in the request history and keep its response as the request's
+/// last one, just as sending it on its own does. Skipped requests weren't sent and aren't logged.
+pub async fn record_run_results(db: &DatabaseService, workspace_id: &str, requests: &[Request], results: &[RequestRunResult]) {
+    for result in results.iter().filter(|result| result.status != RunStatus::Skipped) {
+        let Some(request) = requests.iter().find(|request| request.id == result.request_id) else {
+            continue;
+        };
+        let outcome = result.response.as_ref().ok_or_else(|| result.error.as_deref().unwrap_or_default());
+        let method = HttpMethod::from(request.method.as_str());
+        record_execution(db, workspace_id, &request.id, &method, &request.url, outcome).await;
+    }
+}
+
+#[tauri::command]
+pub async fn execute_request_with_assertions(
+    request_id: String,
+    environment_id: Option<String>,
+    db_service: State<'_, Mutex<Option<Arc<DatabaseService>>>>,
+    http_service: State<'_, Arc<Mutex<HttpService>>>,
+) -> Result<RequestRunResult, String> {
+    let db = get_database_service!(db_service);
+    let http_service = http_service
+        .lock()
+        .map_err(|e| format!("HTTP service lock error: {}", e))?
+        .clone();
+
+    let collection_service = CollectionService::new(db.get_pool());
+    let request = collection_service
+        .get_request(&request_id).await
+        .map_err(|e| e.to_string())?
+        .ok_or("Request not found")?;
+    let collection = collection_service.get_collection(&request.collection_id).await
+        .map_err(|e| e.to_string())?
+        .ok_or("Collection not found")?;
+
+    let mut options = ExecuteOptions { workspace_id: Some(collection.workspace_id.clone()), ..Default::default() };
+    apply_workspace_settings(&db, &http_service, &mut options).await;
+
+    let mut environment_variables = EnvironmentService::new(db.clone())
+        .resolve_variables(&collection.workspace_id, Some(&collection.id), Some(&request.id), environment_id.as_deref()).await
+        .map_err(|e| e.to_string())?;
+
+    let result = CollectionRunService::new(http_service)
+        .execute(&request, &mut environment_variables, &options)
+        .await;
+    record_run_results(&db, &collection.workspace_id, std::slice::from_ref(&request), std::slice::from_ref(&result)).await;
+    Ok(result)
+}
@@ -75,11 +75,57 @@ pub async fn delete_environment(
 #[tauri::command]
 pub async fn list_environments(
     workspace_id: String,
+    resolve_inherited: Option<bool>,
     service_state: tauri::State<'_, Arc<Mutex<Option<EnvironmentService>>>>,
     db_state: tauri::State<'_, DatabaseServiceState>,
 ) -> Result<Vec<Environment>, String> {
     let service = get_environment_service!(service_state, db_state);
-    service.list_environments(&workspace_id)
+    let environments = if resolve_inherited.unwrap_or(false) {
+        service.list_resolved_environments(&workspace_id).await
+    } else {
+        service.list_environments(&workspace_id).await
+    };
+    environments.map_err(|e| e.to_string())
+}
+
+/// An environment with its parents' variables merged in. For display and sending only;
+/// saving it back would copy the inherited variables into the environment.
+#[tauri::command]
+pub async fn get_resolved_environment(
+    environment_id: String,
+    service_state: tauri::State<'_, Arc<Mutex<Option<EnvironmentService>>>>,
+    db_state: tauri::State<'_, DatabaseServiceState>,
+) -> Result<Option<Environment>, String> {
+    let service = get_environment_service!(service_state, db_state);
+    service.get_resolved_environment(&environment_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn import_env_file(
+    workspace_id: String,
+    name: String,
+    contents: String,
+    service_state: tauri::State<'_, Arc<Mutex<Option<EnvironmentService>>>>,
+    db_state: tauri::State<'_, DatabaseServiceState>,
+) -> Result<Environment, String> {
+    let service = get_environment_service!(service_state, db_state);
+    service.import_env_file(&workspace_id, &name, &contents)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// The environment as a `.env` file; secrets are left out unless `include_secrets` is set
+#[tauri::command]
+pub async fn export_env_file(
+    environment_id: String,
+    include_secrets: Option<bool>,
+    service_state: tauri::State<'_, Arc<Mutex<Option<EnvironmentService>>>>,
+    db_state: tauri::State<'_, DatabaseServiceState>,
+) -> Result<String, String> {
+    let service = get_environment_service!(service_state, db_state);
+    service.export_env_file(&environment_id, include_secrets.unwrap_or(false))
         .await
         .map_err(|e| e.to_string())
 }
@@ -110,6 +156,20 @@ pub async fn update_environment_variable(
         .map_err(|e| e.to_string())
 }
 
+#[tauri::command]
+pub async fn set_environment_variables(
+    environment_id: String,
+    variables: Vec<EnvironmentVariable>,
+    mode: MergeMode,
+    service_state: tauri::State<'_, Arc<Mutex<Option<EnvironmentService>>>>,
+    db_state: tauri::State<'_, DatabaseServiceState>,
+) -> Result<Environment, String> {
+    let service = get_environment_service!(service_state, db_state);
+    service.set_variables(&environment_id, variables, mode)
+        .await
+        .map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 pub async fn remove_environment_variable(
     environment_id: String,
@@ -123,6 +183,114 @@ pub async fn remove_environment_variable(
         .map_err(|e| e.to_string())
 }
 
+#[tauri::command]
+pub async fn get_scoped_variables(
+    scope: VariableScope,
+    owner_id: String,
+    service_state: tauri::State<'_, Arc<Mutex<Option<EnvironmentService>>>>,
+    db_state: tauri::State<'_, DatabaseServiceState>,
+) -> Result<HashMap<String, String>, String> {
+    let service = get_environment_service!(service_state, db_state);
+    service.get_scoped_variables(scope, &owner_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn set_scoped_variable(
+    scope: VariableScope,
+    owner_id: String,
+    key: String,
+    value: String,
+    service_state: tauri::State<'_, Arc<Mutex<Option<EnvironmentService>>>>,
+    db_state: tauri::State<'_, DatabaseServiceState>,
+) -> Result<(), String> {
+    let service = get_environment_service!(service_state, db_state);
+    service.set_scoped_variable(scope, &owner_id, &key, &value)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn remove_scoped_variable(
+    scope: VariableScope,
+    owner_id: String,
+    key: String,
+    service_state: tauri::State<'_, Arc<Mutex<Option<EnvironmentService>>>>,
+    db_state: tauri::State<'_, DatabaseServiceState>,
+) -> Result<bool, String> {
+    let service = get_environment_service!(service_state, db_state);
+    service.remove_scoped_variable(scope, &owner_id, &key)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// The variables to send a request with: request > collection > environment > global
+#[tauri::command]
+pub async fn resolve_variables(
+    workspace_id: String,
+    collection_id: Option<String>,
+    request_id: Option<String>,
+    environment_id: Option<String>,
+    service_state: tauri::State<'_, Arc<Mutex<Option<EnvironmentService>>>>,
+    db_state: tauri::State<'_, DatabaseServiceState>,
+) -> Result<HashMap<String, String>, String> {
+    let service = get_environment_service!(service_state, db_state);
+    service.resolve_variables(&workspace_id, collection_id.as_deref(), request_id.as_deref(), environment_id.as_deref())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn find_variable_usages(
+    collection_id: String,
+    variable_name: String,
+    service_state: tauri::State<'_, Arc<Mutex<Option<EnvironmentService>>>>,
+    db_state: tauri::State<'_, DatabaseServiceState>,
+) -> Result<Vec<VariableUsage>, String> {
+    let service = get_environment_service!(service_state, db_state);
+    service.find_variable_usages(&collection_id, &variable_name)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn find_undefined_variables(
+    collection_id: String,
+    environment_id: Option<String>,
+    service_state: tauri::State<'_, Arc<Mutex<Option<EnvironmentService>>>>,
+    db_state: tauri::State<'_, DatabaseServiceState>,
+) -> Result<Vec<UndefinedVariable>, String> {
+    let service = get_environment_service!(service_state, db_state);
+    service.find_undefined_variables(&collection_id, environment_id.as_deref())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn find_orphaned_environment_files(
+    workspace_id: String,
+    service_state: tauri::State<'_, Arc<Mutex<Option<EnvironmentService>>>>,
+    db_state: tauri::State<'_, DatabaseServiceState>,
+) -> Result<Vec<String>, String> {
+    let service = get_environment_service!(service_state, db_state);
+    service.find_orphaned_environment_files(&workspace_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn import_orphaned_environment_file(
+    workspace_id: String,
+    name: String,
+    service_state: tauri::State<'_, Arc<Mutex<Option<EnvironmentService>>>>,
+    db_state: tauri::State<'_, DatabaseServiceState>,
+) -> Result<Environment, String> {
+    let service = get_environment_service!(service_state, db_state);
+    service.import_orphaned_environment_file(&workspace_id, &name)
+        .await
+        .map_err(|e| e.to_string())
+}
 
 #[tauri::command]
 pub async fn substitute_environment_variables(
@@ -135,6 +303,17 @@ pub async fn substitute_environment_variables(
     Ok(service.substitute_variables(&text, &variables))
 }
 
+#[tauri::command]
+pub async fn substitute_with_spans(
+    text: String,
+    variables: HashMap<String, EnvironmentVariable>,
+    service_state: tauri::State<'_, Arc<Mutex<Option<EnvironmentService>>>>,
+    db_state: tauri::State<'_, DatabaseServiceState>,
+) -> Result<SubstitutionResult, String> {
+    let service = get_environment_service!(service_state, db_state);
+    Ok(service.substitute_with_spans(&text, &variables))
+}
+
 #[tauri::command]
 pub async fn extract_environment_variables(
     text: String,
@@ -188,22 +367,25 @@ pub async fn set_active_environment(
     db_state: tauri::State<'_, DatabaseServiceState>,
 ) -> Result<bool, String> {
     let service = get_environment_service!(service_state, db_state);
-    
-    // Get all environments for the workspace
-    let environments = service.list_environments(&workspace_id)
+
+    service.set_active_environment(&workspace_id, &environment_id)
         .await
         .map_err(|e| e.to_string())?;
 
-    // Deactivate all environments and activate the selected one
-    for mut env in environments {
-        let should_be_active = env.id == environment_id;
-        if env.is_active != should_be_active {
-            env.is_active = should_be_active;
-            service.update_environment(env)
-                .await
-                .map_err(|e| e.to_string())?;
-        }
-    }
+    Ok(true)
+}
+
+#[tauri::command]
+pub async fn clear_active_environment(
+    workspace_id: String,
+    service_state: tauri::State<'_, Arc<Mutex<Option<EnvironmentService>>>>,
+    db_state: tauri::State<'_, DatabaseServiceState>,
+) -> Result<bool, String> {
+    let service = get_environment_service!(service_state, db_state);
+
+    service.clear_active_environment(&workspace_id)
+        .await
+        .map_err(|e| e.to_string())?;
 
     Ok(true)
 }
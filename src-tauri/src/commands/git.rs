@@ -1,7 +1,7 @@
 use crate::models::git::*;
-use crate::services::{credential_service::CredentialService, git_service::GitService};
+use crate::services::{credential_service::CredentialService, git_service::{self, GitService}};
 use std::sync::Mutex;
-use tauri::State;
+use tauri::{AppHandle, Emitter, State};
 
 // Global state for Git service
 pub type GitServiceState = Mutex<GitService>;
@@ -12,12 +12,24 @@ pub async fn git_clone_repository(
     url: String,
     path: String,
     credentials: Option<GitCredentials>,
+    clone_id: Option<String>,
+    app_handle: AppHandle,
     git_service: State<'_, GitServiceState>,
 ) -> Result<CloneResult, String> {
     let service = git_service.lock().map_err(|e| format!("Service lock error: {}", e))?;
-    
+
+    // Progress is only reported when the caller passes an ID to listen on
+    let event_name = clone_id.as_deref().map(git_service::clone_progress_event_name);
+    let emit = |progress: CloneProgress| {
+        if let Some(event_name) = &event_name {
+            if let Err(e) = app_handle.emit(event_name, progress) {
+                eprintln!("Warning: Failed to emit clone progress: {}", e);
+            }
+        }
+    };
+
     service
-        .clone_repository(&url, &path, credentials.as_ref())
+        .clone_repository(&url, &path, credentials.as_ref(), Some(&emit))
         .map_err(|e| format!("Clone failed: {}", e))
 }
 
@@ -57,6 +69,267 @@ pub async fn git_get_branches(
         .map_err(|e| format!("Get branches failed: {}", e))
 }
 
+#[tauri::command]
+pub async fn git_trust_host_key(
+    host_key: UnknownHostKey,
+    git_service: State<'_, GitServiceState>,
+) -> Result<(), String> {
+    let service = git_service.lock().map_err(|e| format!("Service lock error: {}", e))?;
+
+    service
+        .trust_host_key(&host_key)
+        .map_err(|e| format!("Trust host key failed: {}", e))
+}
+
+#[tauri::command]
+pub async fn git_set_skip_host_key_verification(
+    skip: bool,
+    git_service: State<'_, GitServiceState>,
+) -> Result<(), String> {
+    let mut service = git_service.lock().map_err(|e| format!("Service lock error: {}", e))?;
+    service.set_skip_host_key_verification(skip);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn git_fetch(
+    repo_path: String,
+    remote: String,
+    credentials: Option<GitCredentials>,
+    git_service: State<'_, GitServiceState>,
+) -> Result<CloneResult, String> {
+    let service = git_service.lock().map_err(|e| format!("Service lock error: {}", e))?;
+    
+    service
+        .fetch(&repo_path, &remote, credentials.as_ref())
+        .map_err(|e| format!("Fetch failed: {}", e))
+}
+
+#[tauri::command]
+pub async fn git_check_remote_access(
+    repo_path: String,
+    remote: String,
+    credentials: Option<GitCredentials>,
+    git_service: State<'_, GitServiceState>,
+) -> Result<RemoteAccess, String> {
+    let service = git_service.lock().map_err(|e| format!("Service lock error: {}", e))?;
+
+    service
+        .check_remote_access(&repo_path, &remote, credentials.as_ref())
+        .map_err(|e| format!("Remote access check failed: {}", e))
+}
+
+#[tauri::command]
+pub async fn git_pull(
+    repo_path: String,
+    remote: String,
+    branch: String,
+    credentials: Option<GitCredentials>,
+    git_service: State<'_, GitServiceState>,
+) -> Result<CloneResult, String> {
+    let service = git_service.lock().map_err(|e| format!("Service lock error: {}", e))?;
+    
+    service
+        .pull(&repo_path, &remote, &branch, credentials.as_ref())
+        .map_err(|e| format!("Pull failed: {}", e))
+}
+
+/// Recent commits on a branch or ref (HEAD by default), newest first
+#[tauri::command]
+pub async fn git_get_log(
+    repo_path: String,
+    reference: Option<String>,
+    limit: Option<usize>,
+    git_service: State<'_, GitServiceState>,
+) -> Result<Vec<GitCommit>, String> {
+    let service = git_service.lock().map_err(|e| format!("Service lock error: {}", e))?;
+
+    service
+        .get_commit_log(&repo_path, reference.as_deref(), limit.unwrap_or(50))
+        .map_err(|e| format!("Failed to get commit log: {}", e))
+}
+
+/// The commit HEAD is on, with any tags pointing at it
+#[tauri::command]
+pub async fn git_get_head_info(
+    repo_path: String,
+    git_service: State<'_, GitServiceState>,
+) -> Result<HeadInfo, String> {
+    let service = git_service.lock().map_err(|e| format!("Service lock error: {}", e))?;
+
+    service
+        .get_head_info(&repo_path)
+        .map_err(|e| format!("Failed to get HEAD: {}", e))
+}
+
+#[tauri::command]
+pub async fn git_get_diff(
+    repo_path: String,
+    git_service: State<'_, GitServiceState>,
+) -> Result<Vec<FileDiff>, String> {
+    let service = git_service.lock().map_err(|e| format!("Service lock error: {}", e))?;
+
+    service
+        .get_diff(&repo_path)
+        .map_err(|e| format!("Failed to get diff: {}", e))
+}
+
+#[tauri::command]
+pub async fn git_stage_all(
+    repo_path: String,
+    git_service: State<'_, GitServiceState>,
+) -> Result<CloneResult, String> {
+    let service = git_service.lock().map_err(|e| format!("Service lock error: {}", e))?;
+
+    service
+        .add_all_changes(&repo_path)
+        .map_err(|e| format!("Failed to stage changes: {}", e))
+}
+
+/// Stage only the given paths, relative to the repository root
+#[tauri::command]
+pub async fn git_stage_files(
+    repo_path: String,
+    paths: Vec<String>,
+    git_service: State<'_, GitServiceState>,
+) -> Result<CloneResult, String> {
+    let service = git_service.lock().map_err(|e| format!("Service lock error: {}", e))?;
+
+    service
+        .stage_files(&repo_path, &paths)
+        .map_err(|e| format!("Failed to stage files: {}", e))
+}
+
+#[tauri::command]
+pub async fn git_unstage_files(
+    repo_path: String,
+    paths: Vec<String>,
+    git_service: State<'_, GitServiceState>,
+) -> Result<CloneResult, String> {
+    let service = git_service.lock().map_err(|e| format!("Service lock error: {}", e))?;
+
+    service
+        .unstage_files(&repo_path, &paths)
+        .map_err(|e| format!("Failed to unstage files: {}", e))
+}
+
+#[tauri::command]
+pub async fn git_list_conflicts(
+    repo_path: String,
+    git_service: State<'_, GitServiceState>,
+) -> Result<Vec<Conflict>, String> {
+    let service = git_service.lock().map_err(|e| format!("Service lock error: {}", e))?;
+
+    service
+        .list_conflicts(&repo_path)
+        .map_err(|e| format!("Failed to list conflicts: {}", e))
+}
+
+#[tauri::command]
+pub async fn git_resolve_conflict(
+    repo_path: String,
+    path: String,
+    resolution: ConflictResolution,
+    git_service: State<'_, GitServiceState>,
+) -> Result<CloneResult, String> {
+    let service = git_service.lock().map_err(|e| format!("Service lock error: {}", e))?;
+
+    service
+        .resolve_conflict(&repo_path, &path, &resolution)
+        .map_err(|e| format!("Failed to resolve conflict: {}", e))
+}
+
+#[tauri::command]
+pub async fn git_abort_merge(
+    repo_path: String,
+    git_service: State<'_, GitServiceState>,
+) -> Result<CloneResult, String> {
+    let service = git_service.lock().map_err(|e| format!("Service lock error: {}", e))?;
+
+    service
+        .abort_merge(&repo_path)
+        .map_err(|e| format!("Failed to abort merge: {}", e))
+}
+
+#[tauri::command]
+pub async fn git_list_remotes(
+    repo_path: String,
+    git_service: State<'_, GitServiceState>,
+) -> Result<Vec<GitRemote>, String> {
+    let service = git_service.lock().map_err(|e| format!("Service lock error: {}", e))?;
+
+    service
+        .list_remotes(&repo_path)
+        .map_err(|e| format!("Failed to list remotes: {}", e))
+}
+
+#[tauri::command]
+pub async fn git_add_remote(
+    repo_path: String,
+    name: String,
+    url: String,
+    git_service: State<'_, GitServiceState>,
+) -> Result<GitRemote, String> {
+    let service = git_service.lock().map_err(|e| format!("Service lock error: {}", e))?;
+
+    service
+        .add_remote(&repo_path, &name, &url)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn git_remove_remote(
+    repo_path: String,
+    name: String,
+    git_service: State<'_, GitServiceState>,
+) -> Result<CloneResult, String> {
+    let service = git_service.lock().map_err(|e| format!("Service lock error: {}", e))?;
+
+    service
+        .remove_remote(&repo_path, &name)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn git_stash_save(
+    repo_path: String,
+    message: Option<String>,
+    git_service: State<'_, GitServiceState>,
+) -> Result<StashResult, String> {
+    let service = git_service.lock().map_err(|e| format!("Service lock error: {}", e))?;
+
+    service
+        .stash_save(&repo_path, message.as_deref())
+        .map_err(|e| format!("Stash failed: {}", e))
+}
+
+#[tauri::command]
+pub async fn git_stash_pop(
+    repo_path: String,
+    git_service: State<'_, GitServiceState>,
+) -> Result<StashResult, String> {
+    let service = git_service.lock().map_err(|e| format!("Service lock error: {}", e))?;
+
+    service
+        .stash_pop(&repo_path)
+        .map_err(|e| format!("Stash pop failed: {}", e))
+}
+
+#[tauri::command]
+pub async fn git_push(
+    repo_path: String,
+    remote: String,
+    branch: String,
+    credentials: Option<GitCredentials>,
+    git_service: State<'_, GitServiceState>,
+) -> Result<CloneResult, String> {
+    let service = git_service.lock().map_err(|e| format!("Service lock error: {}", e))?;
+    
+    service
+        .push(&repo_path, &remote, &branch, credentials.as_ref())
+        .map_err(|e| format!("Push failed: {}", e))
+}
+
 #[tauri::command]
 pub async fn git_check_repository(
     path: String,
@@ -67,51 +340,67 @@ pub async fn git_check_repository(
     Ok(service.check_repository_exists(&path))
 }
 
+/// Save credentials for a host; each username on a host is kept separately
 #[tauri::command]
 pub async fn git_store_credentials(
-    key: String,
+    host: String,
     credentials: GitCredentials,
     credential_service: State<'_, CredentialServiceState>,
 ) -> Result<bool, String> {
     let service = credential_service.lock().map_err(|e| format!("Service lock error: {}", e))?;
     
     service
-        .store_credentials(&key, &credentials)
+        .store_credentials(&host, &credentials)
         .map(|_| true)
         .map_err(|e| format!("Store credentials failed: {}", e))
 }
 
 #[tauri::command]
 pub async fn git_get_credentials(
-    key: String,
+    host: String,
+    username: Option<String>,
     credential_service: State<'_, CredentialServiceState>,
 ) -> Result<GitCredentials, String> {
     let service = credential_service.lock().map_err(|e| format!("Service lock error: {}", e))?;
     
     service
-        .get_credentials(&key)
+        .get_credentials(&host, username.as_deref())
         .map_err(|e| format!("Get credentials failed: {}", e))
 }
 
 #[tauri::command]
 pub async fn git_delete_credentials(
-    key: String,
+    host: String,
+    username: Option<String>,
     credential_service: State<'_, CredentialServiceState>,
 ) -> Result<bool, String> {
     let service = credential_service.lock().map_err(|e| format!("Service lock error: {}", e))?;
     
     service
-        .delete_credentials(&key)
+        .delete_credentials(&host, username.as_deref())
         .map(|_| true)
         .map_err(|e| format!("Delete credentials failed: {}", e))
 }
 
 #[tauri::command]
 pub async fn git_credentials_exist(
-    key: String,
+    host: String,
+    username: Option<String>,
     credential_service: State<'_, CredentialServiceState>,
 ) -> Result<bool, String> {
     let service = credential_service.lock().map_err(|e| format!("Service lock error: {}", e))?;
     
-    Ok(service.credentials_exist(&key))
+    Ok(service.credentials_exist(&host, username.as_deref()))
+}
+
+/// Hosts with saved credentials, without the secrets
+#[tauri::command]
+pub async fn git_list_credential_hosts(
+    credential_service: State<'_, CredentialServiceState>,
+) -> Result<Vec<CredentialHost>, String> {
+    let service = credential_service.lock().map_err(|e| format!("Service lock error: {}", e))?;
+
+    service
+        .list_credential_hosts()
+        .map_err(|e| format!("Failed to list credential hosts: {}", e))
 }
\ No newline at end of file
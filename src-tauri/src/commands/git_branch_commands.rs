@@ -1,6 +1,6 @@
 use crate::models::git::{
-    BranchConfig, BranchCreateRequest, BranchCreateResult, BranchPattern, FeatureType, GitBranch,
-    SystemInfo,
+    BranchConfig, BranchCreateRequest, BranchCreateResult, BranchDeleteResult, BranchPattern, FeatureType,
+    GitBranch, SystemInfo,
 };
 use crate::services::git_branch_service::GitBranchService;
 use anyhow::Result;
@@ -99,6 +99,65 @@ pub async fn list_branches(
     service.list_branches(&workspace_path).map_err(|e| e.to_string())
 }
 
+/// Check out an existing branch, refusing when uncommitted changes would be overwritten
+#[command]
+pub async fn git_switch_branch(
+    workspace_path: String,
+    branch_name: String,
+    service_state: State<'_, Mutex<Option<GitBranchService>>>,
+) -> Result<(), String> {
+    let service_guard = service_state.lock().unwrap();
+    let service = service_guard
+        .as_ref()
+        .ok_or("Git branch service not initialized")?;
+
+    service.switch_branch(&workspace_path, &branch_name).map_err(|e| e.to_string())
+}
+
+/// Delete a branch; refusals come back in the result rather than as errors
+#[command]
+pub async fn git_delete_branch(
+    workspace_path: String,
+    branch_name: String,
+    force: Option<bool>,
+    service_state: State<'_, Mutex<Option<GitBranchService>>>,
+) -> Result<BranchDeleteResult, String> {
+    let service = {
+        let service_guard = service_state.lock().unwrap();
+        service_guard
+            .as_ref()
+            .ok_or("Git branch service not initialized")?
+            .clone()
+    };
+
+    service
+        .delete_branch(&workspace_path, &branch_name, force.unwrap_or(false))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Rename a local branch; fails when the new name is invalid or already taken
+#[command]
+pub async fn git_rename_branch(
+    workspace_path: String,
+    old_name: String,
+    new_name: String,
+    service_state: State<'_, Mutex<Option<GitBranchService>>>,
+) -> Result<(), String> {
+    let service = {
+        let service_guard = service_state.lock().unwrap();
+        service_guard
+            .as_ref()
+            .ok_or("Git branch service not initialized")?
+            .clone()
+    };
+
+    service
+        .rename_branch(&workspace_path, &old_name, &new_name)
+        .await
+        .map_err(|e| e.to_string())
+}
+
 #[command]
 pub async fn get_branch_history(
     limit: Option<i32>,
@@ -136,12 +195,17 @@ pub async fn update_branch_config(
     config: BranchConfig,
     service_state: State<'_, Mutex<Option<GitBranchService>>>,
 ) -> Result<(), String> {
-    let mut service_guard = service_state.lock().unwrap();
-    let service = service_guard
-        .as_mut()
-        .ok_or("Git branch service not initialized")?;
-    
-    service.update_config(config).map_err(|e| e.to_string())
+    let mut service = {
+        let service_guard = service_state.lock().unwrap();
+        service_guard
+            .as_ref()
+            .ok_or("Git branch service not initialized")?
+            .clone()
+    };
+
+    service.update_config(config).await.map_err(|e| e.to_string())?;
+    *service_state.lock().unwrap() = Some(service);
+    Ok(())
 }
 
 /// Quick action to create a feature branch with minimal input
@@ -1,8 +1,23 @@
+use crate::models::environment::Environment;
 use crate::models::http::*;
-use crate::services::http_service::HttpService;
+use base64::prelude::*;
+use crate::services::code_export_service::CodeExportService;
+use crate::services::collection_service::CollectionService;
+use crate::services::curl_import_service::CurlImportService;
+use crate::services::database_service::DatabaseService;
+use crate::services::environment_service::EnvironmentService;
+use crate::services::http_service::{
+    HttpService, RequestCancelled, RequestFailed, ResponseTooLarge, TlsConfigError, DEFAULT_CORS_ORIGIN,
+};
+use crate::services::last_response_service::LastResponseService;
+use crate::services::pre_request_service::PreRequestService;
+use crate::services::redaction::{default_sensitive_headers, is_sensitive_header, mask_header_value};
+use crate::services::request_history_service::RequestHistoryService;
+use crate::services::url_components;
+use crate::services::xml_formatter::pretty_print_xml;
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
-use tauri::State;
+use tauri::{AppHandle, Emitter, State};
 
 type HttpServiceState = Arc<Mutex<HttpService>>;
 
@@ -18,20 +33,99 @@ macro_rules! get_http_service {
 pub async fn execute_http_request(
     request: HttpRequest,
     environment_variables: Option<HashMap<String, String>>,
+    options: Option<ExecuteOptions>,
+    confirm: Option<bool>,
+    app_handle: AppHandle,
     http_service: State<'_, HttpServiceState>,
+    db_service: State<'_, Mutex<Option<Arc<DatabaseService>>>>,
 ) -> Result<ExecuteRequestResponse, String> {
     let service = get_http_service!(http_service);
     let request_id = request.id.clone();
+    let mut options = options.unwrap_or_default();
+    // The database may not be initialized yet; sends still go through without it
+    let db = db_service.lock().ok().and_then(|db| db.as_ref().cloned());
+
+    // Hold back mutating requests against a protected environment until the user confirms
+    if !confirm.unwrap_or(false) {
+        if let Some(db) = &db {
+            let environment = environment_for_send(db, &options).await.map_err(|e| e.to_string())?;
+            if HttpService::requires_confirmation(&request.method, environment.as_ref()) {
+                return Ok(ExecuteRequestResponse {
+                    response: None,
+                    error: None,
+                    request_id,
+                    requires_confirmation: true,
+                });
+            }
+        }
+    }
+
+    // Pick up the workspace's current settings before sending
+    if let Some(db) = &db {
+        apply_workspace_settings(db, &service, &mut options).await;
+    }
+
+    // A saved request's pre-request steps set their variables first, as they do in a collection run
+    let environment_variables = match &db {
+        Some(db) => match apply_pre_request_steps(db, &request_id, environment_variables).await {
+            Ok(variables) => variables,
+            Err(e) => {
+                let error = service.create_error(
+                    HttpErrorType::InvalidRequest,
+                    e.to_string(),
+                    Some(format!("Pre-request steps failed: {}", e)),
+                );
+                return Ok(ExecuteRequestResponse {
+                    response: None,
+                    error: Some(error),
+                    request_id,
+                    requires_confirmation: false,
+                });
+            }
+        },
+        None => environment_variables,
+    };
     
-    match service.execute_request(request, environment_variables).await {
-        Ok(response) => Ok(ExecuteRequestResponse {
-            response: Some(response),
-            error: None,
-            request_id,
-        }),
+    // Event stream responses are delivered event by event as they arrive
+    let event_name = sse_event_name(&request_id);
+    let emit = move |event: SseEvent| {
+        if let Err(e) = app_handle.emit(&event_name, event) {
+            eprintln!("Warning: Failed to emit server-sent event: {}", e);
+        }
+    };
+    let (method, url) = (request.method.clone(), request.url.clone());
+    let result = service.execute_request_with_events(request, environment_variables, &options, emit).await;
+
+    // Log the execution in the workspace's history and remember a successful response
+    if let (Some(workspace_id), Some(db)) = (&options.workspace_id, &db) {
+        let error = result.as_ref().err().map(|e| e.to_string());
+        let outcome = result.as_ref().map_err(|_| error.as_deref().unwrap_or_default());
+        record_execution(db, workspace_id, &request_id, &method, &url, outcome).await;
+    }
+
+    match result {
+        Ok(response) => {
+            Ok(ExecuteRequestResponse {
+                response: Some(response),
+                error: None,
+                request_id,
+                requires_confirmation: false,
+            })
+        }
         Err(e) => {
+            let error_type = if e.downcast_ref::<ResponseTooLarge>().is_some() {
+                HttpErrorType::ResponseTooLarge
+            } else if e.downcast_ref::<TlsConfigError>().is_some() {
+                HttpErrorType::SslError
+            } else if e.downcast_ref::<RequestCancelled>().is_some() {
+                HttpErrorType::Cancelled
+            } else if let Some(failed) = e.downcast_ref::<RequestFailed>() {
+                failed.error_type.clone()
+            } else {
+                HttpErrorType::UnknownError
+            };
             let error = service.create_error(
-                HttpErrorType::UnknownError,
+                error_type,
                 e.to_string(),
                 Some(format!("Request execution failed: {}", e)),
             );
@@ -39,18 +133,281 @@ pub async fn execute_http_request(
                 response: None,
                 error: Some(error),
                 request_id,
+                requires_confirmation: false,
             })
         }
     }
 }
 
+/// Fill in what the options' workspace sets and the caller left unset, and apply the workspace's
+/// send throttle. Shared by single sends and collection runs so both go out the same way.
+pub async fn apply_workspace_settings(db: &DatabaseService, service: &HttpService, options: &mut ExecuteOptions) {
+    let Some(workspace_id) = &options.workspace_id else {
+        return;
+    };
+    let Ok(Some(settings)) = db.get_workspace_settings(workspace_id).await else {
+        return;
+    };
+    service.set_min_request_interval(workspace_id, settings.min_request_interval_ms as u64);
+    if options.max_response_bytes.is_none() && settings.max_response_bytes > 0 {
+        options.max_response_bytes = Some(settings.max_response_bytes);
+    }
+    if options.connect_timeout_ms.is_none() && settings.connect_timeout_ms > 0 {
+        options.connect_timeout_ms = Some(settings.connect_timeout_ms as u64);
+    }
+    if options.proxy.is_none() {
+        options.proxy = settings.proxy_url.filter(|url| !url.trim().is_empty()).map(|url| ProxyConfig {
+            url,
+            username: settings.proxy_username,
+            password: settings.proxy_password,
+            no_proxy: settings.no_proxy,
+        });
+    }
+    if options.tls.is_none() {
+        options.tls = Some(TlsConfig {
+            client_cert_path: settings.client_cert_path,
+            client_key_path: settings.client_key_path,
+            client_cert_password: settings.client_cert_password,
+            ca_bundle_path: settings.ca_bundle_path,
+            accept_invalid_certs: !settings.verify_ssl,
+        });
+    }
+
+    // The caller's default headers override the workspace's one header at a time
+    let mut default_headers = settings.default_headers;
+    if let Some(user_agent) = settings.user_agent.filter(|agent| !agent.trim().is_empty()) {
+        default_headers.retain(|name, _| !name.eq_ignore_ascii_case("user-agent"));
+        default_headers.insert("User-Agent".to_string(), user_agent);
+    }
+    default_headers.retain(|name, _| !options.default_headers.keys().any(|own| own.eq_ignore_ascii_case(name)));
+    default_headers.extend(std::mem::take(&mut options.default_headers));
+    options.default_headers = default_headers;
+}
+
+/// The environment a send substitutes its variables from: the one the options name, or else
+/// the workspace's active one
+pub async fn environment_for_send(db: &Arc<DatabaseService>, options: &ExecuteOptions) -> anyhow::Result<Option<Environment>> {
+    let environments = EnvironmentService::new(db.clone());
+    match (&options.environment_id, &options.workspace_id) {
+        (Some(environment_id), _) => environments.get_environment(environment_id).await,
+        (None, Some(workspace_id)) => Ok(environments
+            .list_environments(workspace_id).await?
+            .into_iter()
+            .find(|env| env.is_active)),
+        (None, None) => Ok(None),
+    }
+}
+
+/// Log an execution in the workspace's request history and, when it got a response, keep it
+/// as the request's last response. Failures are logged rather than failing the send.
+pub async fn record_execution(
+    db: &DatabaseService,
+    workspace_id: &str,
+    request_id: &str,
+    method: &HttpMethod,
+    url: &str,
+    result: Result<&HttpResponse, &str>,
+) {
+    if let Err(e) = RequestHistoryService::new(db.get_pool())
+        .record(workspace_id, request_id, method, url, result)
+        .await
+    {
+        eprintln!("Warning: Failed to record request history: {}", e);
+    }
+    if let Ok(response) = result {
+        if let Err(e) = LastResponseService::new(db.get_pool()).save(response).await {
+            eprintln!("Warning: Failed to store last response: {}", e);
+        }
+    }
+}
+
+/// Run the saved request's pre-request steps over the variables it's about to be sent with.
+/// Requests that aren't saved, or have no steps, keep their variables as they are.
+pub async fn apply_pre_request_steps(
+    db: &DatabaseService,
+    request_id: &str,
+    variables: Option<HashMap<String, String>>,
+) -> anyhow::Result<Option<HashMap<String, String>>> {
+    let steps = CollectionService::new(db.get_pool())
+        .get_request(request_id).await?
+        .map(|request| request.get_pre_request())
+        .unwrap_or_default();
+    if steps.is_empty() {
+        return Ok(variables);
+    }
+    let mut variables = variables.unwrap_or_default();
+    PreRequestService::new().apply(&steps, &mut variables)?;
+    Ok(Some(variables))
+}
+
+#[tauri::command]
+pub async fn get_last_response(
+    request_id: String,
+    db_service: State<'_, Mutex<Option<Arc<DatabaseService>>>>,
+) -> Result<Option<HttpResponse>, String> {
+    let db = db_service
+        .lock()
+        .map_err(|e| format!("Database service lock error: {}", e))?
+        .as_ref()
+        .ok_or("Database not initialized")?
+        .clone();
+
+    LastResponseService::new(db.get_pool())
+        .get(&request_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// A workspace's executed requests, newest first
+#[tauri::command]
+pub async fn get_request_history(
+    workspace_id: String,
+    limit: Option<i64>,
+    db_service: State<'_, Mutex<Option<Arc<DatabaseService>>>>,
+) -> Result<Vec<RequestHistoryEntry>, String> {
+    let db = db_service
+        .lock()
+        .map_err(|e| format!("Database service lock error: {}", e))?
+        .as_ref()
+        .ok_or("Database not initialized")?
+        .clone();
+
+    RequestHistoryService::new(db.get_pool())
+        .list(&workspace_id, limit.unwrap_or(100))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn clear_request_history(
+    workspace_id: String,
+    db_service: State<'_, Mutex<Option<Arc<DatabaseService>>>>,
+) -> Result<u64, String> {
+    let db = db_service
+        .lock()
+        .map_err(|e| format!("Database service lock error: {}", e))?
+        .as_ref()
+        .ok_or("Database not initialized")?
+        .clone();
+
+    RequestHistoryService::new(db.get_pool())
+        .clear(&workspace_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Tauri event carrying a request's server-sent events
+fn sse_event_name(request_id: &str) -> String {
+    format!("sse-event:{}", request_id)
+}
+
+/// Stop reading a request's event stream. The request then completes with what was received.
+#[tauri::command]
+pub async fn stop_sse(
+    request_id: String,
+    http_service: State<'_, HttpServiceState>,
+) -> Result<bool, String> {
+    let service = get_http_service!(http_service);
+    Ok(service.stop_event_stream(&request_id))
+}
+
+/// Stop an in-flight request; returns false when it had already finished
+#[tauri::command]
+pub async fn cancel_request(
+    request_id: String,
+    http_service: State<'_, HttpServiceState>,
+) -> Result<bool, String> {
+    let service = get_http_service!(http_service);
+    Ok(service.cancel_request(&request_id))
+}
+
+#[tauri::command]
+pub async fn get_cookies(
+    workspace_id: String,
+    http_service: State<'_, HttpServiceState>,
+) -> Result<Vec<CookieInfo>, String> {
+    let service = get_http_service!(http_service);
+    Ok(service.get_cookies(&workspace_id))
+}
+
+#[tauri::command]
+pub async fn clear_cookies(
+    workspace_id: String,
+    http_service: State<'_, HttpServiceState>,
+) -> Result<(), String> {
+    let service = get_http_service!(http_service);
+    service.clear_cookies(&workspace_id);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn save_response_body(
+    request_id: String,
+    path: String,
+    http_service: State<'_, HttpServiceState>,
+) -> Result<usize, String> {
+    let service = get_http_service!(http_service);
+    service.save_response_body(&request_id, &path).await.map_err(|e| e.to_string())
+}
+
+/// The complete body of a recent binary or truncated response, base64-encoded
+#[tauri::command]
+pub async fn get_response_body_bytes(
+    request_id: String,
+    http_service: State<'_, HttpServiceState>,
+) -> Result<String, String> {
+    let service = get_http_service!(http_service);
+    let bytes = service.response_body_bytes(&request_id).map_err(|e| e.to_string())?;
+    Ok(BASE64_STANDARD.encode(bytes))
+}
+
+/// Send a request and stream the response to a file, for bodies too large to load
+#[tauri::command]
+pub async fn save_response_to_file(
+    request: HttpRequest,
+    path: String,
+    environment_variables: Option<HashMap<String, String>>,
+    options: Option<ExecuteOptions>,
+    http_service: State<'_, HttpServiceState>,
+) -> Result<u64, String> {
+    let service = get_http_service!(http_service);
+    service
+        .save_response_to_file(request, environment_variables, &options.unwrap_or_default(), &path)
+        .await
+        .map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 pub async fn test_http_connection(
     url: String,
+    options: Option<ConnectionTestOptions>,
     http_service: State<'_, HttpServiceState>,
 ) -> Result<bool, String> {
     let service = get_http_service!(http_service);
-    service.test_connection(&url).await.map_err(|e| e.to_string())
+    service.test_connection(&url, &options.unwrap_or_default()).await.map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn inspect_cors(
+    url: String,
+    method: String,
+    request_headers: Option<Vec<String>>,
+    origin: Option<String>,
+    options: Option<ExecuteOptions>,
+    http_service: State<'_, HttpServiceState>,
+    db_service: State<'_, Mutex<Option<Arc<DatabaseService>>>>,
+) -> Result<CorsInspection, String> {
+    let service = get_http_service!(http_service);
+    let origin = origin.unwrap_or_else(|| DEFAULT_CORS_ORIGIN.to_string());
+    let mut options = options.unwrap_or_default();
+    let db = db_service.lock().ok().and_then(|db| db.as_ref().cloned());
+    if let Some(db) = &db {
+        apply_workspace_settings(db, &service, &mut options).await;
+    }
+    service
+        .inspect_cors(&url, &method, &request_headers.unwrap_or_default(), &origin, &options)
+        .await
+        .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
@@ -66,6 +423,24 @@ pub async fn create_default_http_request() -> Result<HttpRequest, String> {
     Ok(HttpRequest::default())
 }
 
+#[tauri::command]
+pub async fn create_graphql_request(
+    url: String,
+    query: String,
+    variables: Option<serde_json::Value>,
+) -> Result<HttpRequest, String> {
+    Ok(HttpRequest {
+        name: "New GraphQL Request".to_string(),
+        method: HttpMethod::Post,
+        url,
+        body: Some(RequestBody::GraphQl {
+            query,
+            variables: variables.unwrap_or_else(|| serde_json::json!({})),
+        }),
+        ..HttpRequest::default()
+    })
+}
+
 #[tauri::command]
 pub async fn validate_http_url(url: String) -> Result<bool, String> {
     match url::Url::parse(&url) {
@@ -78,65 +453,49 @@ pub async fn validate_http_url(url: String) -> Result<bool, String> {
     }
 }
 
+/// Split a URL into scheme, host, port, path and ordered query parameters for editing
+#[tauri::command]
+pub async fn parse_url_components(url: String) -> Result<UrlComponents, String> {
+    url_components::parse_url_components(&url).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn build_url_from_components(components: UrlComponents) -> Result<String, String> {
+    Ok(url_components::build_url_from_components(&components))
+}
+
+#[tauri::command]
+pub async fn validate_request(request: HttpRequest) -> Result<Vec<ValidationIssue>, String> {
+    Ok(HttpService::validate_request(&request))
+}
+
 #[tauri::command]
 pub async fn parse_curl_command(curl_command: String) -> Result<HttpRequest, String> {
-    // Basic curl parsing - this is a simplified implementation
-    // In production, you'd want a more robust curl parser
-    
-    let mut request = HttpRequest::default();
-    let parts: Vec<&str> = curl_command.split_whitespace().collect();
-    
-    let mut i = 0;
-    while i < parts.len() {
-        match parts[i] {
-            "curl" => {}, // Skip curl command
-            "-X" | "--request" => {
-                if i + 1 < parts.len() {
-                    request.method = HttpMethod::from(parts[i + 1]);
-                    i += 1;
-                }
-            },
-            "-H" | "--header" => {
-                if i + 1 < parts.len() {
-                    let header = parts[i + 1];
-                    if let Some((key, value)) = header.split_once(':') {
-                        request.headers.insert(
-                            key.trim().to_string(),
-                            value.trim().to_string(),
-                        );
-                    }
-                    i += 1;
-                }
-            },
-            "-d" | "--data" => {
-                if i + 1 < parts.len() {
-                    let data = parts[i + 1];
-                    // Try to parse as JSON, fallback to raw
-                    if let Ok(json) = serde_json::from_str::<serde_json::Value>(data) {
-                        request.body = Some(RequestBody::Json { data: json });
-                    } else {
-                        request.body = Some(RequestBody::Raw {
-                            content: data.to_string(),
-                            content_type: "text/plain".to_string(),
-                        });
-                    }
-                    i += 1;
-                }
-            },
-            url if url.starts_with("http") => {
-                request.url = url.to_string();
-            },
-            _ => {}, // Skip unknown options
-        }
-        i += 1;
-    }
-    
-    Ok(request)
+    CurlImportService::new()
+        .parse(&curl_command)
+        .map_err(|e| e.to_string())
 }
 
 // Helper function to format response for debugging
 #[tauri::command]
-pub async fn format_http_response_debug(response: HttpResponse) -> Result<String, String> {
+pub async fn export_request_as_curl(request: HttpRequest) -> Result<String, String> {
+    Ok(CodeExportService::new().to_curl(&request))
+}
+
+#[tauri::command]
+pub async fn generate_code_snippet(request: HttpRequest, target: CodeTarget) -> Result<String, String> {
+    Ok(CodeExportService::new().generate_snippet(&request, target))
+}
+
+#[tauri::command]
+pub async fn format_http_response_debug(
+    response: HttpResponse,
+    include_secrets: Option<bool>,
+    sensitive_headers: Option<Vec<String>>,
+) -> Result<String, String> {
+    // Masked by default so the output can be pasted into an issue or chat
+    let include_secrets = include_secrets.unwrap_or(false);
+    let sensitive_headers = sensitive_headers.unwrap_or_else(default_sensitive_headers);
     let mut debug_info = String::new();
     
     debug_info.push_str(&format!("Status: {} {}\n", response.status, response.status_text));
@@ -144,12 +503,19 @@ pub async fn format_http_response_debug(response: HttpResponse) -> Result<String
     debug_info.push_str("\nHeaders:\n");
     
     for (key, value) in &response.headers {
+        if !include_secrets && is_sensitive_header(key, &sensitive_headers) {
+            debug_info.push_str(&format!("{}: {}\n", key, mask_header_value(value)));
+            continue;
+        }
         debug_info.push_str(&format!("{}: {}\n", key, value));
     }
     
     debug_info.push_str("\nBody:\n");
     match &response.body {
-        ResponseBody::Text { content } => {
+        ResponseBody::Text { content, content_subtype: TextSubtype::Xml } => {
+            debug_info.push_str(&pretty_print_xml(content).unwrap_or_else(|| content.clone()));
+        },
+        ResponseBody::Text { content, .. } => {
             debug_info.push_str(content);
         },
         ResponseBody::Json { data } => {
@@ -159,8 +525,8 @@ pub async fn format_http_response_debug(response: HttpResponse) -> Result<String
                 debug_info.push_str(&data.to_string());
             }
         },
-        ResponseBody::Binary { size, .. } => {
-            debug_info.push_str(&format!("Binary data ({} bytes)", size));
+        ResponseBody::Binary { size, mime_type, .. } => {
+            debug_info.push_str(&format!("Binary data ({}, {} bytes)", mime_type, size));
         },
         ResponseBody::Empty => {
             debug_info.push_str("(empty)");
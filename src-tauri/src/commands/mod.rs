@@ -3,4 +3,5 @@ pub mod environment;
 pub mod git;
 pub mod git_branch_commands;
 pub mod http;
+pub mod websocket;
 pub mod workspace;
\ No newline at end of file
@@ -0,0 +1,78 @@
+use crate::services::database_service::DatabaseService;
+use crate::services::environment_service::EnvironmentService;
+use crate::services::websocket_service::{WebSocketService, WEBSOCKET_EVENT};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Emitter, State};
+
+pub type WebSocketServiceState = Arc<Mutex<WebSocketService>>;
+
+// Macro to get cloned service to avoid holding lock across await
+macro_rules! get_websocket_service {
+    ($service_state:expr) => {{
+        let service_state = $service_state.lock().map_err(|e| format!("WebSocket service lock error: {}", e))?;
+        service_state.clone()
+    }};
+}
+
+/// Open a WebSocket connection and return its id. Received frames are emitted as
+/// `websocket-event` events. Variables resolve as they do for requests: global ones, then
+/// the workspace's active environment with what it inherits, then the collection's.
+#[tauri::command]
+pub async fn ws_connect(
+    url: String,
+    headers: Option<HashMap<String, String>>,
+    workspace_id: Option<String>,
+    collection_id: Option<String>,
+    app_handle: AppHandle,
+    websocket_service: State<'_, WebSocketServiceState>,
+    db_service: State<'_, Mutex<Option<Arc<DatabaseService>>>>,
+) -> Result<String, String> {
+    let service = get_websocket_service!(websocket_service);
+    // The database may not be initialized yet; connections still go through without it
+    let db = db_service.lock().ok().and_then(|db| db.as_ref().cloned());
+
+    let mut environment_variables = HashMap::new();
+    if let (Some(workspace_id), Some(db)) = (&workspace_id, db) {
+        let environment_service = EnvironmentService::new(db);
+        let active = environment_service
+            .list_environments(workspace_id)
+            .await
+            .map_err(|e| e.to_string())?
+            .into_iter()
+            .find(|env| env.is_active);
+        environment_variables = environment_service
+            .resolve_variables(workspace_id, collection_id.as_deref(), None, active.map(|env| env.id).as_deref())
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+
+    service
+        .connect(&url, &headers.unwrap_or_default(), &environment_variables, move |event| {
+            if let Err(e) = app_handle.emit(WEBSOCKET_EVENT, event) {
+                eprintln!("Warning: Failed to emit WebSocket event: {}", e);
+            }
+        })
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn ws_send(
+    connection_id: String,
+    message: String,
+    websocket_service: State<'_, WebSocketServiceState>,
+) -> Result<(), String> {
+    let service = get_websocket_service!(websocket_service);
+    service.send(&connection_id, message).map_err(|e| e.to_string())
+}
+
+/// Start closing a connection; the final `closed` event carries the close code
+#[tauri::command]
+pub async fn ws_close(
+    connection_id: String,
+    websocket_service: State<'_, WebSocketServiceState>,
+) -> Result<(), String> {
+    let service = get_websocket_service!(websocket_service);
+    service.close(&connection_id).map_err(|e| e.to_string())
+}
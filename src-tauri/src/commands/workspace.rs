@@ -1,10 +1,15 @@
 use crate::models::workspace::{
     CreateWorkspaceRequest, UpdateWorkspaceRequest, Workspace, WorkspaceSettings, WorkspaceSummary,
 };
+use crate::services::backup_service::BackupService;
 use crate::services::database_service::DatabaseService;
-use crate::services::git_service::GitService;
+pub use crate::services::file_sync_service::expand_tilde_path;
+use crate::models::git::CloneProgress;
+use crate::services::git_service::{self, GitService};
+use crate::services::redaction::redact_url_userinfo;
+use crate::services::workspace_archive_service::WorkspaceArchiveService;
 use std::sync::{Arc, Mutex};
-use tauri::State;
+use tauri::{AppHandle, Emitter, State};
 use tokio::fs;
 
 // Global state for Database service
@@ -91,6 +96,8 @@ pub async fn workspace_run_migrations(
 #[tauri::command]
 pub async fn workspace_create(
     request: CreateWorkspaceRequest,
+    clone_id: Option<String>,
+    app_handle: AppHandle,
     db_service: State<'_, DatabaseServiceState>,
 ) -> Result<Workspace, String> {
     let db = get_db!(db_service);
@@ -100,9 +107,25 @@ pub async fn workspace_create(
     let git_service = GitService::new();
     
     if let Some(git_url) = &workspace.git_repository_url {
+        // Clear what an earlier, interrupted clone left behind so it doesn't block this one
+        if git_service.is_failed_clone(&workspace_path) {
+            fs::remove_dir_all(&workspace_path)
+                .await
+                .map_err(|e| format!("Failed to remove incomplete clone at '{}': {}", workspace_path, e))?;
+        }
+
         // Clone existing repository (this will create the directory and populate it)
-        eprintln!("Cloning Git repository: {} -> {}", git_url, workspace_path);
-        match git_service.clone_repository(git_url, &workspace_path, None) {
+        eprintln!("Cloning Git repository: {} -> {}", redact_url_userinfo(git_url), workspace_path);
+        // Progress is only reported when the caller passes an ID to listen on
+        let event_name = clone_id.as_deref().map(git_service::clone_progress_event_name);
+        let emit = |progress: CloneProgress| {
+            if let Some(event_name) = &event_name {
+                if let Err(e) = app_handle.emit(event_name, progress) {
+                    eprintln!("Warning: Failed to emit clone progress: {}", e);
+                }
+            }
+        };
+        match git_service.clone_repository(git_url, &workspace_path, None, Some(&emit)) {
             Ok(result) => {
                 eprintln!("Git clone result: success={}, message={}", result.success, result.message);
                 if !result.success {
@@ -216,19 +239,15 @@ Thumbs.db
     Ok(workspace)
 }
 
-// Helper function to expand tilde paths
-pub fn expand_tilde_path(path: &str) -> String {
-    if path.starts_with("~/") {
-        if let Ok(home_dir) = std::env::var("HOME") {
-            return path.replacen("~", &home_dir, 1);
-        }
-    }
-    path.to_string()
-}
 
 #[tauri::command]
 pub async fn workspace_check_directory_exists(path: String) -> Result<bool, String> {
     let expanded_path = expand_tilde_path(&path);
+
+    // Leftovers of an interrupted clone are cleared by workspace_create, so they don't conflict
+    if GitService::new().is_failed_clone(&expanded_path) {
+        return Ok(false);
+    }
     
     match fs::metadata(&expanded_path).await {
         Ok(metadata) => {
@@ -381,6 +400,26 @@ pub async fn workspace_get_summaries(
         .map_err(|e| format!("Failed to get workspace summaries: {}", e))
 }
 
+/// Summaries with each workspace's git status filled in. Slower than
+/// `workspace_get_summaries` since every workspace directory is inspected.
+#[tauri::command]
+pub async fn workspace_get_summaries_with_git(
+    db_service: State<'_, DatabaseServiceState>,
+) -> Result<Vec<WorkspaceSummary>, String> {
+    let db = get_db!(db_service);
+
+    let mut summaries = db
+        .get_workspace_summaries()
+        .await
+        .map_err(|e| format!("Failed to get workspace summaries: {}", e))?;
+
+    let git_service = GitService::new();
+    for summary in &mut summaries {
+        summary.git_status = Some(git_service.status_summary(&summary.local_path));
+    }
+    Ok(summaries)
+}
+
 #[tauri::command]
 pub async fn workspace_access(
     id: String,
@@ -446,4 +485,103 @@ pub async fn workspace_settings_update(
         .map_err(|e| format!("Failed to update workspace settings: {}", e))?;
 
     Ok(true)
-}
\ No newline at end of file
+}
+
+#[tauri::command]
+pub async fn workspace_set_min_request_interval(
+    workspace_id: String,
+    interval_ms: u32,
+    db_service: State<'_, DatabaseServiceState>,
+) -> Result<WorkspaceSettings, String> {
+    let db = get_db!(db_service);
+
+    let existing = db.get_workspace_settings(&workspace_id)
+        .await
+        .map_err(|e| format!("Failed to get workspace settings: {}", e))?;
+
+    let settings = match existing {
+        Some(mut settings) => {
+            settings.min_request_interval_ms = interval_ms;
+            settings.updated_at = chrono::Utc::now();
+            db.update_workspace_settings(&settings)
+                .await
+                .map_err(|e| format!("Failed to update workspace settings: {}", e))?;
+            settings
+        }
+        None => {
+            let mut settings = WorkspaceSettings::default();
+            settings.workspace_id = workspace_id;
+            settings.min_request_interval_ms = interval_ms;
+            db.create_workspace_settings(&settings)
+                .await
+                .map_err(|e| format!("Failed to create workspace settings: {}", e))?;
+            settings
+        }
+    };
+
+    Ok(settings)
+}
+
+#[tauri::command]
+pub async fn export_encrypted_backup(
+    dest_path: String,
+    passphrase: String,
+    include_secrets: Option<bool>,
+    db_service: State<'_, DatabaseServiceState>,
+) -> Result<bool, String> {
+    let db = get_db!(db_service);
+
+    BackupService::new(db.get_pool())
+        .export_encrypted(std::path::Path::new(&dest_path), &passphrase, include_secrets.unwrap_or(false))
+        .await
+        .map_err(|e| format!("Failed to export backup: {}", e))?;
+
+    Ok(true)
+}
+
+#[tauri::command]
+pub async fn import_encrypted_backup(
+    src_path: String,
+    passphrase: String,
+    db_service: State<'_, DatabaseServiceState>,
+) -> Result<bool, String> {
+    let db = get_db!(db_service);
+
+    BackupService::new(db.get_pool())
+        .import_encrypted(std::path::Path::new(&src_path), &passphrase)
+        .await
+        .map_err(|e| format!("Failed to import backup: {}", e))?;
+
+    Ok(true)
+}
+
+#[tauri::command]
+pub async fn export_workspace(
+    workspace_id: String,
+    output_path: String,
+    include_secrets: Option<bool>,
+    db_service: State<'_, DatabaseServiceState>,
+) -> Result<bool, String> {
+    let db = get_db!(db_service);
+
+    WorkspaceArchiveService::new(db)
+        .export_workspace(&workspace_id, std::path::Path::new(&output_path), include_secrets.unwrap_or(false))
+        .await
+        .map_err(|e| format!("Failed to export workspace: {}", e))?;
+
+    Ok(true)
+}
+
+#[tauri::command]
+pub async fn import_workspace(
+    archive_path: String,
+    local_path: String,
+    db_service: State<'_, DatabaseServiceState>,
+) -> Result<Workspace, String> {
+    let db = get_db!(db_service);
+
+    WorkspaceArchiveService::new(db)
+        .import_workspace(std::path::Path::new(&archive_path), &local_path)
+        .await
+        .map_err(|e| format!("Failed to import workspace: {}", e))
+}
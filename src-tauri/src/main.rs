@@ -5,8 +5,8 @@ mod commands;
 mod models;
 mod services;
 
-use commands::{collection::*, environment::*, git::*, git_branch_commands::*, http::*, workspace::*};
-use services::{credential_service::CredentialService, environment_service::EnvironmentService, git_service::GitService, http_service::HttpService, database_service::DatabaseService};
+use commands::{collection::*, environment::*, git::*, git_branch_commands::*, http::*, websocket::*, workspace::*};
+use services::{credential_service::CredentialService, environment_service::EnvironmentService, git_service::GitService, http_service::HttpService, database_service::DatabaseService, websocket_service::WebSocketService};
 use tauri::Manager;
 use std::sync::{Mutex, Arc};
 
@@ -52,6 +52,7 @@ pub fn run() {
         .manage(CredentialServiceState::new(CredentialService::new()))
         .manage(DatabaseServiceState::new(None))
         .manage(std::sync::Arc::new(std::sync::Mutex::new(HttpService::new())))
+        .manage(WebSocketServiceState::new(Mutex::new(WebSocketService::new())))
         .manage(std::sync::Arc::new(std::sync::Mutex::new(None::<EnvironmentService>)))
         .manage(Mutex::new(None::<services::git_branch_service::GitBranchService>))
         .invoke_handler(tauri::generate_handler![
@@ -62,10 +63,31 @@ pub fn run() {
             git_get_status,
             git_get_branches,
             git_check_repository,
+            git_trust_host_key,
+            git_set_skip_host_key_verification,
+            git_fetch,
+            git_check_remote_access,
+            git_pull,
+            git_get_log,
+            git_get_head_info,
+            git_get_diff,
+            git_stage_all,
+            git_stage_files,
+            git_unstage_files,
+            git_list_conflicts,
+            git_resolve_conflict,
+            git_abort_merge,
+            git_list_remotes,
+            git_add_remote,
+            git_remove_remote,
+            git_stash_save,
+            git_stash_pop,
+            git_push,
             git_store_credentials,
             git_get_credentials,
             git_delete_credentials,
             git_credentials_exist,
+            git_list_credential_hosts,
             workspace_initialize_database,
             workspace_database_health_check,
             workspace_run_migrations,
@@ -77,45 +99,117 @@ pub fn run() {
             workspace_delete,
             workspace_set_active,
             workspace_get_summaries,
+            workspace_get_summaries_with_git,
             workspace_access,
             workspace_settings_create,
             workspace_settings_get,
             workspace_settings_update,
+            workspace_set_min_request_interval,
+            export_encrypted_backup,
+            import_encrypted_backup,
+            export_workspace,
+            import_workspace,
             workspace_check_directory_exists,
             workspace_check_parent_directory,
             execute_http_request,
+            cancel_request,
+            stop_sse,
+            get_last_response,
+            get_request_history,
+            clear_request_history,
+            save_response_body,
+            get_response_body_bytes,
+            save_response_to_file,
+            get_cookies,
+            clear_cookies,
+            ws_connect,
+            ws_send,
+            ws_close,
             test_http_connection,
+            inspect_cors,
             get_supported_http_methods,
             create_default_http_request,
+            create_graphql_request,
             validate_http_url,
+            parse_url_components,
+            build_url_from_components,
+            validate_request,
             parse_curl_command,
+            export_request_as_curl,
+            generate_code_snippet,
             format_http_response_debug,
             create_environment,
             get_environment,
             update_environment,
             delete_environment,
             list_environments,
+            get_resolved_environment,
+            import_env_file,
+            export_env_file,
             add_environment_variable,
             update_environment_variable,
+            set_environment_variables,
             remove_environment_variable,
+            get_scoped_variables,
+            set_scoped_variable,
+            remove_scoped_variable,
+            resolve_variables,
+            find_variable_usages,
+            find_undefined_variables,
+            find_orphaned_environment_files,
+            import_orphaned_environment_file,
             substitute_environment_variables,
+            substitute_with_spans,
             extract_environment_variables,
             create_default_environments,
             set_active_environment,
+            clear_active_environment,
             get_active_environment,
             create_collection,
             get_collection,
             update_collection,
             delete_collection,
+            restore_collection,
+            purge_deleted,
             list_collections,
+            list_collections_by_branch,
+            set_collection_branch,
+            set_collection_parent,
+            list_child_collections,
+            sync_workspace_from_disk,
+            list_conflicted_files,
             get_collection_summaries,
             create_request,
             get_request,
             update_request,
             delete_request,
+            restore_request,
             list_requests,
+            search_requests,
             duplicate_request,
+            duplicate_collection,
+            move_request,
+            suggest_duplicate_name,
+            suggest_duplicate_collection_name,
             reorder_requests,
+            export_collection_as_curl_script,
+            set_request_run_inclusion,
+            set_request_raw_stream,
+            set_request_connect_timeout,
+            set_request_run_condition,
+            set_request_pre_request,
+            set_request_extractions,
+            set_request_assertions,
+            import_curls,
+            import_har,
+            export_har,
+            create_request_example,
+            compare_request_to_example,
+            save_response_snapshot,
+            diff_against_snapshot,
+            preview_auth_header,
+            run_collection,
+            execute_request_with_assertions,
             init_git_branch_service,
             get_system_info,
             get_branch_config,
@@ -123,6 +217,9 @@ pub fn run() {
             suggest_branch_pattern,
             create_branch,
             list_branches,
+            git_switch_branch,
+            git_delete_branch,
+            git_rename_branch,
             get_branch_history,
             get_suggested_branches,
             update_branch_config,
@@ -145,8 +242,18 @@ pub fn run() {
             }
             Ok(())
         })
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app_handle, event| {
+            // Send close frames so servers see a clean disconnect rather than a dropped socket
+            if let tauri::RunEvent::Exit = event {
+                let state = app_handle.state::<WebSocketServiceState>();
+                let websocket_service = state.lock().map(|service| service.clone());
+                if let Ok(websocket_service) = websocket_service {
+                    tauri::async_runtime::block_on(websocket_service.close_all());
+                }
+            }
+        });
 }
 
 fn main() {
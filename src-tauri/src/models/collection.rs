@@ -1,8 +1,11 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
+use std::collections::HashMap;
 use uuid::Uuid;
 
+use crate::models::http::{FormFile, HttpMethod, HttpRequest, RequestBody};
+
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct Collection {
     pub id: String,
@@ -11,6 +14,8 @@ pub struct Collection {
     pub description: Option<String>,
     pub folder_path: Option<String>, // For organizing collections in folders
     pub git_branch: Option<String>,  // Git branch this collection belongs to
+    #[serde(default)]
+    pub parent_id: Option<String>,   // The collection this one is nested in
     pub is_active: bool,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
@@ -32,10 +37,113 @@ pub struct Request {
     pub follow_redirects: bool,
     pub timeout_ms: u32,
     pub order_index: i32, // For ordering within collection
+    #[serde(default = "default_include_in_run")]
+    pub include_in_run: bool, // Skipped by collection runs when false
+    #[serde(default)]
+    pub raw_stream: bool, // Responses are returned as raw streamed text
+    #[serde(default)]
+    pub connect_timeout_ms: Option<u32>, // Overrides the workspace's connect timeout
+    #[serde(default)]
+    pub run_condition: Option<String>, // Collection runs skip the request unless this holds, e.g. `{{token}} exists`
+    #[serde(default = "default_json_array")]
+    pub pre_request: String, // JSON array of PreRequestStep run before each send
+    #[serde(default = "default_json_array")]
+    pub extractions: String, // JSON array of Extraction rules applied in collection runs
+    #[serde(default = "default_json_array")]
+    pub assertions: String, // JSON array of Assertion rules checked against each response
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
+fn default_include_in_run() -> bool {
+    true
+}
+
+fn default_json_array() -> String {
+    "[]".to_string()
+}
+
+/// Where an extraction reads its value from
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ExtractionSource {
+    Body,
+    Header,
+}
+
+/// Captures a value from a response into a variable for the requests run after it
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Extraction {
+    pub variable: String,
+    pub source: ExtractionSource,
+    pub path: String, // JSONPath such as `$.data.token` for bodies, the header name for headers
+}
+
+/// Sets a variable just before a request is sent. Templates are substituted first, so they can
+/// use other variables and built-ins such as `{{$timestamp}}`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "operation", rename_all = "snake_case")]
+pub enum PreRequestStep {
+    SetVariable { variable: String, value: String },
+    Base64Encode { variable: String, value: String },
+    HmacSha256 { variable: String, message: String, secret_variable: String }, // lowercase hex digest
+}
+
+impl PreRequestStep {
+    /// The variable the step sets
+    pub fn variable(&self) -> &str {
+        match self {
+            PreRequestStep::SetVariable { variable, .. }
+            | PreRequestStep::Base64Encode { variable, .. }
+            | PreRequestStep::HmacSha256 { variable, .. } => variable,
+        }
+    }
+}
+
+/// The part of a response an assertion checks
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum AssertionTarget {
+    Status,
+    Header,
+    JsonPath,
+    ResponseTime, // total time in milliseconds
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum AssertionOperator {
+    Equals,
+    NotEquals,
+    Contains,
+    LessThan,
+    GreaterThan,
+    Exists,
+}
+
+/// A check on a response, e.g. "header Content-Type contains json"
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Assertion {
+    pub target: AssertionTarget,
+    pub property: Option<String>, // header name or JSONPath; unused for status and response time
+    pub operator: AssertionOperator,
+    #[serde(default)]
+    pub expected: String, // ignored by `exists`
+}
+
+/// A saved response documenting what a request is expected to return
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct RequestExample {
+    pub id: String,
+    pub request_id: String,
+    pub name: String,
+    pub status: u16,
+    pub headers: String, // JSON string of headers
+    pub body: String,
+    pub volatile_fields: String, // JSON array of field paths/names ignored when comparing
+    pub created_at: DateTime<Utc>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CreateCollectionRequest {
     pub workspace_id: String,
@@ -43,6 +151,8 @@ pub struct CreateCollectionRequest {
     pub description: Option<String>,
     pub folder_path: Option<String>,
     pub git_branch: Option<String>,
+    #[serde(default)]
+    pub parent_id: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -52,6 +162,8 @@ pub struct UpdateCollectionRequest {
     pub description: Option<String>,
     pub folder_path: Option<String>,
     pub git_branch: Option<String>,
+    #[serde(default)]
+    pub parent_id: Option<String>, // moving back to the top level is done with set_collection_parent
     pub is_active: Option<bool>,
 }
 
@@ -90,6 +202,43 @@ pub struct UpdateRequestRequest {
     pub order_index: Option<i32>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateRequestExampleRequest {
+    pub request_id: String,
+    pub name: String,
+    pub status: u16,
+    pub headers: Option<serde_json::Value>,
+    pub body: String,
+    pub volatile_fields: Option<Vec<String>>,
+}
+
+/// Outcome of importing a batch of curl commands; bad commands don't stop the rest
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CurlImportResult {
+    pub requests: Vec<Request>,
+    pub errors: Vec<CurlImportError>,
+}
+
+/// A request matching a workspace search, with the text around the first match
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RequestSearchResult {
+    pub request_id: String,
+    pub name: String,
+    pub method: String,
+    pub url: String,
+    pub collection_id: String,
+    pub collection_name: String,
+    pub matched_field: String, // name, url, description, headers or body
+    pub snippet: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CurlImportError {
+    pub index: usize, // position in the submitted list
+    pub command: String,
+    pub message: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CollectionSummary {
     pub id: String,
@@ -98,6 +247,7 @@ pub struct CollectionSummary {
     pub description: Option<String>,
     pub folder_path: Option<String>,
     pub git_branch: Option<String>,
+    pub parent_id: Option<String>,
     pub is_active: bool,
     pub request_count: i64,
     pub created_at: DateTime<Utc>,
@@ -114,6 +264,7 @@ impl Collection {
             description: request.description,
             folder_path: request.folder_path,
             git_branch: request.git_branch,
+            parent_id: request.parent_id,
             is_active: false,
             created_at: now,
             updated_at: now,
@@ -133,6 +284,9 @@ impl Collection {
         if let Some(git_branch) = request.git_branch {
             self.git_branch = Some(git_branch);
         }
+        if let Some(parent_id) = request.parent_id {
+            self.parent_id = Some(parent_id);
+        }
         if let Some(is_active) = request.is_active {
             self.is_active = is_active;
         }
@@ -167,6 +321,13 @@ impl Request {
             follow_redirects: request.follow_redirects.unwrap_or(true),
             timeout_ms: request.timeout_ms.unwrap_or(30000),
             order_index: request.order_index.unwrap_or(0),
+            include_in_run: true,
+            raw_stream: false,
+            connect_timeout_ms: None,
+            run_condition: None,
+            pre_request: "[]".to_string(),
+            extractions: "[]".to_string(),
+            assertions: "[]".to_string(),
             created_at: now,
             updated_at: now,
         }
@@ -224,6 +385,21 @@ impl Request {
         }
     }
 
+    /// Parse the extraction rules from their JSON string
+    pub fn get_extractions(&self) -> Vec<Extraction> {
+        serde_json::from_str(&self.extractions).unwrap_or_default()
+    }
+
+    /// Parse the pre-request steps from their JSON string
+    pub fn get_pre_request(&self) -> Vec<PreRequestStep> {
+        serde_json::from_str(&self.pre_request).unwrap_or_default()
+    }
+
+    /// Parse the assertion rules from their JSON string
+    pub fn get_assertions(&self) -> Vec<Assertion> {
+        serde_json::from_str(&self.assertions).unwrap_or_default()
+    }
+
     /// Parse auth config from JSON string
     pub fn get_auth_config(&self) -> Result<Option<serde_json::Value>, serde_json::Error> {
         match &self.auth_config {
@@ -231,4 +407,175 @@ impl Request {
             None => Ok(None),
         }
     }
-}
\ No newline at end of file
+
+    /// Convert a stored request into an executable HttpRequest
+    pub fn to_http_request(&self) -> HttpRequest {
+        let headers: HashMap<String, String> = self.get_headers()
+            .ok()
+            .and_then(|h| h.as_object().cloned())
+            .map(|h| {
+                h.into_iter()
+                    .map(|(k, v)| (k, v.as_str().map(|s| s.to_string()).unwrap_or_else(|| v.to_string())))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let body = self.body.as_deref()
+            .filter(|b| !b.trim().is_empty())
+            .and_then(|b| self.parse_body(b, &headers));
+
+        HttpRequest {
+            id: self.id.clone(),
+            name: self.name.clone(),
+            method: HttpMethod::from(self.method.as_str()),
+            url: self.url.clone(),
+            headers,
+            body,
+            timeout_ms: Some(self.timeout_ms as u64),
+            follow_redirects: self.follow_redirects,
+            auth_type: self.auth_type.clone(),
+            auth_config: self.get_auth_config().ok().flatten(),
+            raw_stream: self.raw_stream,
+            connect_timeout_ms: self.connect_timeout_ms.map(u64::from),
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+        }
+    }
+
+    fn parse_body(&self, body: &str, headers: &HashMap<String, String>) -> Option<RequestBody> {
+        let content_type = headers.iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case("content-type"))
+            .map(|(_, v)| v.clone());
+
+        match self.body_type.as_str() {
+            "none" => None,
+            "json" => match serde_json::from_str(body) {
+                Ok(data) => Some(RequestBody::Json { data }),
+                // Keep bodies with placeholders like {{ID}} outside strings intact
+                Err(_) => Some(RequestBody::Raw {
+                    content: body.to_string(),
+                    content_type: content_type.unwrap_or_else(|| "application/json".to_string()),
+                }),
+            },
+            // Stored as {"query": ..., "variables": ...}; a bare query is accepted too
+            "graphql" => {
+                let payload = serde_json::from_str::<serde_json::Value>(body).ok()
+                    .filter(|p| p.get("query").and_then(|q| q.as_str()).is_some());
+                Some(match payload {
+                    Some(payload) => RequestBody::GraphQl {
+                        query: payload["query"].as_str().unwrap_or_default().to_string(),
+                        variables: payload.get("variables").cloned().unwrap_or(serde_json::json!({})),
+                    },
+                    None => RequestBody::GraphQl {
+                        query: body.to_string(),
+                        variables: serde_json::json!({}),
+                    },
+                })
+            }
+            "form" | "urlencoded" => {
+                let fields = url::form_urlencoded::parse(body.as_bytes())
+                    .into_owned()
+                    .collect();
+                Some(RequestBody::FormUrlEncoded { fields })
+            }
+            // Stored as a map of fields, or as {"fields": ..., "files": [...]} once files are attached
+            "form-data" | "multipart" => {
+                let form = serde_json::from_str::<HashMap<String, String>>(body)
+                    .map(|fields| StoredFormData { fields, files: Vec::new() })
+                    .or_else(|_| serde_json::from_str::<StoredFormData>(body))
+                    .unwrap_or_else(|_| StoredFormData {
+                        fields: url::form_urlencoded::parse(body.as_bytes()).into_owned().collect(),
+                        files: Vec::new(),
+                    });
+                Some(RequestBody::FormData { fields: form.fields, files: form.files })
+            }
+            _ => Some(RequestBody::Raw {
+                content: body.to_string(),
+                content_type: content_type.unwrap_or_else(|| "text/plain".to_string()),
+            }),
+        }
+    }
+}
+
+/// How a form-data body with files is kept in a request's `body` column
+#[derive(Serialize, Deserialize)]
+struct StoredFormData {
+    fields: HashMap<String, String>,
+    files: Vec<FormFile>,
+}
+
+impl CreateRequestRequest {
+    /// Build a stored request from an executable one (the inverse of `Request::to_http_request`)
+    pub fn from_http_request(collection_id: &str, name: &str, request: &HttpRequest, order_index: i32) -> Self {
+        let (body, body_type) = match &request.body {
+            None | Some(RequestBody::None) => (None, "none"),
+            Some(RequestBody::Json { data }) => (Some(data.to_string()), "json"),
+            Some(RequestBody::Raw { content, .. }) => (Some(content.clone()), "raw"),
+            Some(RequestBody::FormUrlEncoded { fields }) => {
+                let encoded = url::form_urlencoded::Serializer::new(String::new())
+                    .extend_pairs(fields.iter())
+                    .finish();
+                (Some(encoded), "form")
+            }
+            Some(RequestBody::FormData { fields, files }) if files.is_empty() => (serde_json::to_string(fields).ok(), "form-data"),
+            Some(RequestBody::FormData { fields, files }) => {
+                let form = StoredFormData { fields: fields.clone(), files: files.clone() };
+                (serde_json::to_string(&form).ok(), "form-data")
+            }
+            Some(RequestBody::Binary { data, .. }) => (Some(String::from_utf8_lossy(data).to_string()), "raw"),
+            Some(RequestBody::GraphQl { query, variables }) => {
+                (Some(RequestBody::graphql_payload(query, variables).to_string()), "graphql")
+            }
+        };
+
+        // Raw bodies carry their content type in the headers once stored
+        let mut headers = request.headers.clone();
+        if let Some(RequestBody::Raw { content_type, .. } | RequestBody::Binary { content_type, .. }) = &request.body {
+            if !headers.keys().any(|k| k.eq_ignore_ascii_case("content-type")) {
+                headers.insert("Content-Type".to_string(), content_type.clone());
+            }
+        }
+
+        Self {
+            collection_id: collection_id.to_string(),
+            name: name.to_string(),
+            description: None,
+            method: request.method.as_str().to_string(),
+            url: request.url.clone(),
+            headers: serde_json::to_value(headers).ok(),
+            body,
+            body_type: Some(body_type.to_string()),
+            auth_type: request.auth_type.clone(),
+            auth_config: request.auth_config.clone(),
+            follow_redirects: Some(request.follow_redirects),
+            timeout_ms: request.timeout_ms.map(|t| t as u32),
+            order_index: Some(order_index),
+        }
+    }
+}
+
+impl RequestExample {
+    pub fn new(request: CreateRequestExampleRequest) -> Self {
+        let headers = request.headers
+            .map(|h| serde_json::to_string(&h).unwrap_or_default())
+            .unwrap_or_else(|| "{}".to_string());
+        let volatile_fields = serde_json::to_string(&request.volatile_fields.unwrap_or_default())
+            .unwrap_or_else(|_| "[]".to_string());
+
+        Self {
+            id: Uuid::new_v4().to_string(),
+            request_id: request.request_id,
+            name: request.name,
+            status: request.status,
+            headers,
+            body: request.body,
+            volatile_fields,
+            created_at: Utc::now(),
+        }
+    }
+
+    /// Parse the configured volatile fields from their JSON string
+    pub fn get_volatile_fields(&self) -> Vec<String> {
+        serde_json::from_str(&self.volatile_fields).unwrap_or_default()
+    }
+}
@@ -0,0 +1,77 @@
+use crate::models::collection::Assertion;
+use crate::models::http::HttpResponse;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum RunStatus {
+    Passed,
+    Failed,
+    Skipped,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RequestRunResult {
+    pub request_id: String,
+    pub name: String,
+    pub status: RunStatus,
+    pub response: Option<HttpResponse>,
+    pub error: Option<String>,
+    pub skip_reason: Option<String>,
+    pub duration_ms: u64, // zero for skipped requests
+    pub assertions: Vec<AssertionResult>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AssertionResult {
+    pub assertion: Assertion,
+    pub passed: bool,
+    pub actual: Option<String>, // None when the header or JSONPath is missing
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CollectionRunResult {
+    pub collection_id: String,
+    pub results: Vec<RequestRunResult>, // in execution order
+    pub executed: usize,
+    pub skipped: usize,
+    pub failed: usize,
+    pub total_time_ms: u64, // wall time, waits included
+    pub pacing_wait_ms: u64, // spent holding requests back to keep to the pacing
+    pub throttle_events: Vec<ThrottleEvent>, // 429s that were waited out and resent
+    pub sequential_reason: Option<String>, // why a run asked to go in parallel went one at a time
+}
+
+/// Spacing between the sends of a collection run, for rate-limited APIs. When both are set
+/// the longer spacing wins.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct RunPacing {
+    pub requests_per_second: Option<f64>,
+    pub delay_between_requests_ms: Option<u64>,
+}
+
+impl RunPacing {
+    /// The minimum time between two sends, if any
+    pub fn interval(&self) -> Option<Duration> {
+        let from_rate = self.requests_per_second
+            .filter(|rate| rate.is_finite() && *rate > 0.0)
+            .map(|rate| Duration::from_secs_f64(1.0 / rate));
+        let from_delay = self.delay_between_requests_ms.filter(|ms| *ms > 0).map(Duration::from_millis);
+        from_rate.max(from_delay)
+    }
+}
+
+/// A request answered with 429 and a Retry-After, which the run waited out before resending
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ThrottleEvent {
+    pub request_id: String,
+    pub name: String,
+    pub waited_ms: u64,
+}
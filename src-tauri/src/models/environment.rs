@@ -9,6 +9,10 @@ pub struct Environment {
     pub name: String,
     pub variables: HashMap<String, EnvironmentVariable>,
     pub is_active: bool,
+    #[serde(default)]
+    pub is_protected: bool, // mutating requests need confirmation while this environment is active
+    #[serde(default)]
+    pub parent_id: Option<String>, // inherits the parent's variables, overriding any it redefines
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -27,6 +31,72 @@ pub struct EnvironmentVariable {
 pub enum VariableType {
     String,
     Secret,
+    Number,
+    Boolean,
+    Url,
+}
+
+/// How `set_variables` combines the given variables with an environment's existing ones
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum MergeMode {
+    Replace, // the given variables become the whole set
+    Merge,   // upsert by key, leaving other variables alone
+}
+
+/// A level variables can be defined at besides an environment. When names clash, request
+/// variables win over collection ones, then the environment's, then global ones.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum VariableScope {
+    Global, // owned by a workspace
+    Collection,
+    Request,
+}
+
+/// Text with its variables substituted, plus where each substitution landed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubstitutionResult {
+    pub output: String,
+    pub spans: Vec<SubstitutionSpan>,
+}
+
+/// A substituted region of the output, in UTF-16 code units as JavaScript indexes strings
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SubstitutionSpan {
+    pub start: usize,
+    pub end: usize, // exclusive
+    pub variable_name: String,
+    pub was_secret: bool,
+}
+
+/// The part of a request a variable placeholder appears in
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum RequestField {
+    Url,
+    Headers,
+    Body,
+    Auth,
+}
+
+/// A request that references a variable, and where
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct VariableUsage {
+    pub request_id: String,
+    pub request_name: String,
+    pub fields: Vec<RequestField>,
+}
+
+/// A placeholder that no variable in scope defines
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct UndefinedVariable {
+    pub name: String,
+    pub usages: Vec<VariableUsage>,
 }
 
 
@@ -40,6 +110,8 @@ impl Default for Environment {
             name: "Development".to_string(),
             variables: HashMap::new(),
             is_active: true,
+            is_protected: false,
+            parent_id: None,
             created_at: now,
             updated_at: now,
         }
@@ -58,10 +130,28 @@ impl Default for EnvironmentVariable {
 }
 
 impl VariableType {
+    /// Values containing `{{VAR}}` placeholders pass the number, boolean and URL checks, since
+    /// they can only be judged once substituted
     pub fn validate_value(&self, value: &str) -> bool {
+        let value = value.trim();
         match self {
             VariableType::String => true,
-            VariableType::Secret => !value.trim().is_empty(),
+            VariableType::Secret => !value.is_empty(),
+            _ if value.contains("{{") => true,
+            VariableType::Number => value.parse::<f64>().is_ok_and(f64::is_finite),
+            VariableType::Boolean => value.eq_ignore_ascii_case("true") || value.eq_ignore_ascii_case("false"),
+            VariableType::Url => url::Url::parse(value).is_ok(),
+        }
+    }
+
+    /// What `validate_value` expects, for error messages
+    pub fn requirement(&self) -> &'static str {
+        match self {
+            VariableType::String => "any text",
+            VariableType::Secret => "a non-empty value",
+            VariableType::Number => "a number",
+            VariableType::Boolean => "true or false",
+            VariableType::Url => "an absolute URL",
         }
     }
 
@@ -69,12 +159,18 @@ impl VariableType {
         match self {
             VariableType::String => "string",
             VariableType::Secret => "secret",
+            VariableType::Number => "number",
+            VariableType::Boolean => "boolean",
+            VariableType::Url => "url",
         }
     }
 
     pub fn from_str(s: &str) -> Self {
         match s {
             "secret" => VariableType::Secret,
+            "number" => VariableType::Number,
+            "boolean" => VariableType::Boolean,
+            "url" => VariableType::Url,
             _ => VariableType::String,
         }
     }
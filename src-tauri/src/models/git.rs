@@ -9,11 +9,57 @@ pub struct GitCredentials {
     pub ssh_key_path: Option<String>,
 }
 
+/// A host with saved credentials, without the secret
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CredentialHost {
+    pub host: String,
+    pub username: String,
+}
+
+/// Name and email recorded as a commit's author and committer
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CommitAuthor {
+    pub name: String,
+    pub email: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CloneResult {
     pub success: bool,
     pub path: String,
     pub message: String,
+    #[serde(default)]
+    pub unknown_host: Option<UnknownHostKey>, // set when the remote's SSH host key isn't in known_hosts
+}
+
+/// How far a clone has got, sent as the `git-clone-progress:<cloneId>` event
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CloneProgress {
+    pub received_objects: usize,
+    pub indexed_objects: usize,
+    pub total_objects: usize,
+    pub received_bytes: usize,
+}
+
+/// SSH host key presented by a host missing from known_hosts, for a "trust this host?" prompt
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct UnknownHostKey {
+    pub host: String,
+    pub port: Option<u16>,
+    pub key_type: String,
+    pub key: String, // base64, as written to known_hosts
+    pub fingerprint: String, // SHA256:...
+}
+
+/// Outcome of connecting to a remote without fetching anything
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RemoteAccess {
+    pub reachable: bool,
+    pub authenticated: bool,
+    pub default_branch: Option<String>,
+    pub message: String,
+    #[serde(default)]
+    pub unknown_host: Option<UnknownHostKey>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -25,6 +71,8 @@ pub struct GitStatus {
     pub untracked_files: Vec<String>,
     pub ahead: usize,
     pub behind: usize,
+    #[serde(default)]
+    pub has_upstream: bool, // false when the branch doesn't track a remote branch
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -41,8 +89,18 @@ pub struct GitCommit {
     pub hash: String,
     pub message: String,
     pub author: String,
-    pub date: String,
-    pub files_changed: usize,
+    pub date: String, // RFC 3339
+    pub files_changed: usize, // compared with the first parent
+}
+
+/// The commit HEAD is on. The commit fields are None on a branch with no commits yet
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HeadInfo {
+    pub hash: Option<String>,
+    pub short_hash: Option<String>,
+    pub message: Option<String>,
+    pub author: Option<String>,
+    pub tags: Vec<String>, // lightweight and annotated tags pointing at the commit
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -50,7 +108,51 @@ pub struct GitRemote {
     pub name: String,
     pub url: String,
     pub fetch_url: String,
-    pub push_url: String,
+    pub push_url: String, // the fetch URL unless a separate push URL is configured
+}
+
+/// A file left conflicted by a merge. A side is None when it deleted the file
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Conflict {
+    pub path: String,
+    pub ours: Option<String>,
+    pub theirs: Option<String>,
+    pub base: Option<String>,
+}
+
+/// Outcome of stashing or popping local changes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StashResult {
+    pub success: bool,
+    pub stash_oid: Option<String>, // the stash commit that was created or applied
+    pub message: String,
+}
+
+/// Uncommitted changes to one file
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FileDiff {
+    pub path: String,
+    pub status: FileDiffStatus,
+    pub staged: bool, // a file with both kinds of change is listed once for each
+    pub hunks: Vec<String>, // unified diff text, each starting with its @@ header; empty for binary files
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum FileDiffStatus {
+    Added,
+    Deleted,
+    Modified,
+    Renamed,
+}
+
+/// How to settle a conflicted file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", content = "content", rename_all = "snake_case")]
+pub enum ConflictResolution {
+    Ours,
+    Theirs,
+    Content(String),
 }
 
 /// Branch naming pattern for automatic branch generation
@@ -139,8 +241,10 @@ pub struct GitBranch {
     pub last_commit_hash: Option<String>,
     pub last_commit_message: Option<String>,
     pub last_commit_date: Option<DateTime<Utc>>,
-    pub ahead_count: Option<i32>,
+    pub ahead_count: Option<i32>, // None when there's no upstream to compare with
     pub behind_count: Option<i32>,
+    #[serde(default)]
+    pub upstream_gone: bool, // the tracked upstream branch was deleted
 }
 
 /// Branch creation request
@@ -160,6 +264,23 @@ pub struct BranchCreateResult {
     pub message: String,
 }
 
+/// Branch deletion result
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BranchDeleteResult {
+    pub branch_name: String,
+    pub deleted: bool,
+    pub refusal: Option<BranchDeleteRefusal>, // why the branch was kept, when it was
+    pub message: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BranchDeleteRefusal {
+    NotFound,
+    CurrentBranch,
+    Unmerged, // has commits not merged into HEAD; delete with force to discard them
+}
+
 /// Branch generator for creating standardized branch names
 #[derive(Clone)]
 pub struct BranchGenerator {
@@ -216,7 +337,7 @@ impl BranchGenerator {
     }
     
     /// Validate that a branch name follows Git naming conventions
-    fn validate_branch_name(&self, name: &str) -> Result<(), String> {
+    pub fn validate_branch_name(&self, name: &str) -> Result<(), String> {
         if name.is_empty() {
             return Err("Branch name cannot be empty".to_string());
         }
@@ -1,3 +1,4 @@
+use base64::prelude::*;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use chrono::{DateTime, Utc};
@@ -13,12 +14,18 @@ pub struct HttpRequest {
     pub body: Option<RequestBody>,
     pub timeout_ms: Option<u64>,
     pub follow_redirects: bool,
+    pub auth_type: Option<String>, // bearer, basic, api_key, oauth2_client_credentials
+    pub auth_config: Option<serde_json::Value>,
+    #[serde(default)]
+    pub raw_stream: bool, // return the body as raw text, read as a stream, without JSON parsing
+    #[serde(default)]
+    pub connect_timeout_ms: Option<u64>, // overrides ExecuteOptions::connect_timeout_ms
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
-#[serde(rename_all = "UPPERCASE")]
+/// Serialized as the method name, so extension methods such as PROPFIND travel as plain strings
+#[derive(Debug, Clone, PartialEq)]
 pub enum HttpMethod {
     Get,
     Post,
@@ -27,17 +34,176 @@ pub enum HttpMethod {
     Patch,
     Head,
     Options,
+    Custom(String), // any other valid method token, e.g. WebDAV's PROPFIND
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(tag = "type", rename_all = "camelCase")]
 pub enum RequestBody {
     None,
     Raw { content: String, content_type: String },
     Json { data: serde_json::Value },
-    FormData { fields: HashMap<String, String> },
+    FormData {
+        fields: HashMap<String, String>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        files: Vec<FormFile>,
+    },
     FormUrlEncoded { fields: HashMap<String, String> },
     Binary { data: Vec<u8>, content_type: String },
+    #[serde(rename = "graphql")]
+    GraphQl { query: String, variables: serde_json::Value },
+}
+
+impl RequestBody {
+    /// The JSON document a GraphQL body is sent as
+    pub fn graphql_payload(query: &str, variables: &serde_json::Value) -> serde_json::Value {
+        serde_json::json!({ "query": query, "variables": variables })
+    }
+}
+
+/// A file sent as one part of a multipart form
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FormFile {
+    pub field: String,
+    pub path: String, // may contain {{VAR}} placeholders
+    pub filename: Option<String>, // defaults to the path's file name
+    pub content_type: Option<String>, // defaults to a guess from the extension
+}
+
+/// A problem with a request found before it is sent
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidationIssue {
+    pub field: String, // e.g. "url" or "authConfig.token"
+    pub message: String,
+}
+
+impl ValidationIssue {
+    pub fn new(field: &str, message: &str) -> Self {
+        Self {
+            field: field.to_string(),
+            message: message.to_string(),
+        }
+    }
+}
+
+/// A URL split up for editing. Parts keep any `{{VAR}}` placeholders as written.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct UrlComponents {
+    pub scheme: String, // empty when the URL starts with a placeholder such as {{BASE_URL}}
+    pub userinfo: Option<String>,
+    pub host: String,
+    pub port: Option<String>, // a number or a placeholder
+    pub path: String,
+    pub query: Vec<QueryParam>, // in order, repeats included
+    pub fragment: Option<String>,
+}
+
+/// One `key=value` pair of a query string, decoded
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct QueryParam {
+    pub key: String,
+    pub value: Option<String>, // None for a bare `key` without `=`
+}
+
+/// Language a request can be exported to as a code snippet
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum CodeTarget {
+    Curl,
+    JavaScriptFetch,
+    PythonRequests,
+}
+
+/// Where a resolved auth credential is placed on the outgoing request
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum AuthPlacement {
+    Header { name: String, value: String },
+    Query { name: String, value: String },
+}
+
+/// A cookie held in a workspace's cookie jar
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CookieInfo {
+    pub name: String,
+    pub value: String,
+    pub domain: String,
+    pub path: String,
+}
+
+/// An event received from a `text/event-stream` response
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SseEvent {
+    pub request_id: String,
+    pub event: String, // "message" when the server didn't name it
+    pub data: String, // multi-line data is joined with newlines
+    pub id: Option<String>,
+}
+
+/// Per-call options for executing a request
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ExecuteOptions {
+    pub preview_bytes: Option<usize>, // bodies larger than this are truncated
+    pub workspace_id: Option<String>, // selects the cookie jar and send throttle
+    pub environment_id: Option<String>, // where the variables come from; the workspace's active one when unset
+    pub max_response_bytes: Option<u64>, // bodies larger than this fail with ResponseTooLarge
+    pub proxy: Option<ProxyConfig>, // send through this proxy instead of connecting directly
+    pub tls: Option<TlsConfig>, // client certificate and trusted CAs
+    pub connect_timeout_ms: Option<u64>, // limit on establishing a connection, within the total timeout
+    pub retry: Option<RetryPolicy>, // resend on flaky statuses or network errors
+    pub force_token_refresh: bool, // fetch a new OAuth2 token even when the cached one is still valid
+    pub default_headers: HashMap<String, String>, // sent unless the request sets the same header
+}
+
+/// When a failed send is retried and how long to wait in between
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct RetryPolicy {
+    pub max_attempts: u32, // including the first send
+    pub retry_on_status: Vec<u16>,
+    pub retry_on_network_error: bool, // connection failures and timeouts
+    pub initial_backoff_ms: u64, // doubled after each attempt, with jitter
+    pub max_backoff_ms: u64, // also caps Retry-After
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            retry_on_status: vec![429, 502, 503, 504],
+            retry_on_network_error: true,
+            initial_backoff_ms: 500,
+            max_backoff_ms: 30_000,
+        }
+    }
+}
+
+/// An HTTP(S) proxy and the hosts that bypass it
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ProxyConfig {
+    pub url: String,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    pub no_proxy: Option<String>, // comma-separated hosts, domains or CIDR ranges
+}
+
+/// Client certificate and certificate trust for mutual TLS or internal CAs
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct TlsConfig {
+    pub client_cert_path: Option<String>, // PKCS#12 bundle, or a PEM certificate with client_key_path
+    pub client_key_path: Option<String>, // PKCS#8 PEM key for a PEM certificate
+    pub client_cert_password: Option<String>, // PKCS#12 passphrase
+    pub ca_bundle_path: Option<String>, // PEM certificates trusted alongside the system roots
+    pub accept_invalid_certs: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -49,18 +215,157 @@ pub struct HttpResponse {
     pub body: ResponseBody,
     pub timing: ResponseTiming,
     pub request_id: String,
+    pub final_url: String, // URL after following any redirects
+    pub truncated: bool, // body holds only a preview of the payload
+    pub full_size: usize, // size of the complete payload in bytes
+    #[serde(default)]
+    pub size: ResponseSize, // bytes on the wire and after undoing Content-Encoding
+    #[serde(default)]
+    pub streamed: bool, // body was read as a stream and kept as raw text; partial when also truncated
+    #[serde(default = "default_attempts")]
+    pub attempts: u32, // sends made, including retries
+    #[serde(default)]
+    pub redirects: Vec<RedirectHop>, // redirects followed to reach final_url, in order
     pub timestamp: DateTime<Utc>,
 }
 
+fn default_attempts() -> u32 {
+    1
+}
+
+/// One execution in a workspace's request history. Status, timing and size are missing
+/// when the request failed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RequestHistoryEntry {
+    pub id: i64,
+    pub workspace_id: String,
+    pub request_id: String,
+    pub method: String,
+    pub url: String, // as written, with {{VAR}} placeholders
+    pub status: Option<u16>,
+    pub total_time_ms: Option<u64>,
+    pub response_size: Option<u64>, // bytes in the complete payload
+    pub error: Option<String>,
+    pub executed_at: DateTime<Utc>,
+}
+
+/// A body's size as transferred and once decompressed. The two only differ when the
+/// server applied a Content-Encoding.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResponseSize {
+    pub transfer_bytes: usize,
+    pub decoded_bytes: usize,
+}
+
+/// A redirect response that was followed
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RedirectHop {
+    pub status: u16,
+    pub url: String, // the URL that answered with the redirect
+    pub location: String, // its Location header, as sent
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "camelCase")]
 pub enum ResponseBody {
-    Text { content: String },
+    Text {
+        content: String,
+        #[serde(rename = "contentSubtype", default)]
+        content_subtype: TextSubtype,
+    },
     Json { data: serde_json::Value },
-    Binary { data: Vec<u8>, size: usize },
+    /// Raw bytes travel base64-encoded; the MIME type and preview let the UI describe the body
+    /// without decoding it
+    Binary {
+        #[serde(rename = "dataBase64")]
+        data_base64: String,
+        size: usize,
+        #[serde(rename = "mimeType")]
+        mime_type: String,
+        #[serde(rename = "previewHex")]
+        preview_hex: String, // first bytes, space separated
+    },
     Empty,
 }
 
+// Bytes shown in a binary body's hex preview
+const BINARY_PREVIEW_BYTES: usize = 16;
+
+impl ResponseBody {
+    /// A binary body, typed by its leading bytes when they're recognised and by the
+    /// Content-Type otherwise
+    pub fn binary(bytes: &[u8], content_type: &str) -> Self {
+        let declared = content_type.split(';').next().unwrap_or_default().trim();
+        let mime_type = sniff_mime_type(bytes)
+            .map(str::to_string)
+            .unwrap_or_else(|| if declared.is_empty() { "application/octet-stream".to_string() } else { declared.to_lowercase() });
+        ResponseBody::Binary {
+            data_base64: BASE64_STANDARD.encode(bytes),
+            size: bytes.len(),
+            mime_type,
+            preview_hex: bytes.iter()
+                .take(BINARY_PREVIEW_BYTES)
+                .map(|b| format!("{:02x}", b))
+                .collect::<Vec<_>>()
+                .join(" "),
+        }
+    }
+
+    /// The decoded bytes of a binary body
+    pub fn binary_bytes(&self) -> Option<Vec<u8>> {
+        match self {
+            ResponseBody::Binary { data_base64, .. } => BASE64_STANDARD.decode(data_base64).ok(),
+            _ => None,
+        }
+    }
+}
+
+fn sniff_mime_type(bytes: &[u8]) -> Option<&'static str> {
+    const SIGNATURES: &[(&[u8], &str)] = &[
+        (b"\x89PNG\r\n\x1a\n", "image/png"),
+        (b"\xff\xd8\xff", "image/jpeg"),
+        (b"GIF87a", "image/gif"),
+        (b"GIF89a", "image/gif"),
+        (b"%PDF-", "application/pdf"),
+        (b"PK\x03\x04", "application/zip"),
+        (b"\x1f\x8b", "application/gzip"),
+        (b"\0asm", "application/wasm"),
+    ];
+    if bytes.len() >= 12 && &bytes[..4] == b"RIFF" && &bytes[8..12] == b"WEBP" {
+        return Some("image/webp");
+    }
+    SIGNATURES.iter()
+        .find(|(magic, _)| bytes.starts_with(magic))
+        .map(|(_, mime)| *mime)
+}
+
+/// What kind of text a body holds, so it can be highlighted
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TextSubtype {
+    Xml,
+    Html,
+    Csv,
+    #[default]
+    Plain,
+}
+
+impl TextSubtype {
+    pub fn from_content_type(content_type: &str) -> Self {
+        let mime = content_type.split(';').next().unwrap_or_default().trim().to_lowercase();
+        match mime.as_str() {
+            "text/html" | "application/html" | "application/xhtml+xml" => TextSubtype::Html,
+            "text/xml" | "application/xml" => TextSubtype::Xml,
+            "text/csv" => TextSubtype::Csv,
+            _ if mime.ends_with("+xml") => TextSubtype::Xml,
+            _ => TextSubtype::Plain,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ResponseTiming {
@@ -70,6 +375,35 @@ pub struct ResponseTiming {
     pub tls_handshake_ms: Option<u64>,
     pub first_byte_ms: Option<u64>,
     pub download_ms: Option<u64>,
+    #[serde(default)]
+    pub connection_reused: Option<bool>, // None when the connection couldn't be identified
+}
+
+/// How `test_connection` probes a URL
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectionTestOptions {
+    #[serde(default)]
+    pub use_get: bool, // for servers that reject or hang on HEAD
+    #[serde(default)]
+    pub timeout_ms: Option<u64>,
+}
+
+/// What a server answered to a CORS preflight, and whether the browser would go on to send
+/// the request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CorsInspection {
+    pub origin: String,
+    pub status: u16,
+    pub allow_origin: Option<String>,
+    pub allow_methods: Vec<String>,
+    pub allow_headers: Vec<String>,
+    pub allow_credentials: bool,
+    pub expose_headers: Vec<String>,
+    pub max_age: Option<u64>, // seconds the preflight may be cached
+    pub allowed: bool,
+    pub problems: Vec<String>, // why the request would be blocked; empty when allowed
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -81,7 +415,7 @@ pub struct HttpError {
     pub timestamp: DateTime<Utc>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub enum HttpErrorType {
     NetworkError,
@@ -90,6 +424,8 @@ pub enum HttpErrorType {
     InvalidUrl,
     InvalidRequest,
     InvalidResponse,
+    ResponseTooLarge,
+    Cancelled,
     UnknownError,
 }
 
@@ -106,10 +442,12 @@ pub struct ExecuteRequestResponse {
     pub response: Option<HttpResponse>,
     pub error: Option<HttpError>,
     pub request_id: String,
+    #[serde(default)]
+    pub requires_confirmation: bool, // nothing was sent; resend with confirm to go ahead
 }
 
 impl HttpMethod {
-    pub fn as_str(&self) -> &'static str {
+    pub fn as_str(&self) -> &str {
         match self {
             HttpMethod::Get => "GET",
             HttpMethod::Post => "POST",
@@ -118,13 +456,23 @@ impl HttpMethod {
             HttpMethod::Patch => "PATCH",
             HttpMethod::Head => "HEAD",
             HttpMethod::Options => "OPTIONS",
+            HttpMethod::Custom(method) => method,
         }
     }
-}
 
-impl From<&str> for HttpMethod {
-    fn from(method: &str) -> Self {
-        match method.to_uppercase().as_str() {
+    /// Whether the method can change state on the server; anything not known to be safe counts
+    pub fn is_mutating(&self) -> bool {
+        !matches!(self.as_str(), "GET" | "HEAD" | "OPTIONS" | "TRACE" | "PROPFIND")
+    }
+
+    /// Parse a method name, rejecting anything that isn't an RFC 9110 token
+    pub fn parse(method: &str) -> Result<Self, String> {
+        let is_tchar = |c: char| c.is_ascii_alphanumeric() || "!#$%&'*+-.^_`|~".contains(c);
+        if method.is_empty() || !method.chars().all(is_tchar) {
+            return Err(format!("Invalid HTTP method '{}'", method));
+        }
+
+        Ok(match method.to_uppercase().as_str() {
             "GET" => HttpMethod::Get,
             "POST" => HttpMethod::Post,
             "PUT" => HttpMethod::Put,
@@ -132,8 +480,27 @@ impl From<&str> for HttpMethod {
             "PATCH" => HttpMethod::Patch,
             "HEAD" => HttpMethod::Head,
             "OPTIONS" => HttpMethod::Options,
-            _ => HttpMethod::Get, // Default fallback
-        }
+            other => HttpMethod::Custom(other.to_string()),
+        })
+    }
+}
+
+impl From<&str> for HttpMethod {
+    fn from(method: &str) -> Self {
+        Self::parse(method).unwrap_or(HttpMethod::Get) // Default fallback
+    }
+}
+
+impl Serialize for HttpMethod {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for HttpMethod {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let method = String::deserialize(deserializer)?;
+        Self::parse(&method).map_err(serde::de::Error::custom)
     }
 }
 
@@ -149,6 +516,10 @@ impl Default for HttpRequest {
             body: None,
             timeout_ms: Some(30000), // 30 seconds default
             follow_redirects: true,
+            auth_type: None,
+            auth_config: None,
+            raw_stream: false,
+            connect_timeout_ms: None,
             created_at: now,
             updated_at: now,
         }
@@ -164,6 +535,7 @@ impl Default for ResponseTiming {
             tls_handshake_ms: None,
             first_byte_ms: None,
             download_ms: None,
+            connection_reused: None,
         }
     }
 }
\ No newline at end of file
@@ -1,5 +1,9 @@
 pub mod collection;
+pub mod collection_run;
 pub mod environment;
 pub mod git;
+pub mod har;
 pub mod http;
+pub mod response_diff;
+pub mod websocket;
 pub mod workspace;
\ No newline at end of file
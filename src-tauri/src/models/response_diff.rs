@@ -0,0 +1,52 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum DiffKind {
+    Added,
+    Removed,
+    Changed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FieldDiff {
+    pub path: String, // e.g. "user.tags[0]"
+    pub kind: DiffKind,
+    pub expected: Option<Value>,
+    pub actual: Option<Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResponseDiff {
+    pub expected_status: u16,
+    pub actual_status: u16,
+    pub differences: Vec<FieldDiff>,
+    pub ignored_fields: Vec<String>,
+    pub identical: bool, // same status and no body differences
+}
+
+/// A request's baseline response, kept for spotting regressions
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResponseSnapshot {
+    pub request_id: String,
+    pub status: u16,
+    pub headers: BTreeMap<String, String>, // only the headers chosen when saving, names lowercased
+    pub body: Value, // canonicalized, with object keys sorted
+    pub created_at: DateTime<Utc>,
+}
+
+/// A response compared with its request's snapshot
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotDiff {
+    pub snapshot_created_at: DateTime<Utc>,
+    pub body: ResponseDiff, // status and body
+    pub header_differences: Vec<FieldDiff>, // paths are the lowercased header names
+    pub identical: bool,
+}
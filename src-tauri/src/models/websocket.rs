@@ -0,0 +1,19 @@
+use serde::{Deserialize, Serialize};
+
+/// Something that happened on a WebSocket connection, delivered to the frontend as an event
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebSocketEvent {
+    pub connection_id: String,
+    pub frame: WebSocketFrame,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum WebSocketFrame {
+    Text { data: String },
+    Binary { data: Vec<u8> },
+    // Always the last event for a connection; code is None when it dropped without a close frame
+    Closed { code: Option<u16>, reason: String },
+    Error { message: String },
+}
@@ -1,8 +1,13 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
+use std::collections::HashMap;
 use uuid::Uuid;
 
+use crate::models::collection::{Collection, Request};
+use crate::models::environment::Environment;
+use crate::models::git::CommitAuthor;
+
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct Workspace {
     pub id: String,
@@ -10,6 +15,10 @@ pub struct Workspace {
     pub description: Option<String>,
     pub git_repository_url: Option<String>,
     pub local_path: String,
+    #[serde(default)]
+    pub git_username: Option<String>, // commit author for synced changes; repo config is used when unset
+    #[serde(default)]
+    pub git_email: Option<String>,
     pub is_active: bool,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
@@ -22,6 +31,10 @@ pub struct CreateWorkspaceRequest {
     pub description: Option<String>,
     pub git_repository_url: Option<String>,
     pub local_path: String,
+    #[serde(default)]
+    pub git_username: Option<String>,
+    #[serde(default)]
+    pub git_email: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -31,6 +44,10 @@ pub struct UpdateWorkspaceRequest {
     pub description: Option<String>,
     pub git_repository_url: Option<String>,
     pub local_path: Option<String>,
+    #[serde(default)]
+    pub git_username: Option<String>,
+    #[serde(default)]
+    pub git_email: Option<String>,
     pub is_active: Option<bool>,
 }
 
@@ -43,6 +60,32 @@ pub struct WorkspaceSettings {
     pub default_timeout: u32,
     pub follow_redirects: bool,
     pub verify_ssl: bool,
+    #[serde(default)]
+    pub min_request_interval_ms: u32, // minimum spacing between sends, 0 disables throttling
+    #[serde(default)]
+    pub max_response_bytes: u64, // largest body a request may download, 0 for no limit
+    #[serde(default)]
+    pub connect_timeout_ms: u32, // time allowed to establish a connection, 0 for no separate limit
+    #[serde(default)]
+    pub proxy_url: Option<String>, // e.g. http://proxy.corp:8080; requests connect directly when unset
+    #[serde(default)]
+    pub proxy_username: Option<String>,
+    #[serde(default)]
+    pub proxy_password: Option<String>,
+    #[serde(default)]
+    pub no_proxy: Option<String>, // comma-separated hosts that bypass the proxy
+    #[serde(default)]
+    pub client_cert_path: Option<String>, // PKCS#12 bundle, or a PEM certificate with client_key_path
+    #[serde(default)]
+    pub client_key_path: Option<String>,
+    #[serde(default)]
+    pub client_cert_password: Option<String>,
+    #[serde(default)]
+    pub ca_bundle_path: Option<String>, // PEM certificates trusted alongside the system roots
+    #[serde(default)]
+    pub user_agent: Option<String>, // sent instead of Postgirl's own when set
+    #[serde(default)]
+    pub default_headers: HashMap<String, String>, // added to every request that doesn't set them itself
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -60,6 +103,32 @@ pub struct WorkspaceSummary {
     pub request_count: i64,
 }
 
+/// A workspace packed into a single JSON file by `export_workspace`, to be recreated with
+/// fresh IDs by `import_workspace`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkspaceArchive {
+    pub format_version: u32,
+    pub exported_at: DateTime<Utc>,
+    pub name: String,
+    pub description: Option<String>,
+    pub settings: Option<WorkspaceSettings>,
+    pub collections: Vec<ArchivedCollection>,
+    pub environments: Vec<ArchivedEnvironment>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchivedCollection {
+    pub collection: Collection,
+    pub requests: Vec<Request>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchivedEnvironment {
+    pub environment: Environment,
+    #[serde(default)]
+    pub omitted_secrets: Vec<String>, // keys of secret variables left out of the export
+}
+
 impl Workspace {
     pub fn new(request: CreateWorkspaceRequest) -> Self {
         let now = Utc::now();
@@ -69,6 +138,8 @@ impl Workspace {
             description: request.description,
             git_repository_url: request.git_repository_url,
             local_path: request.local_path,
+            git_username: request.git_username,
+            git_email: request.git_email,
             is_active: false,
             created_at: now,
             updated_at: now,
@@ -89,12 +160,25 @@ impl Workspace {
         if let Some(local_path) = request.local_path {
             self.local_path = local_path;
         }
+        if let Some(git_username) = request.git_username {
+            self.git_username = Some(git_username);
+        }
+        if let Some(git_email) = request.git_email {
+            self.git_email = Some(git_email);
+        }
         if let Some(is_active) = request.is_active {
             self.is_active = is_active;
         }
         self.updated_at = Utc::now();
     }
 
+    /// The identity to commit synced changes as, when both name and email are set
+    pub fn commit_author(&self) -> Option<CommitAuthor> {
+        let name = self.git_username.as_deref().map(str::trim).filter(|name| !name.is_empty())?;
+        let email = self.git_email.as_deref().map(str::trim).filter(|email| !email.is_empty())?;
+        Some(CommitAuthor { name: name.to_string(), email: email.to_string() })
+    }
+
     pub fn access(&mut self) {
         self.last_accessed_at = Some(Utc::now());
         self.updated_at = Utc::now();
@@ -112,6 +196,19 @@ impl Default for WorkspaceSettings {
             default_timeout: 30000,
             follow_redirects: true,
             verify_ssl: true,
+            min_request_interval_ms: 0,
+            max_response_bytes: 0,
+            connect_timeout_ms: 0,
+            proxy_url: None,
+            proxy_username: None,
+            proxy_password: None,
+            no_proxy: None,
+            client_cert_path: None,
+            client_key_path: None,
+            client_cert_password: None,
+            ca_bundle_path: None,
+            user_agent: None,
+            default_headers: HashMap::new(),
             created_at: now,
             updated_at: now,
         }
@@ -0,0 +1,214 @@
+use crate::models::collection::{Assertion, AssertionOperator, AssertionTarget};
+use crate::models::collection_run::AssertionResult;
+use crate::models::http::HttpResponse;
+use crate::services::extraction_service::{body_value, parse_json_path, select, value_to_string};
+use anyhow::{anyhow, Result};
+
+#[derive(Debug, Clone, Default)]
+pub struct AssertionService;
+
+impl AssertionService {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Check each assertion against the response, in order
+    pub fn evaluate(&self, assertions: &[Assertion], response: &HttpResponse) -> Vec<AssertionResult> {
+        let body = body_value(&response.body);
+
+        assertions
+            .iter()
+            .map(|assertion| {
+                let actual = match assertion.target {
+                    AssertionTarget::Status => Some(response.status.to_string()),
+                    AssertionTarget::ResponseTime => Some(response.timing.total_time_ms.to_string()),
+                    AssertionTarget::Header => {
+                        let name = assertion.property.as_deref().unwrap_or_default();
+                        response
+                            .headers
+                            .iter()
+                            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+                            .map(|(_, value)| value.clone())
+                    }
+                    AssertionTarget::JsonPath => assertion
+                        .property
+                        .as_deref()
+                        .and_then(|path| parse_json_path(path).ok())
+                        .and_then(|segments| body.as_ref().and_then(|body| select(body, &segments)))
+                        .map(value_to_string),
+                };
+
+                let passed = actual
+                    .as_deref()
+                    .is_some_and(|actual| compare(&assertion.operator, actual, &assertion.expected));
+                AssertionResult {
+                    message: describe(assertion, actual.as_deref()),
+                    assertion: assertion.clone(),
+                    passed,
+                    actual,
+                }
+            })
+            .collect()
+    }
+}
+
+/// Reject assertions that could never be evaluated
+pub fn validate_assertion(assertion: &Assertion) -> Result<()> {
+    let property = assertion.property.as_deref().map(str::trim).unwrap_or_default();
+    match assertion.target {
+        AssertionTarget::Header if property.is_empty() => {
+            return Err(anyhow!("Header assertion is missing a header name"));
+        }
+        AssertionTarget::JsonPath => {
+            parse_json_path(property)?;
+        }
+        _ => {}
+    }
+
+    if matches!(assertion.operator, AssertionOperator::LessThan | AssertionOperator::GreaterThan)
+        && assertion.expected.trim().parse::<f64>().is_err()
+    {
+        return Err(anyhow!("Expected value '{}' is not a number", assertion.expected));
+    }
+    Ok(())
+}
+
+/// Equality compares numerically when both sides are numbers, so `200` matches `200.0`
+fn compare(operator: &AssertionOperator, actual: &str, expected: &str) -> bool {
+    let numbers = actual.trim().parse::<f64>().ok().zip(expected.trim().parse::<f64>().ok());
+    match operator {
+        AssertionOperator::Equals => numbers.map_or(actual == expected, |(a, e)| a == e),
+        AssertionOperator::NotEquals => numbers.map_or(actual != expected, |(a, e)| a != e),
+        AssertionOperator::Contains => actual.contains(expected),
+        AssertionOperator::LessThan => numbers.is_some_and(|(a, e)| a < e),
+        AssertionOperator::GreaterThan => numbers.is_some_and(|(a, e)| a > e),
+        AssertionOperator::Exists => true,
+    }
+}
+
+/// An actual-vs-expected description, e.g. "Expected status to equal 200, got 404"
+fn describe(assertion: &Assertion, actual: Option<&str>) -> String {
+    let property = assertion.property.as_deref().unwrap_or_default();
+    let subject = match assertion.target {
+        AssertionTarget::Status => "status".to_string(),
+        AssertionTarget::ResponseTime => "response time (ms)".to_string(),
+        AssertionTarget::Header => format!("header {}", property),
+        AssertionTarget::JsonPath => property.to_string(),
+    };
+    let expectation = match assertion.operator {
+        AssertionOperator::Equals => format!("equal {}", assertion.expected),
+        AssertionOperator::NotEquals => format!("not equal {}", assertion.expected),
+        AssertionOperator::Contains => format!("contain {}", assertion.expected),
+        AssertionOperator::LessThan => format!("be less than {}", assertion.expected),
+        AssertionOperator::GreaterThan => format!("be greater than {}", assertion.expected),
+        AssertionOperator::Exists => "exist".to_string(),
+    };
+
+    match actual {
+        Some(actual) => format!("Expected {} to {}, got {}", subject, expectation, actual),
+        None => format!("Expected {} to {}, but it was not found", subject, expectation),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::http::{ResponseBody, ResponseTiming};
+
+    fn response() -> HttpResponse {
+        HttpResponse {
+            status: 200,
+            status_text: "OK".to_string(),
+            headers: [("content-type".to_string(), "application/json; charset=utf-8".to_string())]
+                .into_iter()
+                .collect(),
+            body: ResponseBody::Json {
+                data: serde_json::json!({"ok": true, "count": 3, "user": {"name": "Ada"}}),
+            },
+            timing: ResponseTiming {
+                total_time_ms: 120,
+                dns_lookup_ms: None,
+                tcp_connect_ms: None,
+                tls_handshake_ms: None,
+                first_byte_ms: None,
+                download_ms: None,
+                connection_reused: None,
+            },
+            request_id: "request-1".to_string(),
+            final_url: "https://api.example.com/me".to_string(),
+            truncated: false,
+            full_size: 0,
+            size: Default::default(),
+            streamed: false,
+            attempts: 1,
+            redirects: Vec::new(),
+            timestamp: chrono::Utc::now(),
+        }
+    }
+
+    fn check(target: AssertionTarget, property: Option<&str>, operator: AssertionOperator, expected: &str) -> AssertionResult {
+        let assertion = Assertion {
+            target,
+            property: property.map(str::to_string),
+            operator,
+            expected: expected.to_string(),
+        };
+        AssertionService::new().evaluate(&[assertion], &response()).remove(0)
+    }
+
+    #[test]
+    fn test_equals_and_not_equals() {
+        assert!(check(AssertionTarget::Status, None, AssertionOperator::Equals, "200").passed);
+        assert!(check(AssertionTarget::JsonPath, Some("$.ok"), AssertionOperator::Equals, "true").passed);
+        assert!(check(AssertionTarget::JsonPath, Some("$.count"), AssertionOperator::Equals, "3.0").passed);
+
+        let failed = check(AssertionTarget::Status, None, AssertionOperator::Equals, "201");
+        assert!(!failed.passed);
+        assert_eq!(failed.actual.as_deref(), Some("200"));
+        assert_eq!(failed.message, "Expected status to equal 201, got 200");
+
+        assert!(check(AssertionTarget::Status, None, AssertionOperator::NotEquals, "500").passed);
+        assert!(!check(AssertionTarget::JsonPath, Some("$.user.name"), AssertionOperator::NotEquals, "Ada").passed);
+    }
+
+    #[test]
+    fn test_contains() {
+        assert!(check(AssertionTarget::Header, Some("Content-Type"), AssertionOperator::Contains, "json").passed);
+        assert!(!check(AssertionTarget::Header, Some("Content-Type"), AssertionOperator::Contains, "xml").passed);
+    }
+
+    #[test]
+    fn test_less_than_and_greater_than() {
+        assert!(check(AssertionTarget::ResponseTime, None, AssertionOperator::LessThan, "500").passed);
+        assert!(!check(AssertionTarget::ResponseTime, None, AssertionOperator::LessThan, "100").passed);
+        assert!(check(AssertionTarget::JsonPath, Some("$.count"), AssertionOperator::GreaterThan, "2").passed);
+        assert!(!check(AssertionTarget::Status, None, AssertionOperator::GreaterThan, "200").passed);
+        // Text never compares as a number
+        assert!(!check(AssertionTarget::JsonPath, Some("$.user.name"), AssertionOperator::GreaterThan, "1").passed);
+    }
+
+    #[test]
+    fn test_exists() {
+        assert!(check(AssertionTarget::Header, Some("content-type"), AssertionOperator::Exists, "").passed);
+
+        let missing = check(AssertionTarget::JsonPath, Some("$.user.email"), AssertionOperator::Exists, "");
+        assert!(!missing.passed);
+        assert_eq!(missing.actual, None);
+        assert_eq!(missing.message, "Expected $.user.email to exist, but it was not found");
+    }
+
+    #[test]
+    fn test_validate_assertion() {
+        let assertion = |target, property: Option<&str>, operator, expected: &str| Assertion {
+            target,
+            property: property.map(str::to_string),
+            operator,
+            expected: expected.to_string(),
+        };
+
+        assert!(validate_assertion(&assertion(AssertionTarget::Header, Some("X-Id"), AssertionOperator::Exists, "")).is_ok());
+        assert!(validate_assertion(&assertion(AssertionTarget::Header, None, AssertionOperator::Exists, "")).is_err());
+        assert!(validate_assertion(&assertion(AssertionTarget::JsonPath, Some("ok"), AssertionOperator::Equals, "true")).is_err());
+        assert!(validate_assertion(&assertion(AssertionTarget::ResponseTime, None, AssertionOperator::LessThan, "fast")).is_err());
+    }
+}
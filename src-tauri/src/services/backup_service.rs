@@ -0,0 +1,297 @@
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::aead::rand_core::RngCore;
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use anyhow::{anyhow, Result};
+use sha2::Sha256;
+use sqlx::{Row, SqlitePool};
+use std::path::{Path, PathBuf};
+
+// File layout: magic, version, PBKDF2 iterations (u32 BE), salt, nonce, ciphertext
+const MAGIC: &[u8; 4] = b"PGBK";
+const VERSION: u8 = 1;
+const PBKDF2_ITERATIONS: u32 = 210_000;
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+const HEADER_LEN: usize = MAGIC.len() + 1 + 4 + SALT_LEN + NONCE_LEN;
+
+/// Passphrase-encrypted snapshots of the whole database
+pub struct BackupService {
+    pool: SqlitePool,
+}
+
+impl BackupService {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    /// Write an AES-256-GCM encrypted copy of the database to `dest_path`.
+    /// Without `include_secrets`, secret environment variable values, proxy passwords, client
+    /// certificate passphrases and OAuth2 client secrets are blanked in the copy.
+    pub async fn export_encrypted(&self, dest_path: &Path, passphrase: &str, include_secrets: bool) -> Result<()> {
+        if passphrase.is_empty() {
+            return Err(anyhow!("A passphrase is required to encrypt the backup"));
+        }
+
+        let snapshot = TempFile::new();
+        sqlx::query("VACUUM INTO ?1")
+            .bind(snapshot.path.to_string_lossy().to_string())
+            .execute(&self.pool)
+            .await
+            .map_err(|e| anyhow!("Failed to snapshot database: {}", e))?;
+
+        if !include_secrets {
+            let snapshot_pool = SqlitePool::connect(&format!("sqlite://{}", snapshot.path.display())).await?;
+            let mut blanked = sqlx::query("UPDATE environment_variables SET value = '' WHERE is_secret = 1")
+                .execute(&snapshot_pool)
+                .await;
+            if blanked.is_ok() {
+                blanked = sqlx::query("UPDATE workspace_settings SET proxy_password = NULL, client_cert_password = NULL")
+                    .execute(&snapshot_pool)
+                    .await;
+            }
+            if blanked.is_ok() {
+                blanked = sqlx::query(
+                    "UPDATE requests SET auth_config = json_set(auth_config, '$.client_secret', '')
+                     WHERE auth_type = 'oauth2_client_credentials' AND json_valid(auth_config)",
+                )
+                .execute(&snapshot_pool)
+                .await;
+            }
+            snapshot_pool.close().await;
+            blanked.map_err(|e| anyhow!("Failed to strip secrets from backup: {}", e))?;
+        }
+
+        let plaintext = tokio::fs::read(&snapshot.path).await?;
+        let encrypted = encrypt(&plaintext, passphrase)?;
+        tokio::fs::write(dest_path, encrypted)
+            .await
+            .map_err(|e| anyhow!("Failed to write backup to {}: {}", dest_path.display(), e))?;
+        Ok(())
+    }
+
+    /// Replace the database contents with those of an encrypted backup. The restore runs in
+    /// a single transaction, so a failure leaves the current data untouched.
+    pub async fn import_encrypted(&self, src_path: &Path, passphrase: &str) -> Result<()> {
+        let encrypted = tokio::fs::read(src_path)
+            .await
+            .map_err(|e| anyhow!("Failed to read backup {}: {}", src_path.display(), e))?;
+        let plaintext = decrypt(&encrypted, passphrase)?;
+
+        let snapshot = TempFile::new();
+        tokio::fs::write(&snapshot.path, plaintext).await?;
+
+        // ATTACH is per connection, so the whole restore uses one
+        let mut conn = self.pool.acquire().await?;
+        sqlx::query("ATTACH DATABASE ?1 AS backup")
+            .bind(snapshot.path.to_string_lossy().to_string())
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| anyhow!("Backup does not contain a valid database: {}", e))?;
+
+        let restored = restore_tables(&mut conn).await;
+
+        sqlx::query("DETACH DATABASE backup").execute(&mut *conn).await?;
+        restored
+    }
+}
+
+async fn restore_tables(conn: &mut sqlx::pool::PoolConnection<sqlx::Sqlite>) -> Result<()> {
+    let tables: Vec<String> = sqlx::query(
+        "SELECT name FROM backup.sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%' \
+         AND name IN (SELECT name FROM main.sqlite_master WHERE type = 'table')",
+    )
+    .fetch_all(&mut **conn)
+    .await
+    .map_err(|e| anyhow!("Backup does not contain a valid database: {}", e))?
+    .iter()
+    .map(|row| row.get("name"))
+    .collect();
+
+    sqlx::query("BEGIN").execute(&mut **conn).await?;
+    let result: Result<()> = async {
+        // Rows are replaced table by table, so foreign keys only need to hold at commit
+        sqlx::query("PRAGMA defer_foreign_keys = ON").execute(&mut **conn).await?;
+
+        for table in &tables {
+            // Older backups may predate columns added since; copy only the shared ones
+            let columns = common_columns(conn, table).await?;
+            sqlx::query(&format!("DELETE FROM main.\"{}\"", table)).execute(&mut **conn).await?;
+            if !columns.is_empty() {
+                sqlx::query(&format!(
+                    "INSERT INTO main.\"{table}\" ({columns}) SELECT {columns} FROM backup.\"{table}\"",
+                    table = table,
+                    columns = columns.join(", "),
+                ))
+                .execute(&mut **conn)
+                .await?;
+            }
+        }
+        Ok(())
+    }
+    .await;
+
+    match result {
+        Ok(()) => {
+            sqlx::query("COMMIT").execute(&mut **conn).await?;
+            Ok(())
+        }
+        Err(e) => {
+            sqlx::query("ROLLBACK").execute(&mut **conn).await?;
+            Err(anyhow!("Failed to restore backup: {}", e))
+        }
+    }
+}
+
+async fn common_columns(conn: &mut sqlx::pool::PoolConnection<sqlx::Sqlite>, table: &str) -> Result<Vec<String>> {
+    let columns_of = |schema: &str| format!("SELECT name FROM pragma_table_info('{}', '{}')", table, schema);
+    let main: Vec<String> = sqlx::query(&columns_of("main"))
+        .fetch_all(&mut **conn)
+        .await?
+        .iter()
+        .map(|row| row.get("name"))
+        .collect();
+    let backup: Vec<String> = sqlx::query(&columns_of("backup"))
+        .fetch_all(&mut **conn)
+        .await?
+        .iter()
+        .map(|row| row.get("name"))
+        .collect();
+
+    Ok(main
+        .into_iter()
+        .filter(|column| backup.contains(column))
+        .map(|column| format!("\"{}\"", column))
+        .collect())
+}
+
+fn derive_key(passphrase: &str, salt: &[u8], iterations: u32) -> Key<Aes256Gcm> {
+    let mut key = [0u8; 32];
+    pbkdf2::pbkdf2_hmac::<Sha256>(passphrase.as_bytes(), salt, iterations, &mut key);
+    key.into()
+}
+
+fn encrypt(plaintext: &[u8], passphrase: &str) -> Result<Vec<u8>> {
+    let mut salt = [0u8; SALT_LEN];
+    OsRng.fill_bytes(&mut salt);
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+
+    let cipher = Aes256Gcm::new(&derive_key(passphrase, &salt, PBKDF2_ITERATIONS));
+    let ciphertext = cipher
+        .encrypt(&nonce, plaintext)
+        .map_err(|_| anyhow!("Failed to encrypt backup"))?;
+
+    let mut out = Vec::with_capacity(HEADER_LEN + ciphertext.len());
+    out.extend_from_slice(MAGIC);
+    out.push(VERSION);
+    out.extend_from_slice(&PBKDF2_ITERATIONS.to_be_bytes());
+    out.extend_from_slice(&salt);
+    out.extend_from_slice(&nonce);
+    out.extend_from_slice(&ciphertext);
+    Ok(out)
+}
+
+fn decrypt(data: &[u8], passphrase: &str) -> Result<Vec<u8>> {
+    if data.len() < HEADER_LEN || &data[..MAGIC.len()] != MAGIC {
+        return Err(anyhow!("Not a Postgirl backup file"));
+    }
+    let version = data[MAGIC.len()];
+    if version != VERSION {
+        return Err(anyhow!("Unsupported backup version {}", version));
+    }
+
+    let mut offset = MAGIC.len() + 1;
+    let iterations = u32::from_be_bytes(data[offset..offset + 4].try_into()?);
+    offset += 4;
+    let salt = &data[offset..offset + SALT_LEN];
+    offset += SALT_LEN;
+    let nonce = Nonce::from_slice(&data[offset..offset + NONCE_LEN]);
+    offset += NONCE_LEN;
+
+    // GCM authentication fails the same way for a wrong passphrase and a tampered file
+    Aes256Gcm::new(&derive_key(passphrase, salt, iterations))
+        .decrypt(nonce, &data[offset..])
+        .map_err(|_| anyhow!("Wrong passphrase, or the backup file is corrupted"))
+}
+
+/// A uniquely named file in the system temp directory, removed on drop
+struct TempFile {
+    path: PathBuf,
+}
+
+impl TempFile {
+    fn new() -> Self {
+        let path = std::env::temp_dir().join(format!("postgirl-backup-{}.db", uuid::Uuid::new_v4()));
+        Self { path }
+    }
+}
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::workspace::Workspace;
+    use crate::services::database_service::DatabaseService;
+    use crate::tests::test_database::add_workspace;
+    use tempfile::TempDir;
+
+    async fn database_with_workspace(dir: &TempDir, name: &str) -> (DatabaseService, Workspace) {
+        let db_path = dir.path().join(format!("{}.db", name));
+        let database = DatabaseService::new(db_path.to_str().unwrap()).await.unwrap();
+        let workspace = add_workspace(&database, name, &dir.path().join(name).to_string_lossy()).await;
+        (database, workspace)
+    }
+
+    #[tokio::test]
+    async fn test_encrypted_backup_round_trip() {
+        let temp_dir = TempDir::new().unwrap();
+        let backup_path = temp_dir.path().join("backup.pgbk");
+
+        let (source, workspace) = database_with_workspace(&temp_dir, "Source").await;
+        BackupService::new(source.get_pool())
+            .export_encrypted(&backup_path, "correct horse battery staple", true)
+            .await
+            .unwrap();
+
+        // The file is not a readable SQLite database
+        let contents = std::fs::read(&backup_path).unwrap();
+        assert!(contents.starts_with(MAGIC));
+        assert!(!contents.windows(6).any(|w| w == b"Source"));
+
+        // Restoring replaces whatever the target database held
+        let (target, other) = database_with_workspace(&temp_dir, "Target").await;
+        BackupService::new(target.get_pool())
+            .import_encrypted(&backup_path, "correct horse battery staple")
+            .await
+            .unwrap();
+
+        let workspaces = target.get_all_workspaces().await.unwrap();
+        assert_eq!(workspaces.len(), 1);
+        assert_eq!(workspaces[0].id, workspace.id);
+        assert!(target.get_workspace(&other.id).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_encrypted_backup_rejects_wrong_passphrase() {
+        let temp_dir = TempDir::new().unwrap();
+        let backup_path = temp_dir.path().join("backup.pgbk");
+
+        let (database, workspace) = database_with_workspace(&temp_dir, "Source").await;
+        let service = BackupService::new(database.get_pool());
+        service.export_encrypted(&backup_path, "right passphrase", false).await.unwrap();
+
+        let error = service.import_encrypted(&backup_path, "wrong passphrase").await.unwrap_err();
+        assert!(error.to_string().contains("Wrong passphrase"), "{}", error);
+
+        std::fs::write(&backup_path, b"not a backup").unwrap();
+        let error = service.import_encrypted(&backup_path, "right passphrase").await.unwrap_err();
+        assert!(error.to_string().contains("Not a Postgirl backup"), "{}", error);
+
+        // Failed imports leave the data alone
+        assert!(database.get_workspace(&workspace.id).await.unwrap().is_some());
+    }
+}
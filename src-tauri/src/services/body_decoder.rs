@@ -0,0 +1,177 @@
+//! Incremental decoding of `Content-Encoding: gzip, deflate, br` bodies
+
+use anyhow::{anyhow, Result};
+use flate2::write::{DeflateDecoder, GzDecoder, ZlibDecoder};
+use std::io::Write;
+
+/// The encodings requested with Accept-Encoding, all of which `BodyDecoder` can undo
+pub const ACCEPT_ENCODING: &str = "gzip, deflate, br";
+
+enum Stage {
+    Gzip(GzDecoder<Vec<u8>>),
+    // "deflate" is meant to be zlib-wrapped, but some servers send a raw stream, so the
+    // format is picked once the first bytes arrive
+    Deflate,
+    Zlib(ZlibDecoder<Vec<u8>>),
+    RawDeflate(DeflateDecoder<Vec<u8>>),
+    Brotli(Box<brotli::DecompressorWriter<Vec<u8>>>),
+}
+
+impl Stage {
+    fn write(&mut self, data: &[u8]) -> std::io::Result<Vec<u8>> {
+        if data.is_empty() {
+            return Ok(Vec::new());
+        }
+        if let Stage::Deflate = self {
+            *self = if is_zlib_header(data) {
+                Stage::Zlib(ZlibDecoder::new(Vec::new()))
+            } else {
+                Stage::RawDeflate(DeflateDecoder::new(Vec::new()))
+            };
+        }
+        match self {
+            Stage::Gzip(decoder) => decoder.write_all(data).map(|_| std::mem::take(decoder.get_mut())),
+            Stage::Zlib(decoder) => decoder.write_all(data).map(|_| std::mem::take(decoder.get_mut())),
+            Stage::RawDeflate(decoder) => decoder.write_all(data).map(|_| std::mem::take(decoder.get_mut())),
+            Stage::Brotli(decoder) => decoder.write_all(data).map(|_| std::mem::take(decoder.get_mut())),
+            Stage::Deflate => unreachable!("deflate format is picked above"),
+        }
+    }
+
+    fn finish(self) -> std::io::Result<Vec<u8>> {
+        match self {
+            Stage::Gzip(decoder) => decoder.finish(),
+            Stage::Zlib(decoder) => decoder.finish(),
+            Stage::RawDeflate(decoder) => decoder.finish(),
+            Stage::Brotli(mut decoder) => {
+                decoder.close()?;
+                Ok(std::mem::take(decoder.get_mut()))
+            }
+            Stage::Deflate => Ok(Vec::new()),
+        }
+    }
+}
+
+/// A zlib stream starts with a CMF/FLG pair whose method is deflate and which is a multiple of 31
+fn is_zlib_header(data: &[u8]) -> bool {
+    data.len() >= 2 && data[0] & 0x0f == 8 && (u16::from(data[0]) << 8 | u16::from(data[1])) % 31 == 0
+}
+
+/// Undoes a response's Content-Encoding chunk by chunk. Bodies with no encoding, or one we
+/// don't support, pass through unchanged.
+pub struct BodyDecoder {
+    stages: Vec<Stage>, // in decoding order, the reverse of the header's
+    transfer_bytes: usize,
+    decoded_bytes: usize,
+}
+
+impl BodyDecoder {
+    pub fn new(content_encoding: Option<&str>) -> Self {
+        let encodings: Vec<String> = content_encoding
+            .unwrap_or_default()
+            .split(',')
+            .map(|encoding| encoding.trim().to_lowercase())
+            .filter(|encoding| !encoding.is_empty() && encoding != "identity")
+            .collect();
+
+        let mut stages = Vec::new();
+        for encoding in encodings.iter().rev() {
+            stages.push(match encoding.as_str() {
+                "gzip" | "x-gzip" => Stage::Gzip(GzDecoder::new(Vec::new())),
+                "deflate" => Stage::Deflate,
+                "br" => Stage::Brotli(Box::new(brotli::DecompressorWriter::new(Vec::new(), 4096))),
+                // Leave the body exactly as received rather than half-decoding it
+                _ => return Self { stages: Vec::new(), transfer_bytes: 0, decoded_bytes: 0 },
+            });
+        }
+        Self { stages, transfer_bytes: 0, decoded_bytes: 0 }
+    }
+
+    /// Decode the next chunk as received off the wire, returning whatever output it completes
+    pub fn feed(&mut self, chunk: &[u8]) -> Result<Vec<u8>> {
+        self.transfer_bytes += chunk.len();
+        let mut data = chunk.to_vec();
+        for stage in &mut self.stages {
+            data = stage.write(&data).map_err(|e| anyhow!("Failed to decode response body: {}", e))?;
+        }
+        self.decoded_bytes += data.len();
+        Ok(data)
+    }
+
+    /// Flush the decoders once the body has ended, returning any remaining output
+    pub fn finish(&mut self) -> Result<Vec<u8>> {
+        let mut data = Vec::new();
+        for stage in std::mem::take(&mut self.stages) {
+            let mut stage = stage;
+            let mut output = stage.write(&data).map_err(|e| anyhow!("Failed to decode response body: {}", e))?;
+            output.extend(stage.finish().map_err(|e| anyhow!("Failed to decode response body: {}", e))?);
+            data = output;
+        }
+        self.decoded_bytes += data.len();
+        Ok(data)
+    }
+
+    /// Bytes received, before decoding
+    pub fn transfer_bytes(&self) -> usize {
+        self.transfer_bytes
+    }
+
+    /// Bytes of payload produced so far
+    pub fn decoded_bytes(&self) -> usize {
+        self.decoded_bytes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::write::{DeflateEncoder, ZlibEncoder};
+    use flate2::Compression;
+
+    const PAYLOAD: &[u8] = b"{\"message\": \"hello hello hello hello hello\"}";
+
+    fn decode_in_chunks(content_encoding: Option<&str>, body: &[u8]) -> (Vec<u8>, BodyDecoder) {
+        let mut decoder = BodyDecoder::new(content_encoding);
+        let mut decoded = Vec::new();
+        for chunk in body.chunks(7) {
+            decoded.extend(decoder.feed(chunk).unwrap());
+        }
+        decoded.extend(decoder.finish().unwrap());
+        (decoded, decoder)
+    }
+
+    #[test]
+    fn test_deflate_with_and_without_zlib_wrapper() {
+        let mut zlib = ZlibEncoder::new(Vec::new(), Compression::default());
+        zlib.write_all(PAYLOAD).unwrap();
+        let mut raw = DeflateEncoder::new(Vec::new(), Compression::default());
+        raw.write_all(PAYLOAD).unwrap();
+
+        for body in [zlib.finish().unwrap(), raw.finish().unwrap()] {
+            let (decoded, decoder) = decode_in_chunks(Some("deflate"), &body);
+            assert_eq!(decoded, PAYLOAD);
+            assert_eq!(decoder.transfer_bytes(), body.len());
+            assert_eq!(decoder.decoded_bytes(), PAYLOAD.len());
+        }
+    }
+
+    #[test]
+    fn test_brotli_and_stacked_encodings() {
+        let mut brotli = brotli::CompressorWriter::new(Vec::new(), 4096, 5, 22);
+        brotli.write_all(PAYLOAD).unwrap();
+        let brotli = brotli.into_inner();
+        assert_eq!(decode_in_chunks(Some("br"), &brotli).0, PAYLOAD);
+
+        // Listed in the order applied, so gzip is undone first
+        let mut gzip = flate2::write::GzEncoder::new(Vec::new(), Compression::default());
+        gzip.write_all(&brotli).unwrap();
+        assert_eq!(decode_in_chunks(Some("br, gzip"), &gzip.finish().unwrap()).0, PAYLOAD);
+    }
+
+    #[test]
+    fn test_unknown_encoding_passes_through() {
+        let (decoded, decoder) = decode_in_chunks(Some("gzip, zstd"), PAYLOAD);
+        assert_eq!(decoded, PAYLOAD);
+        assert_eq!(decoder.transfer_bytes(), decoder.decoded_bytes());
+    }
+}
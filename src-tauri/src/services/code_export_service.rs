@@ -0,0 +1,718 @@
+use crate::models::collection::{Collection, Request};
+use crate::models::environment::Environment;
+use crate::models::http::{AuthPlacement, CodeTarget, HttpMethod, HttpRequest, RequestBody};
+use crate::services::http_service::HttpService;
+use crate::services::variable_substitution::parse_placeholder;
+use regex::Regex;
+use std::collections::{BTreeMap, BTreeSet};
+
+#[derive(Debug, Clone, Default)]
+pub struct CodeExportService;
+
+impl CodeExportService {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Render a single request as a curl command with literal, single-quoted arguments
+    pub fn to_curl(&self, request: &HttpRequest) -> String {
+        self.render_curl(request, single_quote)
+    }
+
+    /// Render a single request as a snippet in the target language, with its auth applied
+    pub fn generate_snippet(&self, request: &HttpRequest, target: CodeTarget) -> String {
+        match target {
+            CodeTarget::Curl => self.to_curl(request),
+            CodeTarget::JavaScriptFetch => self.render_fetch(request),
+            CodeTarget::PythonRequests => self.render_python(request),
+        }
+    }
+
+    /// Render a whole collection as a bash script, one curl per request in order.
+    /// `{{VAR}}` placeholders become shell variables: plain environment values are
+    /// defined at the top of the script, secrets and unknown variables must be
+    /// exported by the caller unless the placeholder has a `|default`. Dynamic
+    /// variables like `{{$uuid}}` are generated by the shell as each command runs.
+    pub fn collection_to_curl_script(
+        &self,
+        collection: &Collection,
+        requests: &[Request],
+        environment: Option<&Environment>,
+    ) -> String {
+        let mut requests: Vec<&Request> = requests.iter().collect();
+        requests.sort_by_key(|r| r.order_index);
+
+        let http_requests: Vec<HttpRequest> = requests.iter().map(|r| r.to_http_request()).collect();
+        let commands: Vec<String> = http_requests
+            .iter()
+            .map(|r| self.render_curl(r, double_quote_with_vars))
+            .collect();
+
+        // Collect every placeholder referenced by the rendered commands, noting whether
+        // each one always comes with a default
+        let placeholder = Regex::new(r"\$\{([A-Za-z_][A-Za-z0-9_]*)(:-)?").unwrap();
+        let mut referenced: BTreeMap<String, bool> = BTreeMap::new();
+        for cap in commands.iter().flat_map(|c| placeholder.captures_iter(c)) {
+            let has_default = cap.get(2).is_some();
+            referenced
+                .entry(cap[1].to_string())
+                .and_modify(|always| *always &= has_default)
+                .or_insert(has_default);
+        }
+
+        let mut defined = BTreeMap::new();
+        let mut required = BTreeSet::new();
+        for (name, always_has_default) in &referenced {
+            let variable = environment.and_then(|env| {
+                env.variables.values().find(|v| shell_var_name(&v.key) == *name)
+            });
+            match variable {
+                Some(v) if !v.is_secret => {
+                    defined.insert(name.clone(), v.value.clone());
+                }
+                _ if *always_has_default => {}
+                _ => {
+                    required.insert(name.clone());
+                }
+            }
+        }
+
+        let mut script = String::from("#!/usr/bin/env bash\n");
+        script.push_str(&format!("# Collection: {}\n", collection.name));
+        if let Some(env) = environment {
+            script.push_str(&format!("# Environment: {}\n", env.name));
+        }
+        script.push_str("# Generated by Postgirl\n");
+        script.push_str("set -euo pipefail\n\n");
+
+        if !required.is_empty() {
+            script.push_str("# Required environment variables (export these before running):\n");
+            for name in &required {
+                script.push_str(&format!("#   {}\n", name));
+            }
+            for name in &required {
+                script.push_str(&format!(": \"${{{}:?{} must be set}}\"\n", name, name));
+            }
+            script.push('\n');
+        }
+
+        if !defined.is_empty() {
+            for (name, value) in &defined {
+                script.push_str(&format!("{}={}\n", name, single_quote(value)));
+            }
+            script.push('\n');
+        }
+
+        for (request, command) in http_requests.iter().zip(commands) {
+            script.push_str(&format!("# {}\n", request.name));
+            script.push_str(&command);
+            script.push_str("\n\n");
+        }
+
+        script
+    }
+
+    fn render_curl(&self, request: &HttpRequest, quote: fn(&str) -> String) -> String {
+        // Basic auth is clearer as -u, which makes curl build the same header
+        let basic_auth = basic_credentials(request);
+        let (url, mut headers) = url_and_headers(request, basic_auth.is_none());
+
+        let has_content_type = headers.keys().any(|k| k.eq_ignore_ascii_case("content-type"));
+        let mut parts = vec![format!("curl -X {} {}", request.method.as_str(), quote(&url))];
+        if request.follow_redirects {
+            parts.push("-L".to_string());
+        }
+        if let Some((username, password)) = &basic_auth {
+            parts.push(format!("-u {}", quote(&format!("{}:{}", username, password))));
+        }
+
+        let mut body_args = Vec::new();
+        match &request.body {
+            Some(RequestBody::Json { data }) => {
+                if !has_content_type {
+                    headers.insert("Content-Type".to_string(), "application/json".to_string());
+                }
+                body_args.push(format!("--data-raw {}", quote(&data.to_string())));
+            }
+            Some(RequestBody::GraphQl { query, variables }) => {
+                if !has_content_type {
+                    headers.insert("Content-Type".to_string(), "application/json".to_string());
+                }
+                let payload = RequestBody::graphql_payload(query, variables);
+                body_args.push(format!("--data-raw {}", quote(&payload.to_string())));
+            }
+            Some(RequestBody::Raw { content, content_type }) => {
+                if !has_content_type {
+                    headers.insert("Content-Type".to_string(), content_type.clone());
+                }
+                body_args.push(format!("--data-raw {}", quote(content)));
+            }
+            Some(RequestBody::FormUrlEncoded { fields }) => {
+                let fields: BTreeMap<_, _> = fields.iter().collect();
+                for (key, value) in fields {
+                    body_args.push(format!("--data-urlencode {}", quote(&format!("{}={}", key, value))));
+                }
+            }
+            Some(RequestBody::FormData { fields, files }) => {
+                let fields: BTreeMap<_, _> = fields.iter().collect();
+                for (key, value) in fields {
+                    body_args.push(format!("-F {}", quote(&format!("{}={}", key, value))));
+                }
+                for file in files {
+                    let mut upload = format!("{}=@{}", file.field, file.path);
+                    if let Some(filename) = &file.filename {
+                        upload.push_str(&format!(";filename={}", filename));
+                    }
+                    if let Some(content_type) = &file.content_type {
+                        upload.push_str(&format!(";type={}", content_type));
+                    }
+                    body_args.push(format!("-F {}", quote(&upload)));
+                }
+            }
+            Some(RequestBody::Binary { data, content_type }) => {
+                if !has_content_type {
+                    headers.insert("Content-Type".to_string(), content_type.clone());
+                }
+                body_args.push(format!("--data-binary {}", quote(&String::from_utf8_lossy(data))));
+            }
+            Some(RequestBody::None) | None => {}
+        }
+
+        for (key, value) in &headers {
+            parts.push(format!("-H {}", quote(&format!("{}: {}", key, value))));
+        }
+        parts.extend(body_args);
+
+        parts.join(" \\\n  ")
+    }
+
+    fn render_fetch(&self, request: &HttpRequest) -> String {
+        let (url, mut headers) = url_and_headers(request, true);
+        let has_content_type = headers.keys().any(|k| k.eq_ignore_ascii_case("content-type"));
+
+        let mut prelude = String::new();
+        let body = match &request.body {
+            Some(RequestBody::Json { data }) => {
+                if !has_content_type {
+                    headers.insert("Content-Type".to_string(), "application/json".to_string());
+                }
+                Some(format!("JSON.stringify({})", indent_continuation(&pretty_json(data), "  ")))
+            }
+            Some(RequestBody::GraphQl { query, variables }) => {
+                if !has_content_type {
+                    headers.insert("Content-Type".to_string(), "application/json".to_string());
+                }
+                let payload = RequestBody::graphql_payload(query, variables);
+                Some(format!("JSON.stringify({})", indent_continuation(&pretty_json(&payload), "  ")))
+            }
+            Some(RequestBody::Raw { content, content_type }) => {
+                if !has_content_type {
+                    headers.insert("Content-Type".to_string(), content_type.clone());
+                }
+                Some(js_string(content))
+            }
+            Some(RequestBody::Binary { data, content_type }) => {
+                if !has_content_type {
+                    headers.insert("Content-Type".to_string(), content_type.clone());
+                }
+                Some(js_string(&String::from_utf8_lossy(data)))
+            }
+            Some(RequestBody::FormUrlEncoded { fields }) => {
+                let fields: BTreeMap<_, _> = fields.iter().collect();
+                let fields = serde_json::to_value(fields).unwrap_or_default();
+                Some(format!("new URLSearchParams({})", indent_continuation(&pretty_json(&fields), "  ")))
+            }
+            Some(RequestBody::FormData { fields, files }) => {
+                // fetch has to set the multipart boundary itself
+                headers.retain(|k, _| !k.eq_ignore_ascii_case("content-type"));
+                if !files.is_empty() {
+                    prelude.push_str("import fs from \"node:fs\";\n\n");
+                }
+                prelude.push_str("const formData = new FormData();\n");
+                let fields: BTreeMap<_, _> = fields.iter().collect();
+                for (key, value) in fields {
+                    prelude.push_str(&format!("formData.append({}, {});\n", js_string(key), js_string(value)));
+                }
+                for file in files {
+                    let blob = match &file.content_type {
+                        Some(content_type) => format!("await fs.openAsBlob({}, {{ type: {} }})", js_string(&file.path), js_string(content_type)),
+                        None => format!("await fs.openAsBlob({})", js_string(&file.path)),
+                    };
+                    let filename = file.filename.clone().unwrap_or_else(|| upload_name(&file.path));
+                    prelude.push_str(&format!("formData.append({}, {}, {});\n", js_string(&file.field), blob, js_string(&filename)));
+                }
+                prelude.push('\n');
+                Some("formData".to_string())
+            }
+            Some(RequestBody::None) | None => None,
+        };
+
+        let mut options = vec![format!("  method: {}", js_string(request.method.as_str()))];
+        if !headers.is_empty() {
+            let headers = serde_json::to_value(&headers).unwrap_or_default();
+            options.push(format!("  headers: {}", indent_continuation(&pretty_json(&headers), "  ")));
+        }
+        if let Some(body) = body {
+            options.push(format!("  body: {}", body));
+        }
+        if !request.follow_redirects {
+            options.push("  redirect: \"manual\"".to_string());
+        }
+
+        format!(
+            "{}const response = await fetch({}, {{\n{}\n}});\n\nconsole.log(response.status);\nconsole.log(await response.text());\n",
+            prelude,
+            js_string(&url),
+            options.join(",\n")
+        )
+    }
+
+    fn render_python(&self, request: &HttpRequest) -> String {
+        let basic_auth = basic_credentials(request);
+        let (url, mut headers) = url_and_headers(request, basic_auth.is_none());
+        let has_content_type = headers.keys().any(|k| k.eq_ignore_ascii_case("content-type"));
+
+        let mut lines = vec!["import requests".to_string(), String::new(), format!("url = {}", js_string(&url))];
+        let mut arguments = vec!["url".to_string()];
+
+        // requests sets the content type itself for json=, data= dicts and files=
+        let payload = match &request.body {
+            Some(RequestBody::Json { data }) => Some(("json", python_literal(data, 0))),
+            Some(RequestBody::GraphQl { query, variables }) => {
+                Some(("json", python_literal(&RequestBody::graphql_payload(query, variables), 0)))
+            }
+            Some(RequestBody::Raw { content, content_type }) => {
+                if !has_content_type {
+                    headers.insert("Content-Type".to_string(), content_type.clone());
+                }
+                Some(("data", js_string(content)))
+            }
+            Some(RequestBody::Binary { data, content_type }) => {
+                if !has_content_type {
+                    headers.insert("Content-Type".to_string(), content_type.clone());
+                }
+                Some(("data", js_string(&String::from_utf8_lossy(data))))
+            }
+            Some(RequestBody::FormUrlEncoded { fields }) => {
+                let fields: BTreeMap<_, _> = fields.iter().collect();
+                Some(("data", python_literal(&serde_json::to_value(fields).unwrap_or_default(), 0)))
+            }
+            Some(RequestBody::FormData { fields, files }) => {
+                headers.retain(|k, _| !k.eq_ignore_ascii_case("content-type"));
+                let fields: BTreeMap<_, _> = fields.iter().collect();
+                let mut entries: Vec<String> = fields
+                    .iter()
+                    .map(|(key, value)| format!("    {}: (None, {}),", js_string(key), js_string(value)))
+                    .collect();
+                for file in files {
+                    let filename = file.filename.clone().unwrap_or_else(|| upload_name(&file.path));
+                    let mut part = format!("{}, open({}, \"rb\")", js_string(&filename), js_string(&file.path));
+                    if let Some(content_type) = &file.content_type {
+                        part.push_str(&format!(", {}", js_string(content_type)));
+                    }
+                    entries.push(format!("    {}: ({}),", js_string(&file.field), part));
+                }
+                Some(("files", format!("{{\n{}\n}}", entries.join("\n"))))
+            }
+            Some(RequestBody::None) | None => None,
+        };
+
+        if !headers.is_empty() {
+            let headers = serde_json::to_value(&headers).unwrap_or_default();
+            lines.push(format!("headers = {}", python_literal(&headers, 0)));
+            arguments.push("headers=headers".to_string());
+        }
+        if let Some((keyword, value)) = payload {
+            lines.push(format!("payload = {}", value));
+            arguments.push(format!("{}=payload", keyword));
+        }
+        if let Some((username, password)) = &basic_auth {
+            arguments.push(format!("auth=({}, {})", js_string(username), js_string(password)));
+        }
+        if !request.follow_redirects {
+            arguments.push("allow_redirects=False".to_string());
+        }
+        if let Some(timeout_ms) = request.timeout_ms {
+            arguments.push(format!("timeout={}", timeout_ms as f64 / 1000.0));
+        }
+
+        lines.push(String::new());
+        // requests only has helpers for the standard methods
+        let call = match &request.method {
+            HttpMethod::Custom(method) => {
+                arguments.insert(0, js_string(method));
+                "request".to_string()
+            }
+            method => method.as_str().to_lowercase(),
+        };
+        lines.push(format!("response = requests.{}({})", call, arguments.join(", ")));
+        lines.push(String::new());
+        lines.push("print(response.status_code)".to_string());
+        lines.push("print(response.text)".to_string());
+
+        lines.join("\n") + "\n"
+    }
+}
+
+/// The request's URL and headers with its auth applied. Auth values keep their
+/// `{{VAR}}` placeholders so script exports can turn them into variables.
+fn url_and_headers(request: &HttpRequest, include_basic_auth: bool) -> (String, BTreeMap<String, String>) {
+    let mut url = request.url.clone();
+    let mut headers: BTreeMap<String, String> = request
+        .headers
+        .iter()
+        .map(|(k, v)| (k.clone(), v.clone()))
+        .collect();
+
+    if !include_basic_auth && request.auth_type.as_deref() == Some("basic") {
+        return (url, headers);
+    }
+
+    match HttpService::resolve_auth_with(&request.auth_type, &request.auth_config, str::to_string) {
+        Some(AuthPlacement::Header { name, value }) => {
+            headers.insert(name, value);
+        }
+        Some(AuthPlacement::Query { name, value }) => {
+            let separator = if url.contains('?') { '&' } else { '?' };
+            url = format!("{}{}{}={}", url, separator, name, value);
+        }
+        None => {}
+    }
+    (url, headers)
+}
+
+/// Username and password of a basic auth config, for targets with native basic auth support
+fn basic_credentials(request: &HttpRequest) -> Option<(String, String)> {
+    if request.auth_type.as_deref() != Some("basic") {
+        return None;
+    }
+    let config = request.auth_config.as_ref()?;
+    let username = config.get("username")?.as_str()?.to_string();
+    let password = config.get("password").and_then(|v| v.as_str()).unwrap_or_default().to_string();
+    Some((username, password))
+}
+
+/// The file name an upload is sent under when none is given
+fn upload_name(path: &str) -> String {
+    path.rsplit(['/', '\\']).next().unwrap_or(path).to_string()
+}
+
+/// A double-quoted string literal, valid in both JavaScript and Python
+fn js_string(value: &str) -> String {
+    serde_json::to_string(value).unwrap_or_default()
+}
+
+fn pretty_json(value: &serde_json::Value) -> String {
+    serde_json::to_string_pretty(value).unwrap_or_default()
+}
+
+/// Indent every line but the first, for values nested inside a literal
+fn indent_continuation(text: &str, indent: &str) -> String {
+    text.replace('\n', &format!("\n{}", indent))
+}
+
+/// Render JSON as a Python literal, four spaces per level
+fn python_literal(value: &serde_json::Value, depth: usize) -> String {
+    let indent = "    ".repeat(depth + 1);
+    let closing = "    ".repeat(depth);
+    match value {
+        serde_json::Value::Null => "None".to_string(),
+        serde_json::Value::Bool(true) => "True".to_string(),
+        serde_json::Value::Bool(false) => "False".to_string(),
+        serde_json::Value::Number(n) => n.to_string(),
+        serde_json::Value::String(s) => js_string(s),
+        serde_json::Value::Array(items) if items.is_empty() => "[]".to_string(),
+        serde_json::Value::Array(items) => {
+            let items: Vec<String> = items
+                .iter()
+                .map(|item| format!("{}{},", indent, python_literal(item, depth + 1)))
+                .collect();
+            format!("[\n{}\n{}]", items.join("\n"), closing)
+        }
+        serde_json::Value::Object(map) if map.is_empty() => "{}".to_string(),
+        serde_json::Value::Object(map) => {
+            let entries: Vec<String> = map
+                .iter()
+                .map(|(key, value)| format!("{}{}: {},", indent, js_string(key), python_literal(value, depth + 1)))
+                .collect();
+            format!("{{\n{}\n{}}}", entries.join("\n"), closing)
+        }
+    }
+}
+
+/// Quote a value literally for POSIX shells
+fn single_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
+
+/// Quote a value in double quotes, turning `{{VAR}}` placeholders into `${VAR}`,
+/// `{{VAR|default}}` into `${VAR:-default}` and dynamic variables into the shell
+/// expression that generates them
+fn double_quote_with_vars(value: &str) -> String {
+    let placeholder = Regex::new(r"\{\{([^{}]+)\}\}").unwrap();
+    let mut quoted = String::from("\"");
+    let mut last = 0;
+    for cap in placeholder.captures_iter(value) {
+        let whole = cap.get(0).unwrap();
+        quoted.push_str(&escape_double_quoted(&value[last..whole.start()]));
+        quoted.push_str(&shell_placeholder(&cap[1], whole.as_str()));
+        last = whole.end();
+    }
+    quoted.push_str(&escape_double_quoted(&value[last..]));
+    quoted.push('"');
+    quoted
+}
+
+/// The shell expansion for a placeholder's body. Unknown dynamic variables stay as
+/// written, as they do when the request is sent.
+fn shell_placeholder(body: &str, written: &str) -> String {
+    let (name, fallback) = parse_placeholder(body);
+    let name = name.trim();
+    if let Some(expression) = dynamic_shell_expression(name) {
+        return expression;
+    }
+    if name.starts_with('$') {
+        return escape_double_quoted(fallback.unwrap_or(written));
+    }
+    match fallback {
+        Some(fallback) => format!("${{{}:-{}}}", shell_var_name(name), escape_double_quoted(fallback).replace('}', "\\}")),
+        None => format!("${{{}}}", shell_var_name(name)),
+    }
+}
+
+/// A shell expression generating a built-in variable such as `$uuid` or `$randomInt:1:100`
+fn dynamic_shell_expression(name: &str) -> Option<String> {
+    let mut parts = name.strip_prefix('$')?.split(':');
+    match (parts.next()?, parts.next(), parts.next(), parts.next()) {
+        ("uuid", None, ..) => {
+            Some("$(cat /proc/sys/kernel/random/uuid 2>/dev/null || uuidgen | tr '[:upper:]' '[:lower:]')".to_string())
+        }
+        ("timestamp", None, ..) => Some("$(date +%s)".to_string()),
+        ("isoTimestamp", None, ..) => Some("$(date -u +%Y-%m-%dT%H:%M:%SZ)".to_string()),
+        ("randomInt", None, ..) => Some("$((RANDOM % 1001))".to_string()),
+        ("randomInt", Some(min), Some(max), None) => {
+            let (min, max) = (min.trim().parse::<i64>().ok()?, max.trim().parse::<i64>().ok()?);
+            (min <= max).then(|| format!("$(({} + RANDOM % {}))", min, max - min + 1))
+        }
+        _ => None,
+    }
+}
+
+/// Escape text so it stays literal inside double quotes
+fn escape_double_quoted(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('`', "\\`")
+        .replace('$', "\\$")
+}
+
+/// Map an environment variable key onto a valid shell identifier
+fn shell_var_name(key: &str) -> String {
+    let mut name: String = key
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '_' { c } else { '_' })
+        .collect();
+    if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) {
+        name.insert(0, '_');
+    }
+    name
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::collection::{CreateCollectionRequest, CreateRequestRequest};
+    use crate::models::environment::{EnvironmentVariable, VariableType};
+    use crate::models::http::FormFile;
+
+    fn create_request(name: &str, url: &str, order_index: i32) -> Request {
+        Request::new(CreateRequestRequest {
+            collection_id: "collection-1".to_string(),
+            name: name.to_string(),
+            description: None,
+            method: "POST".to_string(),
+            url: url.to_string(),
+            headers: Some(serde_json::json!({"Accept": "application/json"})),
+            body: Some(r#"{"name": "test"}"#.to_string()),
+            body_type: Some("json".to_string()),
+            auth_type: Some("bearer".to_string()),
+            auth_config: Some(serde_json::json!({"token": "{{API_TOKEN}}"})),
+            follow_redirects: Some(true),
+            timeout_ms: None,
+            order_index: Some(order_index),
+        })
+    }
+
+    #[test]
+    fn test_collection_curl_script() {
+        let collection = Collection::new(CreateCollectionRequest {
+            workspace_id: "workspace-1".to_string(),
+            name: "Users API".to_string(),
+            description: None,
+            folder_path: None,
+            git_branch: None,
+            parent_id: None,
+        });
+        let requests = vec![
+            create_request("Update user", "{{BASE_URL}}/users/1", 1),
+            create_request("Create user", "{{BASE_URL}}/users", 0),
+        ];
+
+        let mut environment = Environment::default();
+        environment.variables.insert("BASE_URL".to_string(), EnvironmentVariable {
+            key: "BASE_URL".to_string(),
+            value: "https://api.example.com".to_string(),
+            is_secret: false,
+            variable_type: VariableType::String,
+        });
+        environment.variables.insert("API_TOKEN".to_string(), EnvironmentVariable {
+            key: "API_TOKEN".to_string(),
+            value: "super-secret".to_string(),
+            is_secret: true,
+            variable_type: VariableType::Secret,
+        });
+
+        let script = CodeExportService::new().collection_to_curl_script(&collection, &requests, Some(&environment));
+
+        assert!(script.starts_with("#!/usr/bin/env bash\n"));
+        assert_eq!(script.matches("curl -X").count(), requests.len());
+        assert!(script.contains("BASE_URL='https://api.example.com'"));
+        assert!(script.contains("Authorization: Bearer ${API_TOKEN}"));
+        assert!(script.contains("#   API_TOKEN"));
+        assert!(!script.contains("super-secret"));
+        // Requests are emitted in collection order
+        assert!(script.find("# Create user").unwrap() < script.find("# Update user").unwrap());
+    }
+
+    #[test]
+    fn test_curl_script_dynamic_variables_and_defaults() {
+        let collection = Collection::new(CreateCollectionRequest {
+            workspace_id: "workspace-1".to_string(),
+            name: "Users API".to_string(),
+            description: None,
+            folder_path: None,
+            git_branch: None,
+            parent_id: None,
+        });
+        let mut request = create_request(
+            "Create user",
+            "{{BASE_URL|https://api.example.com}}/users?id={{$uuid}}&at={{$timestamp}}&n={{$randomInt:5:10}}&x={{$unknown}}",
+            0,
+        );
+        request.auth_config = Some(r#"{"token": "{{API_TOKEN|anonymous}}"}"#.to_string());
+
+        let script = CodeExportService::new().collection_to_curl_script(&collection, &[request], None);
+
+        assert!(script.contains("\"${BASE_URL:-https://api.example.com}/users?id=$(cat /proc/sys/kernel/random/uuid"));
+        assert!(script.contains("&at=$(date +%s)&n=$((5 + RANDOM % 6))&x={{\\$unknown}}\""));
+        assert!(script.contains("Authorization: Bearer ${API_TOKEN:-anonymous}"));
+        assert!(!script.contains("${_uuid}"));
+        // Variables with defaults don't have to be exported
+        assert!(!script.contains("must be set"));
+    }
+
+    #[test]
+    fn test_single_request_curl() {
+        let request = create_request("Create user", "https://api.example.com/users", 0).to_http_request();
+        let curl = CodeExportService::new().to_curl(&request);
+
+        assert!(curl.starts_with("curl -X POST 'https://api.example.com/users'"));
+        assert!(curl.contains("-H 'Content-Type: application/json'"));
+        assert!(curl.contains(r#"--data-raw '{"name":"test"}'"#));
+    }
+
+    fn http_request(body: RequestBody, auth_type: &str, auth_config: serde_json::Value) -> HttpRequest {
+        HttpRequest {
+            method: crate::models::http::HttpMethod::Put,
+            url: "https://api.example.com/users/1?expand=roles".to_string(),
+            headers: [("Accept".to_string(), "application/json".to_string())].into_iter().collect(),
+            body: Some(body),
+            auth_type: Some(auth_type.to_string()),
+            auth_config: Some(auth_config),
+            ..HttpRequest::default()
+        }
+    }
+
+    #[test]
+    fn test_curl_snippet_round_trips_through_parser() {
+        use crate::services::curl_import_service::CurlImportService;
+
+        let bearer = || serde_json::json!({"token": "abc123"});
+        let fields = |pairs: &[(&str, &str)]| pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect();
+        let requests = vec![
+            http_request(RequestBody::Json { data: serde_json::json!({"name": "O'Brien", "tags": ["a b", "c"]}) }, "bearer", bearer()),
+            http_request(RequestBody::FormUrlEncoded { fields: fields(&[("q", "rust & go"), ("page", "2")]) }, "bearer", bearer()),
+            http_request(RequestBody::FormData { fields: fields(&[("name", "report"), ("kind", "pdf")]), files: Vec::new() }, "bearer", bearer()),
+            http_request(
+                RequestBody::FormData {
+                    fields: fields(&[("name", "avatar")]),
+                    files: vec![FormFile {
+                        field: "image".to_string(),
+                        path: "{{FIXTURE_DIR}}/me.png".to_string(),
+                        filename: Some("avatar.png".to_string()),
+                        content_type: Some("image/png".to_string()),
+                    }],
+                },
+                "bearer",
+                bearer(),
+            ),
+            http_request(
+                RequestBody::Raw { content: "line one\nline 'two'".to_string(), content_type: "text/plain".to_string() },
+                "basic",
+                serde_json::json!({"username": "admin", "password": "p@ss word"}),
+            ),
+        ];
+
+        for request in requests {
+            let curl = CodeExportService::new().generate_snippet(&request, CodeTarget::Curl);
+            let parsed = CurlImportService::new().parse(&curl).unwrap();
+
+            assert_eq!(parsed.method, request.method, "{}", curl);
+            assert_eq!(parsed.url, request.url, "{}", curl);
+            assert_eq!(parsed.body, request.body, "{}", curl);
+            assert_eq!(parsed.follow_redirects, request.follow_redirects);
+            assert_eq!(parsed.headers.get("Accept").map(String::as_str), Some("application/json"));
+            if request.auth_type.as_deref() == Some("basic") {
+                assert_eq!(parsed.auth_type, request.auth_type);
+                assert_eq!(parsed.auth_config, request.auth_config);
+            } else {
+                assert_eq!(parsed.headers.get("Authorization").map(String::as_str), Some("Bearer abc123"));
+            }
+        }
+    }
+
+    #[test]
+    fn test_fetch_and_python_snippets() {
+        let request = http_request(
+            RequestBody::Json { data: serde_json::json!({"active": true, "manager": null}) },
+            "api_key",
+            serde_json::json!({"key": "api_key", "value": "k-1", "location": "query"}),
+        );
+        let service = CodeExportService::new();
+
+        let fetch = service.generate_snippet(&request, CodeTarget::JavaScriptFetch);
+        assert!(fetch.contains(r#"fetch("https://api.example.com/users/1?expand=roles&api_key=k-1", {"#));
+        assert!(fetch.contains(r#"method: "PUT""#));
+        assert!(fetch.contains(r#""Content-Type": "application/json""#));
+        assert!(fetch.contains(r#""active": true"#));
+
+        let python = service.generate_snippet(&request, CodeTarget::PythonRequests);
+        assert!(python.starts_with("import requests\n"));
+        assert!(python.contains(r#""active": True,"#));
+        assert!(python.contains(r#""manager": None,"#));
+        assert!(python.contains("response = requests.put(url, headers=headers, json=payload, timeout=30)"));
+
+        let form = http_request(
+            RequestBody::FormData { fields: [("file".to_string(), "notes".to_string())].into_iter().collect(), files: Vec::new() },
+            "basic",
+            serde_json::json!({"username": "admin", "password": "secret"}),
+        );
+        let python = service.generate_snippet(&form, CodeTarget::PythonRequests);
+        assert!(python.contains(r#""file": (None, "notes"),"#));
+        assert!(python.contains(r#"files=payload, auth=("admin", "secret")"#));
+        let fetch = service.generate_snippet(&form, CodeTarget::JavaScriptFetch);
+        assert!(fetch.contains(r#"formData.append("file", "notes");"#));
+        assert!(fetch.contains(r#""Authorization": "Basic YWRtaW46c2VjcmV0""#));
+    }
+}
@@ -0,0 +1,339 @@
+use crate::models::collection::Request;
+use crate::models::collection_run::{CollectionRunResult, RequestRunResult, RunPacing, RunStatus, ThrottleEvent};
+use crate::models::environment::Environment;
+use crate::models::http::{ExecuteOptions, HttpMethod};
+use crate::services::assertion_service::AssertionService;
+use crate::services::extraction_service::ExtractionService;
+use crate::services::http_service::{parse_retry_after, HttpService};
+use crate::services::pre_request_service::PreRequestService;
+use crate::services::run_condition::parse_run_condition;
+use futures_util::stream::{self, StreamExt};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+// A request answered with 429 is resent at most this many times
+const MAX_THROTTLE_RETRIES: usize = 3;
+// Longer Retry-After waits are cut short to this
+const MAX_THROTTLE_WAIT: Duration = Duration::from_secs(60);
+
+/// Hands out send slots at least `interval` apart, to one run's requests however many are in flight
+struct Pacer {
+    interval: Option<Duration>,
+    state: Mutex<PacerState>,
+}
+
+#[derive(Default)]
+struct PacerState {
+    last_slot: Option<Instant>,
+    waited: Duration, // summed over every request held back
+}
+
+impl Pacer {
+    fn new(interval: Option<Duration>) -> Self {
+        Self { interval, state: Mutex::new(PacerState::default()) }
+    }
+
+    async fn wait_turn(&self) {
+        let wait = {
+            let mut state = self.state.lock().unwrap();
+            let now = Instant::now();
+            let slot = match state.last_slot.zip(self.interval) {
+                Some((last, interval)) => (last + interval).max(now),
+                None => now,
+            };
+            state.last_slot = Some(slot);
+            state.waited += slot - now;
+            slot - now
+        };
+        tokio::time::sleep(wait).await;
+    }
+
+    fn waited(&self) -> Duration {
+        self.state.lock().unwrap().waited
+    }
+}
+
+#[derive(Clone)]
+pub struct CollectionRunService {
+    http_service: HttpService,
+    request_variables: HashMap<String, HashMap<String, String>>, // keyed by request id
+}
+
+impl CollectionRunService {
+    pub fn new(http_service: HttpService) -> Self {
+        Self { http_service, request_variables: HashMap::new() }
+    }
+
+    /// Variables scoped to single requests, keyed by request id. Each applies to its own
+    /// request only, over the run's variables.
+    pub fn with_request_variables(mut self, request_variables: HashMap<String, HashMap<String, String>>) -> Self {
+        self.request_variables = request_variables;
+        self
+    }
+
+    /// Execute a collection's requests in order, skipping those excluded from runs and those
+    /// whose run condition doesn't hold against the variables so far.
+    /// Without `continue_on_error` the first failure skips everything after it.
+    /// Values extracted from each response are available to the requests after it.
+    /// Sends are spaced out per `pacing`, and a 429 with a Retry-After is waited out and resent.
+    /// With a `concurrency` above one, up to that many requests are in flight at once and the
+    /// results keep the collection's order. Requests can't see each other's variables that way,
+    /// so a run with extractions, pre-request steps or stop-on-failure stays sequential.
+    /// Every request is sent with `options`, so a run shares the workspace's cookie jar,
+    /// throttle and connection settings with single sends.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn run(
+        &self,
+        collection_id: &str,
+        requests: &[Request],
+        environment_variables: Option<HashMap<String, String>>,
+        continue_on_error: bool,
+        pacing: &RunPacing,
+        concurrency: usize,
+        options: &ExecuteOptions,
+    ) -> CollectionRunResult {
+        let started = Instant::now();
+        let mut requests: Vec<&Request> = requests.iter().collect();
+        requests.sort_by_key(|r| r.order_index);
+
+        let pacer = Pacer::new(pacing.interval());
+        let variables = environment_variables.unwrap_or_default();
+        let sequential_reason = if concurrency > 1 {
+            Self::sequential_reason(&requests, continue_on_error)
+        } else {
+            None
+        };
+        let (results, throttle_events) = if concurrency > 1 && sequential_reason.is_none() {
+            self.run_parallel(&requests, &variables, &pacer, concurrency, options).await
+        } else {
+            self.run_sequential(&requests, variables, continue_on_error, &pacer, options).await
+        };
+
+        let skipped = results.iter().filter(|r| r.status == RunStatus::Skipped).count();
+        CollectionRunResult {
+            collection_id: collection_id.to_string(),
+            executed: results.len() - skipped,
+            skipped,
+            failed: results.iter().filter(|r| r.status == RunStatus::Failed).count(),
+            total_time_ms: started.elapsed().as_millis() as u64,
+            pacing_wait_ms: pacer.waited().as_millis() as u64,
+            throttle_events,
+            sequential_reason,
+            results,
+        }
+    }
+
+    async fn run_sequential(
+        &self,
+        requests: &[&Request],
+        mut variables: HashMap<String, String>,
+        continue_on_error: bool,
+        pacer: &Pacer,
+        options: &ExecuteOptions,
+    ) -> (Vec<RequestRunResult>, Vec<ThrottleEvent>) {
+        let mut results = Vec::new();
+        let mut throttle_events = Vec::new();
+        let mut stopped = false;
+        for request in requests {
+            let skip_reason = if stopped && request.include_in_run {
+                Some("Run stopped after an earlier failure".to_string())
+            } else {
+                Self::skip_reason(request, &variables)
+            };
+            if let Some(reason) = skip_reason {
+                results.push(Self::skipped(request, reason));
+                continue;
+            }
+
+            let (result, throttled) = self.send(request, &mut variables, pacer, options).await;
+            throttle_events.extend(throttled);
+            stopped = result.status == RunStatus::Failed && !continue_on_error;
+            results.push(result);
+        }
+        (results, throttle_events)
+    }
+
+    /// Every request starts from the same variables, so run conditions see only those
+    async fn run_parallel(
+        &self,
+        requests: &[&Request],
+        variables: &HashMap<String, String>,
+        pacer: &Pacer,
+        concurrency: usize,
+        options: &ExecuteOptions,
+    ) -> (Vec<RequestRunResult>, Vec<ThrottleEvent>) {
+        let mut outcomes: Vec<_> = stream::iter(requests.iter().enumerate())
+            .map(|(index, request)| async move {
+                if let Some(reason) = Self::skip_reason(request, variables) {
+                    return (index, Self::skipped(request, reason), Vec::new());
+                }
+                let (result, throttled) = self.send(request, &mut variables.clone(), pacer, options).await;
+                (index, result, throttled)
+            })
+            .buffer_unordered(concurrency)
+            .collect()
+            .await;
+        outcomes.sort_by_key(|(index, _, _)| *index);
+
+        let mut results = Vec::new();
+        let mut throttle_events = Vec::new();
+        for (_, result, throttled) in outcomes {
+            results.push(result);
+            throttle_events.extend(throttled);
+        }
+        (results, throttle_events)
+    }
+
+    /// Names of the requests a run would send that need confirming first, being mutating
+    /// requests against a protected environment
+    pub fn requests_needing_confirmation<'a>(requests: &'a [Request], environment: Option<&Environment>) -> Vec<&'a str> {
+        requests.iter()
+            .filter(|request| request.include_in_run)
+            .filter(|request| HttpService::requires_confirmation(&HttpMethod::from(request.method.as_str()), environment))
+            .map(|request| request.name.as_str())
+            .collect()
+    }
+
+    /// Why a run asked to go in parallel has to go one request at a time instead
+    fn sequential_reason(requests: &[&Request], continue_on_error: bool) -> Option<String> {
+        if !continue_on_error {
+            return Some("Stopping at the first failure needs a sequential run".to_string());
+        }
+        let included = requests.iter().filter(|request| request.include_in_run);
+        for request in included {
+            if !request.get_extractions().is_empty() {
+                return Some(format!("'{}' extracts variables for the requests after it", request.name));
+            }
+            if !request.get_pre_request().is_empty() {
+                return Some(format!("'{}' has pre-request steps that set variables", request.name));
+            }
+        }
+        None
+    }
+
+    fn skip_reason(request: &Request, variables: &HashMap<String, String>) -> Option<String> {
+        if !request.include_in_run {
+            return Some("Excluded from collection runs".to_string());
+        }
+        // A condition made invalid by hand-editing the collection file skips the request too
+        let condition = request.run_condition.as_ref()?;
+        match parse_run_condition(condition) {
+            Ok(parsed) if parsed.evaluate(variables) => None,
+            Ok(_) => Some(format!("Condition not met: {}", condition)),
+            Err(e) => Some(e.to_string()),
+        }
+    }
+
+    fn skipped(request: &Request, reason: String) -> RequestRunResult {
+        RequestRunResult {
+            request_id: request.id.clone(),
+            name: request.name.clone(),
+            status: RunStatus::Skipped,
+            response: None,
+            error: None,
+            skip_reason: Some(reason),
+            duration_ms: 0,
+            assertions: Vec::new(),
+        }
+    }
+
+    /// Send a request when the pacing allows, resending after each 429 it's asked to wait out
+    async fn send(
+        &self,
+        request: &Request,
+        variables: &mut HashMap<String, String>,
+        pacer: &Pacer,
+        options: &ExecuteOptions,
+    ) -> (RequestRunResult, Vec<ThrottleEvent>) {
+        let mut throttle_events = Vec::new();
+        loop {
+            pacer.wait_turn().await;
+
+            // Values extracted from a throttled response are thrown away with it
+            let mut attempt_variables = variables.clone();
+            let result = self.execute(request, &mut attempt_variables, options).await;
+            let retry_after = Self::retry_after(&result).filter(|_| throttle_events.len() < MAX_THROTTLE_RETRIES);
+            if let Some(wait) = retry_after {
+                throttle_events.push(ThrottleEvent {
+                    request_id: request.id.clone(),
+                    name: request.name.clone(),
+                    waited_ms: wait.as_millis() as u64,
+                });
+                tokio::time::sleep(wait).await;
+                continue;
+            }
+            *variables = attempt_variables;
+            return (result, throttle_events);
+        }
+    }
+
+    /// How long a 429 response asked to be left alone, capped at `MAX_THROTTLE_WAIT`
+    fn retry_after(result: &RequestRunResult) -> Option<Duration> {
+        let response = result.response.as_ref().filter(|response| response.status == 429)?;
+        let value = response.headers.iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case("retry-after"))
+            .map(|(_, value)| value)?;
+        parse_retry_after(value).map(|wait| wait.min(MAX_THROTTLE_WAIT))
+    }
+
+    /// Run the request's pre-request steps, send it and check its assertions. Variables the
+    /// steps set and values the response yields are added to `variables`.
+    /// A request with assertions passes when they all do; otherwise any status below 400 passes.
+    pub async fn execute(
+        &self,
+        request: &Request,
+        variables: &mut HashMap<String, String>,
+        options: &ExecuteOptions,
+    ) -> RequestRunResult {
+        let started = Instant::now();
+        let mut scoped = variables.clone();
+        scoped.extend(self.request_variables.get(&request.id).cloned().unwrap_or_default());
+        let steps = request.get_pre_request();
+        let sent = match PreRequestService::new().apply(&steps, &mut scoped) {
+            Ok(()) => {
+                // What the steps set carries on to later requests; the request's own variables don't
+                for step in &steps {
+                    if let Some(value) = scoped.get(step.variable()) {
+                        variables.insert(step.variable().to_string(), value.clone());
+                    }
+                }
+                self.http_service
+                    .execute_request(request.to_http_request(), Some(scoped), options)
+                    .await
+            }
+            Err(e) => Err(e),
+        };
+        match sent {
+            Ok(response) => {
+                variables.extend(ExtractionService::new().extract(&request.get_extractions(), &response));
+                let assertions = AssertionService::new().evaluate(&request.get_assertions(), &response);
+                let passed = if assertions.is_empty() {
+                    response.status < 400
+                } else {
+                    assertions.iter().all(|a| a.passed)
+                };
+                RequestRunResult {
+                    request_id: request.id.clone(),
+                    name: request.name.clone(),
+                    status: if passed { RunStatus::Passed } else { RunStatus::Failed },
+                    duration_ms: response.timing.total_time_ms,
+                    response: Some(response),
+                    error: None,
+                    skip_reason: None,
+                    assertions,
+                }
+            }
+            Err(e) => RequestRunResult {
+                request_id: request.id.clone(),
+                name: request.name.clone(),
+                status: RunStatus::Failed,
+                response: None,
+                error: Some(e.to_string()),
+                skip_reason: None,
+                duration_ms: started.elapsed().as_millis() as u64,
+                assertions: Vec::new(),
+            },
+        }
+    }
+}
@@ -1,10 +1,79 @@
 use crate::models::collection::{
     Collection, Request, CreateCollectionRequest, UpdateCollectionRequest,
     CreateRequestRequest, UpdateRequestRequest, CollectionSummary,
+    RequestExample, CreateRequestExampleRequest, CurlImportResult, CurlImportError,
+    Extraction, ExtractionSource, Assertion, PreRequestStep, RequestSearchResult,
 };
+use crate::services::assertion_service::validate_assertion;
+use crate::services::curl_import_service::CurlImportService;
+use crate::services::database_service::DatabaseService;
+use crate::services::extraction_service::parse_json_path;
 use crate::services::file_sync_service::FileSyncService;
-use sqlx::{SqlitePool, Row};
+use crate::services::har_service::HarService;
+use crate::services::pre_request_service::validate_pre_request_step;
+use crate::services::run_condition::parse_run_condition;
+use sqlx::{sqlite::SqliteRow, SqlitePool, Row};
 use anyhow::{Result, anyhow};
+use regex::Regex;
+use std::sync::Arc;
+
+const SEARCH_RESULT_LIMIT: i64 = 50;
+
+/// Insert every column of a request, on the pool or inside a transaction
+async fn insert_request_row<'e, E>(executor: E, req: &Request) -> Result<(), sqlx::Error>
+where
+    E: sqlx::Executor<'e, Database = sqlx::Sqlite>,
+{
+    sqlx::query(
+        r#"
+        INSERT INTO requests (
+            id, collection_id, name, description, method, url, headers, body, body_type,
+            auth_type, auth_config, follow_redirects, timeout_ms, order_index, include_in_run,
+            raw_stream, connect_timeout_ms, run_condition, pre_request, extractions, assertions, created_at, updated_at
+        )
+        VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21, ?22, ?23)
+        "#
+    )
+    .bind(&req.id)
+    .bind(&req.collection_id)
+    .bind(&req.name)
+    .bind(&req.description)
+    .bind(&req.method)
+    .bind(&req.url)
+    .bind(&req.headers)
+    .bind(&req.body)
+    .bind(&req.body_type)
+    .bind(&req.auth_type)
+    .bind(&req.auth_config)
+    .bind(req.follow_redirects)
+    .bind(req.timeout_ms as i64)
+    .bind(req.order_index)
+    .bind(req.include_in_run)
+    .bind(req.raw_stream)
+    .bind(req.connect_timeout_ms.map(i64::from))
+    .bind(&req.run_condition)
+    .bind(&req.pre_request)
+    .bind(&req.extractions)
+    .bind(&req.assertions)
+    .bind(req.created_at.to_rfc3339())
+    .bind(req.updated_at.to_rfc3339())
+    .execute(executor)
+    .await?;
+    Ok(())
+}
+
+/// `original` with every setting kept, under a new ID in `collection_id`
+fn copied_request(original: &Request, collection_id: &str) -> Request {
+    let now = chrono::Utc::now();
+    Request {
+        id: uuid::Uuid::new_v4().to_string(),
+        collection_id: collection_id.to_string(),
+        created_at: now,
+        updated_at: now,
+        ..original.clone()
+    }
+}
+const SNIPPET_CONTEXT_CHARS: usize = 30;
 
 pub struct CollectionService {
     pool: SqlitePool,
@@ -14,19 +83,22 @@ pub struct CollectionService {
 impl CollectionService {
     pub fn new(pool: SqlitePool) -> Self {
         Self { 
+            file_sync: FileSyncService::new(Arc::new(DatabaseService::from_pool(pool.clone()))),
             pool,
-            file_sync: FileSyncService::new(),
         }
     }
 
     // Collection CRUD operations
     pub async fn create_collection(&self, request: CreateCollectionRequest) -> Result<Collection> {
         let collection = Collection::new(request);
+        if let Some(parent_id) = &collection.parent_id {
+            self.check_parent(&collection, parent_id).await?;
+        }
         
         sqlx::query(
             r#"
-            INSERT INTO collections (id, workspace_id, name, description, folder_path, git_branch, is_active, created_at, updated_at)
-            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
+            INSERT INTO collections (id, workspace_id, name, description, folder_path, git_branch, parent_id, is_active, created_at, updated_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
             "#
         )
         .bind(&collection.id)
@@ -35,6 +107,7 @@ impl CollectionService {
         .bind(&collection.description)
         .bind(&collection.folder_path)
         .bind(&collection.git_branch)
+        .bind(&collection.parent_id)
         .bind(collection.is_active)
         .bind(&collection.created_at.to_rfc3339())
         .bind(&collection.updated_at.to_rfc3339())
@@ -54,28 +127,14 @@ impl CollectionService {
 
     pub async fn get_collection(&self, id: &str) -> Result<Option<Collection>> {
         let row = sqlx::query(
-            "SELECT * FROM collections WHERE id = ?1"
+            "SELECT * FROM collections WHERE id = ?1 AND deleted_at IS NULL"
         )
         .bind(id)
         .fetch_optional(&self.pool)
         .await
         .map_err(|e| anyhow!("Failed to get collection: {}", e))?;
 
-        if let Some(row) = row {
-            Ok(Some(Collection {
-                id: row.get("id"),
-                workspace_id: row.get("workspace_id"),
-                name: row.get("name"),
-                description: row.get("description"),
-                folder_path: row.get("folder_path"),
-                git_branch: row.get("git_branch"),
-                is_active: row.get::<i64, _>("is_active") != 0,
-                created_at: chrono::DateTime::parse_from_rfc3339(&row.get::<String, _>("created_at"))?.with_timezone(&chrono::Utc),
-                updated_at: chrono::DateTime::parse_from_rfc3339(&row.get::<String, _>("updated_at"))?.with_timezone(&chrono::Utc),
-            }))
-        } else {
-            Ok(None)
-        }
+        row.map(|row| Self::row_to_collection(&row)).transpose()
     }
 
     pub async fn update_collection(&self, request: UpdateCollectionRequest) -> Result<Collection> {
@@ -83,18 +142,22 @@ impl CollectionService {
             .ok_or_else(|| anyhow!("Collection not found"))?;
         
         collection.update(request);
+        if let Some(parent_id) = &collection.parent_id {
+            self.check_parent(&collection, parent_id).await?;
+        }
 
         sqlx::query(
             r#"
             UPDATE collections 
-            SET name = ?1, description = ?2, folder_path = ?3, git_branch = ?4, is_active = ?5, updated_at = ?6
-            WHERE id = ?7
+            SET name = ?1, description = ?2, folder_path = ?3, git_branch = ?4, parent_id = ?5, is_active = ?6, updated_at = ?7
+            WHERE id = ?8
             "#
         )
         .bind(&collection.name)
         .bind(&collection.description)
         .bind(&collection.folder_path)
         .bind(&collection.git_branch)
+        .bind(&collection.parent_id)
         .bind(collection.is_active)
         .bind(&collection.updated_at.to_rfc3339())
         .bind(&collection.id)
@@ -112,11 +175,21 @@ impl CollectionService {
         Ok(collection)
     }
 
+    /// Move a collection to the trash. It stays restorable until `purge_deleted` removes it.
     pub async fn delete_collection(&self, id: &str) -> Result<()> {
         // Get collection info before deleting
         let collection = self.get_collection(id).await?;
+
+        // Nested collections move up to the deleted collection's parent
+        sqlx::query("UPDATE collections SET parent_id = ?1 WHERE parent_id = ?2")
+            .bind(collection.as_ref().and_then(|c| c.parent_id.as_ref()))
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| anyhow!("Failed to detach child collections: {}", e))?;
         
-        sqlx::query("DELETE FROM collections WHERE id = ?1")
+        sqlx::query("UPDATE collections SET deleted_at = ?1 WHERE id = ?2")
+            .bind(chrono::Utc::now().to_rfc3339())
             .bind(id)
             .execute(&self.pool)
             .await
@@ -133,42 +206,175 @@ impl CollectionService {
         Ok(())
     }
 
+    /// Bring a deleted collection back with its requests. It returns to its old parent if that
+    /// still exists, otherwise to the top level; collections nested under it before the delete
+    /// were moved up then and stay where they are.
+    pub async fn restore_collection(&self, id: &str) -> Result<Collection> {
+        let row = sqlx::query("SELECT * FROM collections WHERE id = ?1 AND deleted_at IS NOT NULL")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| anyhow!("Failed to get collection: {}", e))?
+            .ok_or_else(|| anyhow!("No deleted collection with id {}", id))?;
+        let mut collection = Self::row_to_collection(&row)?;
+
+        if let Some(parent_id) = &collection.parent_id {
+            if self.get_collection(parent_id).await?.is_none() {
+                collection.parent_id = None;
+            }
+        }
+        collection.updated_at = chrono::Utc::now();
+
+        sqlx::query("UPDATE collections SET deleted_at = NULL, parent_id = ?1, updated_at = ?2 WHERE id = ?3")
+            .bind(&collection.parent_id)
+            .bind(&collection.updated_at.to_rfc3339())
+            .bind(&collection.id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| anyhow!("Failed to restore collection: {}", e))?;
+
+        let requests = self.list_requests(&collection.id).await?;
+        if let Err(e) = self.file_sync.write_collection_file(&collection, requests).await {
+            eprintln!("Warning: Failed to write collection file: {}", e);
+        }
+
+        Ok(collection)
+    }
+
     pub async fn list_collections(&self, workspace_id: &str) -> Result<Vec<Collection>> {
         let rows = sqlx::query(
-            "SELECT * FROM collections WHERE workspace_id = ?1 ORDER BY updated_at DESC"
+            "SELECT * FROM collections WHERE workspace_id = ?1 AND deleted_at IS NULL ORDER BY updated_at DESC"
         )
         .bind(workspace_id)
         .fetch_all(&self.pool)
         .await
         .map_err(|e| anyhow!("Failed to list collections: {}", e))?;
 
-        let mut collections = Vec::new();
-        for row in rows {
-            collections.push(Collection {
-                id: row.get("id"),
-                workspace_id: row.get("workspace_id"),
-                name: row.get("name"),
-                description: row.get("description"),
-                folder_path: row.get("folder_path"),
-                git_branch: row.get("git_branch"),
-                is_active: row.get::<i64, _>("is_active") != 0,
-                created_at: chrono::DateTime::parse_from_rfc3339(&row.get::<String, _>("created_at"))?.with_timezone(&chrono::Utc),
-                updated_at: chrono::DateTime::parse_from_rfc3339(&row.get::<String, _>("updated_at"))?.with_timezone(&chrono::Utc),
-            });
+        rows.iter().map(Self::row_to_collection).collect()
+    }
+
+    /// Collections nested directly under `parent_id`, by name
+    pub async fn list_child_collections(&self, parent_id: &str) -> Result<Vec<Collection>> {
+        let rows = sqlx::query(
+            "SELECT * FROM collections WHERE parent_id = ?1 AND deleted_at IS NULL ORDER BY name ASC"
+        )
+        .bind(parent_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| anyhow!("Failed to list child collections: {}", e))?;
+
+        rows.iter().map(Self::row_to_collection).collect()
+    }
+
+    fn row_to_collection(row: &SqliteRow) -> Result<Collection> {
+        Ok(Collection {
+            id: row.get("id"),
+            workspace_id: row.get("workspace_id"),
+            name: row.get("name"),
+            description: row.get("description"),
+            folder_path: row.get("folder_path"),
+            git_branch: row.get("git_branch"),
+            parent_id: row.get("parent_id"),
+            is_active: row.get::<i64, _>("is_active") != 0,
+            created_at: chrono::DateTime::parse_from_rfc3339(&row.get::<String, _>("created_at"))?.with_timezone(&chrono::Utc),
+            updated_at: chrono::DateTime::parse_from_rfc3339(&row.get::<String, _>("updated_at"))?.with_timezone(&chrono::Utc),
+        })
+    }
+
+    /// A parent must be in the same workspace and mustn't be the collection itself or one of its descendants
+    async fn check_parent(&self, collection: &Collection, parent_id: &str) -> Result<()> {
+        let parent = self.get_collection(parent_id).await?
+            .ok_or_else(|| anyhow!("Parent collection {} not found", parent_id))?;
+        if parent.workspace_id != collection.workspace_id {
+            return Err(anyhow!("A parent collection must be in the same workspace"));
+        }
+
+        let mut next = Some(parent);
+        let mut seen = std::collections::HashSet::new();
+        while let Some(ancestor) = next {
+            if ancestor.id == collection.id {
+                return Err(anyhow!("A collection can't be nested inside itself or one of its descendants"));
+            }
+            if !seen.insert(ancestor.id.clone()) {
+                break;
+            }
+            next = match &ancestor.parent_id {
+                Some(id) => self.get_collection(id).await?,
+                None => None,
+            };
+        }
+        Ok(())
+    }
+
+    /// Collections labelled with `branch`, plus those without a branch label, which belong to every branch
+    pub async fn list_collections_by_branch(&self, workspace_id: &str, branch: &str) -> Result<Vec<Collection>> {
+        Ok(self.list_collections(workspace_id).await?
+            .into_iter()
+            .filter(|c| c.git_branch.as_deref().is_none_or(|b| b == branch))
+            .collect())
+    }
+
+    /// Move a collection to another branch label; `None` makes it visible on every branch
+    pub async fn set_collection_branch(&self, id: &str, branch: Option<&str>) -> Result<Collection> {
+        let mut collection = self.get_collection(id).await?
+            .ok_or_else(|| anyhow!("Collection not found"))?;
+
+        collection.git_branch = branch.map(str::trim).filter(|b| !b.is_empty()).map(str::to_string);
+        collection.updated_at = chrono::Utc::now();
+
+        sqlx::query("UPDATE collections SET git_branch = ?1, updated_at = ?2 WHERE id = ?3")
+            .bind(&collection.git_branch)
+            .bind(&collection.updated_at.to_rfc3339())
+            .bind(&collection.id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| anyhow!("Failed to update collection branch: {}", e))?;
+
+        let requests = self.list_requests(&collection.id).await?;
+        if let Err(e) = self.file_sync.write_collection_file(&collection, requests).await {
+            eprintln!("Warning: Failed to update collection file: {}", e);
+        }
+
+        Ok(collection)
+    }
+
+    /// Nest a collection under another; `None` moves it back to the top level
+    pub async fn set_collection_parent(&self, id: &str, parent_id: Option<&str>) -> Result<Collection> {
+        let mut collection = self.get_collection(id).await?
+            .ok_or_else(|| anyhow!("Collection not found"))?;
+        if let Some(parent_id) = parent_id {
+            self.check_parent(&collection, parent_id).await?;
         }
 
-        Ok(collections)
+        collection.parent_id = parent_id.map(str::to_string);
+        collection.updated_at = chrono::Utc::now();
+
+        sqlx::query("UPDATE collections SET parent_id = ?1, updated_at = ?2 WHERE id = ?3")
+            .bind(&collection.parent_id)
+            .bind(&collection.updated_at.to_rfc3339())
+            .bind(&collection.id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| anyhow!("Failed to update collection parent: {}", e))?;
+
+        let requests = self.list_requests(&collection.id).await?;
+        if let Err(e) = self.file_sync.write_collection_file(&collection, requests).await {
+            eprintln!("Warning: Failed to update collection file: {}", e);
+        }
+
+        Ok(collection)
     }
 
     pub async fn get_collection_summaries(&self, workspace_id: &str) -> Result<Vec<CollectionSummary>> {
         let rows = sqlx::query(
             r#"
             SELECT 
-                c.id, c.workspace_id, c.name, c.description, c.folder_path, c.git_branch, c.is_active, c.created_at, c.updated_at,
+                c.id, c.workspace_id, c.name, c.description, c.folder_path, c.git_branch, c.parent_id, c.is_active,
+                c.created_at, c.updated_at,
                 COUNT(r.id) as request_count
             FROM collections c
-            LEFT JOIN requests r ON c.id = r.collection_id
-            WHERE c.workspace_id = ?1
+            LEFT JOIN requests r ON c.id = r.collection_id AND r.deleted_at IS NULL
+            WHERE c.workspace_id = ?1 AND c.deleted_at IS NULL
             GROUP BY c.id
             ORDER BY c.updated_at DESC
             "#
@@ -187,6 +393,7 @@ impl CollectionService {
                 description: row.get("description"),
                 folder_path: row.get("folder_path"),
                 git_branch: row.get("git_branch"),
+                parent_id: row.get("parent_id"),
                 is_active: row.get::<i64, _>("is_active") != 0,
                 request_count: row.get::<i64, _>("request_count"),
                 created_at: chrono::DateTime::parse_from_rfc3339(&row.get::<String, _>("created_at"))?.with_timezone(&chrono::Utc),
@@ -201,34 +408,9 @@ impl CollectionService {
     pub async fn create_request(&self, request: CreateRequestRequest) -> Result<Request> {
         let req = Request::new(request);
         
-        sqlx::query(
-            r#"
-            INSERT INTO requests (
-                id, collection_id, name, description, method, url, headers, body, body_type,
-                auth_type, auth_config, follow_redirects, timeout_ms, order_index, created_at, updated_at
-            )
-            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16)
-            "#
-        )
-        .bind(&req.id)
-        .bind(&req.collection_id)
-        .bind(&req.name)
-        .bind(&req.description)
-        .bind(&req.method)
-        .bind(&req.url)
-        .bind(&req.headers)
-        .bind(&req.body)
-        .bind(&req.body_type)
-        .bind(&req.auth_type)
-        .bind(&req.auth_config)
-        .bind(req.follow_redirects)
-        .bind(req.timeout_ms as i64)
-        .bind(req.order_index)
-        .bind(&req.created_at.to_rfc3339())
-        .bind(&req.updated_at.to_rfc3339())
-        .execute(&self.pool)
-        .await
-        .map_err(|e| anyhow!("Failed to create request: {}", e))?;
+        insert_request_row(&self.pool, &req)
+            .await
+            .map_err(|e| anyhow!("Failed to create request: {}", e))?;
 
         // Update collection file with new request
         if let Ok(Some(collection)) = self.get_collection(&req.collection_id).await {
@@ -242,34 +424,13 @@ impl CollectionService {
     }
 
     pub async fn get_request(&self, id: &str) -> Result<Option<Request>> {
-        let row = sqlx::query("SELECT * FROM requests WHERE id = ?1")
+        let row = sqlx::query("SELECT * FROM requests WHERE id = ?1 AND deleted_at IS NULL")
             .bind(id)
             .fetch_optional(&self.pool)
             .await
             .map_err(|e| anyhow!("Failed to get request: {}", e))?;
 
-        if let Some(row) = row {
-            Ok(Some(Request {
-                id: row.get("id"),
-                collection_id: row.get("collection_id"),
-                name: row.get("name"),
-                description: row.get("description"),
-                method: row.get("method"),
-                url: row.get("url"),
-                headers: row.get("headers"),
-                body: row.get("body"),
-                body_type: row.get("body_type"),
-                auth_type: row.get("auth_type"),
-                auth_config: row.get("auth_config"),
-                follow_redirects: row.get::<i64, _>("follow_redirects") != 0,
-                timeout_ms: row.get::<i64, _>("timeout_ms") as u32,
-                order_index: row.get("order_index"),
-                created_at: chrono::DateTime::parse_from_rfc3339(&row.get::<String, _>("created_at"))?.with_timezone(&chrono::Utc),
-                updated_at: chrono::DateTime::parse_from_rfc3339(&row.get::<String, _>("updated_at"))?.with_timezone(&chrono::Utc),
-            }))
-        } else {
-            Ok(None)
-        }
+        row.map(|row| Self::row_to_request(&row)).transpose()
     }
 
     pub async fn update_request(&self, request: UpdateRequestRequest) -> Result<Request> {
@@ -317,11 +478,13 @@ impl CollectionService {
         Ok(req)
     }
 
+    /// Move a request to the trash. It stays restorable until `purge_deleted` removes it.
     pub async fn delete_request(&self, id: &str) -> Result<()> {
         // Get request info before deleting
         let request = self.get_request(id).await?;
         
-        sqlx::query("DELETE FROM requests WHERE id = ?1")
+        sqlx::query("UPDATE requests SET deleted_at = ?1 WHERE id = ?2")
+            .bind(chrono::Utc::now().to_rfc3339())
             .bind(id)
             .execute(&self.pool)
             .await
@@ -340,67 +503,743 @@ impl CollectionService {
         Ok(())
     }
 
+    /// Bring a deleted request back into its collection, which must not itself be deleted
+    pub async fn restore_request(&self, id: &str) -> Result<Request> {
+        let row = sqlx::query("SELECT * FROM requests WHERE id = ?1 AND deleted_at IS NOT NULL")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| anyhow!("Failed to get request: {}", e))?
+            .ok_or_else(|| anyhow!("No deleted request with id {}", id))?;
+        let mut req = Self::row_to_request(&row)?;
+        let collection = self.get_collection(&req.collection_id).await?
+            .ok_or_else(|| anyhow!("The request's collection is deleted; restore the collection first"))?;
+        req.updated_at = chrono::Utc::now();
+
+        sqlx::query("UPDATE requests SET deleted_at = NULL, updated_at = ?1 WHERE id = ?2")
+            .bind(&req.updated_at.to_rfc3339())
+            .bind(&req.id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| anyhow!("Failed to restore request: {}", e))?;
+
+        let requests = self.list_requests(&req.collection_id).await?;
+        if let Err(e) = self.file_sync.write_collection_file(&collection, requests).await {
+            eprintln!("Warning: Failed to update collection file after restoring request: {}", e);
+        }
+
+        Ok(req)
+    }
+
+    /// Permanently remove the workspace's collections and requests deleted more than
+    /// `older_than_days` days ago, returning how many were removed. A purged collection takes
+    /// its remaining requests with it; those aren't counted.
+    pub async fn purge_deleted(&self, workspace_id: &str, older_than_days: u32) -> Result<u64> {
+        let cutoff = (chrono::Utc::now() - chrono::Duration::days(older_than_days.into())).to_rfc3339();
+
+        let mut tx = self.pool.begin().await?;
+        let requests = sqlx::query(
+            r#"
+            DELETE FROM requests
+            WHERE deleted_at IS NOT NULL AND deleted_at <= ?1
+              AND collection_id IN (SELECT id FROM collections WHERE workspace_id = ?2)
+            "#
+        )
+        .bind(&cutoff)
+        .bind(workspace_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| anyhow!("Failed to purge deleted requests: {}", e))?
+        .rows_affected();
+        let collections = sqlx::query("DELETE FROM collections WHERE workspace_id = ?1 AND deleted_at IS NOT NULL AND deleted_at <= ?2")
+            .bind(workspace_id)
+            .bind(&cutoff)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| anyhow!("Failed to purge deleted collections: {}", e))?
+            .rows_affected();
+        tx.commit().await?;
+
+        Ok(requests + collections)
+    }
+
     pub async fn list_requests(&self, collection_id: &str) -> Result<Vec<Request>> {
         let rows = sqlx::query(
-            "SELECT * FROM requests WHERE collection_id = ?1 ORDER BY order_index ASC, created_at ASC"
+            "SELECT * FROM requests WHERE collection_id = ?1 AND deleted_at IS NULL ORDER BY order_index ASC, created_at ASC"
         )
         .bind(collection_id)
         .fetch_all(&self.pool)
         .await
         .map_err(|e| anyhow!("Failed to list requests: {}", e))?;
 
+        rows.iter().map(Self::row_to_request).collect()
+    }
+
+    /// Requests in the workspace whose name, description, URL, headers or body contain `query`,
+    /// ignoring case. Best matches come first.
+    pub async fn search_requests(&self, workspace_id: &str, query: &str) -> Result<Vec<RequestSearchResult>> {
+        let query = query.trim();
+        if query.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        // Trigrams can't match fewer than three characters, so short queries scan with LIKE
+        let rows = if query.chars().count() >= 3 {
+            sqlx::query(
+                r#"
+                SELECT r.*, c.name AS collection_name
+                FROM requests_fts
+                JOIN requests r ON r.rowid = requests_fts.rowid
+                JOIN collections c ON c.id = r.collection_id
+                WHERE requests_fts MATCH ?1 AND c.workspace_id = ?2 AND r.deleted_at IS NULL AND c.deleted_at IS NULL
+                ORDER BY requests_fts.rank
+                LIMIT ?3
+                "#
+            )
+            .bind(format!("\"{}\"", query.replace('"', "\"\"")))
+            .bind(workspace_id)
+            .bind(SEARCH_RESULT_LIMIT)
+            .fetch_all(&self.pool)
+            .await
+        } else {
+            let pattern = format!("%{}%", query.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_"));
+            sqlx::query(
+                r#"
+                SELECT r.*, c.name AS collection_name
+                FROM requests r
+                JOIN collections c ON c.id = r.collection_id
+                WHERE c.workspace_id = ?2 AND r.deleted_at IS NULL AND c.deleted_at IS NULL AND (
+                    r.name LIKE ?1 ESCAPE '\' OR r.description LIKE ?1 ESCAPE '\' OR r.url LIKE ?1 ESCAPE '\'
+                    OR r.headers LIKE ?1 ESCAPE '\' OR r.body LIKE ?1 ESCAPE '\'
+                )
+                ORDER BY r.name ASC
+                LIMIT ?3
+                "#
+            )
+            .bind(pattern)
+            .bind(workspace_id)
+            .bind(SEARCH_RESULT_LIMIT)
+            .fetch_all(&self.pool)
+            .await
+        }
+        .map_err(|e| anyhow!("Failed to search requests: {}", e))?;
+
+        rows.iter().map(|row| {
+            let request = Self::row_to_request(row)?;
+            let fields = [
+                ("name", Some(&request.name)),
+                ("url", Some(&request.url)),
+                ("description", request.description.as_ref()),
+                ("headers", Some(&request.headers)),
+                ("body", request.body.as_ref()),
+            ];
+            let (matched_field, snippet) = fields.iter()
+                .find_map(|(field, text)| text.and_then(|text| match_snippet(text, query)).map(|snippet| (field.to_string(), snippet)))
+                .unwrap_or_else(|| ("name".to_string(), request.name.clone()));
+
+            Ok(RequestSearchResult {
+                request_id: request.id,
+                name: request.name,
+                method: request.method,
+                url: request.url,
+                collection_id: request.collection_id,
+                collection_name: row.get("collection_name"),
+                matched_field,
+                snippet,
+            })
+        }).collect()
+    }
+
+    fn row_to_request(row: &SqliteRow) -> Result<Request> {
+        Ok(Request {
+            id: row.get("id"),
+            collection_id: row.get("collection_id"),
+            name: row.get("name"),
+            description: row.get("description"),
+            method: row.get("method"),
+            url: row.get("url"),
+            headers: row.get("headers"),
+            body: row.get("body"),
+            body_type: row.get("body_type"),
+            auth_type: row.get("auth_type"),
+            auth_config: row.get("auth_config"),
+            follow_redirects: row.get::<i64, _>("follow_redirects") != 0,
+            timeout_ms: row.get::<i64, _>("timeout_ms") as u32,
+            order_index: row.get("order_index"),
+            include_in_run: row.get::<i64, _>("include_in_run") != 0,
+            raw_stream: row.get::<i64, _>("raw_stream") != 0,
+            connect_timeout_ms: row.get::<Option<i64>, _>("connect_timeout_ms").map(|ms| ms as u32),
+            run_condition: row.get("run_condition"),
+            pre_request: row.get("pre_request"),
+            extractions: row.get("extractions"),
+            assertions: row.get("assertions"),
+            created_at: chrono::DateTime::parse_from_rfc3339(&row.get::<String, _>("created_at"))?.with_timezone(&chrono::Utc),
+            updated_at: chrono::DateTime::parse_from_rfc3339(&row.get::<String, _>("updated_at"))?.with_timezone(&chrono::Utc),
+        })
+    }
+
+    /// Create one request per curl command, appended to the collection in the given order.
+    /// Commands that fail to parse are reported instead of aborting the import.
+    pub async fn import_curl_commands(&self, collection_id: &str, curl_commands: &[String]) -> Result<CurlImportResult> {
+        self.get_collection(collection_id).await?
+            .ok_or_else(|| anyhow!("Collection not found"))?;
+
+        let parser = CurlImportService::new();
+        let mut next_index = self.list_requests(collection_id).await?
+            .iter()
+            .map(|r| r.order_index + 1)
+            .max()
+            .unwrap_or(0);
+
+        let mut result = CurlImportResult { requests: Vec::new(), errors: Vec::new() };
+        for (index, command) in curl_commands.iter().enumerate() {
+            match parser.parse(command) {
+                Ok(http_request) => {
+                    let name = request_name_from_url(&http_request.url);
+                    let create = CreateRequestRequest::from_http_request(collection_id, &name, &http_request, next_index);
+                    result.requests.push(self.create_request(create).await?);
+                    next_index += 1;
+                }
+                Err(e) => result.errors.push(CurlImportError {
+                    index,
+                    command: command.clone(),
+                    message: e.to_string(),
+                }),
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// Create one request per HAR entry, appended to the collection in capture order
+    pub async fn import_har(&self, collection_id: &str, har_json: &str) -> Result<Vec<Request>> {
+        self.get_collection(collection_id).await?
+            .ok_or_else(|| anyhow!("Collection not found"))?;
+        let http_requests = HarService::new().import(har_json)?;
+
+        let mut next_index = self.list_requests(collection_id).await?
+            .iter()
+            .map(|r| r.order_index + 1)
+            .max()
+            .unwrap_or(0);
+
         let mut requests = Vec::new();
-        for row in rows {
-            requests.push(Request {
+        for http_request in &http_requests {
+            let name = request_name_from_url(&http_request.url);
+            let create = CreateRequestRequest::from_http_request(collection_id, &name, http_request, next_index);
+            requests.push(self.create_request(create).await?);
+            next_index += 1;
+        }
+        Ok(requests)
+    }
+
+    // Request example operations
+    pub async fn create_request_example(&self, request: CreateRequestExampleRequest) -> Result<RequestExample> {
+        self.get_request(&request.request_id).await?
+            .ok_or_else(|| anyhow!("Request not found"))?;
+
+        let example = RequestExample::new(request);
+
+        sqlx::query(
+            r#"
+            INSERT INTO request_examples (id, request_id, name, status, headers, body, volatile_fields, created_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+            "#
+        )
+        .bind(&example.id)
+        .bind(&example.request_id)
+        .bind(&example.name)
+        .bind(example.status as i64)
+        .bind(&example.headers)
+        .bind(&example.body)
+        .bind(&example.volatile_fields)
+        .bind(&example.created_at.to_rfc3339())
+        .execute(&self.pool)
+        .await
+        .map_err(|e| anyhow!("Failed to create request example: {}", e))?;
+
+        Ok(example)
+    }
+
+    pub async fn get_request_example(&self, id: &str) -> Result<Option<RequestExample>> {
+        let row = sqlx::query("SELECT * FROM request_examples WHERE id = ?1")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| anyhow!("Failed to get request example: {}", e))?;
+
+        if let Some(row) = row {
+            Ok(Some(RequestExample {
                 id: row.get("id"),
-                collection_id: row.get("collection_id"),
+                request_id: row.get("request_id"),
                 name: row.get("name"),
-                description: row.get("description"),
-                method: row.get("method"),
-                url: row.get("url"),
+                status: row.get::<i64, _>("status") as u16,
                 headers: row.get("headers"),
                 body: row.get("body"),
-                body_type: row.get("body_type"),
-                auth_type: row.get("auth_type"),
-                auth_config: row.get("auth_config"),
-                follow_redirects: row.get::<i64, _>("follow_redirects") != 0,
-                timeout_ms: row.get::<i64, _>("timeout_ms") as u32,
-                order_index: row.get("order_index"),
+                volatile_fields: row.get("volatile_fields"),
                 created_at: chrono::DateTime::parse_from_rfc3339(&row.get::<String, _>("created_at"))?.with_timezone(&chrono::Utc),
-                updated_at: chrono::DateTime::parse_from_rfc3339(&row.get::<String, _>("updated_at"))?.with_timezone(&chrono::Utc),
-            });
+            }))
+        } else {
+            Ok(None)
         }
+    }
 
-        Ok(requests)
+    /// Reload a request after one of its settings changed and write its collection file.
+    /// Trashed requests aren't updated, so they come back as not found.
+    async fn updated_request(&self, id: &str) -> Result<Request> {
+        let req = self.get_request(id).await?
+            .ok_or_else(|| anyhow!("Request not found"))?;
+        self.sync_collection_file(&req.collection_id).await?;
+        Ok(req)
+    }
+
+    /// Rewrite a collection's file from its current requests
+    async fn sync_collection_file(&self, collection_id: &str) -> Result<()> {
+        if let Ok(Some(collection)) = self.get_collection(collection_id).await {
+            let requests = self.list_requests(collection_id).await?;
+            if let Err(e) = self.file_sync.write_collection_file(&collection, requests).await {
+                eprintln!("Warning: Failed to update collection file after updating request: {}", e);
+            }
+        }
+        Ok(())
     }
 
-    pub async fn duplicate_request(&self, id: &str, new_name: &str) -> Result<Request> {
+    /// Include or exclude a request from automated collection runs
+    pub async fn set_request_run_inclusion(&self, id: &str, included: bool) -> Result<Request> {
+        sqlx::query("UPDATE requests SET include_in_run = ?1, updated_at = ?2 WHERE id = ?3 AND deleted_at IS NULL")
+            .bind(included)
+            .bind(&chrono::Utc::now().to_rfc3339())
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| anyhow!("Failed to update request run inclusion: {}", e))?;
+
+        self.updated_request(id).await
+    }
+
+    /// Give the request its own connect timeout, or None to use the workspace's
+    pub async fn set_request_connect_timeout(&self, id: &str, connect_timeout_ms: Option<u32>) -> Result<Request> {
+        sqlx::query("UPDATE requests SET connect_timeout_ms = ?1, updated_at = ?2 WHERE id = ?3 AND deleted_at IS NULL")
+            .bind(connect_timeout_ms.map(i64::from))
+            .bind(&chrono::Utc::now().to_rfc3339())
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| anyhow!("Failed to update request connect timeout: {}", e))?;
+
+        self.updated_request(id).await
+    }
+
+    /// Only send the request in collection runs when `condition` holds, or always when None
+    pub async fn set_request_run_condition(&self, id: &str, condition: Option<&str>) -> Result<Request> {
+        let condition = condition.map(str::trim).filter(|condition| !condition.is_empty());
+        if let Some(condition) = condition {
+            parse_run_condition(condition)?;
+        }
+
+        sqlx::query("UPDATE requests SET run_condition = ?1, updated_at = ?2 WHERE id = ?3 AND deleted_at IS NULL")
+            .bind(condition)
+            .bind(&chrono::Utc::now().to_rfc3339())
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| anyhow!("Failed to update request run condition: {}", e))?;
+
+        self.updated_request(id).await
+    }
+
+    /// Return the request's responses as raw streamed text instead of parsing them
+    pub async fn set_request_raw_stream(&self, id: &str, raw_stream: bool) -> Result<Request> {
+        sqlx::query("UPDATE requests SET raw_stream = ?1, updated_at = ?2 WHERE id = ?3 AND deleted_at IS NULL")
+            .bind(raw_stream)
+            .bind(&chrono::Utc::now().to_rfc3339())
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| anyhow!("Failed to update request raw stream setting: {}", e))?;
+
+        self.updated_request(id).await
+    }
+
+    /// Replace the steps that set variables just before the request is sent
+    pub async fn set_request_pre_request(&self, id: &str, steps: &[PreRequestStep]) -> Result<Request> {
+        for step in steps {
+            validate_pre_request_step(step)?;
+        }
+
+        sqlx::query("UPDATE requests SET pre_request = ?1, updated_at = ?2 WHERE id = ?3 AND deleted_at IS NULL")
+            .bind(serde_json::to_string(steps)?)
+            .bind(chrono::Utc::now().to_rfc3339())
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| anyhow!("Failed to update request pre-request steps: {}", e))?;
+
+        self.updated_request(id).await
+    }
+
+    /// Replace the rules that capture response values into variables during collection runs
+    pub async fn set_request_extractions(&self, id: &str, extractions: &[Extraction]) -> Result<Request> {
+        for extraction in extractions {
+            if extraction.variable.trim().is_empty() {
+                return Err(anyhow!("Extraction is missing a variable name"));
+            }
+            if extraction.source == ExtractionSource::Body {
+                parse_json_path(&extraction.path)?;
+            }
+        }
+
+        sqlx::query("UPDATE requests SET extractions = ?1, updated_at = ?2 WHERE id = ?3 AND deleted_at IS NULL")
+            .bind(serde_json::to_string(extractions)?)
+            .bind(chrono::Utc::now().to_rfc3339())
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| anyhow!("Failed to update request extractions: {}", e))?;
+
+        self.updated_request(id).await
+    }
+
+    /// Replace the checks evaluated against the request's responses
+    pub async fn set_request_assertions(&self, id: &str, assertions: &[Assertion]) -> Result<Request> {
+        for assertion in assertions {
+            validate_assertion(assertion)?;
+        }
+
+        sqlx::query("UPDATE requests SET assertions = ?1, updated_at = ?2 WHERE id = ?3 AND deleted_at IS NULL")
+            .bind(serde_json::to_string(assertions)?)
+            .bind(chrono::Utc::now().to_rfc3339())
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| anyhow!("Failed to update request assertions: {}", e))?;
+
+        self.updated_request(id).await
+    }
+
+    /// Duplicate a request; without a name it gets the next free "(copy)" name
+    pub async fn duplicate_request(&self, id: &str, new_name: Option<&str>) -> Result<Request> {
         let original = self.get_request(id).await?
             .ok_or_else(|| anyhow!("Request not found"))?;
 
-        let headers = original.get_headers().ok();
-        let auth_config = original.get_auth_config().ok().flatten();
-
-        let request = CreateRequestRequest {
-            collection_id: original.collection_id.clone(),
-            name: new_name.to_string(),
-            description: original.description.clone(),
-            method: original.method.clone(),
-            url: original.url.clone(),
-            headers,
-            body: original.body.clone(),
-            body_type: Some(original.body_type.clone()),
-            auth_type: original.auth_type.clone(),
-            auth_config,
-            follow_redirects: Some(original.follow_redirects),
-            timeout_ms: Some(original.timeout_ms),
-            order_index: Some(original.order_index + 1),
+        let new_name = match new_name {
+            Some(name) => name.to_string(),
+            None => self.suggest_duplicate_request_name(&original.collection_id, &original.name).await?,
         };
 
-        let duplicated_request = self.create_request(request).await?;
-        
-        // File sync is already handled in create_request
-        Ok(duplicated_request)
+        self.copy_request(&original, &original.collection_id, new_name, original.order_index + 1).await
+    }
+
+    /// Create a new request with the same settings as `original` in `collection_id`
+    pub async fn copy_request(&self, original: &Request, collection_id: &str, name: String, order_index: i32) -> Result<Request> {
+        let copy = Request { name, order_index, ..copied_request(original, collection_id) };
+        let mut copies = self.insert_copies(collection_id, vec![copy]).await?;
+        Ok(copies.remove(0))
+    }
+
+    /// Copy requests into `collection_id` under new IDs, keeping their names and order. All of
+    /// them are inserted in one transaction and the collection file is written once.
+    pub async fn copy_requests(&self, originals: &[Request], collection_id: &str) -> Result<Vec<Request>> {
+        let copies = originals.iter().map(|original| copied_request(original, collection_id)).collect();
+        self.insert_copies(collection_id, copies).await
+    }
+
+    async fn insert_copies(&self, collection_id: &str, copies: Vec<Request>) -> Result<Vec<Request>> {
+        let mut tx = self.pool.begin().await?;
+        for copy in &copies {
+            insert_request_row(&mut *tx, copy)
+                .await
+                .map_err(|e| anyhow!("Failed to copy request '{}': {}", copy.name, e))?;
+        }
+        tx.commit().await?;
+
+        self.sync_collection_file(collection_id).await?;
+        Ok(copies)
+    }
+
+    /// Copy a collection and all of its requests under new IDs, optionally into another
+    /// workspace. Without a name the copy gets the next free "(copy)" name in the target
+    /// workspace. Everything is inserted in one transaction, so a failure leaves no partial copy.
+    pub async fn duplicate_collection(
+        &self,
+        id: &str,
+        new_name: Option<&str>,
+        target_workspace_id: Option<&str>,
+    ) -> Result<Collection> {
+        let original = self.get_collection(id).await?
+            .ok_or_else(|| anyhow!("Collection not found"))?;
+        let workspace_id = target_workspace_id.unwrap_or(&original.workspace_id).to_string();
+        let name = match new_name {
+            Some(name) => name.to_string(),
+            None => self.suggest_duplicate_collection_name(&workspace_id, &original.name).await?,
+        };
+
+        let now = chrono::Utc::now();
+        // A parent in the original workspace means nothing in another one
+        let parent_id = original.parent_id.clone().filter(|_| workspace_id == original.workspace_id);
+        let collection = Collection {
+            id: uuid::Uuid::new_v4().to_string(),
+            workspace_id,
+            name,
+            parent_id,
+            is_active: false,
+            created_at: now,
+            updated_at: now,
+            ..original
+        };
+        let requests: Vec<Request> = self.list_requests(id).await?
+            .into_iter()
+            .map(|req| Request {
+                id: uuid::Uuid::new_v4().to_string(),
+                collection_id: collection.id.clone(),
+                created_at: now,
+                updated_at: now,
+                ..req
+            })
+            .collect();
+
+        let mut tx = self.pool.begin().await?;
+        sqlx::query(
+            r#"
+            INSERT INTO collections (id, workspace_id, name, description, folder_path, git_branch, parent_id, is_active, created_at, updated_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
+            "#
+        )
+        .bind(&collection.id)
+        .bind(&collection.workspace_id)
+        .bind(&collection.name)
+        .bind(&collection.description)
+        .bind(&collection.folder_path)
+        .bind(&collection.git_branch)
+        .bind(&collection.parent_id)
+        .bind(collection.is_active)
+        .bind(&collection.created_at.to_rfc3339())
+        .bind(&collection.updated_at.to_rfc3339())
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| anyhow!("Failed to duplicate collection: {}", e))?;
+
+        for req in &requests {
+            insert_request_row(&mut *tx, req)
+                .await
+                .map_err(|e| anyhow!("Failed to duplicate request '{}': {}", req.name, e))?;
+        }
+        tx.commit().await?;
+
+        if let Err(e) = self.file_sync.write_collection_file(&collection, requests).await {
+            eprintln!("Warning: Failed to write collection file: {}", e);
+        }
+
+        Ok(collection)
+    }
+
+    /// Reload collections from the workspace's JSON files, e.g. after a git pull. Collections
+    /// and requests are matched by their stored IDs, so edited entries are updated in place
+    /// rather than duplicated, and entries in the trash come back. Collections and requests no
+    /// longer in the files are moved to the trash. Nothing is written back to disk.
+    pub async fn sync_workspace_from_disk(&self, workspace_id: &str) -> Result<Vec<Collection>> {
+        let mut synced = Vec::new();
+        let mut on_disk_requests = std::collections::HashSet::new();
+        let mut tx = self.pool.begin().await?;
+
+        for name in self.file_sync.list_collection_files(workspace_id).await? {
+            let Some((collection, requests)) = self.file_sync.read_collection_file(workspace_id, &name).await? else {
+                continue;
+            };
+
+            sqlx::query(
+                r#"
+                INSERT INTO collections (id, workspace_id, name, description, folder_path, git_branch, parent_id, is_active, created_at, updated_at)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
+                ON CONFLICT(id) DO UPDATE SET
+                    name = excluded.name, description = excluded.description, folder_path = excluded.folder_path,
+                    git_branch = excluded.git_branch, parent_id = excluded.parent_id, is_active = excluded.is_active,
+                    updated_at = excluded.updated_at, deleted_at = NULL
+                "#
+            )
+            .bind(&collection.id)
+            .bind(&collection.workspace_id)
+            .bind(&collection.name)
+            .bind(&collection.description)
+            .bind(&collection.folder_path)
+            .bind(&collection.git_branch)
+            .bind(&collection.parent_id)
+            .bind(collection.is_active)
+            .bind(&collection.created_at.to_rfc3339())
+            .bind(&collection.updated_at.to_rfc3339())
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| anyhow!("Failed to sync collection '{}': {}", collection.name, e))?;
+
+            for req in &requests {
+                sqlx::query(
+                    r#"
+                    INSERT INTO requests (
+                        id, collection_id, name, description, method, url, headers, body, body_type,
+                        auth_type, auth_config, follow_redirects, timeout_ms, order_index, include_in_run,
+                        raw_stream, connect_timeout_ms, run_condition, pre_request, extractions, assertions, created_at, updated_at
+                    )
+                    VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21, ?22, ?23)
+                    ON CONFLICT(id) DO UPDATE SET
+                        collection_id = excluded.collection_id, name = excluded.name, description = excluded.description,
+                        method = excluded.method, url = excluded.url, headers = excluded.headers, body = excluded.body,
+                        body_type = excluded.body_type, auth_type = excluded.auth_type, auth_config = excluded.auth_config,
+                        follow_redirects = excluded.follow_redirects, timeout_ms = excluded.timeout_ms,
+                        order_index = excluded.order_index, include_in_run = excluded.include_in_run,
+                        raw_stream = excluded.raw_stream, connect_timeout_ms = excluded.connect_timeout_ms,
+                        run_condition = excluded.run_condition, pre_request = excluded.pre_request,
+                        extractions = excluded.extractions,
+                        assertions = excluded.assertions, updated_at = excluded.updated_at, deleted_at = NULL
+                    "#
+                )
+                .bind(&req.id)
+                .bind(&req.collection_id)
+                .bind(&req.name)
+                .bind(&req.description)
+                .bind(&req.method)
+                .bind(&req.url)
+                .bind(&req.headers)
+                .bind(&req.body)
+                .bind(&req.body_type)
+                .bind(&req.auth_type)
+                .bind(&req.auth_config)
+                .bind(req.follow_redirects)
+                .bind(req.timeout_ms as i64)
+                .bind(req.order_index)
+                .bind(req.include_in_run)
+                .bind(req.raw_stream)
+                .bind(req.connect_timeout_ms.map(i64::from))
+                .bind(&req.run_condition)
+                .bind(&req.pre_request)
+                .bind(&req.extractions)
+                .bind(&req.assertions)
+                .bind(&req.created_at.to_rfc3339())
+                .bind(&req.updated_at.to_rfc3339())
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| anyhow!("Failed to sync request '{}': {}", req.name, e))?;
+                on_disk_requests.insert(req.id.clone());
+            }
+
+            synced.push(collection);
+        }
+
+        // Whatever was deleted upstream goes to the trash, like a local delete
+        let now = chrono::Utc::now().to_rfc3339();
+        let stored_collections: Vec<String> = sqlx::query("SELECT id FROM collections WHERE workspace_id = ?1 AND deleted_at IS NULL")
+            .bind(workspace_id)
+            .fetch_all(&mut *tx)
+            .await?
+            .iter()
+            .map(|row| row.get("id"))
+            .collect();
+        for id in stored_collections.iter().filter(|id| !synced.iter().any(|c| &c.id == *id)) {
+            sqlx::query("UPDATE collections SET deleted_at = ?1 WHERE id = ?2")
+                .bind(&now)
+                .bind(id)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| anyhow!("Failed to remove collection deleted upstream: {}", e))?;
+        }
+        for collection in &synced {
+            let stored_requests: Vec<String> = sqlx::query("SELECT id FROM requests WHERE collection_id = ?1 AND deleted_at IS NULL")
+                .bind(&collection.id)
+                .fetch_all(&mut *tx)
+                .await?
+                .iter()
+                .map(|row| row.get("id"))
+                .collect();
+            for id in stored_requests.iter().filter(|id| !on_disk_requests.contains(*id)) {
+                sqlx::query("UPDATE requests SET deleted_at = ?1 WHERE id = ?2")
+                    .bind(&now)
+                    .bind(id)
+                    .execute(&mut *tx)
+                    .await
+                    .map_err(|e| anyhow!("Failed to remove request deleted upstream: {}", e))?;
+            }
+        }
+
+        tx.commit().await?;
+        Ok(synced)
+    }
+
+    /// A "(copy)" name for `base_name` not yet used by a request in the collection
+    pub async fn suggest_duplicate_request_name(&self, collection_id: &str, base_name: &str) -> Result<String> {
+        let existing: Vec<String> = self.list_requests(collection_id).await?
+            .into_iter()
+            .map(|r| r.name)
+            .collect();
+        Ok(duplicate_name(base_name, &existing))
+    }
+
+    /// A "(copy)" name for `base_name` not yet used by a collection in the workspace
+    pub async fn suggest_duplicate_collection_name(&self, workspace_id: &str, base_name: &str) -> Result<String> {
+        let existing: Vec<String> = self.list_collections(workspace_id).await?
+            .into_iter()
+            .map(|c| c.name)
+            .collect();
+        Ok(duplicate_name(base_name, &existing))
+    }
+
+    /// Move a request to `new_order_index` in another collection (or within its own). Both
+    /// collections are renumbered from 0 so their order indexes stay contiguous.
+    pub async fn move_request(&self, request_id: &str, target_collection_id: &str, new_order_index: usize) -> Result<Request> {
+        let request = self.get_request(request_id).await?
+            .ok_or_else(|| anyhow!("Request not found"))?;
+        self.get_collection(target_collection_id).await?
+            .ok_or_else(|| anyhow!("Collection not found"))?;
+        let source_collection_id = request.collection_id.clone();
+
+        let mut transaction = self.pool.begin().await?;
+        let now = chrono::Utc::now().to_rfc3339();
+        let ordered_ids = |collection_id: String| {
+            sqlx::query_scalar::<_, String>(
+                "SELECT id FROM requests WHERE collection_id = ?1 AND id != ?2 AND deleted_at IS NULL ORDER BY order_index ASC, created_at ASC"
+            )
+            .bind(collection_id)
+            .bind(request_id)
+        };
+
+        let mut target_ids = ordered_ids(target_collection_id.to_string())
+            .fetch_all(&mut *transaction)
+            .await
+            .map_err(|e| anyhow!("Failed to list requests: {}", e))?;
+        target_ids.insert(new_order_index.min(target_ids.len()), request_id.to_string());
+
+        let mut renumber = vec![(target_collection_id.to_string(), target_ids)];
+        if source_collection_id != target_collection_id {
+            let source_ids = ordered_ids(source_collection_id.clone())
+                .fetch_all(&mut *transaction)
+                .await
+                .map_err(|e| anyhow!("Failed to list requests: {}", e))?;
+            renumber.push((source_collection_id.clone(), source_ids));
+        }
+
+        for (collection_id, ids) in &renumber {
+            for (order_index, id) in ids.iter().enumerate() {
+                sqlx::query("UPDATE requests SET collection_id = ?1, order_index = ?2, updated_at = ?3 WHERE id = ?4")
+                    .bind(collection_id)
+                    .bind(order_index as i32)
+                    .bind(&now)
+                    .bind(id)
+                    .execute(&mut *transaction)
+                    .await
+                    .map_err(|e| anyhow!("Failed to move request: {}", e))?;
+            }
+        }
+        transaction.commit().await?;
+
+        for (collection_id, _) in &renumber {
+            if let Ok(Some(collection)) = self.get_collection(collection_id).await {
+                let requests = self.list_requests(collection_id).await?;
+                if let Err(e) = self.file_sync.write_collection_file(&collection, requests).await {
+                    eprintln!("Warning: Failed to update collection file after moving request: {}", e);
+                }
+            }
+        }
+
+        self.get_request(request_id).await?
+            .ok_or_else(|| anyhow!("Request not found"))
     }
 
     pub async fn reorder_requests(&self, collection_id: &str, request_orders: Vec<(String, i32)>) -> Result<()> {
@@ -422,4 +1261,527 @@ impl CollectionService {
         transaction.commit().await?;
         Ok(())
     }
-}
\ No newline at end of file
+}
+
+/// The first case-insensitive occurrence of `query` in `text` with some context either side,
+/// on one line
+fn match_snippet(text: &str, query: &str) -> Option<String> {
+    let chars: Vec<char> = text.chars().collect();
+    let query: Vec<char> = query.chars().flat_map(char::to_lowercase).collect();
+    let start = (0..chars.len()).find(|&i| {
+        chars.len() - i >= query.len()
+            && chars[i..i + query.len()].iter().flat_map(|c| c.to_lowercase()).eq(query.iter().copied())
+    })?;
+    let end = start + query.len();
+
+    let from = start.saturating_sub(SNIPPET_CONTEXT_CHARS);
+    let to = (end + SNIPPET_CONTEXT_CHARS).min(chars.len());
+    let mut snippet: String = chars[from..to].iter()
+        .map(|&c| if c.is_whitespace() { ' ' } else { c })
+        .collect();
+    if from > 0 {
+        snippet.insert(0, '…');
+    }
+    if to < chars.len() {
+        snippet.push('…');
+    }
+    Some(snippet)
+}
+
+/// "X (copy)", then "X (copy 2)", "X (copy 3)"... skipping names already taken.
+/// Copying a copy continues its numbering rather than nesting suffixes.
+fn duplicate_name(base_name: &str, existing: &[String]) -> String {
+    let suffix = Regex::new(r" \(copy(?: \d+)?\)$").unwrap();
+    let base = suffix.replace(base_name, "");
+
+    let taken = |name: &str| existing.iter().any(|n| n == name);
+    let first = format!("{} (copy)", base);
+    if !taken(&first) {
+        return first;
+    }
+    (2..)
+        .map(|n| format!("{} (copy {})", base, n))
+        .find(|name| !taken(name))
+        .unwrap()
+}
+
+/// Name an imported request after its host and path, e.g. "api.example.com/users"
+fn request_name_from_url(url: &str) -> String {
+    match url::Url::parse(url) {
+        Ok(parsed) => {
+            let path = parsed.path().trim_end_matches('/');
+            format!("{}{}", parsed.host_str().unwrap_or_default(), path)
+        }
+        Err(_) => url.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::test_database::{add_workspace, database_with_workspace};
+    use tempfile::TempDir;
+
+    async fn setup() -> (TempDir, CollectionService, Collection) {
+        let temp_dir = TempDir::new().unwrap();
+        let (database, workspace) = database_with_workspace(temp_dir.path().to_str().unwrap()).await;
+
+        let service = CollectionService::new(database.get_pool());
+        let collection = service.create_collection(CreateCollectionRequest {
+            workspace_id: workspace.id.clone(),
+            name: "Scratchpad".to_string(),
+            description: None,
+            folder_path: None,
+            git_branch: None,
+            parent_id: None,
+        }).await.unwrap();
+
+        (temp_dir, service, collection)
+    }
+
+    #[tokio::test]
+    async fn test_import_curls_reports_malformed_commands() {
+        let (_temp_dir, service, collection) = setup().await;
+
+        let commands = vec![
+            "curl https://api.example.com/users".to_string(),
+            "curl -X POST -H".to_string(),
+            r#"curl -X POST https://api.example.com/users/ -H 'Content-Type: application/json' -d '{"name": "ada"}'"#.to_string(),
+        ];
+        let result = service.import_curl_commands(&collection.id, &commands).await.unwrap();
+
+        assert_eq!(result.requests.len(), 2);
+        assert_eq!(result.errors.len(), 1);
+        assert_eq!(result.errors[0].index, 1);
+
+        // Requests keep the order of the commands and are named from their URLs
+        let stored = service.list_requests(&collection.id).await.unwrap();
+        assert_eq!(stored.len(), 2);
+        assert_eq!(stored[0].name, "api.example.com/users");
+        assert_eq!(stored[0].method, "GET");
+        assert_eq!(stored[1].method, "POST");
+        assert_eq!(stored[1].body_type, "json");
+        assert!(stored[0].order_index < stored[1].order_index);
+    }
+
+    #[tokio::test]
+    async fn test_duplicate_request_suggests_copy_names() {
+        let (_temp_dir, service, collection) = setup().await;
+        let original = service.create_request(CreateRequestRequest::from_http_request(
+            &collection.id,
+            "Get user",
+            &crate::models::http::HttpRequest::default(),
+            0,
+        )).await.unwrap();
+
+        let first = service.duplicate_request(&original.id, None).await.unwrap();
+        let second = service.duplicate_request(&original.id, None).await.unwrap();
+        assert_eq!(first.name, "Get user (copy)");
+        assert_eq!(second.name, "Get user (copy 2)");
+
+        // Copying a copy continues the numbering
+        let third = service.duplicate_request(&first.id, None).await.unwrap();
+        assert_eq!(third.name, "Get user (copy 3)");
+
+        let name = service.suggest_duplicate_collection_name(&collection.workspace_id, &collection.name).await.unwrap();
+        assert_eq!(name, "Scratchpad (copy)");
+    }
+
+    #[tokio::test]
+    async fn test_duplicate_request_keeps_run_settings() {
+        let (_temp_dir, service, collection) = setup().await;
+        let original = service.create_request(CreateRequestRequest::from_http_request(
+            &collection.id,
+            "Login",
+            &crate::models::http::HttpRequest::default(),
+            0,
+        )).await.unwrap();
+        service.set_request_run_inclusion(&original.id, false).await.unwrap();
+        service.set_request_raw_stream(&original.id, true).await.unwrap();
+        service.set_request_connect_timeout(&original.id, Some(1500)).await.unwrap();
+        service.set_request_run_condition(&original.id, Some("{{token}} exists")).await.unwrap();
+        let original = service.set_request_pre_request(&original.id, &[
+            PreRequestStep::SetVariable { variable: "nonce".to_string(), value: "{{$uuid}}".to_string() },
+        ]).await.unwrap();
+
+        let copy = service.duplicate_request(&original.id, None).await.unwrap();
+        let stored = service.get_request(&copy.id).await.unwrap().unwrap();
+        assert_ne!(stored.id, original.id);
+        assert_eq!(stored.name, "Login (copy)");
+        assert!(!stored.include_in_run);
+        assert!(stored.raw_stream);
+        assert_eq!(stored.connect_timeout_ms, Some(1500));
+        assert_eq!(stored.run_condition, original.run_condition);
+        assert_eq!(stored.get_pre_request(), original.get_pre_request());
+    }
+
+    #[tokio::test]
+    async fn test_duplicate_collection_copies_requests() {
+        let (_temp_dir, service, collection) = setup().await;
+        for (index, name) in ["List users", "Get user", "Delete user"].iter().enumerate() {
+            service.create_request(CreateRequestRequest::from_http_request(
+                &collection.id,
+                name,
+                &crate::models::http::HttpRequest::default(),
+                index as i32,
+            )).await.unwrap();
+        }
+
+        let copy = service.duplicate_collection(&collection.id, None, None).await.unwrap();
+        assert_ne!(copy.id, collection.id);
+        assert_eq!(copy.name, "Scratchpad (copy)");
+        assert_eq!(copy.workspace_id, collection.workspace_id);
+
+        let originals = service.list_requests(&collection.id).await.unwrap();
+        let copies = service.list_requests(&copy.id).await.unwrap();
+        assert_eq!(copies.len(), 3);
+        for (original, copied) in originals.iter().zip(&copies) {
+            assert_ne!(copied.id, original.id);
+            assert_eq!(copied.name, original.name);
+            assert_eq!(copied.order_index, original.order_index);
+        }
+
+        // The copies are independent rows
+        service.delete_request(&copies[0].id).await.unwrap();
+        assert_eq!(service.list_requests(&collection.id).await.unwrap().len(), 3);
+        assert_eq!(service.list_requests(&copy.id).await.unwrap().len(), 2);
+
+        assert!(service.duplicate_collection("missing", None, None).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_deleted_collection_can_be_restored() {
+        let (_temp_dir, service, collection) = setup().await;
+        let request = service.create_request(CreateRequestRequest::from_http_request(
+            &collection.id,
+            "Ping",
+            &crate::models::http::HttpRequest::default(),
+            0,
+        )).await.unwrap();
+
+        service.delete_collection(&collection.id).await.unwrap();
+        assert!(service.get_collection(&collection.id).await.unwrap().is_none());
+        assert!(service.list_collections(&collection.workspace_id).await.unwrap().is_empty());
+        assert!(service.get_collection_summaries(&collection.workspace_id).await.unwrap().is_empty());
+        assert!(service.search_requests(&collection.workspace_id, "Ping").await.unwrap().is_empty());
+        assert!(service.restore_request(&request.id).await.is_err());
+
+        let restored = service.restore_collection(&collection.id).await.unwrap();
+        assert_eq!(restored.id, collection.id);
+        let listed = service.list_collections(&collection.workspace_id).await.unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(service.list_requests(&collection.id).await.unwrap()[0].id, request.id);
+        assert_eq!(service.search_requests(&collection.workspace_id, "Ping").await.unwrap().len(), 1);
+
+        // Only deleted collections can be restored
+        assert!(service.restore_collection(&collection.id).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_deleted_requests_are_restored_or_purged() {
+        let (_temp_dir, service, collection) = setup().await;
+        let mut created = Vec::new();
+        for name in ["keep", "restore", "purge"] {
+            created.push(service.create_request(CreateRequestRequest::from_http_request(
+                &collection.id,
+                name,
+                &crate::models::http::HttpRequest::default(),
+                0,
+            )).await.unwrap());
+        }
+        service.delete_request(&created[1].id).await.unwrap();
+        service.delete_request(&created[2].id).await.unwrap();
+        assert!(service.get_request(&created[1].id).await.unwrap().is_none());
+        assert_eq!(service.list_requests(&collection.id).await.unwrap().len(), 1);
+        assert_eq!(service.get_collection_summaries(&collection.workspace_id).await.unwrap()[0].request_count, 1);
+
+        // Requests in the trash can't be changed
+        assert!(service.set_request_run_inclusion(&created[1].id, false).await.is_err());
+
+        service.restore_request(&created[1].id).await.unwrap();
+        assert_eq!(service.list_requests(&collection.id).await.unwrap().len(), 2);
+        assert!(service.get_request(&created[1].id).await.unwrap().unwrap().include_in_run);
+
+        // Items deleted just now are younger than a day, so only a zero-day purge removes them
+        assert_eq!(service.purge_deleted(&collection.workspace_id, 1).await.unwrap(), 0);
+        assert!(service.restore_request(&created[2].id).await.is_ok());
+        service.delete_request(&created[2].id).await.unwrap();
+        assert_eq!(service.purge_deleted(&collection.workspace_id, 0).await.unwrap(), 1);
+        assert!(service.restore_request(&created[2].id).await.is_err());
+        assert_eq!(service.list_requests(&collection.id).await.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_move_request_keeps_ordering_contiguous() {
+        let (_temp_dir, service, source) = setup().await;
+        let target = service.create_collection(CreateCollectionRequest {
+            workspace_id: source.workspace_id.clone(),
+            name: "Target".to_string(),
+            description: None,
+            folder_path: None,
+            git_branch: None,
+            parent_id: None,
+        }).await.unwrap();
+
+        let mut created = Vec::new();
+        for (collection, names) in [(&source, ["a", "b", "c"]), (&target, ["x", "y", "z"])] {
+            for (index, name) in names.iter().enumerate() {
+                created.push(service.create_request(CreateRequestRequest::from_http_request(
+                    &collection.id,
+                    name,
+                    &crate::models::http::HttpRequest::default(),
+                    index as i32,
+                )).await.unwrap());
+            }
+        }
+
+        let moved = service.move_request(&created[1].id, &target.id, 1).await.unwrap();
+        assert_eq!(moved.collection_id, target.id);
+        assert_eq!(moved.order_index, 1);
+
+        let ordering = |requests: Vec<Request>| -> Vec<(String, i32)> {
+            requests.into_iter().map(|r| (r.name, r.order_index)).collect()
+        };
+        let named = |pairs: &[(&str, i32)]| -> Vec<(String, i32)> {
+            pairs.iter().map(|(name, index)| (name.to_string(), *index)).collect()
+        };
+        assert_eq!(ordering(service.list_requests(&source.id).await.unwrap()), named(&[("a", 0), ("c", 1)]));
+        assert_eq!(
+            ordering(service.list_requests(&target.id).await.unwrap()),
+            named(&[("x", 0), ("b", 1), ("y", 2), ("z", 3)])
+        );
+
+        // An index past the end appends
+        service.move_request(&created[0].id, &target.id, 99).await.unwrap();
+        assert_eq!(ordering(service.list_requests(&source.id).await.unwrap()), named(&[("c", 0)]));
+        assert_eq!(service.get_request(&created[0].id).await.unwrap().unwrap().order_index, 4);
+
+        assert!(service.move_request(&created[2].id, "missing", 0).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_nested_collections() {
+        let (_temp_dir, service, root) = setup().await;
+        let create = |name: &str, parent_id: Option<String>| CreateCollectionRequest {
+            workspace_id: root.workspace_id.clone(),
+            name: name.to_string(),
+            description: None,
+            folder_path: None,
+            git_branch: None,
+            parent_id,
+        };
+        let child = service.create_collection(create("Users", Some(root.id.clone()))).await.unwrap();
+        let grandchild = service.create_collection(create("Admin", Some(child.id.clone()))).await.unwrap();
+        let sibling = service.create_collection(create("Auth", Some(root.id.clone()))).await.unwrap();
+
+        let names = |collections: Vec<Collection>| -> Vec<String> { collections.into_iter().map(|c| c.name).collect() };
+        assert_eq!(names(service.list_child_collections(&root.id).await.unwrap()), vec!["Auth", "Users"]);
+        assert_eq!(names(service.list_child_collections(&child.id).await.unwrap()), vec!["Admin"]);
+        assert!(service.list_child_collections(&grandchild.id).await.unwrap().is_empty());
+        assert_eq!(service.get_collection(&grandchild.id).await.unwrap().unwrap().parent_id, Some(child.id.clone()));
+
+        // A collection can't end up inside its own subtree
+        assert!(service.set_collection_parent(&root.id, Some(&grandchild.id)).await.is_err());
+        assert!(service.create_collection(create("Lost", Some("missing".to_string()))).await.is_err());
+
+        let moved = service.set_collection_parent(&sibling.id, None).await.unwrap();
+        assert!(moved.parent_id.is_none());
+
+        // Deleting a collection moves its children up a level
+        service.delete_collection(&child.id).await.unwrap();
+        assert_eq!(service.get_collection(&grandchild.id).await.unwrap().unwrap().parent_id, Some(root.id.clone()));
+    }
+
+    #[tokio::test]
+    async fn test_search_requests_by_url_and_name() {
+        let (_temp_dir, service, collection) = setup().await;
+        let add = |name: &str, url: &str| {
+            let http_request = crate::models::http::HttpRequest { url: url.to_string(), ..Default::default() };
+            CreateRequestRequest::from_http_request(&collection.id, name, &http_request, 0)
+        };
+        let users = service.create_request(add("List users", "https://api.example.com/v2/users?page=1")).await.unwrap();
+        service.create_request(add("Get order", "https://shop.example.com/orders/42")).await.unwrap();
+
+        let results = service.search_requests(&collection.workspace_id, "/V2/USERS").await.unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].request_id, users.id);
+        assert_eq!(results[0].collection_name, "Scratchpad");
+        assert_eq!(results[0].matched_field, "url");
+        assert_eq!(results[0].snippet, "https://api.example.com/v2/users?page=1");
+
+        let results = service.search_requests(&collection.workspace_id, "get ORD").await.unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].name, "Get order");
+        assert_eq!(results[0].matched_field, "name");
+
+        // Too short for the index, but still found
+        assert_eq!(service.search_requests(&collection.workspace_id, "42").await.unwrap().len(), 1);
+        assert_eq!(service.search_requests(&collection.workspace_id, "example.com").await.unwrap().len(), 2);
+        assert!(service.search_requests("other-workspace", "example.com").await.unwrap().is_empty());
+
+        // The index follows renames and deletions
+        service.update_request(UpdateRequestRequest {
+            id: users.id.clone(),
+            collection_id: None,
+            name: Some("Fetch members".to_string()),
+            description: None,
+            method: None,
+            url: None,
+            headers: None,
+            body: None,
+            body_type: None,
+            auth_type: None,
+            auth_config: None,
+            follow_redirects: None,
+            timeout_ms: None,
+            order_index: None,
+        }).await.unwrap();
+        assert!(service.search_requests(&collection.workspace_id, "list users").await.unwrap().is_empty());
+        assert_eq!(service.search_requests(&collection.workspace_id, "members").await.unwrap().len(), 1);
+        service.delete_request(&users.id).await.unwrap();
+        assert!(service.search_requests(&collection.workspace_id, "members").await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_collection_branches() {
+        let (temp_dir, service, shared) = setup().await;
+        let feature = service.create_collection(CreateCollectionRequest {
+            workspace_id: shared.workspace_id.clone(),
+            name: "Feature".to_string(),
+            description: None,
+            folder_path: None,
+            git_branch: Some("main".to_string()),
+            parent_id: None,
+        }).await.unwrap();
+
+        let moved = service.set_collection_branch(&feature.id, Some("feature/login")).await.unwrap();
+        assert_eq!(moved.git_branch.as_deref(), Some("feature/login"));
+        let stored = service.get_collection(&feature.id).await.unwrap().unwrap();
+        assert_eq!(stored.git_branch.as_deref(), Some("feature/login"));
+        let file = std::fs::read_to_string(temp_dir.path().join("collections/feature.json")).unwrap();
+        assert!(file.contains("feature/login"));
+
+        // Unlabelled collections show up on every branch
+        let names = |collections: Vec<Collection>| {
+            let mut names: Vec<String> = collections.into_iter().map(|c| c.name).collect();
+            names.sort();
+            names
+        };
+        let on_feature = service.list_collections_by_branch(&shared.workspace_id, "feature/login").await.unwrap();
+        assert_eq!(names(on_feature), vec!["Feature", "Scratchpad"]);
+        let on_main = service.list_collections_by_branch(&shared.workspace_id, "main").await.unwrap();
+        assert_eq!(names(on_main), vec!["Scratchpad"]);
+
+        service.set_collection_branch(&feature.id, None).await.unwrap();
+        let on_main = service.list_collections_by_branch(&shared.workspace_id, "main").await.unwrap();
+        assert_eq!(names(on_main), vec!["Feature", "Scratchpad"]);
+    }
+
+    #[tokio::test]
+    async fn test_sync_workspace_from_disk() {
+        let (temp_dir, service, collection) = setup().await;
+        let request = service.create_request(CreateRequestRequest {
+            collection_id: collection.id.clone(),
+            name: "List users".to_string(),
+            description: None,
+            method: "GET".to_string(),
+            url: "https://api.example.com/users".to_string(),
+            headers: None,
+            body: None,
+            body_type: None,
+            auth_type: None,
+            auth_config: None,
+            follow_redirects: None,
+            timeout_ms: None,
+            order_index: None,
+        }).await.unwrap();
+
+        // Simulate a pull that edits the request and adds another one
+        let path = temp_dir.path().join("collections/scratchpad.json");
+        let mut file: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        file["description"] = serde_json::json!("Pulled from main");
+        let mut added = file["requests"][0].clone();
+        file["requests"][0]["url"] = serde_json::json!("https://api.example.com/v2/users");
+        added["id"] = serde_json::json!("request-from-git");
+        added["name"] = serde_json::json!("Create user");
+        added["method"] = serde_json::json!("POST");
+        added["order_index"] = serde_json::json!(1);
+        file["requests"].as_array_mut().unwrap().push(added);
+        std::fs::write(&path, serde_json::to_string_pretty(&file).unwrap()).unwrap();
+
+        let synced = service.sync_workspace_from_disk(&collection.workspace_id).await.unwrap();
+        assert_eq!(synced.len(), 1);
+
+        let collections = service.list_collections(&collection.workspace_id).await.unwrap();
+        assert_eq!(collections.len(), 1);
+        assert_eq!(collections[0].description.as_deref(), Some("Pulled from main"));
+
+        let requests = service.list_requests(&collection.id).await.unwrap();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[0].id, request.id);
+        assert_eq!(requests[0].url, "https://api.example.com/v2/users");
+        assert_eq!(requests[1].id, "request-from-git");
+        assert_eq!(requests[1].method, "POST");
+
+        // Syncing again finds nothing new
+        service.sync_workspace_from_disk(&collection.workspace_id).await.unwrap();
+        assert_eq!(service.list_requests(&collection.id).await.unwrap().len(), 2);
+
+        // A pull that drops a request trashes it, and one that brings it back restores it
+        let with_both = std::fs::read_to_string(&path).unwrap();
+        let mut file: serde_json::Value = serde_json::from_str(&with_both).unwrap();
+        file["requests"].as_array_mut().unwrap().remove(1);
+        std::fs::write(&path, serde_json::to_string_pretty(&file).unwrap()).unwrap();
+        service.sync_workspace_from_disk(&collection.workspace_id).await.unwrap();
+        assert_eq!(service.list_requests(&collection.id).await.unwrap().len(), 1);
+        assert!(service.get_request("request-from-git").await.unwrap().is_none());
+
+        std::fs::write(&path, with_both).unwrap();
+        service.sync_workspace_from_disk(&collection.workspace_id).await.unwrap();
+        assert_eq!(service.list_requests(&collection.id).await.unwrap().len(), 2);
+
+        // as does a pull that removes the whole collection file
+        std::fs::remove_file(&path).unwrap();
+        assert!(service.sync_workspace_from_disk(&collection.workspace_id).await.unwrap().is_empty());
+        assert!(service.list_collections(&collection.workspace_id).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_collection_files_follow_workspace_path() {
+        let first_dir = TempDir::new().unwrap();
+        let second_dir = TempDir::new().unwrap();
+        let (database, first) = database_with_workspace(first_dir.path().to_str().unwrap()).await;
+        let second = add_workspace(&database, "Second", second_dir.path().to_str().unwrap()).await;
+        let workspace_ids = [first.id, second.id];
+
+        let service = CollectionService::new(database.get_pool());
+        for (workspace_id, name) in workspace_ids.iter().zip(["Users", "Orders"]) {
+            service.create_collection(CreateCollectionRequest {
+                workspace_id: workspace_id.clone(),
+                name: name.to_string(),
+                description: None,
+                folder_path: None,
+                git_branch: None,
+                parent_id: None,
+            }).await.unwrap();
+        }
+
+        assert!(first_dir.path().join("collections/users.json").exists());
+        assert!(!first_dir.path().join("collections/orders.json").exists());
+        assert!(second_dir.path().join("collections/orders.json").exists());
+        assert!(!second_dir.path().join("collections/users.json").exists());
+
+        // Unknown workspaces are an error rather than a write to some default location
+        let orphan = Collection::new(CreateCollectionRequest {
+            workspace_id: "missing".to_string(),
+            name: "Orphan".to_string(),
+            description: None,
+            folder_path: None,
+            git_branch: None,
+            parent_id: None,
+        });
+        let file_sync = FileSyncService::new(database.clone());
+        let error = file_sync.write_collection_file(&orphan, Vec::new()).await.unwrap_err();
+        assert!(error.to_string().contains("not found"), "{}", error);
+    }
+}
@@ -0,0 +1,168 @@
+use crate::models::http::CookieInfo;
+use chrono::{DateTime, Duration, Utc};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+#[derive(Debug, Clone)]
+struct StoredCookie {
+    name: String,
+    value: String,
+    domain: String,
+    path: String,
+    host_only: bool, // set without a Domain attribute, so subdomains don't receive it
+    secure: bool,
+    expires: Option<DateTime<Utc>>, // None for session cookies
+}
+
+/// Cookies received from Set-Cookie headers, kept separately for each workspace so
+/// sessions against different environments never leak into each other
+#[derive(Clone, Default)]
+pub struct CookieJarService {
+    jars: Arc<Mutex<HashMap<String, Vec<StoredCookie>>>>,
+}
+
+impl CookieJarService {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Build the Cookie header value for a request to `url`
+    pub fn cookie_header(&self, workspace_id: &str, url: &url::Url) -> Option<String> {
+        let host = url.host_str()?.to_lowercase();
+        let now = Utc::now();
+
+        let mut jars = self.jars.lock().unwrap();
+        let jar = jars.get_mut(workspace_id)?;
+        jar.retain(|c| c.expires.is_none_or(|expires| expires > now));
+
+        let pairs: Vec<String> = jar
+            .iter()
+            .filter(|c| domain_matches(c, &host) && path_matches(&c.path, url.path()))
+            .filter(|c| !c.secure || url.scheme() == "https")
+            .map(|c| format!("{}={}", c.name, c.value))
+            .collect();
+
+        if pairs.is_empty() {
+            None
+        } else {
+            Some(pairs.join("; "))
+        }
+    }
+
+    /// Store the cookies from a response's Set-Cookie headers
+    pub fn store(&self, workspace_id: &str, url: &url::Url, set_cookie_headers: &[String]) {
+        let Some(host) = url.host_str().map(|h| h.to_lowercase()) else {
+            return;
+        };
+
+        let mut jars = self.jars.lock().unwrap();
+        let jar = jars.entry(workspace_id.to_string()).or_default();
+
+        for header in set_cookie_headers {
+            let Some(cookie) = parse_set_cookie(header, &host, url.path()) else {
+                continue;
+            };
+            jar.retain(|c| !(c.name == cookie.name && c.domain == cookie.domain && c.path == cookie.path));
+            // An expiry in the past is how servers delete cookies
+            if cookie.expires.is_none_or(|expires| expires > Utc::now()) {
+                jar.push(cookie);
+            }
+        }
+    }
+
+    pub fn list(&self, workspace_id: &str) -> Vec<CookieInfo> {
+        let now = Utc::now();
+        self.jars
+            .lock()
+            .unwrap()
+            .get(workspace_id)
+            .map(|jar| {
+                jar.iter()
+                    .filter(|c| c.expires.is_none_or(|expires| expires > now))
+                    .map(|c| CookieInfo {
+                        name: c.name.clone(),
+                        value: c.value.clone(),
+                        domain: c.domain.clone(),
+                        path: c.path.clone(),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    pub fn clear(&self, workspace_id: &str) {
+        self.jars.lock().unwrap().remove(workspace_id);
+    }
+}
+
+fn parse_set_cookie(header: &str, host: &str, request_path: &str) -> Option<StoredCookie> {
+    let mut parts = header.split(';');
+    let (name, value) = parts.next()?.split_once('=')?;
+    let name = name.trim();
+    if name.is_empty() {
+        return None;
+    }
+
+    let mut cookie = StoredCookie {
+        name: name.to_string(),
+        value: value.trim().trim_matches('"').to_string(),
+        domain: host.to_string(),
+        path: default_path(request_path),
+        host_only: true,
+        secure: false,
+        expires: None,
+    };
+    let mut max_age = None;
+
+    for attribute in parts {
+        let (key, value) = attribute.split_once('=').unwrap_or((attribute, ""));
+        let value = value.trim();
+        match key.trim().to_lowercase().as_str() {
+            "domain" if !value.is_empty() => {
+                let domain = value.trim_start_matches('.').to_lowercase();
+                // Servers may only set cookies for their own domain or a parent of it
+                if host != domain && !host.ends_with(&format!(".{}", domain)) {
+                    return None;
+                }
+                cookie.domain = domain;
+                cookie.host_only = false;
+            }
+            "path" if value.starts_with('/') => cookie.path = value.to_string(),
+            "secure" => cookie.secure = true,
+            "expires" => {
+                cookie.expires = DateTime::parse_from_rfc2822(value).ok().map(|d| d.with_timezone(&Utc));
+            }
+            "max-age" => max_age = value.parse::<i64>().ok(),
+            _ => {}
+        }
+    }
+
+    // Max-Age takes precedence over Expires
+    if let Some(seconds) = max_age {
+        cookie.expires = Some(Utc::now() + Duration::seconds(seconds));
+    }
+
+    Some(cookie)
+}
+
+/// The directory of the request path, per RFC 6265 section 5.1.4
+fn default_path(request_path: &str) -> String {
+    match request_path.rfind('/') {
+        Some(0) | None => "/".to_string(),
+        Some(index) => request_path[..index].to_string(),
+    }
+}
+
+fn domain_matches(cookie: &StoredCookie, host: &str) -> bool {
+    if cookie.host_only {
+        host == cookie.domain
+    } else {
+        host == cookie.domain || host.ends_with(&format!(".{}", cookie.domain))
+    }
+}
+
+fn path_matches(cookie_path: &str, request_path: &str) -> bool {
+    request_path == cookie_path
+        || (request_path.starts_with(cookie_path)
+            && (cookie_path.ends_with('/') || request_path[cookie_path.len()..].starts_with('/')))
+}
@@ -1,53 +1,138 @@
-use crate::models::git::GitCredentials;
-use anyhow::Result;
+use crate::models::git::{CredentialHost, GitCredentials};
+use anyhow::{anyhow, Result};
 use keyring::Entry;
 use serde_json;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
 
 const SERVICE_NAME: &str = "postgirl";
+const HOST_INDEX_KEY: &str = "credential_hosts"; // keyring can't enumerate entries, so we keep our own list
 
-pub struct CredentialService;
+type SecretMap = HashMap<(String, String), String>; // (service, account) -> secret
+
+/// Credentials are stored per host and username, under keyring entry `postgirl:<host>`
+#[derive(Clone)]
+pub struct CredentialService {
+    memory: Option<Arc<Mutex<SecretMap>>>, // stands in for the keychain in tests
+}
 
 impl CredentialService {
     pub fn new() -> Self {
-        Self
+        Self { memory: None }
     }
 
-    pub fn store_credentials(&self, key: &str, credentials: &GitCredentials) -> Result<()> {
-        let entry = Entry::new(SERVICE_NAME, key)?;
+    #[cfg(test)]
+    pub fn in_memory() -> Self {
+        Self { memory: Some(Arc::new(Mutex::new(HashMap::new()))) }
+    }
+
+    pub fn store_credentials(&self, host: &str, credentials: &GitCredentials) -> Result<()> {
         let credentials_json = serde_json::to_string(credentials)?;
-        entry.set_password(&credentials_json)?;
+        self.set_secret(&service_for(host), &credentials.username, &credentials_json)?;
+
+        let mut hosts = self.list_credential_hosts()?;
+        if !hosts.iter().any(|h| h.host == host && h.username == credentials.username) {
+            hosts.push(CredentialHost { host: host.to_string(), username: credentials.username.clone() });
+            self.save_hosts(&hosts)?;
+        }
         Ok(())
     }
 
-    pub fn get_credentials(&self, key: &str) -> Result<GitCredentials> {
-        let entry = Entry::new(SERVICE_NAME, key)?;
-        let credentials_json = entry.get_password()?;
+    /// Credentials for a host. Without a username, the first account saved for the host is used.
+    pub fn get_credentials(&self, host: &str, username: Option<&str>) -> Result<GitCredentials> {
+        let username = self.resolve_username(host, username)?;
+        let credentials_json = self
+            .get_secret(&service_for(host), &username)?
+            .ok_or_else(|| anyhow!("No credentials saved for {}@{}", username, host))?;
         let credentials: GitCredentials = serde_json::from_str(&credentials_json)?;
         Ok(credentials)
     }
 
-    pub fn delete_credentials(&self, key: &str) -> Result<()> {
-        let entry = Entry::new(SERVICE_NAME, key)?;
-        entry.delete_credential()?;
-        Ok(())
+    pub fn delete_credentials(&self, host: &str, username: Option<&str>) -> Result<()> {
+        let username = self.resolve_username(host, username)?;
+        self.delete_secret(&service_for(host), &username)?;
+
+        let mut hosts = self.list_credential_hosts()?;
+        hosts.retain(|h| !(h.host == host && h.username == username));
+        self.save_hosts(&hosts)
+    }
+
+    pub fn credentials_exist(&self, host: &str, username: Option<&str>) -> bool {
+        self.get_credentials(host, username).is_ok()
     }
 
-    pub fn credentials_exist(&self, key: &str) -> bool {
-        if let Ok(entry) = Entry::new(SERVICE_NAME, key) {
-            entry.get_password().is_ok()
-        } else {
-            false
+    /// Hosts and usernames that have saved credentials; never the secrets themselves
+    pub fn list_credential_hosts(&self) -> Result<Vec<CredentialHost>> {
+        match self.get_secret(SERVICE_NAME, HOST_INDEX_KEY)? {
+            Some(json) => Ok(serde_json::from_str(&json)?),
+            None => Ok(Vec::new()),
         }
     }
 
-    pub fn list_stored_credentials(&self) -> Result<Vec<String>> {
-        // Note: keyring doesn't provide a way to list all entries
-        // This would need to be implemented using a separate index
-        // For now, return empty list
-        Ok(Vec::new())
+    /// Saved credentials matching a remote URL's host, preferring the username in the URL
+    pub fn credentials_for_url(&self, url: &str) -> Option<GitCredentials> {
+        let parsed = url::Url::parse(url).ok()?;
+        let host = parsed.host_str()?;
+        let username = Some(parsed.username()).filter(|u| !u.is_empty());
+        self.get_credentials(host, username)
+            .or_else(|_| self.get_credentials(host, None))
+            .ok()
+    }
+
+    fn resolve_username(&self, host: &str, username: Option<&str>) -> Result<String> {
+        if let Some(username) = username {
+            return Ok(username.to_string());
+        }
+        self.list_credential_hosts()?
+            .into_iter()
+            .find(|h| h.host == host)
+            .map(|h| h.username)
+            .ok_or_else(|| anyhow!("No credentials saved for {}", host))
+    }
+
+    fn save_hosts(&self, hosts: &[CredentialHost]) -> Result<()> {
+        self.set_secret(SERVICE_NAME, HOST_INDEX_KEY, &serde_json::to_string(hosts)?)
+    }
+
+    fn get_secret(&self, service: &str, account: &str) -> Result<Option<String>> {
+        if let Some(memory) = &self.memory {
+            let memory = memory.lock().map_err(|e| anyhow!("Credential store lock error: {}", e))?;
+            return Ok(memory.get(&(service.to_string(), account.to_string())).cloned());
+        }
+        match Entry::new(service, account)?.get_password() {
+            Ok(secret) => Ok(Some(secret)),
+            Err(keyring::Error::NoEntry) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn set_secret(&self, service: &str, account: &str, secret: &str) -> Result<()> {
+        if let Some(memory) = &self.memory {
+            let mut memory = memory.lock().map_err(|e| anyhow!("Credential store lock error: {}", e))?;
+            memory.insert((service.to_string(), account.to_string()), secret.to_string());
+            return Ok(());
+        }
+        Entry::new(service, account)?.set_password(secret)?;
+        Ok(())
+    }
+
+    fn delete_secret(&self, service: &str, account: &str) -> Result<()> {
+        if let Some(memory) = &self.memory {
+            let mut memory = memory.lock().map_err(|e| anyhow!("Credential store lock error: {}", e))?;
+            return memory
+                .remove(&(service.to_string(), account.to_string()))
+                .map(|_| ())
+                .ok_or_else(|| anyhow!("No credentials saved for {}", service));
+        }
+        Entry::new(service, account)?.delete_credential()?;
+        Ok(())
     }
 }
 
+fn service_for(host: &str) -> String {
+    format!("{}:{}", SERVICE_NAME, host)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -68,15 +153,15 @@ mod tests {
         let store_result = service.store_credentials(test_key, &credentials);
         if store_result.is_ok() {
             // Only test if storage succeeded
-            assert!(service.credentials_exist(test_key));
+            assert!(service.credentials_exist(test_key, None));
 
             // Retrieve credentials
-            let retrieved = service.get_credentials(test_key).unwrap();
+            let retrieved = service.get_credentials(test_key, None).unwrap();
             assert_eq!(retrieved.username, credentials.username);
             assert_eq!(retrieved.password, credentials.password);
 
             // Clean up
-            let _ = service.delete_credentials(test_key);
+            let _ = service.delete_credentials(test_key, None);
         }
         // If keychain access fails, that's acceptable in test environments
     }
@@ -95,20 +180,53 @@ mod tests {
 
         // Store and then delete - might fail in CI environments
         if service.store_credentials(test_key, &credentials).is_ok() {
-            assert!(service.credentials_exist(test_key));
+            assert!(service.credentials_exist(test_key, None));
 
-            service.delete_credentials(test_key).unwrap();
-            assert!(!service.credentials_exist(test_key));
+            service.delete_credentials(test_key, None).unwrap();
+            assert!(!service.credentials_exist(test_key, None));
         }
         // If keychain access fails, that's acceptable in test environments
     }
 
+    #[test]
+    fn test_credentials_per_host() {
+        let service = CredentialService::in_memory();
+        let credentials = |username: &str, password: &str| GitCredentials {
+            username: username.to_string(),
+            password: password.to_string(),
+            ssh_key_path: None,
+        };
+
+        service.store_credentials("github.com", &credentials("work", "work-token")).unwrap();
+        service.store_credentials("gitlab.com", &credentials("me", "gitlab-token")).unwrap();
+        service.store_credentials("github.com", &credentials("personal", "personal-token")).unwrap();
+
+        assert_eq!(service.get_credentials("gitlab.com", None).unwrap().password, "gitlab-token");
+        assert_eq!(service.get_credentials("github.com", Some("work")).unwrap().password, "work-token");
+        assert_eq!(service.get_credentials("github.com", Some("personal")).unwrap().password, "personal-token");
+        assert!(service.get_credentials("bitbucket.org", None).is_err());
+
+        let hosts = service.list_credential_hosts().unwrap();
+        assert_eq!(hosts.len(), 3);
+        assert!(hosts.iter().any(|h| h.host == "gitlab.com" && h.username == "me"));
+
+        // The URL's username picks between accounts on the same host
+        let for_url = service.credentials_for_url("https://personal@github.com/me/api.git").unwrap();
+        assert_eq!(for_url.password, "personal-token");
+        assert_eq!(service.credentials_for_url("https://gitlab.com/team/api.git").unwrap().password, "gitlab-token");
+
+        service.delete_credentials("github.com", Some("work")).unwrap();
+        assert!(!service.credentials_exist("github.com", Some("work")));
+        assert!(service.credentials_exist("github.com", Some("personal")));
+        assert_eq!(service.list_credential_hosts().unwrap().len(), 2);
+    }
+
     #[test]
     fn test_nonexistent_credentials() {
         let service = CredentialService::new();
         let nonexistent_key = "nonexistent_key_12345";
 
-        assert!(!service.credentials_exist(nonexistent_key));
-        assert!(service.get_credentials(nonexistent_key).is_err());
+        assert!(!service.credentials_exist(nonexistent_key, None));
+        assert!(service.get_credentials(nonexistent_key, None).is_err());
     }
 }
\ No newline at end of file
@@ -0,0 +1,366 @@
+use crate::models::http::{FormFile, HttpMethod, HttpRequest, RequestBody};
+use anyhow::{anyhow, Result};
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Default)]
+pub struct CurlImportService;
+
+impl CurlImportService {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Parse a curl command line into an HttpRequest
+    pub fn parse(&self, curl_command: &str) -> Result<HttpRequest> {
+        let mut request = HttpRequest {
+            follow_redirects: false, // curl only follows redirects with -L
+            ..HttpRequest::default()
+        };
+        let tokens = tokenize(curl_command)?;
+
+        if tokens.first().map(String::as_str) != Some("curl") {
+            return Err(anyhow!("Not a curl command"));
+        }
+
+        let mut url = None;
+        let mut method_set = false;
+        let mut data: Vec<String> = Vec::new();
+        let mut form_fields = HashMap::new();
+        let mut form_files = Vec::new();
+        let mut use_get = false;
+
+        let mut i = 1;
+        while i < tokens.len() {
+            let (flag, attached) = split_flag(&tokens[i]);
+
+            if takes_value(flag) {
+                let value = match attached {
+                    Some(value) => value.to_string(),
+                    None => {
+                        i += 1;
+                        tokens.get(i).cloned().ok_or_else(|| anyhow!("Missing value for {}", flag))?
+                    }
+                };
+
+                match flag {
+                    "-X" | "--request" => {
+                        request.method = HttpMethod::from(value.as_str());
+                        method_set = true;
+                    }
+                    "-H" | "--header" => {
+                        if let Some((key, value)) = value.split_once(':') {
+                            add_header(&mut request, key.trim(), value.trim());
+                        }
+                    }
+                    "-d" | "--data" | "--data-raw" | "--data-binary" | "--data-ascii" => data.push(value),
+                    "--data-urlencode" => data.push(url_encode_data(&value)),
+                    "-F" | "--form" => {
+                        // `name=@path` uploads a file; `name=<path` sends a file's contents as text,
+                        // which can't be carried over
+                        if let Some((name, content)) = value.split_once('=') {
+                            if let Some(upload) = content.strip_prefix('@') {
+                                form_files.push(form_file(name, upload));
+                            } else if !content.starts_with('<') {
+                                form_fields.insert(name.to_string(), content.to_string());
+                            }
+                        }
+                    }
+                    "-u" | "--user" => {
+                        let (username, password) = value.split_once(':').unwrap_or((value.as_str(), ""));
+                        request.auth_type = Some("basic".to_string());
+                        request.auth_config = Some(serde_json::json!({
+                            "username": username,
+                            "password": password,
+                        }));
+                    }
+                    // Without `=` the value names a cookie file, which can't be imported
+                    "-b" | "--cookie" if value.contains('=') => add_header(&mut request, "Cookie", &value),
+                    "-A" | "--user-agent" => add_header(&mut request, "User-Agent", &value),
+                    "-e" | "--referer" => add_header(&mut request, "Referer", &value),
+                    "-m" | "--max-time" => {
+                        if let Ok(seconds) = value.parse::<f64>() {
+                            request.timeout_ms = Some((seconds * 1000.0) as u64);
+                        }
+                    }
+                    "--url" => url = Some(value),
+                    _ => {} // Value-taking options with no request equivalent
+                }
+            } else {
+                match flag {
+                    "-G" | "--get" => use_get = true,
+                    "-L" | "--location" => request.follow_redirects = true,
+                    // curl only negotiates compression here; the server's encoding is handled by the client
+                    "--compressed" => {}
+                    token if !token.starts_with('-') && url.is_none() => url = Some(token.to_string()),
+                    _ => {} // Skip unknown options
+                }
+            }
+            i += 1;
+        }
+
+        let mut url = url.ok_or_else(|| anyhow!("No URL found in curl command"))?;
+        if !form_fields.is_empty() || !form_files.is_empty() {
+            request.body = Some(RequestBody::FormData { fields: form_fields, files: form_files });
+            if !method_set {
+                request.method = HttpMethod::Post;
+            }
+        } else if !data.is_empty() {
+            // Multiple data flags are joined the way curl joins them
+            let joined = data.join("&");
+            if use_get {
+                url.push(if url.contains('?') { '&' } else { '?' });
+                url.push_str(&joined);
+            } else {
+                request.body = Some(body_from_data(&request, joined));
+                if !method_set {
+                    request.method = HttpMethod::Post;
+                }
+            }
+        }
+        if use_get && !method_set {
+            request.method = HttpMethod::Get;
+        }
+
+        request.url = url;
+        Ok(request)
+    }
+}
+
+/// Split a command line into words following POSIX shell quoting rules
+fn tokenize(command: &str) -> Result<Vec<String>> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    let mut in_token = false;
+    let mut chars = command.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' => {
+                in_token = true;
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(c) => current.push(c),
+                        None => return Err(anyhow!("Unterminated single quote")),
+                    }
+                }
+            }
+            '"' => {
+                in_token = true;
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        // Inside double quotes a backslash only escapes these characters
+                        Some('\\') => match chars.next() {
+                            Some(c @ ('"' | '\\' | '$' | '`')) => current.push(c),
+                            Some('\n') => {}
+                            Some(c) => {
+                                current.push('\\');
+                                current.push(c);
+                            }
+                            None => return Err(anyhow!("Unterminated double quote")),
+                        },
+                        Some(c) => current.push(c),
+                        None => return Err(anyhow!("Unterminated double quote")),
+                    }
+                }
+            }
+            '\\' => match chars.next() {
+                // A backslash before a newline continues the command on the next line
+                Some('\n') => {}
+                Some('\r') if chars.peek() == Some(&'\n') => {
+                    chars.next();
+                }
+                Some(c) => {
+                    in_token = true;
+                    current.push(c);
+                }
+                None => {}
+            },
+            c if c.is_whitespace() => {
+                if in_token {
+                    tokens.push(std::mem::take(&mut current));
+                    in_token = false;
+                }
+            }
+            c => {
+                in_token = true;
+                current.push(c);
+            }
+        }
+    }
+
+    if in_token {
+        tokens.push(current);
+    }
+    Ok(tokens)
+}
+
+/// Split short options with an attached value, such as `-XPOST`, into flag and value
+fn split_flag(token: &str) -> (&str, Option<&str>) {
+    if token.len() > 2 && token.starts_with('-') && !token.starts_with("--") && token.is_char_boundary(2) {
+        let (flag, rest) = token.split_at(2);
+        if takes_value(flag) {
+            return (flag, Some(rest));
+        }
+    }
+    (token, None)
+}
+
+fn takes_value(flag: &str) -> bool {
+    matches!(
+        flag,
+        "-X" | "--request"
+            | "-H" | "--header"
+            | "-d" | "--data" | "--data-raw" | "--data-binary" | "--data-ascii" | "--data-urlencode"
+            | "-u" | "--user"
+            | "-b" | "--cookie"
+            | "-A" | "--user-agent"
+            | "-e" | "--referer"
+            | "-m" | "--max-time"
+            | "--url"
+            | "-o" | "--output"
+            | "-c" | "--cookie-jar"
+            | "-w" | "--write-out"
+            | "-x" | "--proxy"
+            | "--connect-timeout"
+            | "--retry"
+            | "-F" | "--form"
+            | "-T" | "--upload-file"
+    )
+}
+
+/// Add a header, joining repeated Cookie values into a single header
+fn add_header(request: &mut HttpRequest, key: &str, value: &str) {
+    let existing = request.headers.keys().find(|k| k.eq_ignore_ascii_case(key)).cloned();
+    match existing {
+        Some(existing) if key.eq_ignore_ascii_case("cookie") => {
+            let cookies = request.headers.get_mut(&existing).unwrap();
+            cookies.push_str("; ");
+            cookies.push_str(value);
+        }
+        Some(existing) => {
+            request.headers.insert(existing, value.to_string());
+        }
+        None => {
+            request.headers.insert(key.to_string(), value.to_string());
+        }
+    }
+}
+
+/// Encode a `--data-urlencode` argument: `content`, `=content` or `name=content`
+fn url_encode_data(value: &str) -> String {
+    let encode = |s: &str| url::form_urlencoded::byte_serialize(s.as_bytes()).collect::<String>();
+    match value.split_once('=') {
+        Some(("", content)) => encode(content),
+        Some((name, content)) => format!("{}={}", name, encode(content)),
+        None => encode(value),
+    }
+}
+
+/// Build a file part from an `-F name=@path;type=...;filename=...` upload
+fn form_file(field: &str, upload: &str) -> FormFile {
+    let mut options = upload.split(';');
+    let mut file = FormFile {
+        field: field.to_string(),
+        path: options.next().unwrap_or_default().to_string(),
+        filename: None,
+        content_type: None,
+    };
+    for option in options {
+        match option.split_once('=') {
+            Some(("type", value)) => file.content_type = Some(value.to_string()),
+            Some(("filename", value)) => file.filename = Some(value.to_string()),
+            _ => {}
+        }
+    }
+    file
+}
+
+fn body_from_data(request: &HttpRequest, data: String) -> RequestBody {
+    let content_type = request
+        .headers
+        .iter()
+        .find(|(k, _)| k.eq_ignore_ascii_case("content-type"))
+        .map(|(_, v)| v.clone());
+
+    if let Ok(json) = serde_json::from_str::<serde_json::Value>(&data) {
+        if content_type.as_deref().is_none_or(|ct| ct.contains("json")) {
+            return RequestBody::Json { data: json };
+        }
+    }
+
+    // curl sends data as a urlencoded form unless told otherwise
+    if content_type.as_deref().is_none_or(|ct| ct.contains("x-www-form-urlencoded")) {
+        if let Some(fields) = form_fields(&data) {
+            return RequestBody::FormUrlEncoded { fields };
+        }
+    }
+
+    RequestBody::Raw {
+        content: data,
+        content_type: content_type.unwrap_or_else(|| "application/x-www-form-urlencoded".to_string()),
+    }
+}
+
+/// Decode `a=1&b=2` into fields, or `None` if it isn't a simple form with unique keys
+fn form_fields(data: &str) -> Option<HashMap<String, String>> {
+    if data.split('&').any(|pair| !pair.contains('=')) {
+        return None;
+    }
+    let pairs: Vec<(String, String)> = url::form_urlencoded::parse(data.as_bytes()).into_owned().collect();
+    let count = pairs.len();
+    let fields: HashMap<String, String> = pairs.into_iter().collect();
+    (fields.len() == count).then_some(fields)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_quoted_json_body() {
+        let command = r#"curl -X POST 'https://api.example.com/x' -H 'Content-Type: application/json' -d '{"a": "b c"}'"#;
+
+        let request = CurlImportService::new().parse(command).unwrap();
+
+        assert_eq!(request.method, HttpMethod::Post);
+        assert_eq!(request.url, "https://api.example.com/x");
+        assert_eq!(request.headers.get("Content-Type").map(String::as_str), Some("application/json"));
+        match request.body {
+            Some(RequestBody::Json { data }) => assert_eq!(data, serde_json::json!({"a": "b c"})),
+            other => panic!("expected a JSON body, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_auth_data_and_cookies() {
+        let command = "curl \"https://api.example.com/search\" \\\n  -u admin:s3cret \\\n  -d q=rust -d 'page=2' \
+                       --data-urlencode 'filter=a b&c' --compressed -b 'session=abc' --cookie \"theme=dark\" -H X-Note:\\ two\\ words";
+
+        let request = CurlImportService::new().parse(command).unwrap();
+
+        assert_eq!(request.url, "https://api.example.com/search");
+        // Data without an explicit method makes it a POST, as in curl
+        assert_eq!(request.method, HttpMethod::Post);
+        assert_eq!(request.auth_type.as_deref(), Some("basic"));
+        assert_eq!(request.auth_config, Some(serde_json::json!({"username": "admin", "password": "s3cret"})));
+        assert_eq!(request.headers.get("Cookie").map(String::as_str), Some("session=abc; theme=dark"));
+        assert_eq!(request.headers.get("X-Note").map(String::as_str), Some("two words"));
+        match request.body {
+            Some(RequestBody::FormUrlEncoded { fields }) => {
+                assert_eq!(fields.len(), 3);
+                assert_eq!(fields.get("page").map(String::as_str), Some("2"));
+                assert_eq!(fields.get("filter").map(String::as_str), Some("a b&c"));
+            }
+            other => panic!("expected a form body, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_rejects_unterminated_quote() {
+        let error = CurlImportService::new().parse("curl 'https://api.example.com").unwrap_err();
+        assert!(error.to_string().contains("Unterminated"));
+    }
+}
@@ -1,9 +1,44 @@
 use crate::models::workspace::{Workspace, WorkspaceSettings, WorkspaceSummary};
 use anyhow::Result;
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use sqlx::{migrate::MigrateDatabase, Sqlite, SqlitePool, Row};
+use std::collections::HashSet;
 use std::path::Path;
 
+/// Columns added after the initial schema, in the order they were introduced. Each is added
+/// once and recorded under its version in schema_migrations; new ones go at the end with the
+/// next version.
+const COLUMN_MIGRATIONS: &[(i64, &str, &str, &str)] = &[
+    (1, "requests", "include_in_run", "BOOLEAN NOT NULL DEFAULT 1"),
+    (2, "requests", "raw_stream", "BOOLEAN NOT NULL DEFAULT 0"),
+    (3, "workspace_settings", "min_request_interval_ms", "INTEGER NOT NULL DEFAULT 0"),
+    (4, "workspace_settings", "max_response_bytes", "INTEGER NOT NULL DEFAULT 0"),
+    (5, "workspace_settings", "connect_timeout_ms", "INTEGER NOT NULL DEFAULT 0"),
+    (6, "requests", "connect_timeout_ms", "INTEGER"),
+    (7, "workspace_settings", "proxy_url", "TEXT"),
+    (8, "workspace_settings", "proxy_username", "TEXT"),
+    (9, "workspace_settings", "proxy_password", "TEXT"),
+    (10, "workspace_settings", "no_proxy", "TEXT"),
+    (11, "workspace_settings", "client_cert_path", "TEXT"),
+    (12, "workspace_settings", "client_key_path", "TEXT"),
+    (13, "workspace_settings", "client_cert_password", "TEXT"),
+    (14, "workspace_settings", "ca_bundle_path", "TEXT"),
+    (15, "requests", "extractions", "TEXT NOT NULL DEFAULT '[]'"),
+    (16, "requests", "assertions", "TEXT NOT NULL DEFAULT '[]'"),
+    (17, "environments", "is_protected", "BOOLEAN NOT NULL DEFAULT 0"),
+    (18, "environments", "parent_id", "TEXT"),
+    (19, "collections", "parent_id", "TEXT"),
+    (20, "workspaces", "git_username", "TEXT"),
+    (21, "workspaces", "git_email", "TEXT"),
+    (22, "collections", "deleted_at", "TEXT"),
+    (23, "requests", "deleted_at", "TEXT"),
+    (24, "requests", "run_condition", "TEXT"),
+    (25, "requests", "pre_request", "TEXT NOT NULL DEFAULT '[]'"),
+    (26, "workspace_settings", "user_agent", "TEXT"),
+    (27, "workspace_settings", "default_headers", "TEXT NOT NULL DEFAULT '{}'"),
+];
+
+#[derive(Clone)]
 pub struct DatabaseService {
     pool: SqlitePool,
 }
@@ -12,40 +47,505 @@ impl DatabaseService {
     pub async fn new(database_path: &str) -> Result<Self> {
         // Ensure the database directory exists
         if let Some(parent) = Path::new(database_path).parent() {
-            tokio::fs::create_dir_all(parent).await
-                .map_err(|e| anyhow::anyhow!("Failed to create database directory: {}", e))?;
+            tokio::fs::create_dir_all(parent).await?;
         }
 
         // Create database if it doesn't exist
         if !Sqlite::database_exists(database_path).await.unwrap_or(false) {
-            println!("Creating new database at: {}", database_path);
-            Sqlite::create_database(database_path).await
-                .map_err(|e| anyhow::anyhow!("Failed to create database: {}", e))?;
+            Sqlite::create_database(database_path).await?;
         }
 
-        // Connect to database with retry logic
-        let pool = match SqlitePool::connect(database_path).await {
-            Ok(pool) => pool,
-            Err(e) => {
-                eprintln!("Failed to connect to database on first attempt: {}", e);
-                // Retry after a short delay
-                tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
-                SqlitePool::connect(database_path).await
-                    .map_err(|e| anyhow::anyhow!("Failed to connect to database after retry: {}", e))?
-            }
-        };
+        // Connect to database
+        let pool = SqlitePool::connect(database_path).await?;
+
+        // Run migrations manually
+        Self::run_migrations(&pool).await?;
+
+        Ok(Self { pool })
+    }
+
+    pub async fn run_migrations(pool: &SqlitePool) -> Result<()> {
+        // Create workspaces table
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS workspaces (
+                id TEXT PRIMARY KEY NOT NULL,
+                name TEXT NOT NULL,
+                description TEXT,
+                git_repository_url TEXT,
+                local_path TEXT NOT NULL,
+                is_active BOOLEAN NOT NULL DEFAULT 0,
+                created_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL,
+                last_accessed_at TEXT
+            )
+            "#,
+        )
+        .execute(pool)
+        .await?;
+
+        // Create indexes
+        sqlx::query("CREATE INDEX IF NOT EXISTS idx_workspaces_active ON workspaces(is_active) WHERE is_active = 1")
+            .execute(pool)
+            .await?;
+
+        sqlx::query("CREATE INDEX IF NOT EXISTS idx_workspaces_last_accessed ON workspaces(last_accessed_at DESC)")
+            .execute(pool)
+            .await?;
+
+        // Create workspace_settings table
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS workspace_settings (
+                id TEXT PRIMARY KEY NOT NULL,
+                workspace_id TEXT NOT NULL,
+                auto_save BOOLEAN NOT NULL DEFAULT 1,
+                sync_on_startup BOOLEAN NOT NULL DEFAULT 1,
+                default_timeout INTEGER NOT NULL DEFAULT 30000,
+                follow_redirects BOOLEAN NOT NULL DEFAULT 1,
+                verify_ssl BOOLEAN NOT NULL DEFAULT 1,
+                created_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL,
+                FOREIGN KEY (workspace_id) REFERENCES workspaces(id) ON DELETE CASCADE
+            )
+            "#,
+        )
+        .execute(pool)
+        .await?;
+
+        sqlx::query("CREATE UNIQUE INDEX IF NOT EXISTS idx_workspace_settings_workspace_id ON workspace_settings(workspace_id)")
+            .execute(pool)
+            .await?;
+
+        // Create collections table
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS collections (
+                id TEXT PRIMARY KEY,
+                workspace_id TEXT NOT NULL,
+                name TEXT NOT NULL,
+                description TEXT,
+                folder_path TEXT,
+                git_branch TEXT,
+                is_active BOOLEAN NOT NULL DEFAULT 0,
+                created_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL,
+                FOREIGN KEY (workspace_id) REFERENCES workspaces (id) ON DELETE CASCADE
+            )
+            "#,
+        )
+        .execute(pool)
+        .await?;
+
+        // Create requests table
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS requests (
+                id TEXT PRIMARY KEY,
+                collection_id TEXT NOT NULL,
+                name TEXT NOT NULL,
+                description TEXT,
+                method TEXT NOT NULL,
+                url TEXT NOT NULL,
+                headers TEXT NOT NULL DEFAULT '{}',
+                body TEXT,
+                body_type TEXT NOT NULL DEFAULT 'json',
+                auth_type TEXT,
+                auth_config TEXT,
+                follow_redirects BOOLEAN NOT NULL DEFAULT 1,
+                timeout_ms INTEGER NOT NULL DEFAULT 30000,
+                order_index INTEGER NOT NULL DEFAULT 0,
+                created_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL,
+                FOREIGN KEY (collection_id) REFERENCES collections (id) ON DELETE CASCADE
+            )
+            "#,
+        )
+        .execute(pool)
+        .await?;
+
+        // Create indexes for collections and requests
+        sqlx::query("CREATE INDEX IF NOT EXISTS idx_collections_workspace_id ON collections(workspace_id)")
+            .execute(pool)
+            .await?;
+            
+        sqlx::query("CREATE INDEX IF NOT EXISTS idx_collections_is_active ON collections(is_active)")
+            .execute(pool)
+            .await?;
+            
+        sqlx::query("CREATE INDEX IF NOT EXISTS idx_requests_collection_id ON requests(collection_id)")
+            .execute(pool)
+            .await?;
+            
+        sqlx::query("CREATE INDEX IF NOT EXISTS idx_requests_order_index ON requests(order_index)")
+            .execute(pool)
+            .await?;
+
+        // Create environments table
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS environments (
+                id TEXT PRIMARY KEY,
+                workspace_id TEXT NOT NULL,
+                name TEXT NOT NULL,
+                is_active BOOLEAN DEFAULT FALSE,
+                created_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL,
+                FOREIGN KEY (workspace_id) REFERENCES workspaces (id) ON DELETE CASCADE,
+                UNIQUE(workspace_id, name)
+            )
+            "#
+        )
+        .execute(pool)
+        .await?;
+
+        // Create environment_variables table
+        sqlx::query(&Self::environment_variables_table("environment_variables"))
+            .execute(pool)
+            .await?;
+        Self::widen_variable_type_check(pool).await?;
+
+        // Create indexes for better performance
+        sqlx::query("CREATE INDEX IF NOT EXISTS idx_environments_workspace_id ON environments(workspace_id)")
+            .execute(pool)
+            .await?;
+            
+        sqlx::query("CREATE INDEX IF NOT EXISTS idx_environments_active ON environments(workspace_id, is_active)")
+            .execute(pool)
+            .await?;
+            
+        sqlx::query("CREATE INDEX IF NOT EXISTS idx_environment_variables_environment_id ON environment_variables(environment_id)")
+            .execute(pool)
+            .await?;
+
+        // Create global, collection and request variable tables (the scopes around an environment)
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS global_variables (
+                workspace_id TEXT NOT NULL,
+                variable_key TEXT NOT NULL,
+                value TEXT NOT NULL,
+                updated_at TEXT NOT NULL,
+                PRIMARY KEY (workspace_id, variable_key),
+                FOREIGN KEY (workspace_id) REFERENCES workspaces (id) ON DELETE CASCADE
+            )
+            "#
+        )
+        .execute(pool)
+        .await?;
+
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS collection_variables (
+                collection_id TEXT NOT NULL,
+                variable_key TEXT NOT NULL,
+                value TEXT NOT NULL,
+                updated_at TEXT NOT NULL,
+                PRIMARY KEY (collection_id, variable_key),
+                FOREIGN KEY (collection_id) REFERENCES collections (id) ON DELETE CASCADE
+            )
+            "#
+        )
+        .execute(pool)
+        .await?;
+
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS request_variables (
+                request_id TEXT NOT NULL,
+                variable_key TEXT NOT NULL,
+                value TEXT NOT NULL,
+                updated_at TEXT NOT NULL,
+                PRIMARY KEY (request_id, variable_key),
+                FOREIGN KEY (request_id) REFERENCES requests (id) ON DELETE CASCADE
+            )
+            "#
+        )
+        .execute(pool)
+        .await?;
+
+        // Create request_examples table
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS request_examples (
+                id TEXT PRIMARY KEY,
+                request_id TEXT NOT NULL,
+                name TEXT NOT NULL,
+                status INTEGER NOT NULL,
+                headers TEXT NOT NULL DEFAULT '{}',
+                body TEXT NOT NULL,
+                volatile_fields TEXT NOT NULL DEFAULT '[]',
+                created_at TEXT NOT NULL,
+                FOREIGN KEY (request_id) REFERENCES requests (id) ON DELETE CASCADE
+            )
+            "#
+        )
+        .execute(pool)
+        .await?;
+
+        sqlx::query("CREATE INDEX IF NOT EXISTS idx_request_examples_request_id ON request_examples(request_id)")
+            .execute(pool)
+            .await?;
+
+        // Create last_responses table (most recent response per request, not a history)
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS last_responses (
+                request_id TEXT PRIMARY KEY,
+                response TEXT NOT NULL,
+                updated_at TEXT NOT NULL
+            )
+            "#
+        )
+        .execute(pool)
+        .await?;
+
+        // Create response_snapshots table (a baseline response per request, for regression diffs)
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS response_snapshots (
+                request_id TEXT PRIMARY KEY,
+                status INTEGER NOT NULL,
+                headers TEXT NOT NULL DEFAULT '{}',
+                body TEXT NOT NULL,
+                created_at TEXT NOT NULL,
+                FOREIGN KEY (request_id) REFERENCES requests (id) ON DELETE CASCADE
+            )
+            "#
+        )
+        .execute(pool)
+        .await?;
+
+        // Create request_history table (a summary of each execution, without bodies)
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS request_history (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                workspace_id TEXT NOT NULL,
+                request_id TEXT NOT NULL,
+                method TEXT NOT NULL,
+                url TEXT NOT NULL,
+                status INTEGER,
+                total_time_ms INTEGER,
+                response_size INTEGER,
+                error TEXT,
+                executed_at TEXT NOT NULL,
+                FOREIGN KEY (workspace_id) REFERENCES workspaces (id) ON DELETE CASCADE
+            )
+            "#
+        )
+        .execute(pool)
+        .await?;
+
+        sqlx::query("CREATE INDEX IF NOT EXISTS idx_request_history_workspace ON request_history(workspace_id, id)")
+            .execute(pool)
+            .await?;
+
+        // Create branch_history table (branches created from naming patterns, used for suggestions)
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS branch_history (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                branch_name TEXT NOT NULL,
+                pattern_json TEXT NOT NULL,
+                created_at TEXT NOT NULL DEFAULT (datetime('now')),
+                workspace_name TEXT,
+                feature_type TEXT,
+                username TEXT,
+                machine_name TEXT
+            )
+            "#
+        )
+        .execute(pool)
+        .await?;
+
+        sqlx::query("CREATE INDEX IF NOT EXISTS idx_branch_history_created_at ON branch_history(created_at)")
+            .execute(pool)
+            .await?;
+
+        sqlx::query("CREATE INDEX IF NOT EXISTS idx_branch_history_workspace ON branch_history(workspace_name)")
+            .execute(pool)
+            .await?;
+
+        // App-wide settings stored as JSON, keyed by name
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS app_settings (
+                key TEXT PRIMARY KEY,
+                value TEXT NOT NULL,
+                updated_at TEXT NOT NULL
+            )
+            "#
+        )
+        .execute(pool)
+        .await?;
+
+        Self::apply_pending_migrations(pool).await?;
+
+        Self::create_request_search_index(pool).await?;
+
+        Ok(())
+    }
+
+    /// Full-text index over requests, kept current by triggers. The trigram tokenizer lets any
+    /// substring of three or more characters match, case-insensitively.
+    async fn create_request_search_index(pool: &SqlitePool) -> Result<()> {
+        let exists = sqlx::query("SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'requests_fts'")
+            .fetch_optional(pool)
+            .await?
+            .is_some();
+
+        sqlx::query(
+            r#"
+            CREATE VIRTUAL TABLE IF NOT EXISTS requests_fts USING fts5(
+                name, description, url, headers, body,
+                content = 'requests', content_rowid = 'rowid', tokenize = 'trigram'
+            )
+            "#
+        )
+        .execute(pool)
+        .await?;
+
+        for trigger in [
+            r#"
+            CREATE TRIGGER IF NOT EXISTS requests_fts_insert AFTER INSERT ON requests BEGIN
+                INSERT INTO requests_fts (rowid, name, description, url, headers, body)
+                VALUES (new.rowid, new.name, new.description, new.url, new.headers, new.body);
+            END
+            "#,
+            r#"
+            CREATE TRIGGER IF NOT EXISTS requests_fts_delete AFTER DELETE ON requests BEGIN
+                INSERT INTO requests_fts (requests_fts, rowid, name, description, url, headers, body)
+                VALUES ('delete', old.rowid, old.name, old.description, old.url, old.headers, old.body);
+            END
+            "#,
+            r#"
+            CREATE TRIGGER IF NOT EXISTS requests_fts_update AFTER UPDATE ON requests BEGIN
+                INSERT INTO requests_fts (requests_fts, rowid, name, description, url, headers, body)
+                VALUES ('delete', old.rowid, old.name, old.description, old.url, old.headers, old.body);
+                INSERT INTO requests_fts (rowid, name, description, url, headers, body)
+                VALUES (new.rowid, new.name, new.description, new.url, new.headers, new.body);
+            END
+            "#,
+        ] {
+            sqlx::query(trigger).execute(pool).await?;
+        }
+
+        // Index the requests saved before the index existed
+        if !exists {
+            sqlx::query("INSERT INTO requests_fts (requests_fts) VALUES ('rebuild')")
+                .execute(pool)
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    fn environment_variables_table(name: &str) -> String {
+        format!(
+            r#"
+            CREATE TABLE IF NOT EXISTS {} (
+                id TEXT PRIMARY KEY DEFAULT (lower(hex(randomblob(16)))),
+                environment_id TEXT NOT NULL,
+                variable_key TEXT NOT NULL,
+                value TEXT NOT NULL,
+                is_secret BOOLEAN DEFAULT FALSE,
+                variable_type TEXT DEFAULT 'string' CHECK (variable_type IN ('string', 'secret', 'number', 'boolean', 'url')),
+                created_at TEXT NOT NULL DEFAULT (datetime('now')),
+                updated_at TEXT NOT NULL DEFAULT (datetime('now')),
+                FOREIGN KEY (environment_id) REFERENCES environments (id) ON DELETE CASCADE,
+                UNIQUE(environment_id, variable_key)
+            )
+            "#,
+            name
+        )
+    }
+
+    /// SQLite cannot alter a CHECK constraint, so databases created before the number, boolean
+    /// and url variable types existed get their environment_variables table rebuilt
+    async fn widen_variable_type_check(pool: &SqlitePool) -> Result<()> {
+        let schema: String = sqlx::query_scalar("SELECT sql FROM sqlite_master WHERE type = 'table' AND name = 'environment_variables'")
+            .fetch_one(pool)
+            .await?;
+        if schema.contains("'number'") {
+            return Ok(());
+        }
+
+        let mut tx = pool.begin().await?;
+        sqlx::query(&Self::environment_variables_table("environment_variables_new"))
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query(
+            r#"
+            INSERT INTO environment_variables_new (
+                id, environment_id, variable_key, value, is_secret, variable_type, created_at, updated_at
+            )
+            SELECT id, environment_id, variable_key, value, is_secret, variable_type, created_at, updated_at
+            FROM environment_variables
+            WHERE environment_id IN (SELECT id FROM environments)
+            "#
+        )
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query("DROP TABLE environment_variables").execute(&mut *tx).await?;
+        sqlx::query("ALTER TABLE environment_variables_new RENAME TO environment_variables")
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+
+        Ok(())
+    }
 
-        // Run migrations with error handling
-        match sqlx::migrate!("./migrations").run(&pool).await {
-            Ok(_) => println!("Database migrations completed successfully"),
-            Err(e) => {
-                eprintln!("Migration error: {}", e);
-                // Try to continue with existing schema - log but don't fail
-                println!("Continuing with existing database schema");
+    /// Apply the column migrations this database hasn't recorded yet. Versions recorded by a
+    /// newer build are left alone, so an older build can still open the database.
+    async fn apply_pending_migrations(pool: &SqlitePool) -> Result<()> {
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS schema_migrations (
+                version INTEGER PRIMARY KEY,
+                applied_at TEXT NOT NULL
+            )
+            "#
+        )
+        .execute(pool)
+        .await?;
+
+        let applied: HashSet<i64> = sqlx::query_scalar("SELECT version FROM schema_migrations")
+            .fetch_all(pool)
+            .await?
+            .into_iter()
+            .collect();
+
+        for &(version, table, column, definition) in COLUMN_MIGRATIONS {
+            if applied.contains(&version) {
+                continue;
             }
+            // Databases from before versioning, or a run cut short before the version was
+            // recorded, may have the column already
+            Self::add_column_if_missing(pool, table, column, definition).await?;
+            sqlx::query("INSERT INTO schema_migrations (version, applied_at) VALUES (?, ?)")
+                .bind(version)
+                .bind(Utc::now().to_rfc3339())
+                .execute(pool)
+                .await?;
         }
 
-        Ok(Self { pool })
+        Ok(())
+    }
+
+    /// Add a column to an existing table unless it is already present
+    async fn add_column_if_missing(pool: &SqlitePool, table: &str, column: &str, definition: &str) -> Result<()> {
+        let columns = sqlx::query(&format!("PRAGMA table_info({})", table))
+            .fetch_all(pool)
+            .await?;
+
+        if !columns.iter().any(|row| row.get::<String, _>("name") == column) {
+            sqlx::query(&format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition))
+                .execute(pool)
+                .await?;
+        }
+
+        Ok(())
     }
 
     // Workspace CRUD operations
@@ -53,9 +553,9 @@ impl DatabaseService {
         sqlx::query(
             r#"
             INSERT INTO workspaces (
-                id, name, description, git_repository_url, local_path, 
+                id, name, description, git_repository_url, local_path, git_username, git_email,
                 is_active, created_at, updated_at, last_accessed_at
-            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
             "#
         )
         .bind(&workspace.id)
@@ -63,10 +563,12 @@ impl DatabaseService {
         .bind(&workspace.description)
         .bind(&workspace.git_repository_url)
         .bind(&workspace.local_path)
+        .bind(&workspace.git_username)
+        .bind(&workspace.git_email)
         .bind(workspace.is_active)
-        .bind(workspace.created_at)
-        .bind(workspace.updated_at)
-        .bind(workspace.last_accessed_at)
+        .bind(workspace.created_at.to_rfc3339())
+        .bind(workspace.updated_at.to_rfc3339())
+        .bind(workspace.last_accessed_at.map(|dt| dt.to_rfc3339()))
         .execute(&self.pool)
         .await?;
 
@@ -74,56 +576,61 @@ impl DatabaseService {
     }
 
     pub async fn get_workspace(&self, id: &str) -> Result<Option<Workspace>> {
-        let workspace = sqlx::query_as!(
-            Workspace,
-            "SELECT * FROM workspaces WHERE id = ?",
-            id
-        )
-        .fetch_optional(&self.pool)
-        .await?;
+        let row = sqlx::query("SELECT * FROM workspaces WHERE id = ?")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?;
 
-        Ok(workspace)
+        if let Some(row) = row {
+            Ok(Some(self.row_to_workspace(row)?))
+        } else {
+            Ok(None)
+        }
     }
 
     pub async fn get_all_workspaces(&self) -> Result<Vec<Workspace>> {
-        let workspaces = sqlx::query_as!(
-            Workspace,
-            "SELECT * FROM workspaces ORDER BY last_accessed_at DESC, created_at DESC"
-        )
-        .fetch_all(&self.pool)
-        .await?;
+        let rows = sqlx::query("SELECT * FROM workspaces ORDER BY last_accessed_at DESC, created_at DESC")
+            .fetch_all(&self.pool)
+            .await?;
 
+        let mut workspaces = Vec::new();
+        for row in rows {
+            workspaces.push(self.row_to_workspace(row)?);
+        }
         Ok(workspaces)
     }
 
     pub async fn get_active_workspace(&self) -> Result<Option<Workspace>> {
-        let workspace = sqlx::query_as!(
-            Workspace,
-            "SELECT * FROM workspaces WHERE is_active = 1 LIMIT 1"
-        )
-        .fetch_optional(&self.pool)
-        .await?;
+        let row = sqlx::query("SELECT * FROM workspaces WHERE is_active = 1 LIMIT 1")
+            .fetch_optional(&self.pool)
+            .await?;
 
-        Ok(workspace)
+        if let Some(row) = row {
+            Ok(Some(self.row_to_workspace(row)?))
+        } else {
+            Ok(None)
+        }
     }
 
     pub async fn update_workspace(&self, workspace: &Workspace) -> Result<()> {
-        sqlx::query!(
+        sqlx::query(
             r#"
             UPDATE workspaces SET 
                 name = ?, description = ?, git_repository_url = ?, 
-                local_path = ?, is_active = ?, updated_at = ?, last_accessed_at = ?
+                local_path = ?, git_username = ?, git_email = ?, is_active = ?, updated_at = ?, last_accessed_at = ?
             WHERE id = ?
-            "#,
-            workspace.name,
-            workspace.description,
-            workspace.git_repository_url,
-            workspace.local_path,
-            workspace.is_active,
-            workspace.updated_at,
-            workspace.last_accessed_at,
-            workspace.id
+            "#
         )
+        .bind(&workspace.name)
+        .bind(&workspace.description)
+        .bind(&workspace.git_repository_url)
+        .bind(&workspace.local_path)
+        .bind(&workspace.git_username)
+        .bind(&workspace.git_email)
+        .bind(workspace.is_active)
+        .bind(workspace.updated_at.to_rfc3339())
+        .bind(workspace.last_accessed_at.map(|dt| dt.to_rfc3339()))
+        .bind(&workspace.id)
         .execute(&self.pool)
         .await?;
 
@@ -132,56 +639,132 @@ impl DatabaseService {
 
     pub async fn delete_workspace(&self, id: &str) -> Result<()> {
         // Delete related settings first
-        sqlx::query!("DELETE FROM workspace_settings WHERE workspace_id = ?", id)
+        sqlx::query("DELETE FROM workspace_settings WHERE workspace_id = ?")
+            .bind(id)
             .execute(&self.pool)
             .await?;
 
         // Delete workspace
-        sqlx::query!("DELETE FROM workspaces WHERE id = ?", id)
+        sqlx::query("DELETE FROM workspaces WHERE id = ?")
+            .bind(id)
             .execute(&self.pool)
             .await?;
 
         Ok(())
     }
 
+    /// Make `id` the only active workspace. An unknown id is an error and leaves the current
+    /// active workspace as it was.
     pub async fn set_active_workspace(&self, id: &str) -> Result<()> {
+        if !self.workspace_exists(id).await? {
+            return Err(anyhow::anyhow!("Workspace not found: {}", id));
+        }
+
+        let now = Utc::now();
+        let mut transaction = self.pool.begin().await?;
+
         // First deactivate all workspaces
-        sqlx::query!("UPDATE workspaces SET is_active = 0, updated_at = ?", Utc::now())
-            .execute(&self.pool)
+        sqlx::query("UPDATE workspaces SET is_active = 0, updated_at = ?")
+            .bind(now.to_rfc3339())
+            .execute(&mut *transaction)
             .await?;
 
         // Then activate the specified workspace and update last_accessed_at
-        sqlx::query!(
-            "UPDATE workspaces SET is_active = 1, last_accessed_at = ?, updated_at = ? WHERE id = ?",
-            Utc::now(),
-            Utc::now(),
-            id
+        let activated = sqlx::query(
+            "UPDATE workspaces SET is_active = 1, last_accessed_at = ?, updated_at = ? WHERE id = ?"
         )
-        .execute(&self.pool)
+        .bind(now.to_rfc3339())
+        .bind(now.to_rfc3339())
+        .bind(id)
+        .execute(&mut *transaction)
         .await?;
 
+        // Deleted since the check above; dropping the transaction rolls the deactivation back
+        if activated.rows_affected() == 0 {
+            return Err(anyhow::anyhow!("Workspace not found: {}", id));
+        }
+
+        transaction.commit().await?;
         Ok(())
     }
 
+    pub async fn workspace_exists(&self, id: &str) -> Result<bool> {
+        let row = sqlx::query("SELECT COUNT(*) as count FROM workspaces WHERE id = ?")
+            .bind(id)
+            .fetch_one(&self.pool)
+            .await?;
+
+        let count: i64 = row.get("count");
+        Ok(count > 0)
+    }
+
+    pub async fn get_workspace_summaries(&self) -> Result<Vec<WorkspaceSummary>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT 
+                id, name, description, local_path, is_active, last_accessed_at
+            FROM workspaces 
+            ORDER BY last_accessed_at DESC, created_at DESC
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut summaries = Vec::new();
+        for row in rows {
+            let last_accessed_at_str: Option<String> = row.get("last_accessed_at");
+            summaries.push(WorkspaceSummary {
+                id: row.get("id"),
+                name: row.get("name"),
+                description: row.get("description"),
+                local_path: row.get("local_path"),
+                is_active: row.get("is_active"),
+                last_accessed_at: last_accessed_at_str
+                    .map(|s| DateTime::parse_from_rfc3339(&s).map(|dt| dt.with_timezone(&Utc)))
+                    .transpose()?,
+                git_status: None,
+                collection_count: 0,
+                request_count: 0,
+            });
+        }
+        Ok(summaries)
+    }
+
     // Workspace Settings operations
     pub async fn create_workspace_settings(&self, settings: &WorkspaceSettings) -> Result<()> {
-        sqlx::query!(
+        sqlx::query(
             r#"
             INSERT INTO workspace_settings (
                 id, workspace_id, auto_save, sync_on_startup, default_timeout,
-                follow_redirects, verify_ssl, created_at, updated_at
-            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
-            "#,
-            settings.id,
-            settings.workspace_id,
-            settings.auto_save,
-            settings.sync_on_startup,
-            settings.default_timeout,
-            settings.follow_redirects,
-            settings.verify_ssl,
-            settings.created_at,
-            settings.updated_at
+                follow_redirects, verify_ssl, min_request_interval_ms, max_response_bytes, connect_timeout_ms,
+                proxy_url, proxy_username, proxy_password, no_proxy,
+                client_cert_path, client_key_path, client_cert_password, ca_bundle_path, user_agent, default_headers,
+                created_at, updated_at
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#
         )
+        .bind(&settings.id)
+        .bind(&settings.workspace_id)
+        .bind(settings.auto_save)
+        .bind(settings.sync_on_startup)
+        .bind(settings.default_timeout as i64)
+        .bind(settings.follow_redirects)
+        .bind(settings.verify_ssl)
+        .bind(settings.min_request_interval_ms as i64)
+        .bind(settings.max_response_bytes as i64)
+        .bind(settings.connect_timeout_ms as i64)
+        .bind(&settings.proxy_url)
+        .bind(&settings.proxy_username)
+        .bind(&settings.proxy_password)
+        .bind(&settings.no_proxy)
+        .bind(&settings.client_cert_path)
+        .bind(&settings.client_key_path)
+        .bind(&settings.client_cert_password)
+        .bind(&settings.ca_bundle_path)
+        .bind(&settings.user_agent)
+        .bind(serde_json::to_string(&settings.default_headers)?)
+        .bind(settings.created_at.to_rfc3339())
+        .bind(settings.updated_at.to_rfc3339())
         .execute(&self.pool)
         .await?;
 
@@ -189,90 +772,139 @@ impl DatabaseService {
     }
 
     pub async fn get_workspace_settings(&self, workspace_id: &str) -> Result<Option<WorkspaceSettings>> {
-        let settings = sqlx::query_as!(
-            WorkspaceSettings,
-            "SELECT * FROM workspace_settings WHERE workspace_id = ?",
-            workspace_id
-        )
-        .fetch_optional(&self.pool)
-        .await?;
+        let row = sqlx::query("SELECT * FROM workspace_settings WHERE workspace_id = ?")
+            .bind(workspace_id)
+            .fetch_optional(&self.pool)
+            .await?;
 
-        Ok(settings)
+        if let Some(row) = row {
+            Ok(Some(self.row_to_workspace_settings(row)?))
+        } else {
+            Ok(None)
+        }
     }
 
     pub async fn update_workspace_settings(&self, settings: &WorkspaceSettings) -> Result<()> {
-        sqlx::query!(
+        sqlx::query(
             r#"
             UPDATE workspace_settings SET 
                 auto_save = ?, sync_on_startup = ?, default_timeout = ?,
-                follow_redirects = ?, verify_ssl = ?, updated_at = ?
+                follow_redirects = ?, verify_ssl = ?, min_request_interval_ms = ?, max_response_bytes = ?,
+                connect_timeout_ms = ?, proxy_url = ?, proxy_username = ?, proxy_password = ?, no_proxy = ?,
+                client_cert_path = ?, client_key_path = ?, client_cert_password = ?, ca_bundle_path = ?,
+                user_agent = ?, default_headers = ?, updated_at = ?
             WHERE workspace_id = ?
-            "#,
-            settings.auto_save,
-            settings.sync_on_startup,
-            settings.default_timeout,
-            settings.follow_redirects,
-            settings.verify_ssl,
-            settings.updated_at,
-            settings.workspace_id
+            "#
         )
+        .bind(settings.auto_save)
+        .bind(settings.sync_on_startup)
+        .bind(settings.default_timeout as i64)
+        .bind(settings.follow_redirects)
+        .bind(settings.verify_ssl)
+        .bind(settings.min_request_interval_ms as i64)
+        .bind(settings.max_response_bytes as i64)
+        .bind(settings.connect_timeout_ms as i64)
+        .bind(&settings.proxy_url)
+        .bind(&settings.proxy_username)
+        .bind(&settings.proxy_password)
+        .bind(&settings.no_proxy)
+        .bind(&settings.client_cert_path)
+        .bind(&settings.client_key_path)
+        .bind(&settings.client_cert_password)
+        .bind(&settings.ca_bundle_path)
+        .bind(&settings.user_agent)
+        .bind(serde_json::to_string(&settings.default_headers)?)
+        .bind(settings.updated_at.to_rfc3339())
+        .bind(&settings.workspace_id)
         .execute(&self.pool)
         .await?;
 
         Ok(())
     }
 
-    // Utility methods
-    pub async fn get_workspace_summaries(&self) -> Result<Vec<WorkspaceSummary>> {
-        let summaries = sqlx::query_as!(
-            WorkspaceSummary,
-            r#"
-            SELECT 
-                w.id,
-                w.name,
-                w.description,
-                w.local_path,
-                w.is_active,
-                w.last_accessed_at,
-                NULL as git_status,
-                0 as collection_count,
-                0 as request_count
-            FROM workspaces w 
-            ORDER BY w.last_accessed_at DESC, w.created_at DESC
-            "#
-        )
-        .fetch_all(&self.pool)
-        .await?;
+    // Helper method to convert row to workspace
+    fn row_to_workspace(&self, row: sqlx::sqlite::SqliteRow) -> Result<Workspace> {
+        let created_at_str: String = row.get("created_at");
+        let updated_at_str: String = row.get("updated_at");
+        let last_accessed_at_str: Option<String> = row.get("last_accessed_at");
 
-        Ok(summaries)
+        Ok(Workspace {
+            id: row.get("id"),
+            name: row.get("name"),
+            description: row.get("description"),
+            git_repository_url: row.get("git_repository_url"),
+            local_path: row.get("local_path"),
+            git_username: row.get("git_username"),
+            git_email: row.get("git_email"),
+            is_active: row.get("is_active"),
+            created_at: DateTime::parse_from_rfc3339(&created_at_str)?.with_timezone(&Utc),
+            updated_at: DateTime::parse_from_rfc3339(&updated_at_str)?.with_timezone(&Utc),
+            last_accessed_at: last_accessed_at_str
+                .map(|s| DateTime::parse_from_rfc3339(&s).map(|dt| dt.with_timezone(&Utc)))
+                .transpose()?,
+        })
     }
 
-    pub async fn workspace_exists(&self, id: &str) -> Result<bool> {
-        let count: i64 = sqlx::query_scalar!(
-            "SELECT COUNT(*) FROM workspaces WHERE id = ?",
-            id
-        )
-        .fetch_one(&self.pool)
-        .await?;
+    // Helper method to convert row to workspace settings
+    fn row_to_workspace_settings(&self, row: sqlx::sqlite::SqliteRow) -> Result<WorkspaceSettings> {
+        let created_at_str: String = row.get("created_at");
+        let updated_at_str: String = row.get("updated_at");
+        let default_timeout: i64 = row.get("default_timeout");
+        let min_request_interval_ms: i64 = row.get("min_request_interval_ms");
+        let max_response_bytes: i64 = row.get("max_response_bytes");
+        let connect_timeout_ms: i64 = row.get("connect_timeout_ms");
+        let default_headers: String = row.get("default_headers");
 
-        Ok(count > 0)
+        Ok(WorkspaceSettings {
+            id: row.get("id"),
+            workspace_id: row.get("workspace_id"),
+            auto_save: row.get("auto_save"),
+            sync_on_startup: row.get("sync_on_startup"),
+            default_timeout: default_timeout as u32,
+            follow_redirects: row.get("follow_redirects"),
+            verify_ssl: row.get("verify_ssl"),
+            min_request_interval_ms: min_request_interval_ms as u32,
+            max_response_bytes: max_response_bytes as u64,
+            connect_timeout_ms: connect_timeout_ms as u32,
+            proxy_url: row.get("proxy_url"),
+            proxy_username: row.get("proxy_username"),
+            proxy_password: row.get("proxy_password"),
+            no_proxy: row.get("no_proxy"),
+            client_cert_path: row.get("client_cert_path"),
+            client_key_path: row.get("client_key_path"),
+            client_cert_password: row.get("client_cert_password"),
+            ca_bundle_path: row.get("ca_bundle_path"),
+            user_agent: row.get("user_agent"),
+            default_headers: serde_json::from_str(&default_headers)?,
+            created_at: DateTime::parse_from_rfc3339(&created_at_str)?.with_timezone(&Utc),
+            updated_at: DateTime::parse_from_rfc3339(&updated_at_str)?.with_timezone(&Utc),
+        })
     }
 
     pub async fn close(&self) {
         self.pool.close().await;
     }
+
+    /// Wrap a pool that has already been migrated
+    pub fn from_pool(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    pub fn get_pool(&self) -> SqlitePool {
+        self.pool.clone()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::models::environment::{EnvironmentVariable, VariableType};
     use crate::models::workspace::CreateWorkspaceRequest;
-    use tempfile::NamedTempFile;
+    use crate::services::environment_service::EnvironmentService;
 
     async fn create_test_db() -> DatabaseService {
-        let temp_file = NamedTempFile::new().unwrap();
-        let db_path = temp_file.path().to_str().unwrap();
-        DatabaseService::new(db_path).await.unwrap()
+        // Use in-memory database for tests
+        DatabaseService::new("sqlite::memory:").await.unwrap()
     }
 
     #[tokio::test]
@@ -284,6 +916,8 @@ mod tests {
             description: Some("Test Description".to_string()),
             git_repository_url: None,
             local_path: "/tmp/test".to_string(),
+            git_username: None,
+            git_email: None,
         };
 
         let workspace = Workspace::new(request);
@@ -309,6 +943,8 @@ mod tests {
             description: None,
             git_repository_url: None,
             local_path: "/tmp/test1".to_string(),
+            git_username: None,
+            git_email: None,
         });
         
         let workspace2 = Workspace::new(CreateWorkspaceRequest {
@@ -316,6 +952,8 @@ mod tests {
             description: None,
             git_repository_url: None,
             local_path: "/tmp/test2".to_string(),
+            git_username: None,
+            git_email: None,
         });
 
         db.create_workspace(&workspace1).await.unwrap();
@@ -338,5 +976,160 @@ mod tests {
         // Verify workspace1 is no longer active
         let workspace1_updated = db.get_workspace(&workspace1.id).await.unwrap().unwrap();
         assert!(!workspace1_updated.is_active);
+
+        // An unknown id changes nothing
+        assert!(db.set_active_workspace("non-existent-id").await.is_err());
+        let active = db.get_active_workspace().await.unwrap().unwrap();
+        assert_eq!(active.id, workspace2.id);
+    }
+
+    #[tokio::test]
+    async fn test_environment_round_trip_on_fresh_database() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let workspace_path = temp_dir.path().to_str().unwrap();
+        let db = std::sync::Arc::new(create_test_db().await);
+        let workspace = Workspace::new(CreateWorkspaceRequest {
+            name: "Fresh".to_string(),
+            description: None,
+            git_repository_url: None,
+            local_path: workspace_path.to_string(),
+            git_username: None,
+            git_email: None,
+        });
+        db.create_workspace(&workspace).await.unwrap();
+
+        let service = EnvironmentService::new(db.clone());
+        let created = service.create_environment(workspace.id.clone(), "Development".to_string()).await.unwrap();
+        service
+            .add_variable(&created.id, EnvironmentVariable {
+                key: "API_URL".to_string(),
+                value: "http://localhost:3000".to_string(),
+                is_secret: false,
+                variable_type: VariableType::String,
+            })
+            .await
+            .unwrap();
+
+        let stored = service.get_environment(&created.id).await.unwrap().unwrap();
+        assert_eq!(stored.name, "Development");
+        assert_eq!(stored.variables["API_URL"].value, "http://localhost:3000");
+
+        // Branch suggestions read from branch_history, which must exist from the start
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM branch_history")
+            .fetch_one(&db.get_pool())
+            .await
+            .unwrap();
+        assert_eq!(count, 0);
     }
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn test_variable_type_check_is_widened_on_existing_database() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let database_path = temp_dir.path().join("postgirl.db");
+        let database_url = format!("sqlite://{}", database_path.display());
+
+        let db = std::sync::Arc::new(DatabaseService::new(&database_url).await.unwrap());
+        let workspace = Workspace::new(CreateWorkspaceRequest {
+            name: "Upgraded".to_string(),
+            description: None,
+            git_repository_url: None,
+            local_path: temp_dir.path().to_str().unwrap().to_string(),
+            git_username: None,
+            git_email: None,
+        });
+        db.create_workspace(&workspace).await.unwrap();
+        let environment = EnvironmentService::new(db.clone())
+            .create_environment(workspace.id.clone(), "Development".to_string())
+            .await
+            .unwrap();
+
+        // Put back the table as it was before the number, boolean and url types, holding one variable
+        let pool = db.get_pool();
+        sqlx::query("DROP TABLE environment_variables").execute(&pool).await.unwrap();
+        sqlx::query(
+            r#"
+            CREATE TABLE environment_variables (
+                id TEXT PRIMARY KEY DEFAULT (lower(hex(randomblob(16)))),
+                environment_id TEXT NOT NULL,
+                variable_key TEXT NOT NULL,
+                value TEXT NOT NULL,
+                is_secret BOOLEAN DEFAULT FALSE,
+                variable_type TEXT DEFAULT 'string' CHECK (variable_type IN ('string', 'secret')),
+                created_at TEXT NOT NULL DEFAULT (datetime('now')),
+                updated_at TEXT NOT NULL DEFAULT (datetime('now')),
+                FOREIGN KEY (environment_id) REFERENCES environments (id) ON DELETE CASCADE,
+                UNIQUE(environment_id, variable_key)
+            )
+            "#
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::query("INSERT INTO environment_variables (environment_id, variable_key, value, variable_type) VALUES (?, 'TOKEN', 'abc', 'secret')")
+            .bind(&environment.id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        pool.close().await;
+
+        let db = DatabaseService::new(&database_url).await.unwrap();
+        let pool = db.get_pool();
+        let kept: String = sqlx::query_scalar("SELECT variable_type FROM environment_variables WHERE variable_key = 'TOKEN'")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(kept, "secret");
+        sqlx::query("INSERT INTO environment_variables (environment_id, variable_key, value, variable_type) VALUES (?, 'RETRIES', '3', 'number')")
+            .bind(&environment.id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        assert!(sqlx::query("INSERT INTO environment_variables (environment_id, variable_key, value, variable_type) VALUES (?, 'X', '1', 'date')")
+            .bind(&environment.id)
+            .execute(&pool)
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_migrations_are_applied_once() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let database_url = format!("sqlite://{}", temp_dir.path().join("postgirl.db").display());
+        let db = DatabaseService::new(&database_url).await.unwrap();
+        let pool = db.get_pool();
+
+        DatabaseService::run_migrations(&pool).await.unwrap();
+        let versions: Vec<i64> = sqlx::query_scalar("SELECT version FROM schema_migrations ORDER BY version")
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+        let expected: Vec<i64> = COLUMN_MIGRATIONS.iter().map(|(version, ..)| *version).collect();
+        assert_eq!(versions, expected);
+
+        let columns: Vec<String> = sqlx::query("PRAGMA table_info(requests)")
+            .fetch_all(&pool)
+            .await
+            .unwrap()
+            .iter()
+            .map(|row| row.get("name"))
+            .collect();
+        assert!(columns.contains(&"pre_request".to_string()));
+        assert!(columns.contains(&"run_condition".to_string()));
+
+        // A database from before versioning has the columns but no versions recorded
+        sqlx::query("DELETE FROM schema_migrations").execute(&pool).await.unwrap();
+        DatabaseService::run_migrations(&pool).await.unwrap();
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM schema_migrations")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(count, COLUMN_MIGRATIONS.len() as i64);
+
+        // Versions from a newer build are kept rather than rejected
+        sqlx::query("INSERT INTO schema_migrations (version, applied_at) VALUES (1000, '2030-01-01T00:00:00Z')")
+            .execute(&pool)
+            .await
+            .unwrap();
+        DatabaseService::run_migrations(&pool).await.unwrap();
+    }
+}
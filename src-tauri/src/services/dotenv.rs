@@ -0,0 +1,170 @@
+//! Reading and writing `.env` files
+
+use anyhow::{anyhow, Result};
+
+/// Parse `KEY=VALUE` lines in file order. Blank lines, `#` comments and `export` prefixes
+/// are skipped. Values may be unquoted (a ` #` starts a comment), single-quoted (taken
+/// literally) or double-quoted (with `\n`, `\t`, `\"` and `\\` escapes, and may span lines).
+pub fn parse(contents: &str) -> Result<Vec<(String, String)>> {
+    let contents = contents.strip_prefix('\u{feff}').unwrap_or(contents);
+    let mut variables = Vec::new();
+    let mut lines = contents.lines().enumerate();
+
+    while let Some((index, line)) = lines.next() {
+        let line_number = index + 1;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let line = line.strip_prefix("export ").map(str::trim_start).unwrap_or(line);
+        let (key, value) = line.split_once('=')
+            .ok_or_else(|| anyhow!("Line {}: expected KEY=VALUE", line_number))?;
+        let key = key.trim();
+        if !is_valid_key(key) {
+            return Err(anyhow!("Line {}: invalid variable name '{}'", line_number, key));
+        }
+
+        let value = value.trim_start();
+        let value = if let Some(rest) = value.strip_prefix('"') {
+            // Keep reading lines until the closing quote
+            let mut quoted = rest.to_string();
+            loop {
+                if let Some(end) = closing_quote(&quoted) {
+                    quoted.truncate(end);
+                    break;
+                }
+                let (_, next) = lines.next()
+                    .ok_or_else(|| anyhow!("Line {}: unterminated double quote", line_number))?;
+                quoted.push('\n');
+                quoted.push_str(next);
+            }
+            unescape(&quoted)
+        } else if let Some(rest) = value.strip_prefix('\'') {
+            let end = rest.find('\'')
+                .ok_or_else(|| anyhow!("Line {}: unterminated single quote", line_number))?;
+            rest[..end].to_string()
+        } else {
+            let end = value.find(" #").unwrap_or(value.len());
+            value[..end].trim_end().to_string()
+        };
+
+        variables.push((key.to_string(), value));
+    }
+
+    Ok(variables)
+}
+
+/// Write variables as `KEY=VALUE` lines, double-quoting values that wouldn't read back as-is
+pub fn serialize<'a>(variables: impl IntoIterator<Item = (&'a str, &'a str)>) -> String {
+    let mut output = String::new();
+    for (key, value) in variables {
+        output.push_str(key);
+        output.push('=');
+        if value.chars().all(|c| c.is_ascii_alphanumeric() || "_-./:@,+=%".contains(c)) {
+            output.push_str(value);
+        } else {
+            output.push('"');
+            for c in value.chars() {
+                match c {
+                    '"' => output.push_str("\\\""),
+                    '\\' => output.push_str("\\\\"),
+                    '\n' => output.push_str("\\n"),
+                    '\r' => output.push_str("\\r"),
+                    '\t' => output.push_str("\\t"),
+                    c => output.push(c),
+                }
+            }
+            output.push('"');
+        }
+        output.push('\n');
+    }
+    output
+}
+
+fn is_valid_key(key: &str) -> bool {
+    let mut chars = key.chars();
+    chars.next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.' || c == '-')
+}
+
+/// Byte offset of the first unescaped `"`
+fn closing_quote(text: &str) -> Option<usize> {
+    let mut escaped = false;
+    for (offset, c) in text.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '"' => return Some(offset),
+            _ => {}
+        }
+    }
+    None
+}
+
+fn unescape(text: &str) -> String {
+    let mut output = String::new();
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            output.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => output.push('\n'),
+            Some('r') => output.push('\r'),
+            Some('t') => output.push('\t'),
+            Some(other) => output.push(other), // \" and \\, and any other escaped character as itself
+            None => output.push('\\'),
+        }
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_comments_quotes_and_export() {
+        let contents = r#"
+# Shared settings
+export FOO=bar
+BASE_URL = https://api.example.com # production
+GREETING="Hello, \"world\"\nsecond line"
+LITERAL='no $expansion \n here'
+MULTILINE="first
+second"
+EMPTY=
+"#;
+        let variables = parse(contents).unwrap();
+        assert_eq!(variables, vec![
+            ("FOO".to_string(), "bar".to_string()),
+            ("BASE_URL".to_string(), "https://api.example.com".to_string()),
+            ("GREETING".to_string(), "Hello, \"world\"\nsecond line".to_string()),
+            ("LITERAL".to_string(), "no $expansion \\n here".to_string()),
+            ("MULTILINE".to_string(), "first\nsecond".to_string()),
+            ("EMPTY".to_string(), String::new()),
+        ]);
+
+        assert_eq!(parse("JUST_A_KEY").unwrap_err().to_string(), "Line 1: expected KEY=VALUE");
+        assert!(parse("A=\"open").is_err());
+        assert!(parse("1ABC=x").is_err());
+    }
+
+    #[test]
+    fn test_serialize_round_trips() {
+        let variables = [
+            ("PLAIN", "https://api.example.com/v1"),
+            ("SPACES", "two words # not a comment"),
+            ("QUOTES", "say \"hi\" and 'bye'"),
+            ("LINES", "a\nb\\c\td"),
+            ("EMPTY", ""),
+        ];
+        let contents = serialize(variables);
+        assert!(contents.starts_with("PLAIN=https://api.example.com/v1\nSPACES=\"two words # not a comment\"\n"));
+
+        let parsed = parse(&contents).unwrap();
+        let expected: Vec<(String, String)> = variables.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect();
+        assert_eq!(parsed, expected);
+    }
+}
@@ -1,12 +1,69 @@
+use crate::models::collection::{Extraction, Request};
 use crate::models::environment::*;
+use crate::services::collection_service::CollectionService;
 use crate::services::file_sync_service::FileSyncService;
 use crate::services::database_service::DatabaseService;
+use crate::services::dotenv;
+use crate::services::variable_substitution;
 use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use anyhow::{Result, anyhow};
 use uuid::Uuid;
 use chrono::Utc;
 use sqlx::Row;
+use std::fmt;
+
+/// A variable's value doesn't suit its type, such as an empty secret or a non-numeric number
+#[derive(Debug, Clone, PartialEq)]
+pub struct InvalidVariable {
+    pub key: String,
+    pub variable_type: VariableType,
+}
+
+impl fmt::Display for InvalidVariable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Invalid value for {} variable '{}': expected {}",
+            self.variable_type.as_str(),
+            self.key,
+            self.variable_type.requirement()
+        )
+    }
+}
+
+impl std::error::Error for InvalidVariable {}
+
+fn validate_variable(variable: &EnvironmentVariable) -> Result<(), InvalidVariable> {
+    if variable.variable_type.validate_value(&variable.value) {
+        return Ok(());
+    }
+    Err(InvalidVariable { key: variable.key.clone(), variable_type: variable.variable_type.clone() })
+}
+
+/// The variables each part of a request references
+fn request_placeholders(request: &Request) -> Vec<(RequestField, Vec<String>)> {
+    let text = |value: &Option<String>| value.as_deref().map(variable_substitution::extract_variables).unwrap_or_default();
+    vec![
+        (RequestField::Url, variable_substitution::extract_variables(&request.url)),
+        (RequestField::Headers, variable_substitution::extract_variables(&request.headers)),
+        (RequestField::Body, text(&request.body)),
+        (RequestField::Auth, text(&request.auth_config)),
+    ]
+}
+
+fn usage_of(request: &Request, name: &str) -> Option<VariableUsage> {
+    let fields: Vec<RequestField> = request_placeholders(request)
+        .into_iter()
+        .filter(|(_, names)| names.iter().any(|used| used == name))
+        .map(|(field, _)| field)
+        .collect();
+    (!fields.is_empty()).then(|| VariableUsage {
+        request_id: request.id.clone(),
+        request_name: request.name.clone(),
+        fields,
+    })
+}
 
 #[derive(Clone)]
 pub struct EnvironmentService {
@@ -17,8 +74,8 @@ pub struct EnvironmentService {
 impl EnvironmentService {
     pub fn new(database: Arc<DatabaseService>) -> Self {
         Self {
+            file_sync: FileSyncService::new(database.clone()),
             database,
-            file_sync: FileSyncService::new(),
         }
     }
 
@@ -30,6 +87,8 @@ impl EnvironmentService {
             name: name.clone(),
             variables: HashMap::new(),
             is_active: false,
+            is_protected: false,
+            parent_id: None,
             created_at: now,
             updated_at: now,
         };
@@ -63,7 +122,7 @@ impl EnvironmentService {
     pub async fn get_environment(&self, environment_id: &str) -> Result<Option<Environment>> {
         // Get basic environment info from database
         let row = sqlx::query(
-            "SELECT id, workspace_id, name, is_active, created_at, updated_at FROM environments WHERE id = ?1"
+            "SELECT id, workspace_id, name, is_active, is_protected, parent_id, created_at, updated_at FROM environments WHERE id = ?1"
         )
         .bind(environment_id)
         .fetch_optional(&self.database.get_pool())
@@ -96,6 +155,8 @@ impl EnvironmentService {
                 name: row.get("name"),
                 variables,
                 is_active: row.get("is_active"),
+                is_protected: row.get("is_protected"),
+                parent_id: row.get("parent_id"),
                 created_at: chrono::DateTime::parse_from_rfc3339(&row.get::<String, _>("created_at"))
                     .unwrap_or_default().with_timezone(&chrono::Utc),
                 updated_at: chrono::DateTime::parse_from_rfc3339(&row.get::<String, _>("updated_at"))
@@ -108,16 +169,42 @@ impl EnvironmentService {
         }
     }
 
+    /// Save an environment and its variables. Only new or changed variables are validated, so
+    /// values stored before a type was enforced don't block unrelated edits.
     pub async fn update_environment(&self, environment: Environment) -> Result<Environment> {
+        let stored = self.get_environment(&environment.id).await?.map(|env| env.variables).unwrap_or_default();
+        let mut variables: Vec<&EnvironmentVariable> = environment.variables.values()
+            .filter(|variable| {
+                stored.get(&variable.key).is_none_or(|existing| {
+                    existing.value != variable.value || existing.variable_type != variable.variable_type
+                })
+            })
+            .collect();
+        variables.sort_by(|a, b| a.key.cmp(&b.key));
+        for variable in variables {
+            validate_variable(variable)?;
+        }
+
         let mut updated_env = environment;
         updated_env.updated_at = Utc::now();
+        if let Some(parent_id) = &updated_env.parent_id {
+            let workspace_id: String = sqlx::query("SELECT workspace_id FROM environments WHERE id = ?1")
+                .bind(&updated_env.id)
+                .fetch_one(&self.database.get_pool())
+                .await
+                .map_err(|e| anyhow!("Failed to get workspace_id: {}", e))?
+                .get("workspace_id");
+            self.check_parent(&workspace_id, &updated_env.id, parent_id).await?;
+        }
 
         // Update in database
         sqlx::query(
-            "UPDATE environments SET name = ?1, is_active = ?2, updated_at = ?3 WHERE id = ?4"
+            "UPDATE environments SET name = ?1, is_active = ?2, is_protected = ?3, parent_id = ?4, updated_at = ?5 WHERE id = ?6"
         )
         .bind(&updated_env.name)
         .bind(updated_env.is_active)
+        .bind(updated_env.is_protected)
+        .bind(&updated_env.parent_id)
         .bind(&updated_env.updated_at.to_rfc3339())
         .bind(&updated_env.id)
         .execute(&self.database.get_pool())
@@ -163,6 +250,81 @@ impl EnvironmentService {
         Ok(updated_env)
     }
 
+    /// Deactivate every environment in a workspace so requests run without substitution
+    /// Make `environment_id` the workspace's only active environment. Only the flag is
+    /// written, so the environments' variables aren't revalidated.
+    pub async fn set_active_environment(&self, workspace_id: &str, environment_id: &str) -> Result<()> {
+        let mut tx = self.database.get_pool().begin().await
+            .map_err(|e| anyhow!("Failed to start transaction: {}", e))?;
+
+        let changed_ids: Vec<String> = sqlx::query(
+            "SELECT id FROM environments WHERE workspace_id = ?1 AND is_active != (id = ?2)"
+        )
+        .bind(workspace_id)
+        .bind(environment_id)
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(|e| anyhow!("Failed to find environments to switch: {}", e))?
+        .iter()
+        .map(|row| row.get("id"))
+        .collect();
+
+        sqlx::query("UPDATE environments SET is_active = (id = ?1), updated_at = ?2 WHERE workspace_id = ?3 AND is_active != (id = ?1)")
+            .bind(environment_id)
+            .bind(Utc::now().to_rfc3339())
+            .bind(workspace_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| anyhow!("Failed to switch active environment: {}", e))?;
+
+        tx.commit().await.map_err(|e| anyhow!("Failed to commit transaction: {}", e))?;
+
+        // Keep the environment files in step with the database
+        for id in changed_ids {
+            if let Some(environment) = self.get_environment(&id).await? {
+                if let Err(e) = self.file_sync.write_environment_file(workspace_id, &environment).await {
+                    eprintln!("Warning: Failed to write environment file: {}", e);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    pub async fn clear_active_environment(&self, workspace_id: &str) -> Result<()> {
+        let mut tx = self.database.get_pool().begin().await
+            .map_err(|e| anyhow!("Failed to start transaction: {}", e))?;
+
+        let active_ids: Vec<String> = sqlx::query("SELECT id FROM environments WHERE workspace_id = ?1 AND is_active = 1")
+            .bind(workspace_id)
+            .fetch_all(&mut *tx)
+            .await
+            .map_err(|e| anyhow!("Failed to find active environments: {}", e))?
+            .iter()
+            .map(|row| row.get("id"))
+            .collect();
+
+        sqlx::query("UPDATE environments SET is_active = 0, updated_at = ?1 WHERE workspace_id = ?2 AND is_active = 1")
+            .bind(Utc::now().to_rfc3339())
+            .bind(workspace_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| anyhow!("Failed to deactivate environments: {}", e))?;
+
+        tx.commit().await.map_err(|e| anyhow!("Failed to commit transaction: {}", e))?;
+
+        // Keep the environment files in step with the database
+        for id in active_ids {
+            if let Some(environment) = self.get_environment(&id).await? {
+                if let Err(e) = self.file_sync.write_environment_file(workspace_id, &environment).await {
+                    eprintln!("Warning: Failed to write environment file: {}", e);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
     pub async fn delete_environment(&self, environment_id: &str) -> Result<bool> {
         // Get environment info before deleting for file cleanup
         let env = self.get_environment(environment_id).await?;
@@ -174,6 +336,13 @@ impl EnvironmentService {
             .await
             .map_err(|e| anyhow!("Failed to delete environment: {}", e))?;
 
+        // Children keep their own variables but no longer inherit
+        sqlx::query("UPDATE environments SET parent_id = NULL WHERE parent_id = ?1")
+            .bind(environment_id)
+            .execute(&self.database.get_pool())
+            .await
+            .map_err(|e| anyhow!("Failed to detach child environments: {}", e))?;
+
         // Clean up file if environment existed
         if let Some(environment) = env {
             let workspace_row = sqlx::query("SELECT workspace_id FROM environments WHERE id = ?1")
@@ -195,7 +364,7 @@ impl EnvironmentService {
 
     pub async fn list_environments(&self, workspace_id: &str) -> Result<Vec<Environment>> {
         let rows = sqlx::query(
-            "SELECT id, workspace_id, name, is_active, created_at, updated_at FROM environments WHERE workspace_id = ?1"
+            "SELECT id, workspace_id, name, is_active, is_protected, parent_id, created_at, updated_at FROM environments WHERE workspace_id = ?1"
         )
         .bind(workspace_id)
         .fetch_all(&self.database.get_pool())
@@ -231,6 +400,8 @@ impl EnvironmentService {
                 name: row.get("name"),
                 variables,
                 is_active: row.get("is_active"),
+                is_protected: row.get("is_protected"),
+                parent_id: row.get("parent_id"),
                 created_at: chrono::DateTime::parse_from_rfc3339(&row.get::<String, _>("created_at"))
                     .unwrap_or_default().with_timezone(&chrono::Utc),
                 updated_at: chrono::DateTime::parse_from_rfc3339(&row.get::<String, _>("updated_at"))
@@ -243,8 +414,82 @@ impl EnvironmentService {
         Ok(environments)
     }
 
+    // Inheritance
+    /// An environment with its ancestors' variables merged in, nearer environments overriding
+    /// farther ones. Fails on an inheritance cycle rather than following it.
+    pub async fn get_resolved_environment(&self, environment_id: &str) -> Result<Option<Environment>> {
+        let Some(environment) = self.get_environment(environment_id).await? else {
+            return Ok(None);
+        };
+        self.resolve_inheritance(environment).await.map(Some)
+    }
+
+    /// Every environment in a workspace, each with its inherited variables merged in
+    pub async fn list_resolved_environments(&self, workspace_id: &str) -> Result<Vec<Environment>> {
+        let mut environments = Vec::new();
+        for environment in self.list_environments(workspace_id).await? {
+            environments.push(self.resolve_inheritance(environment).await?);
+        }
+        Ok(environments)
+    }
+
+    async fn resolve_inheritance(&self, mut environment: Environment) -> Result<Environment> {
+        let mut chain = vec![environment.name.clone()];
+        let mut seen = HashSet::from([environment.id.clone()]);
+        let mut ancestors = Vec::new();
+        let mut next = environment.parent_id.clone();
+        while let Some(parent_id) = next {
+            let parent = self.get_environment(&parent_id).await?
+                .ok_or_else(|| anyhow!("Parent environment {} not found", parent_id))?;
+            chain.push(parent.name.clone());
+            if !seen.insert(parent.id.clone()) {
+                return Err(anyhow!("Environment inheritance cycle: {}", chain.join(" -> ")));
+            }
+            next = parent.parent_id.clone();
+            ancestors.push(parent);
+        }
+
+        let mut variables = HashMap::new();
+        for ancestor in ancestors.into_iter().rev() {
+            variables.extend(ancestor.variables);
+        }
+        variables.extend(environment.variables);
+        environment.variables = variables;
+        Ok(environment)
+    }
+
+    /// Make sure `parent_id` can be an environment's parent: it exists in the same workspace
+    /// and doesn't already inherit from the environment
+    async fn check_parent(&self, workspace_id: &str, environment_id: &str, parent_id: &str) -> Result<()> {
+        let parent_workspace: String = sqlx::query("SELECT workspace_id FROM environments WHERE id = ?1")
+            .bind(parent_id)
+            .fetch_optional(&self.database.get_pool())
+            .await
+            .map_err(|e| anyhow!("Failed to get workspace_id: {}", e))?
+            .ok_or_else(|| anyhow!("Parent environment {} not found", parent_id))?
+            .get("workspace_id");
+        if parent_workspace != workspace_id {
+            return Err(anyhow!("A parent environment must be in the same workspace"));
+        }
+
+        let mut next = Some(parent_id.to_string());
+        let mut seen = HashSet::new();
+        while let Some(id) = next {
+            if id == environment_id {
+                return Err(anyhow!("Environment inheritance cycle: an environment can't inherit from itself or its descendants"));
+            }
+            if !seen.insert(id.clone()) {
+                break; // an existing cycle further up, reported when resolved
+            }
+            next = self.get_environment(&id).await?.and_then(|parent| parent.parent_id);
+        }
+        Ok(())
+    }
+
     // Environment variable operations
     pub async fn add_variable(&self, environment_id: &str, variable: EnvironmentVariable) -> Result<Environment> {
+        validate_variable(&variable)?;
+
         // Insert variable into database
         sqlx::query(
             "INSERT OR REPLACE INTO environment_variables (environment_id, variable_key, value, is_secret, variable_type) VALUES (?1, ?2, ?3, ?4, ?5)"
@@ -272,6 +517,8 @@ impl EnvironmentService {
     }
 
     pub async fn update_variable(&self, environment_id: &str, variable: EnvironmentVariable) -> Result<Environment> {
+        validate_variable(&variable)?;
+
         // Update variable in database
         sqlx::query(
             "UPDATE environment_variables SET value = ?1, is_secret = ?2, variable_type = ?3, updated_at = ?4 WHERE environment_id = ?5 AND variable_key = ?6"
@@ -321,30 +568,764 @@ impl EnvironmentService {
             .ok_or_else(|| anyhow!("Environment not found after removing variable"))
     }
 
-    // Variable substitution
-    pub fn substitute_variables(&self, text: &str, variables: &HashMap<String, String>) -> String {
-        let mut result = text.to_string();
-        
+    /// Write many variables at once, in one transaction so a failure leaves the environment as it
+    /// was. The environment file is synced once at the end.
+    pub async fn set_variables(&self, environment_id: &str, variables: Vec<EnvironmentVariable>, mode: MergeMode) -> Result<Environment> {
+        for variable in &variables {
+            validate_variable(variable)?;
+        }
+
+        let workspace_id: String = sqlx::query("SELECT workspace_id FROM environments WHERE id = ?1")
+            .bind(environment_id)
+            .fetch_optional(&self.database.get_pool())
+            .await
+            .map_err(|e| anyhow!("Failed to get workspace_id: {}", e))?
+            .ok_or_else(|| anyhow!("Environment not found"))?
+            .get("workspace_id");
+
+        let mut tx = self.database.get_pool().begin().await
+            .map_err(|e| anyhow!("Failed to start transaction: {}", e))?;
+
+        if mode == MergeMode::Replace {
+            sqlx::query("DELETE FROM environment_variables WHERE environment_id = ?1")
+                .bind(environment_id)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| anyhow!("Failed to delete existing variables: {}", e))?;
+        }
+
+        let now = Utc::now().to_rfc3339();
+        for variable in &variables {
+            sqlx::query(
+                r#"
+                INSERT INTO environment_variables (environment_id, variable_key, value, is_secret, variable_type)
+                VALUES (?1, ?2, ?3, ?4, ?5)
+                ON CONFLICT(environment_id, variable_key) DO UPDATE SET
+                    value = excluded.value, is_secret = excluded.is_secret,
+                    variable_type = excluded.variable_type, updated_at = ?6
+                "#
+            )
+            .bind(environment_id)
+            .bind(&variable.key)
+            .bind(&variable.value)
+            .bind(variable.is_secret)
+            .bind(variable.variable_type.as_str())
+            .bind(&now)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| anyhow!("Failed to set variable '{}': {}", variable.key, e))?;
+        }
+
+        sqlx::query("UPDATE environments SET updated_at = ?1 WHERE id = ?2")
+            .bind(&now)
+            .bind(environment_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| anyhow!("Failed to update environment timestamp: {}", e))?;
+
+        tx.commit().await
+            .map_err(|e| anyhow!("Failed to commit variables: {}", e))?;
+
+        let environment = self.get_environment(environment_id).await?
+            .ok_or_else(|| anyhow!("Environment not found after setting variables"))?;
+        if let Err(e) = self.file_sync.write_environment_file(&workspace_id, &environment).await {
+            eprintln!("Warning: Failed to write environment file: {}", e);
+        }
+
+        Ok(environment)
+    }
+
+    // Global, collection and request variables
+    /// The variables defined at a scope. `owner_id` is the workspace, collection or request id.
+    pub async fn get_scoped_variables(&self, scope: VariableScope, owner_id: &str) -> Result<HashMap<String, String>> {
+        let (table, owner_column) = Self::scope_table(scope);
+        let rows = sqlx::query(&format!("SELECT variable_key, value FROM {} WHERE {} = ?1", table, owner_column))
+            .bind(owner_id)
+            .fetch_all(&self.database.get_pool())
+            .await
+            .map_err(|e| anyhow!("Failed to get variables: {}", e))?;
+
+        Ok(rows.iter().map(|row| (row.get("variable_key"), row.get("value"))).collect())
+    }
+
+    pub async fn set_scoped_variable(&self, scope: VariableScope, owner_id: &str, key: &str, value: &str) -> Result<()> {
+        let (table, owner_column) = Self::scope_table(scope);
+        sqlx::query(&format!(
+            "INSERT OR REPLACE INTO {} ({}, variable_key, value, updated_at) VALUES (?1, ?2, ?3, ?4)",
+            table, owner_column
+        ))
+        .bind(owner_id)
+        .bind(key)
+        .bind(value)
+        .bind(Utc::now().to_rfc3339())
+        .execute(&self.database.get_pool())
+        .await
+        .map_err(|e| anyhow!("Failed to set variable: {}", e))?;
+        Ok(())
+    }
+
+    /// Returns false when the variable wasn't defined at that scope
+    pub async fn remove_scoped_variable(&self, scope: VariableScope, owner_id: &str, key: &str) -> Result<bool> {
+        let (table, owner_column) = Self::scope_table(scope);
+        let result = sqlx::query(&format!("DELETE FROM {} WHERE {} = ?1 AND variable_key = ?2", table, owner_column))
+            .bind(owner_id)
+            .bind(key)
+            .execute(&self.database.get_pool())
+            .await
+            .map_err(|e| anyhow!("Failed to remove variable: {}", e))?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// The variables a request is sent with, merged so that request variables override
+    /// collection ones, which override the environment's, which override global ones
+    pub async fn resolve_variables(
+        &self,
+        workspace_id: &str,
+        collection_id: Option<&str>,
+        request_id: Option<&str>,
+        environment_id: Option<&str>,
+    ) -> Result<HashMap<String, String>> {
+        let mut variables = self.get_scoped_variables(VariableScope::Global, workspace_id).await?;
+        if let Some(environment_id) = environment_id {
+            let environment = self.get_resolved_environment(environment_id).await?
+                .ok_or_else(|| anyhow!("Environment not found"))?;
+            variables.extend(environment.variables.into_values().map(|variable| (variable.key, variable.value)));
+        }
+        if let Some(collection_id) = collection_id {
+            variables.extend(self.get_scoped_variables(VariableScope::Collection, collection_id).await?);
+        }
+        if let Some(request_id) = request_id {
+            variables.extend(self.get_scoped_variables(VariableScope::Request, request_id).await?);
+        }
+        Ok(variables)
+    }
+
+    // Variable usage
+    /// The requests in a collection whose URL, headers, body or auth reference `variable_name`
+    pub async fn find_variable_usages(&self, collection_id: &str, variable_name: &str) -> Result<Vec<VariableUsage>> {
+        let requests = CollectionService::new(self.database.get_pool()).list_requests(collection_id).await?;
+        Ok(requests.iter().filter_map(|request| usage_of(request, variable_name)).collect())
+    }
+
+    /// Placeholders in a collection's requests that nothing defines: not the environment, a
+    /// global, collection or request variable, nor an extraction earlier in a run. Built-in
+    /// `$` variables are never reported; placeholders with a fallback still are.
+    pub async fn find_undefined_variables(&self, collection_id: &str, environment_id: Option<&str>) -> Result<Vec<UndefinedVariable>> {
+        let collections = CollectionService::new(self.database.get_pool());
+        let collection = collections.get_collection(collection_id).await?
+            .ok_or_else(|| anyhow!("Collection not found"))?;
+        let requests = collections.list_requests(collection_id).await?;
+
+        let mut defined: HashSet<String> = self
+            .resolve_variables(&collection.workspace_id, Some(collection_id), None, environment_id)
+            .await?
+            .into_keys()
+            .collect();
+        for request in &requests {
+            let extractions: Vec<Extraction> = serde_json::from_str(&request.extractions).unwrap_or_default();
+            defined.extend(extractions.into_iter().map(|extraction| extraction.variable));
+        }
+
+        let mut undefined: Vec<UndefinedVariable> = Vec::new();
+        for request in &requests {
+            let request_variables = self.get_scoped_variables(VariableScope::Request, &request.id).await?;
+            let mut names: Vec<String> = Vec::new();
+            for (_, used) in request_placeholders(request) {
+                for name in used {
+                    if !name.starts_with('$') && !defined.contains(&name) && !request_variables.contains_key(&name) && !names.contains(&name) {
+                        names.push(name);
+                    }
+                }
+            }
+            for name in names {
+                let usage = usage_of(request, &name).expect("the name came from this request");
+                match undefined.iter_mut().find(|variable| variable.name == name) {
+                    Some(variable) => variable.usages.push(usage),
+                    None => undefined.push(UndefinedVariable { name, usages: vec![usage] }),
+                }
+            }
+        }
+        Ok(undefined)
+    }
+
+    fn scope_table(scope: VariableScope) -> (&'static str, &'static str) {
+        match scope {
+            VariableScope::Global => ("global_variables", "workspace_id"),
+            VariableScope::Collection => ("collection_variables", "collection_id"),
+            VariableScope::Request => ("request_variables", "request_id"),
+        }
+    }
+
+    // .env files
+    /// Create an environment from the contents of a `.env` file
+    pub async fn import_env_file(&self, workspace_id: &str, name: &str, contents: &str) -> Result<Environment> {
+        // Parse first so a malformed file doesn't leave an empty environment behind
+        let variables = dotenv::parse(contents)?;
+
+        let mut environment = self.create_environment(workspace_id.to_string(), name.to_string()).await?;
         for (key, value) in variables {
-            let placeholder = format!("{{{{{}}}}}", key);
-            result = result.replace(&placeholder, value);
+            environment.variables.insert(key.clone(), EnvironmentVariable { key, value, ..Default::default() });
         }
+        self.update_environment(environment).await
+    }
+
+    /// An environment's variables, inherited ones included, in `.env` format. Secret variables
+    /// are left out unless `include_secrets` is set; a comment names each one that was skipped.
+    pub async fn export_env_file(&self, environment_id: &str, include_secrets: bool) -> Result<String> {
+        let environment = self.get_resolved_environment(environment_id).await?
+            .ok_or_else(|| anyhow!("Environment not found"))?;
+
+        let mut variables: Vec<&EnvironmentVariable> = environment.variables.values().collect();
+        variables.sort_by(|a, b| a.key.cmp(&b.key));
+        let is_secret = |variable: &EnvironmentVariable| variable.is_secret || variable.variable_type == VariableType::Secret;
 
-        result
+        let mut contents = dotenv::serialize(variables.iter()
+            .filter(|variable| include_secrets || !is_secret(variable))
+            .map(|variable| (variable.key.as_str(), variable.value.as_str())));
+        for variable in variables.iter().filter(|variable| !include_secrets && is_secret(variable)) {
+            contents.push_str(&format!("# {}= (secret, not exported)\n", variable.key));
+        }
+        Ok(contents)
     }
 
-    pub fn extract_variables(&self, text: &str) -> Vec<String> {
-        let re = regex::Regex::new(r"\{\{([^}]+)\}\}").unwrap();
-        let mut seen = HashSet::new();
-        let mut variables = Vec::new();
-        
-        for cap in re.captures_iter(text) {
-            let var = cap[1].to_string();
-            if seen.insert(var.clone()) {
-                variables.push(var);
+    // Orphaned environment files
+    /// List environment files in the workspace that have no matching database row
+    pub async fn find_orphaned_environment_files(&self, workspace_id: &str) -> Result<Vec<String>> {
+        let known: HashSet<String> = self.list_environments(workspace_id).await?
+            .iter()
+            .map(|env| self.file_sync.sanitize_filename(&env.name))
+            .collect();
+
+        let mut orphaned: Vec<String> = self.file_sync.list_environment_files(workspace_id).await?
+            .into_iter()
+            .filter(|name| !known.contains(name))
+            .collect();
+        orphaned.sort();
+
+        Ok(orphaned)
+    }
+
+    /// Adopt an orphaned environment file into the database
+    pub async fn import_orphaned_environment_file(&self, workspace_id: &str, name: &str) -> Result<Environment> {
+        let mut environment = self.file_sync.read_environment_file(workspace_id, name).await?
+            .ok_or_else(|| anyhow!("Environment file not found: {}", name))?;
+
+        if environment.name.is_empty() {
+            environment.name = name.to_string();
+        }
+        // Keep the stored id unless it is missing or already taken by another environment
+        if environment.id.is_empty() || self.get_environment(&environment.id).await?.is_some() {
+            environment.id = Uuid::new_v4().to_string();
+        }
+        // Imported environments never take over the active slot
+        environment.is_active = false;
+        // Only keep a parent that is already in this workspace
+        if let Some(parent_id) = environment.parent_id.clone() {
+            if self.check_parent(workspace_id, &environment.id, &parent_id).await.is_err() {
+                environment.parent_id = None;
             }
         }
-        
-        variables
+
+        sqlx::query(
+            r#"
+            INSERT INTO environments (id, workspace_id, name, is_active, is_protected, parent_id, created_at, updated_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+            "#
+        )
+        .bind(&environment.id)
+        .bind(workspace_id)
+        .bind(&environment.name)
+        .bind(environment.is_active)
+        .bind(environment.is_protected)
+        .bind(&environment.parent_id)
+        .bind(&environment.created_at.to_rfc3339())
+        .bind(&environment.updated_at.to_rfc3339())
+        .execute(&self.database.get_pool())
+        .await
+        .map_err(|e| anyhow!("Failed to import environment into database: {}", e))?;
+
+        for variable in environment.variables.values() {
+            sqlx::query(
+                "INSERT INTO environment_variables (environment_id, variable_key, value, is_secret, variable_type) VALUES (?1, ?2, ?3, ?4, ?5)"
+            )
+            .bind(&environment.id)
+            .bind(&variable.key)
+            .bind(&variable.value)
+            .bind(variable.is_secret)
+            .bind(variable.variable_type.as_str())
+            .execute(&self.database.get_pool())
+            .await
+            .map_err(|e| anyhow!("Failed to insert variable: {}", e))?;
+        }
+
+        self.get_environment(&environment.id).await?
+            .ok_or_else(|| anyhow!("Environment not found after import"))
+    }
+
+    // Variable substitution
+    pub fn substitute_variables(&self, text: &str, variables: &HashMap<String, String>) -> String {
+        variable_substitution::substitute_variables(text, variables)
+    }
+
+    /// Substitute an environment's variables, reporting where each one landed in the output
+    pub fn substitute_with_spans(&self, text: &str, variables: &HashMap<String, EnvironmentVariable>) -> SubstitutionResult {
+        variable_substitution::substitute_with_spans(text, variables)
+    }
+
+    pub fn extract_variables(&self, text: &str) -> Vec<String> {
+        variable_substitution::extract_variables(text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::test_database::database_with_workspace;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_invalid_variable_values_are_rejected() {
+        let temp_dir = TempDir::new().unwrap();
+        let (database, workspace) = database_with_workspace(temp_dir.path().to_str().unwrap()).await;
+        let service = EnvironmentService::new(database);
+        let environment = service.create_environment(workspace.id.clone(), "Dev".to_string()).await.unwrap();
+        let variable = |key: &str, value: &str, variable_type: VariableType| EnvironmentVariable {
+            key: key.to_string(),
+            value: value.to_string(),
+            is_secret: variable_type == VariableType::Secret,
+            variable_type,
+        };
+
+        let error = service.add_variable(&environment.id, variable("TOKEN", "  ", VariableType::Secret)).await.unwrap_err();
+        assert_eq!(
+            error.downcast_ref::<InvalidVariable>(),
+            Some(&InvalidVariable { key: "TOKEN".to_string(), variable_type: VariableType::Secret })
+        );
+        let error = service.add_variable(&environment.id, variable("RETRIES", "three", VariableType::Number)).await.unwrap_err();
+        assert_eq!(error.to_string(), "Invalid value for number variable 'RETRIES': expected a number");
+        assert!(service.get_environment(&environment.id).await.unwrap().unwrap().variables.is_empty());
+
+        // Valid values are stored, and updates are held to the same rules
+        service.add_variable(&environment.id, variable("RETRIES", "3", VariableType::Number)).await.unwrap();
+        assert!(service.update_variable(&environment.id, variable("RETRIES", "3x", VariableType::Number)).await.is_err());
+        let mut stored = service.get_environment(&environment.id).await.unwrap().unwrap();
+        assert_eq!(stored.variables["RETRIES"].value, "3");
+        assert_eq!(stored.variables["RETRIES"].variable_type, VariableType::Number);
+
+        stored.variables.insert("VERBOSE".to_string(), variable("VERBOSE", "maybe", VariableType::Boolean));
+        let error = service.update_environment(stored).await.unwrap_err();
+        assert!(error.downcast_ref::<InvalidVariable>().is_some_and(|invalid| invalid.key == "VERBOSE"));
+
+        // A value stored before its type was enforced doesn't block switching or other edits
+        sqlx::query("UPDATE environment_variables SET value = 'many' WHERE environment_id = ?1 AND variable_key = 'RETRIES'")
+            .bind(&environment.id)
+            .execute(&service.database.get_pool())
+            .await
+            .unwrap();
+        service.set_active_environment(&workspace.id, &environment.id).await.unwrap();
+        let mut stored = service.get_environment(&environment.id).await.unwrap().unwrap();
+        assert!(stored.is_active);
+        stored.is_protected = true;
+        service.update_environment(stored).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_find_and_import_orphaned_environment_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let workspace_path = temp_dir.path().to_str().unwrap();
+
+        let (database, workspace) = database_with_workspace(workspace_path).await;
+
+        let service = EnvironmentService::new(database);
+
+        // An environment created through the service is backed by the database
+        service.create_environment(workspace.id.clone(), "Development".to_string()).await.unwrap();
+
+        // A file written by hand has no database row
+        let orphan = serde_json::json!({
+            "id": "orphan-env",
+            "name": "staging",
+            "variables": {
+                "API_URL": {"key": "API_URL", "value": "https://staging.example.com", "isSecret": false, "variableType": "string"}
+            },
+            "is_active": true,
+            "created_at": "2024-01-01T00:00:00Z",
+            "updated_at": "2024-01-01T00:00:00Z"
+        });
+        std::fs::write(
+            temp_dir.path().join("environments/staging.json"),
+            serde_json::to_string_pretty(&orphan).unwrap(),
+        ).unwrap();
+
+        let orphaned = service.find_orphaned_environment_files(&workspace.id).await.unwrap();
+        assert_eq!(orphaned, vec!["staging".to_string()]);
+
+        let imported = service.import_orphaned_environment_file(&workspace.id, "staging").await.unwrap();
+        assert_eq!(imported.id, "orphan-env");
+        assert_eq!(imported.name, "staging");
+        assert!(!imported.is_active);
+        assert_eq!(imported.variables["API_URL"].value, "https://staging.example.com");
+
+        let orphaned = service.find_orphaned_environment_files(&workspace.id).await.unwrap();
+        assert!(orphaned.is_empty());
     }
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn test_clear_active_environment() {
+        let temp_dir = TempDir::new().unwrap();
+        let workspace_path = temp_dir.path().to_str().unwrap();
+
+        let (database, workspace) = database_with_workspace(workspace_path).await;
+
+        let service = EnvironmentService::new(database);
+
+        let mut development = service.create_environment(workspace.id.clone(), "Development".to_string()).await.unwrap();
+        service.create_environment(workspace.id.clone(), "Staging".to_string()).await.unwrap();
+        development.is_active = true;
+        service.update_environment(development).await.unwrap();
+
+        service.clear_active_environment(&workspace.id).await.unwrap();
+
+        let environments = service.list_environments(&workspace.id).await.unwrap();
+        assert_eq!(environments.len(), 2);
+        assert!(environments.iter().all(|env| !env.is_active));
+
+        let file = std::fs::read_to_string(temp_dir.path().join("environments/development.json")).unwrap();
+        let stored: serde_json::Value = serde_json::from_str(&file).unwrap();
+        assert_eq!(stored["is_active"], serde_json::json!(false));
+    }
+
+    #[tokio::test]
+    async fn test_resolve_variables_precedence() {
+        use crate::models::collection::{CreateCollectionRequest, CreateRequestRequest};
+        use crate::services::collection_service::CollectionService;
+
+        let temp_dir = TempDir::new().unwrap();
+        let (database, workspace) = database_with_workspace(temp_dir.path().to_str().unwrap()).await;
+
+        let collections = CollectionService::new(database.get_pool());
+        let collection = collections.create_collection(CreateCollectionRequest {
+            workspace_id: workspace.id.clone(),
+            name: "Users".to_string(),
+            description: None,
+            folder_path: None,
+            git_branch: None,
+            parent_id: None,
+        }).await.unwrap();
+        let request = collections.create_request(CreateRequestRequest {
+            collection_id: collection.id.clone(),
+            name: "Get user".to_string(),
+            description: None,
+            method: "GET".to_string(),
+            url: "{{BASE_URL}}/users/{{USER_ID}}".to_string(),
+            headers: None,
+            body: None,
+            body_type: None,
+            auth_type: None,
+            auth_config: None,
+            follow_redirects: None,
+            timeout_ms: None,
+            order_index: None,
+        }).await.unwrap();
+
+        let service = EnvironmentService::new(database);
+        let environment = service.create_environment(workspace.id.clone(), "Development".to_string()).await.unwrap();
+        for (key, value) in [("BASE_URL", "https://dev.example.com"), ("USER_ID", "env-user"), ("TOKEN", "env-token")] {
+            service.add_variable(&environment.id, EnvironmentVariable {
+                key: key.to_string(),
+                value: value.to_string(),
+                ..Default::default()
+            }).await.unwrap();
+        }
+        service.set_scoped_variable(VariableScope::Global, &workspace.id, "BASE_URL", "https://global.example.com").await.unwrap();
+        service.set_scoped_variable(VariableScope::Global, &workspace.id, "TIMEOUT", "30").await.unwrap();
+        service.set_scoped_variable(VariableScope::Collection, &collection.id, "USER_ID", "collection-user").await.unwrap();
+        service.set_scoped_variable(VariableScope::Collection, &collection.id, "TOKEN", "collection-token").await.unwrap();
+        service.set_scoped_variable(VariableScope::Request, &request.id, "TOKEN", "request-token").await.unwrap();
+
+        // The collection's USER_ID overrides the environment's
+        let variables = service
+            .resolve_variables(&workspace.id, Some(&collection.id), None, Some(&environment.id))
+            .await
+            .unwrap();
+        assert_eq!(variables["BASE_URL"], "https://dev.example.com");
+        assert_eq!(variables["USER_ID"], "collection-user");
+        assert_eq!(variables["TOKEN"], "collection-token");
+        assert_eq!(variables["TIMEOUT"], "30");
+
+        // The request's TOKEN overrides both the collection's and the environment's
+        let variables = service
+            .resolve_variables(&workspace.id, Some(&collection.id), Some(&request.id), Some(&environment.id))
+            .await
+            .unwrap();
+        assert_eq!(variables["TOKEN"], "request-token");
+        assert_eq!(variables["USER_ID"], "collection-user");
+
+        // A deleted request keeps its variables until it is purged
+        collections.delete_request(&request.id).await.unwrap();
+        assert_eq!(service.get_scoped_variables(VariableScope::Request, &request.id).await.unwrap().len(), 1);
+        collections.purge_deleted(&workspace.id, 0).await.unwrap();
+        assert!(service.get_scoped_variables(VariableScope::Request, &request.id).await.unwrap().is_empty());
+        assert!(service.remove_scoped_variable(VariableScope::Global, &workspace.id, "TIMEOUT").await.unwrap());
+        assert!(!service.remove_scoped_variable(VariableScope::Global, &workspace.id, "TIMEOUT").await.unwrap());
+    }
+
+    fn new_request(collection_id: &str, name: &str, url: &str) -> crate::models::collection::CreateRequestRequest {
+        crate::models::collection::CreateRequestRequest {
+            collection_id: collection_id.to_string(),
+            name: name.to_string(),
+            description: None,
+            method: "GET".to_string(),
+            url: url.to_string(),
+            headers: None,
+            body: None,
+            body_type: None,
+            auth_type: None,
+            auth_config: None,
+            follow_redirects: None,
+            timeout_ms: None,
+            order_index: None,
+        }
+    }
+
+    /// A collection in the same workspace as `environment`
+    async fn collection_beside(database: &Arc<DatabaseService>, environment: &Environment) -> crate::models::collection::Collection {
+        let workspace_id: String = sqlx::query("SELECT workspace_id FROM environments WHERE id = ?1")
+            .bind(&environment.id)
+            .fetch_one(&database.get_pool())
+            .await
+            .unwrap()
+            .get("workspace_id");
+        CollectionService::new(database.get_pool())
+            .create_collection(crate::models::collection::CreateCollectionRequest {
+                workspace_id,
+                name: "Users".to_string(),
+                description: None,
+                folder_path: None,
+                git_branch: None,
+                parent_id: None,
+            })
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_find_variable_usages() {
+        let temp_dir = TempDir::new().unwrap();
+        let (service, database, environment) = environment_with_variables(&temp_dir, &[("TOKEN", "secret")]).await;
+        let collection = collection_beside(&database, &environment).await;
+        let collections = CollectionService::new(database.get_pool());
+
+        let mut with_header = new_request(&collection.id, "Get user", "https://api.example.com/users/{{USER_ID}}");
+        with_header.headers = Some(serde_json::json!({ "Authorization": "Bearer {{TOKEN}}" }));
+        let with_header = collections.create_request(with_header).await.unwrap();
+        let mut in_url_and_body = new_request(&collection.id, "Login", "https://api.example.com/login?token={{TOKEN}}");
+        in_url_and_body.body = Some(r#"{"token":"{{TOKEN}}"}"#.to_string());
+        let in_url_and_body = collections.create_request(in_url_and_body).await.unwrap();
+        collections.create_request(new_request(&collection.id, "Health", "https://api.example.com/health")).await.unwrap();
+
+        let usages = service.find_variable_usages(&collection.id, "TOKEN").await.unwrap();
+        assert_eq!(usages, vec![
+            VariableUsage { request_id: with_header.id.clone(), request_name: "Get user".to_string(), fields: vec![RequestField::Headers] },
+            VariableUsage { request_id: in_url_and_body.id.clone(), request_name: "Login".to_string(), fields: vec![RequestField::Url, RequestField::Body] },
+        ]);
+        assert!(service.find_variable_usages(&collection.id, "UNUSED").await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_find_undefined_variables() {
+        let temp_dir = TempDir::new().unwrap();
+        let (service, database, environment) = environment_with_variables(&temp_dir, &[("BASE_URL", "https://api.example.com")]).await;
+        let collection = collection_beside(&database, &environment).await;
+        let collections = CollectionService::new(database.get_pool());
+
+        let mut login = new_request(&collection.id, "Login", "{{BASE_URL}}/login?trace={{$uuid}}");
+        login.auth_config = Some(serde_json::json!({ "token": "{{API_KEY}}" }));
+        let login = collections.create_request(login).await.unwrap();
+        collections.set_request_extractions(&login.id, &[Extraction {
+            variable: "SESSION".to_string(),
+            source: crate::models::collection::ExtractionSource::Body,
+            path: "$.session".to_string(),
+        }]).await.unwrap();
+        collections
+            .create_request(new_request(&collection.id, "Profile", "{{BASE_URL}}/users/{{USER_ID}}?s={{SESSION}}&k={{API_KEY}}"))
+            .await
+            .unwrap();
+        let orders = collections
+            .create_request(new_request(&collection.id, "Orders", "{{BASE_URL}}/orders/{{ORDER_ID}}"))
+            .await
+            .unwrap();
+        service.set_scoped_variable(VariableScope::Request, &orders.id, "ORDER_ID", "7").await.unwrap();
+
+        let undefined = service.find_undefined_variables(&collection.id, Some(&environment.id)).await.unwrap();
+        let names: Vec<&str> = undefined.iter().map(|variable| variable.name.as_str()).collect();
+        assert_eq!(names, vec!["API_KEY", "USER_ID"]);
+        let requests: Vec<&str> = undefined[0].usages.iter().map(|usage| usage.request_name.as_str()).collect();
+        assert_eq!(requests, vec!["Login", "Profile"]);
+        assert_eq!(undefined[0].usages[0].fields, vec![RequestField::Auth]);
+
+        // Without the environment BASE_URL is missing too
+        let undefined = service.find_undefined_variables(&collection.id, None).await.unwrap();
+        assert_eq!(undefined[0].name, "BASE_URL");
+        assert_eq!(undefined[0].usages.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_environment_inherits_parent_variables() {
+        let temp_dir = TempDir::new().unwrap();
+        let (database, workspace) = database_with_workspace(temp_dir.path().to_str().unwrap()).await;
+        let service = EnvironmentService::new(database);
+
+        let base = service.create_environment(workspace.id.clone(), "base".to_string()).await.unwrap();
+        for (key, value) in [("BASE_URL", "https://base.example.com"), ("TIMEOUT", "30")] {
+            service.add_variable(&base.id, EnvironmentVariable { key: key.to_string(), value: value.to_string(), ..Default::default() }).await.unwrap();
+        }
+        let mut staging = service.create_environment(workspace.id.clone(), "staging".to_string()).await.unwrap();
+        staging.parent_id = Some(base.id.clone());
+        staging.variables.insert("BASE_URL".to_string(), EnvironmentVariable {
+            key: "BASE_URL".to_string(),
+            value: "https://staging.example.com".to_string(),
+            ..Default::default()
+        });
+        service.update_environment(staging.clone()).await.unwrap();
+
+        let resolved = service.get_resolved_environment(&staging.id).await.unwrap().unwrap();
+        assert_eq!(resolved.variables["BASE_URL"].value, "https://staging.example.com");
+        assert_eq!(resolved.variables["TIMEOUT"].value, "30");
+
+        // The raw view keeps only the environment's own variables
+        let raw = service.get_environment(&staging.id).await.unwrap().unwrap();
+        assert_eq!(raw.parent_id, Some(base.id.clone()));
+        assert!(!raw.variables.contains_key("TIMEOUT"));
+    }
+
+    async fn environment_with_variables(temp_dir: &TempDir, variables: &[(&str, &str)]) -> (EnvironmentService, Arc<DatabaseService>, Environment) {
+        let (database, workspace) = database_with_workspace(temp_dir.path().to_str().unwrap()).await;
+        let service = EnvironmentService::new(database.clone());
+        let environment = service.create_environment(workspace.id.clone(), "Dev".to_string()).await.unwrap();
+        let environment = service.set_variables(&environment.id, variable_list(variables), MergeMode::Replace).await.unwrap();
+        (service, database, environment)
+    }
+
+    fn variable_list(variables: &[(&str, &str)]) -> Vec<EnvironmentVariable> {
+        variables.iter()
+            .map(|(key, value)| EnvironmentVariable { key: key.to_string(), value: value.to_string(), ..Default::default() })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_set_variables_merge_keeps_untouched_keys() {
+        let temp_dir = TempDir::new().unwrap();
+        let (service, _, environment) = environment_with_variables(&temp_dir, &[("HOST", "localhost"), ("PORT", "3000")]).await;
+
+        let merged = service
+            .set_variables(&environment.id, variable_list(&[("PORT", "8080"), ("TOKEN", "abc")]), MergeMode::Merge)
+            .await
+            .unwrap();
+        assert_eq!(merged.variables.len(), 3);
+        assert_eq!(merged.variables["HOST"].value, "localhost");
+        assert_eq!(merged.variables["PORT"].value, "8080");
+        assert_eq!(merged.variables["TOKEN"].value, "abc");
+
+        // One invalid variable rejects the whole batch
+        let mut batch = variable_list(&[("HOST", "example.com")]);
+        batch.push(EnvironmentVariable { key: "RETRIES".to_string(), value: "many".to_string(), is_secret: false, variable_type: VariableType::Number });
+        assert!(service.set_variables(&environment.id, batch, MergeMode::Merge).await.is_err());
+        let stored = service.get_environment(&environment.id).await.unwrap().unwrap();
+        assert_eq!(stored.variables["HOST"].value, "localhost");
+        assert!(!stored.variables.contains_key("RETRIES"));
+    }
+
+    #[tokio::test]
+    async fn test_set_variables_replace_removes_absent_keys() {
+        let temp_dir = TempDir::new().unwrap();
+        let (service, database, environment) = environment_with_variables(&temp_dir, &[("HOST", "localhost"), ("PORT", "3000")]).await;
+
+        let replaced = service
+            .set_variables(&environment.id, variable_list(&[("PORT", "8080"), ("TOKEN", "abc")]), MergeMode::Replace)
+            .await
+            .unwrap();
+        let mut keys: Vec<&String> = replaced.variables.keys().collect();
+        keys.sort();
+        assert_eq!(keys, ["PORT", "TOKEN"]);
+        assert_eq!(replaced.variables["PORT"].value, "8080");
+
+        // The environment file matches the database
+        let workspace_id: String = sqlx::query_scalar("SELECT workspace_id FROM environments WHERE id = ?1")
+            .bind(&environment.id)
+            .fetch_one(&database.get_pool())
+            .await
+            .unwrap();
+        let synced = FileSyncService::new(database)
+            .read_environment_file(&workspace_id, "Dev")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(synced.variables.len(), 2);
+        assert_eq!(synced.variables["TOKEN"].value, "abc");
+    }
+
+    #[tokio::test]
+    async fn test_environment_inheritance_cycle_is_an_error() {
+        let temp_dir = TempDir::new().unwrap();
+        let (database, workspace) = database_with_workspace(temp_dir.path().to_str().unwrap()).await;
+        let service = EnvironmentService::new(database.clone());
+
+        let mut base = service.create_environment(workspace.id.clone(), "base".to_string()).await.unwrap();
+        let mut prod = service.create_environment(workspace.id.clone(), "prod".to_string()).await.unwrap();
+        prod.parent_id = Some(base.id.clone());
+        service.update_environment(prod.clone()).await.unwrap();
+
+        base.parent_id = Some(prod.id.clone());
+        let error = service.update_environment(base.clone()).await.unwrap_err();
+        assert!(error.to_string().contains("cycle"), "{}", error);
+
+        // A cycle that got in some other way is reported instead of followed
+        sqlx::query("UPDATE environments SET parent_id = ?1 WHERE id = ?2")
+            .bind(&prod.id)
+            .bind(&base.id)
+            .execute(&database.get_pool())
+            .await
+            .unwrap();
+        let error = service.get_resolved_environment(&prod.id).await.unwrap_err();
+        assert_eq!(error.to_string(), "Environment inheritance cycle: prod -> base -> prod");
+        assert!(service.list_resolved_environments(&workspace.id).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_env_file_round_trip() {
+        let temp_dir = TempDir::new().unwrap();
+        let (database, workspace) = database_with_workspace(temp_dir.path().to_str().unwrap()).await;
+        let service = EnvironmentService::new(database);
+
+        let contents = "# CI settings\nexport API_URL=https://ci.example.com\nMESSAGE=\"hello world\"\n";
+        let mut environment = service.import_env_file(&workspace.id, "ci", contents).await.unwrap();
+        assert_eq!(environment.name, "ci");
+        assert_eq!(environment.variables["API_URL"].value, "https://ci.example.com");
+        assert_eq!(environment.variables["MESSAGE"].value, "hello world");
+
+        environment.variables.insert("TOKEN".to_string(), EnvironmentVariable {
+            key: "TOKEN".to_string(),
+            value: "s3cret".to_string(),
+            is_secret: true,
+            variable_type: VariableType::Secret,
+        });
+        service.update_environment(environment.clone()).await.unwrap();
+
+        let exported = service.export_env_file(&environment.id, false).await.unwrap();
+        assert_eq!(exported, "API_URL=https://ci.example.com\nMESSAGE=\"hello world\"\n# TOKEN= (secret, not exported)\n");
+
+        let exported = service.export_env_file(&environment.id, true).await.unwrap();
+        let reimported = service.import_env_file(&workspace.id, "ci-copy", &exported).await.unwrap();
+        let values = |env: &Environment| {
+            let mut values: Vec<(String, String)> = env.variables.values().map(|v| (v.key.clone(), v.value.clone())).collect();
+            values.sort();
+            values
+        };
+        assert_eq!(values(&reimported), values(&service.get_environment(&environment.id).await.unwrap().unwrap()));
+
+        // Nothing is created from a malformed file
+        assert!(service.import_env_file(&workspace.id, "broken", "NOT A LINE").await.is_err());
+        assert_eq!(service.list_environments(&workspace.id).await.unwrap().len(), 2);
+    }
+}
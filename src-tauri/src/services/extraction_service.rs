@@ -0,0 +1,193 @@
+use crate::models::collection::{Extraction, ExtractionSource};
+use crate::models::http::{HttpResponse, ResponseBody};
+use anyhow::{anyhow, Result};
+use serde_json::Value;
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PathSegment {
+    Key(String),
+    Index(usize),
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ExtractionService;
+
+impl ExtractionService {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Values captured from a response by the given rules. Rules that match nothing
+    /// are left out so an earlier value of the variable stays in place.
+    pub fn extract(&self, extractions: &[Extraction], response: &HttpResponse) -> HashMap<String, String> {
+        let body = body_value(&response.body);
+
+        extractions
+            .iter()
+            .filter_map(|extraction| {
+                let value = match extraction.source {
+                    ExtractionSource::Header => response
+                        .headers
+                        .iter()
+                        .find(|(name, _)| name.eq_ignore_ascii_case(&extraction.path))
+                        .map(|(_, value)| value.clone()),
+                    ExtractionSource::Body => {
+                        let segments = parse_json_path(&extraction.path).ok()?;
+                        body.as_ref().and_then(|body| select(body, &segments)).map(value_to_string)
+                    }
+                };
+                value.map(|value| (extraction.variable.clone(), value))
+            })
+            .collect()
+    }
+}
+
+/// Parse the JSONPath subset used for extractions: `$`, `.key`, `['key']` and `[0]`
+pub fn parse_json_path(path: &str) -> Result<Vec<PathSegment>> {
+    let rest = path
+        .trim()
+        .strip_prefix('$')
+        .ok_or_else(|| anyhow!("Invalid JSONPath '{}': must start with $", path))?;
+    let chars: Vec<char> = rest.chars().collect();
+    let invalid = || anyhow!("Invalid JSONPath '{}'", path);
+
+    let mut segments = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        match chars[i] {
+            '.' => {
+                let start = i + 1;
+                i = start;
+                while i < chars.len() && chars[i] != '.' && chars[i] != '[' {
+                    i += 1;
+                }
+                let key: String = chars[start..i].iter().collect();
+                if key.is_empty() || key == "*" {
+                    return Err(invalid());
+                }
+                segments.push(PathSegment::Key(key));
+            }
+            '[' => {
+                let close = chars[i..].iter().position(|c| *c == ']').ok_or_else(invalid)? + i;
+                let inner: String = chars[i + 1..close].iter().collect();
+                let quoted = inner
+                    .strip_prefix('\'')
+                    .and_then(|s| s.strip_suffix('\''))
+                    .or_else(|| inner.strip_prefix('"').and_then(|s| s.strip_suffix('"')));
+                match quoted {
+                    Some(key) => segments.push(PathSegment::Key(key.to_string())),
+                    None => segments.push(PathSegment::Index(inner.parse().map_err(|_| invalid())?)),
+                }
+                i = close + 1;
+            }
+            _ => return Err(invalid()),
+        }
+    }
+    Ok(segments)
+}
+
+/// The value at `segments` within `value`, if there is one
+pub fn select<'a>(value: &'a Value, segments: &[PathSegment]) -> Option<&'a Value> {
+    segments.iter().try_fold(value, |current, segment| match segment {
+        PathSegment::Key(key) => current.get(key),
+        PathSegment::Index(index) => current.get(index),
+    })
+}
+
+/// A response body as JSON, when it is JSON
+pub fn body_value(body: &ResponseBody) -> Option<Value> {
+    match body {
+        ResponseBody::Json { data } => Some(data.clone()),
+        ResponseBody::Text { content, .. } => serde_json::from_str(content).ok(),
+        _ => None,
+    }
+}
+
+/// Strings are used as-is; anything else keeps its JSON form
+pub fn value_to_string(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::http::ResponseTiming;
+
+    fn response(headers: &[(&str, &str)], body: ResponseBody) -> HttpResponse {
+        HttpResponse {
+            status: 200,
+            status_text: "OK".to_string(),
+            headers: headers.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect(),
+            body,
+            timing: ResponseTiming {
+                total_time_ms: 5,
+                dns_lookup_ms: None,
+                tcp_connect_ms: None,
+                tls_handshake_ms: None,
+                first_byte_ms: None,
+                download_ms: None,
+                connection_reused: None,
+            },
+            request_id: "request-1".to_string(),
+            final_url: "https://api.example.com/login".to_string(),
+            truncated: false,
+            full_size: 0,
+            size: Default::default(),
+            streamed: false,
+            attempts: 1,
+            redirects: Vec::new(),
+            timestamp: chrono::Utc::now(),
+        }
+    }
+
+    fn rule(variable: &str, source: ExtractionSource, path: &str) -> Extraction {
+        Extraction {
+            variable: variable.to_string(),
+            source,
+            path: path.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_extract_from_json_body() {
+        let response = response(&[], ResponseBody::Json {
+            data: serde_json::json!({
+                "data": {"token": "abc123", "user": {"id": 42}},
+                "items": [{"name": "first"}, {"name": "second"}]
+            }),
+        });
+        let rules = vec![
+            rule("token", ExtractionSource::Body, "$.data.token"),
+            rule("user_id", ExtractionSource::Body, "$.data.user.id"),
+            rule("second", ExtractionSource::Body, "$.items[1]['name']"),
+            rule("missing", ExtractionSource::Body, "$.data.refresh"),
+        ];
+
+        let values = ExtractionService::new().extract(&rules, &response);
+
+        assert_eq!(values.get("token").map(String::as_str), Some("abc123"));
+        assert_eq!(values.get("user_id").map(String::as_str), Some("42"));
+        assert_eq!(values.get("second").map(String::as_str), Some("second"));
+        assert!(!values.contains_key("missing"));
+    }
+
+    #[test]
+    fn test_extract_from_header() {
+        let response = response(&[("x-request-id", "req-7"), ("location", "/orders/9")], ResponseBody::Empty);
+        let rules = vec![
+            rule("request_id", ExtractionSource::Header, "X-Request-Id"),
+            rule("order_url", ExtractionSource::Header, "Location"),
+        ];
+
+        let values = ExtractionService::new().extract(&rules, &response);
+
+        assert_eq!(values.get("request_id").map(String::as_str), Some("req-7"));
+        assert_eq!(values.get("order_url").map(String::as_str), Some("/orders/9"));
+        assert!(parse_json_path("data.token").is_err());
+        assert!(parse_json_path("$.items[*]").is_err());
+    }
+}
@@ -1,29 +1,93 @@
 use crate::models::collection::{Collection, Request};
 use crate::models::environment::Environment;
+use crate::models::workspace::Workspace;
+use crate::services::database_service::DatabaseService;
 use crate::services::git_service::GitService;
 use anyhow::{Result, anyhow};
+use serde::Serialize;
 use tokio::fs;
 use serde_json;
+use std::fmt;
 use std::path::Path;
+use std::sync::Arc;
 
 #[derive(Clone)]
 pub struct FileSyncService {
+    database: Arc<DatabaseService>,
     git_service: GitService,
 }
 
+// Helper function to expand tilde paths
+pub fn expand_tilde_path(path: &str) -> String {
+    if path.starts_with("~/") {
+        if let Ok(home_dir) = std::env::var("HOME") {
+            return path.replacen("~", &home_dir, 1);
+        }
+    }
+    path.to_string()
+}
+
+/// Sync failures the UI handles specially rather than showing as a plain message
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SyncError {
+    MergeConflict { path: String }, // relative to the workspace, as git reports it
+}
+
+impl fmt::Display for SyncError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SyncError::MergeConflict { path } => {
+                write!(f, "Merge conflict in {}; resolve it before syncing", path)
+            }
+        }
+    }
+}
+
+impl std::error::Error for SyncError {}
+
+/// Whether a file still has git conflict markers in it
+fn has_conflict_markers(content: &str) -> bool {
+    content.lines().any(|line| {
+        line.starts_with("<<<<<<< ") || line.starts_with(">>>>>>> ") || line == "======="
+    })
+}
+
+/// RFC 3339 timestamp from a file, falling back to the epoch
+fn parse_timestamp(value: &serde_json::Value) -> chrono::DateTime<chrono::Utc> {
+    chrono::DateTime::parse_from_rfc3339(value.as_str().unwrap_or("1970-01-01T00:00:00Z"))
+        .unwrap_or_default()
+        .with_timezone(&chrono::Utc)
+}
+
+/// Extraction and assertion lists are arrays in files but JSON strings in the database
+fn json_array_string(value: &serde_json::Value) -> String {
+    if value.is_array() {
+        value.to_string()
+    } else {
+        "[]".to_string()
+    }
+}
+
 impl FileSyncService {
-    pub fn new() -> Self {
+    pub fn new(database: Arc<DatabaseService>) -> Self {
         Self {
+            database,
             git_service: GitService::new(),
         }
     }
 
+    async fn get_workspace(&self, workspace_id: &str) -> Result<Workspace> {
+        self.database
+            .get_workspace(workspace_id)
+            .await?
+            .ok_or_else(|| anyhow!("Workspace {} not found", workspace_id))
+    }
+
     /// Get the workspace path from workspace ID by looking it up in the database
-    async fn get_workspace_path(&self, _workspace_id: &str) -> Result<String> {
-        // For now, we'll use the known workspace path
-        // TODO: Look up actual workspace path from database
-        let home = std::env::var("HOME").unwrap_or_else(|_| "/".to_string());
-        Ok(format!("{}/Documents/Postgirl/postgirl-workspace", home))
+    async fn get_workspace_path(&self, workspace_id: &str) -> Result<String> {
+        let workspace = self.get_workspace(workspace_id).await?;
+        Ok(expand_tilde_path(&workspace.local_path))
     }
 
     /// Write collection to JSON file
@@ -42,6 +106,7 @@ impl FileSyncService {
             "description": collection.description,
             "folder_path": collection.folder_path,
             "git_branch": collection.git_branch,
+            "parent_id": collection.parent_id,
             "is_active": collection.is_active,
             "created_at": collection.created_at.to_rfc3339(),
             "updated_at": collection.updated_at.to_rfc3339(),
@@ -60,6 +125,13 @@ impl FileSyncService {
                     "follow_redirects": req.follow_redirects,
                     "timeout_ms": req.timeout_ms,
                     "order_index": req.order_index,
+                    "include_in_run": req.include_in_run,
+                    "raw_stream": req.raw_stream,
+                    "connect_timeout_ms": req.connect_timeout_ms,
+                    "run_condition": req.run_condition,
+                    "pre_request": req.get_pre_request(),
+                    "extractions": req.get_extractions(),
+                    "assertions": req.get_assertions(),
                     "created_at": req.created_at.to_rfc3339(),
                     "updated_at": req.updated_at.to_rfc3339()
                 })
@@ -80,7 +152,7 @@ impl FileSyncService {
         println!("✅ Written collection file: {}", file_path);
 
         // Commit to Git
-        self.commit_changes(&workspace_path, &format!("Update collection: {}", collection.name)).await?;
+        self.commit_changes(&collection.workspace_id, &format!("collections/{}.json", safe_filename), &format!("Update collection: {}", collection.name)).await?;
 
         Ok(())
     }
@@ -100,16 +172,161 @@ impl FileSyncService {
             println!("🗑️ Deleted collection file: {}", file_path);
 
             // Commit to Git
-            self.commit_changes(&workspace_path, &format!("Delete collection: {}", collection_name)).await?;
+            self.commit_changes(workspace_id, &format!("collections/{}.json", safe_filename), &format!("Delete collection: {}", collection_name)).await?;
         }
 
         Ok(())
     }
 
-    /// Commit changes to Git repository
-    async fn commit_changes(&self, workspace_path: &str, commit_message: &str) -> Result<()> {
-        // Add all changes
-        match self.git_service.add_all_changes(workspace_path) {
+    /// Read a collection and its requests back from its JSON file. The workspace ID isn't
+    /// stored in the file, so it is taken from the caller.
+    pub async fn read_collection_file(&self, workspace_id: &str, collection_name: &str) -> Result<Option<(Collection, Vec<Request>)>> {
+        let workspace_path = self.get_workspace_path(workspace_id).await?;
+        let collections_dir = format!("{}/collections", workspace_path);
+
+        let safe_filename = self.sanitize_filename(collection_name);
+        let file_path = format!("{}/{}.json", collections_dir, safe_filename);
+
+        if !Path::new(&file_path).exists() {
+            return Ok(None);
+        }
+
+        let json_content = fs::read_to_string(&file_path).await
+            .map_err(|e| anyhow!("Failed to read collection file: {}", e))?;
+
+        if has_conflict_markers(&json_content) {
+            return Err(SyncError::MergeConflict { path: format!("collections/{}.json", safe_filename) }.into());
+        }
+
+        let collection_data: serde_json::Value = serde_json::from_str(&json_content)
+            .map_err(|e| anyhow!("Failed to parse collection file: {}", e))?;
+
+        // Hand-written files may leave out IDs; those entries get fresh ones
+        let id_or_new = |value: &serde_json::Value| {
+            value.as_str()
+                .filter(|id| !id.is_empty())
+                .map(str::to_string)
+                .unwrap_or_else(|| uuid::Uuid::new_v4().to_string())
+        };
+        let optional_string = |value: &serde_json::Value| value.as_str().map(str::to_string);
+
+        let collection = Collection {
+            id: id_or_new(&collection_data["id"]),
+            workspace_id: workspace_id.to_string(),
+            name: collection_data["name"].as_str().unwrap_or(collection_name).to_string(),
+            description: optional_string(&collection_data["description"]),
+            folder_path: optional_string(&collection_data["folder_path"]),
+            git_branch: optional_string(&collection_data["git_branch"]),
+            parent_id: optional_string(&collection_data["parent_id"]),
+            is_active: collection_data["is_active"].as_bool().unwrap_or(true),
+            created_at: parse_timestamp(&collection_data["created_at"]),
+            updated_at: parse_timestamp(&collection_data["updated_at"]),
+        };
+
+        let requests = collection_data["requests"]
+            .as_array()
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+            .iter()
+            .enumerate()
+            .map(|(index, req)| Request {
+                id: id_or_new(&req["id"]),
+                collection_id: collection.id.clone(),
+                name: req["name"].as_str().unwrap_or("Untitled Request").to_string(),
+                description: optional_string(&req["description"]),
+                method: req["method"].as_str().unwrap_or("GET").to_string(),
+                url: req["url"].as_str().unwrap_or_default().to_string(),
+                headers: req["headers"].as_str().unwrap_or("{}").to_string(),
+                body: optional_string(&req["body"]),
+                body_type: req["body_type"].as_str().unwrap_or("json").to_string(),
+                auth_type: optional_string(&req["auth_type"]),
+                auth_config: optional_string(&req["auth_config"]),
+                follow_redirects: req["follow_redirects"].as_bool().unwrap_or(true),
+                timeout_ms: req["timeout_ms"].as_u64().unwrap_or(30000) as u32,
+                order_index: req["order_index"].as_i64().unwrap_or(index as i64) as i32,
+                include_in_run: req["include_in_run"].as_bool().unwrap_or(true),
+                raw_stream: req["raw_stream"].as_bool().unwrap_or(false),
+                connect_timeout_ms: req["connect_timeout_ms"].as_u64().map(|ms| ms as u32),
+                run_condition: optional_string(&req["run_condition"]),
+                pre_request: json_array_string(&req["pre_request"]),
+                extractions: json_array_string(&req["extractions"]),
+                assertions: json_array_string(&req["assertions"]),
+                created_at: parse_timestamp(&req["created_at"]),
+                updated_at: parse_timestamp(&req["updated_at"]),
+            })
+            .collect();
+
+        Ok(Some((collection, requests)))
+    }
+
+    /// List all collection files in the workspace
+    pub async fn list_collection_files(&self, workspace_id: &str) -> Result<Vec<String>> {
+        let workspace_path = self.get_workspace_path(workspace_id).await?;
+        let collections_dir = format!("{}/collections", workspace_path);
+
+        if !Path::new(&collections_dir).exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut collection_names = Vec::new();
+        let mut entries = fs::read_dir(&collections_dir).await
+            .map_err(|e| anyhow!("Failed to read collections directory: {}", e))?;
+
+        while let Some(entry) = entries.next_entry().await
+            .map_err(|e| anyhow!("Failed to read directory entry: {}", e))? {
+
+            let path = entry.path();
+            if path.is_file() && path.extension().and_then(|s| s.to_str()) == Some("json") {
+                if let Some(stem) = path.file_stem().and_then(|s| s.to_str()) {
+                    collection_names.push(stem.to_string());
+                }
+            }
+        }
+
+        collection_names.sort();
+        Ok(collection_names)
+    }
+
+    /// Collection and environment files that still contain conflict markers, relative to the workspace
+    pub async fn list_conflicted_files(&self, workspace_id: &str) -> Result<Vec<String>> {
+        let workspace_path = self.get_workspace_path(workspace_id).await?;
+        let mut conflicted = Vec::new();
+
+        for dir in ["collections", "environments"] {
+            let dir_path = format!("{}/{}", workspace_path, dir);
+            if !Path::new(&dir_path).exists() {
+                continue;
+            }
+
+            let mut entries = fs::read_dir(&dir_path).await
+                .map_err(|e| anyhow!("Failed to read {} directory: {}", dir, e))?;
+            while let Some(entry) = entries.next_entry().await
+                .map_err(|e| anyhow!("Failed to read directory entry: {}", e))? {
+
+                let path = entry.path();
+                if !path.is_file() || path.extension().and_then(|s| s.to_str()) != Some("json") {
+                    continue;
+                }
+                let content = fs::read_to_string(&path).await.unwrap_or_default();
+                if has_conflict_markers(&content) {
+                    if let Some(file_name) = path.file_name().and_then(|s| s.to_str()) {
+                        conflicted.push(format!("{}/{}", dir, file_name));
+                    }
+                }
+            }
+        }
+
+        conflicted.sort();
+        Ok(conflicted)
+    }
+
+    /// Commit a single written or deleted file, leaving any unrelated edits unstaged. The
+    /// workspace's git identity is used as the author when one is set.
+    async fn commit_changes(&self, workspace_id: &str, relative_path: &str, commit_message: &str) -> Result<()> {
+        let workspace = self.get_workspace(workspace_id).await?;
+        let workspace_path = expand_tilde_path(&workspace.local_path);
+        let workspace_path = workspace_path.as_str();
+        match self.git_service.stage_files(workspace_path, &[relative_path.to_string()]) {
             Ok(result) => {
                 if !result.success {
                     eprintln!("Warning: Failed to add changes to Git: {}", result.message);
@@ -123,7 +340,7 @@ impl FileSyncService {
         }
 
         // Commit changes
-        match self.git_service.commit_changes(workspace_path, commit_message) {
+        match self.git_service.commit_changes(workspace_path, commit_message, workspace.commit_author().as_ref()) {
             Ok(result) => {
                 if result.success {
                     println!("📝 Git commit: {}", commit_message);
@@ -140,7 +357,7 @@ impl FileSyncService {
     }
 
     /// Sanitize filename to be filesystem-safe
-    fn sanitize_filename(&self, name: &str) -> String {
+    pub fn sanitize_filename(&self, name: &str) -> String {
         name.chars()
             .map(|c| match c {
                 ' ' => '-',
@@ -168,6 +385,8 @@ impl FileSyncService {
             "name": environment.name,
             "variables": environment.variables,
             "is_active": environment.is_active,
+            "is_protected": environment.is_protected,
+            "parent_id": environment.parent_id,
             "created_at": environment.created_at.to_rfc3339(),
             "updated_at": environment.updated_at.to_rfc3339()
         });
@@ -186,7 +405,7 @@ impl FileSyncService {
         println!("✅ Written environment file: {}", file_path);
 
         // Commit to Git
-        self.commit_changes(&workspace_path, &format!("Update environment: {}", environment.name)).await?;
+        self.commit_changes(workspace_id, &format!("environments/{}.json", safe_filename), &format!("Update environment: {}", environment.name)).await?;
 
         Ok(())
     }
@@ -206,7 +425,7 @@ impl FileSyncService {
             println!("🗑️ Deleted environment file: {}", file_path);
 
             // Commit to Git
-            self.commit_changes(&workspace_path, &format!("Delete environment: {}", environment_name)).await?;
+            self.commit_changes(workspace_id, &format!("environments/{}.json", safe_filename), &format!("Delete environment: {}", environment_name)).await?;
         }
 
         Ok(())
@@ -227,6 +446,10 @@ impl FileSyncService {
         let json_content = fs::read_to_string(&file_path).await
             .map_err(|e| anyhow!("Failed to read environment file: {}", e))?;
 
+        if has_conflict_markers(&json_content) {
+            return Err(SyncError::MergeConflict { path: format!("environments/{}.json", safe_filename) }.into());
+        }
+
         let environment_data: serde_json::Value = serde_json::from_str(&json_content)
             .map_err(|e| anyhow!("Failed to parse environment file: {}", e))?;
 
@@ -237,6 +460,8 @@ impl FileSyncService {
             variables: serde_json::from_value(environment_data["variables"].clone())
                 .unwrap_or_default(),
             is_active: environment_data["is_active"].as_bool().unwrap_or(false),
+            is_protected: environment_data["is_protected"].as_bool().unwrap_or(false),
+            parent_id: environment_data["parent_id"].as_str().map(str::to_string),
             created_at: chrono::DateTime::parse_from_rfc3339(
                 environment_data["created_at"].as_str().unwrap_or("1970-01-01T00:00:00Z")
             ).unwrap_or_default().with_timezone(&chrono::Utc),
@@ -274,4 +499,84 @@ impl FileSyncService {
 
         Ok(environment_names)
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::workspace::{CreateWorkspaceRequest, Workspace};
+    use crate::tests::test_database::database_with_workspace;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_conflict_markers_are_a_typed_error() {
+        let temp_dir = TempDir::new().unwrap();
+        let (database, workspace) = database_with_workspace(temp_dir.path().to_str().unwrap()).await;
+        let file_sync = FileSyncService::new(database);
+
+        let collections_dir = temp_dir.path().join("collections");
+        std::fs::create_dir_all(&collections_dir).unwrap();
+        std::fs::write(
+            collections_dir.join("users.json"),
+            "{\n<<<<<<< HEAD\n  \"name\": \"Users\",\n=======\n  \"name\": \"People\",\n>>>>>>> feature\n  \"requests\": []\n}\n",
+        ).unwrap();
+        std::fs::write(collections_dir.join("orders.json"), r#"{"name": "Orders", "requests": []}"#).unwrap();
+
+        let error = file_sync.read_collection_file(&workspace.id, "users").await.unwrap_err();
+        assert_eq!(
+            error.downcast_ref::<SyncError>(),
+            Some(&SyncError::MergeConflict { path: "collections/users.json".to_string() })
+        );
+        assert!(file_sync.read_collection_file(&workspace.id, "orders").await.unwrap().is_some());
+
+        let conflicted = file_sync.list_conflicted_files(&workspace.id).await.unwrap();
+        assert_eq!(conflicted, vec!["collections/users.json".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_commit_leaves_unrelated_changes_alone() {
+        let temp_dir = TempDir::new().unwrap();
+        let workspace_path = temp_dir.path().to_str().unwrap();
+        let git = GitService::new();
+        git.initialize_repository(workspace_path).unwrap();
+        std::fs::write(temp_dir.path().join("README.md"), "# API\n").unwrap();
+        git.add_all_changes(workspace_path).unwrap();
+        git.commit_changes(workspace_path, "Initial commit", None).unwrap();
+
+        let database = DatabaseService::new(temp_dir.path().join("test.db").to_str().unwrap()).await.unwrap();
+        let workspace = Workspace::new(CreateWorkspaceRequest {
+            name: "Test".to_string(),
+            description: None,
+            git_repository_url: None,
+            local_path: workspace_path.to_string(),
+            git_username: Some("Ada Lovelace".to_string()),
+            git_email: Some("ada@example.com".to_string()),
+        });
+        database.create_workspace(&workspace).await.unwrap();
+        let file_sync = FileSyncService::new(Arc::new(database));
+
+        std::fs::write(temp_dir.path().join("README.md"), "# API\n\nWork in progress\n").unwrap();
+        let environment = Environment {
+            id: "env-1".to_string(),
+            name: "Staging".to_string(),
+            variables: Default::default(),
+            is_active: true,
+            is_protected: false,
+            parent_id: None,
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+        };
+        file_sync.write_environment_file(&workspace.id, &environment).await.unwrap();
+
+        let log = git.get_commit_log(workspace_path, None, 1).unwrap();
+        assert_eq!(log[0].message.trim(), "Update environment: Staging");
+        assert_eq!(log[0].author, "Ada Lovelace");
+        let repo = git2::Repository::open(workspace_path).unwrap();
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+        assert_eq!(head.author().email(), Some("ada@example.com"));
+        assert_eq!(head.committer().name(), Some("Ada Lovelace"));
+        let status = git.get_repository_status(workspace_path).unwrap();
+        assert_eq!(status.modified_files, vec!["README.md".to_string()]);
+        assert!(status.staged_files.is_empty());
+        assert!(!status.untracked_files.iter().any(|path| path.starts_with("environments")));
+    }
+}
@@ -1,6 +1,6 @@
 use crate::models::git::{
-    BranchConfig, BranchCreateRequest, BranchCreateResult, BranchGenerator, BranchPattern,
-    FeatureType, GitBranch, SystemInfo,
+    BranchConfig, BranchCreateRequest, BranchCreateResult, BranchDeleteRefusal, BranchDeleteResult,
+    BranchGenerator, BranchPattern, FeatureType, GitBranch, SystemInfo,
 };
 use crate::services::database_service::DatabaseService;
 use anyhow::{Context, Result};
@@ -10,6 +10,9 @@ use std::env;
 use std::process::Command;
 use tauri::{AppHandle, Manager};
 
+// app_settings key holding the serialized BranchConfig
+const BRANCH_CONFIG_KEY: &str = "branch_config";
+
 #[derive(Clone)]
 pub struct GitBranchService {
     db: DatabaseService,
@@ -24,13 +27,35 @@ impl GitBranchService {
             .map_err(|e| anyhow::anyhow!("Failed to get app data directory: {}", e))?;
         let db_path = app_data_dir.join("postgirl.db");
         let db = DatabaseService::new(db_path.to_str().unwrap()).await?;
+        Self::with_database(db).await
+    }
+
+    /// Build the service on an open database, loading the saved branch config if there is one
+    pub async fn with_database(db: DatabaseService) -> Result<Self> {
         let system_info = Self::detect_system_info()?;
-        let config = BranchConfig::default(); // TODO: Load from settings
+        let config = Self::load_config(&db).await?.unwrap_or_default();
         let generator = BranchGenerator::new(config, system_info);
 
         Ok(Self { db, generator })
     }
 
+    async fn load_config(db: &DatabaseService) -> Result<Option<BranchConfig>> {
+        let row = sqlx::query("SELECT value FROM app_settings WHERE key = ?")
+            .bind(BRANCH_CONFIG_KEY)
+            .fetch_optional(&db.get_pool())
+            .await
+            .context("Failed to load branch config")?;
+
+        match row {
+            Some(row) => {
+                let value: String = row.get("value");
+                let config = serde_json::from_str(&value).context("Saved branch config is invalid")?;
+                Ok(Some(config))
+            }
+            None => Ok(None),
+        }
+    }
+
     /// Detect system information (username, machine name, OS)
     pub fn detect_system_info() -> Result<SystemInfo> {
         // Get username
@@ -177,6 +202,130 @@ impl GitBranchService {
         Ok(!output.stdout.is_empty())
     }
 
+    /// Check out an existing local branch. Refuses, naming the files, when uncommitted changes
+    /// would be overwritten; changes that carry over cleanly are kept.
+    pub fn switch_branch(&self, workspace_path: &str, branch_name: &str) -> Result<()> {
+        if !self.branch_exists(workspace_path, branch_name)? {
+            return Err(anyhow::anyhow!("Branch '{}' does not exist", branch_name));
+        }
+
+        let output = Command::new("git")
+            .current_dir(workspace_path)
+            .env("LC_ALL", "C") // the error text is matched below
+            .args(&["checkout", branch_name, "--"])
+            .output()
+            .context("Failed to switch branch")?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            if stderr.contains("would be overwritten") {
+                let files: Vec<&str> = stderr
+                    .lines()
+                    .filter(|line| line.starts_with('\t'))
+                    .map(str::trim)
+                    .collect();
+                return Err(anyhow::anyhow!(
+                    "Cannot switch to '{}': uncommitted changes to {} would be overwritten. Commit or stash them first.",
+                    branch_name,
+                    files.join(", ")
+                ));
+            }
+            return Err(anyhow::anyhow!("Failed to switch to '{}': {}", branch_name, stderr.trim()));
+        }
+
+        Ok(())
+    }
+
+    /// Delete a local branch and its history entry. The checked-out branch is never deleted,
+    /// and one with unmerged commits only when `force` is set.
+    pub async fn delete_branch(
+        &self,
+        workspace_path: &str,
+        branch_name: &str,
+        force: bool,
+    ) -> Result<BranchDeleteResult> {
+        let refuse = |refusal, message: String| BranchDeleteResult {
+            branch_name: branch_name.to_string(),
+            deleted: false,
+            refusal: Some(refusal),
+            message,
+        };
+
+        if !self.branch_exists(workspace_path, branch_name)? {
+            return Ok(refuse(BranchDeleteRefusal::NotFound, format!("Branch '{}' does not exist", branch_name)));
+        }
+        if self.get_current_branch(workspace_path)? == branch_name {
+            return Ok(refuse(
+                BranchDeleteRefusal::CurrentBranch,
+                format!("Branch '{}' is checked out; switch to another branch first", branch_name),
+            ));
+        }
+
+        let output = Command::new("git")
+            .current_dir(workspace_path)
+            .env("LC_ALL", "C") // the error text is matched below
+            .args(&["branch", if force { "-D" } else { "-d" }, branch_name])
+            .output()
+            .context("Failed to delete branch")?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            if stderr.contains("not fully merged") {
+                return Ok(refuse(
+                    BranchDeleteRefusal::Unmerged,
+                    format!("Branch '{}' has unmerged commits; force the delete to discard them", branch_name),
+                ));
+            }
+            return Err(anyhow::anyhow!("Failed to delete branch '{}': {}", branch_name, stderr.trim()));
+        }
+
+        sqlx::query("DELETE FROM branch_history WHERE branch_name = ?")
+            .bind(branch_name)
+            .execute(&self.db.get_pool())
+            .await
+            .context("Failed to remove branch history")?;
+
+        Ok(BranchDeleteResult {
+            branch_name: branch_name.to_string(),
+            deleted: true,
+            refusal: None,
+            message: format!("Deleted branch '{}'", branch_name),
+        })
+    }
+
+    /// Rename a local branch, carrying its branch history entry over to the new name
+    pub async fn rename_branch(&self, workspace_path: &str, old_name: &str, new_name: &str) -> Result<()> {
+        self.generator
+            .validate_branch_name(new_name)
+            .map_err(|e| anyhow::anyhow!("Invalid branch name '{}': {}", new_name, e))?;
+        if !self.branch_exists(workspace_path, old_name)? {
+            return Err(anyhow::anyhow!("Branch '{}' does not exist", old_name));
+        }
+        if self.branch_exists(workspace_path, new_name)? {
+            return Err(anyhow::anyhow!("Branch '{}' already exists", new_name));
+        }
+
+        let output = Command::new("git")
+            .current_dir(workspace_path)
+            .args(&["branch", "-m", old_name, new_name])
+            .output()
+            .context("Failed to rename branch")?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(anyhow::anyhow!("Failed to rename '{}': {}", old_name, stderr.trim()));
+        }
+
+        sqlx::query("UPDATE branch_history SET branch_name = ? WHERE branch_name = ?")
+            .bind(new_name)
+            .bind(old_name)
+            .execute(&self.db.get_pool())
+            .await
+            .context("Failed to update branch history")?;
+
+        Ok(())
+    }
+
     /// Get current branch name
     fn get_current_branch(&self, workspace_path: &str) -> Result<String> {
         let output = Command::new("git")
@@ -197,7 +346,7 @@ impl GitBranchService {
     pub fn list_branches(&self, workspace_path: &str) -> Result<Vec<GitBranch>> {
         let output = Command::new("git")
             .current_dir(workspace_path)
-            .args(&["branch", "-a", "--format=%(refname:short)|%(HEAD)|%(upstream:track)"])
+            .args(&["branch", "-a", "--format=%(refname:short)|%(HEAD)|%(upstream:short)|%(upstream:track)"])
             .output()
             .context("Failed to list branches")?;
 
@@ -213,25 +362,23 @@ impl GitBranchService {
                 continue;
             }
 
-            let parts: Vec<&str> = line.split('|').collect();
-            if parts.len() >= 2 {
-                let name = parts[0].trim().to_string();
-                let is_current = parts[1].trim() == "*";
-                let is_remote = name.starts_with("origin/") || name.contains("remotes/");
+            if let Some(branch) = parse_branch_line(line) {
+                let is_remote = branch.name.starts_with("origin/") || branch.name.contains("remotes/");
 
                 // Get last commit info
                 let (last_commit_hash, last_commit_message, last_commit_date) =
-                    self.get_branch_commit_info(workspace_path, &name)?;
+                    self.get_branch_commit_info(workspace_path, &branch.name)?;
 
                 branches.push(GitBranch {
-                    name,
-                    is_current,
+                    name: branch.name,
+                    is_current: branch.is_current,
                     is_remote,
                     last_commit_hash: Some(last_commit_hash),
                     last_commit_message: Some(last_commit_message),
                     last_commit_date: Some(last_commit_date),
-                    ahead_count: None, // TODO: Parse from upstream:track
-                    behind_count: None,
+                    ahead_count: branch.ahead_count,
+                    behind_count: branch.behind_count,
+                    upstream_gone: branch.upstream_gone,
                 });
             }
         }
@@ -333,8 +480,21 @@ impl GitBranchService {
         Ok(history)
     }
 
-    /// Update branch configuration
-    pub fn update_config(&mut self, config: BranchConfig) -> Result<()> {
+    /// Update branch configuration and save it for future sessions
+    pub async fn update_config(&mut self, config: BranchConfig) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO app_settings (key, value, updated_at) VALUES (?, ?, ?)
+            ON CONFLICT(key) DO UPDATE SET value = excluded.value, updated_at = excluded.updated_at
+            "#
+        )
+        .bind(BRANCH_CONFIG_KEY)
+        .bind(serde_json::to_string(&config)?)
+        .bind(chrono::Utc::now().to_rfc3339())
+        .execute(&self.db.get_pool())
+        .await
+        .context("Failed to save branch config")?;
+
         let system_info = self.generator.system_info.clone();
         self.generator = BranchGenerator::new(config, system_info);
         Ok(())
@@ -353,4 +513,233 @@ impl GitBranchService {
         
         suggestions
     }
-}
\ No newline at end of file
+}
+
+/// One line of `git branch --format=%(refname:short)|%(HEAD)|%(upstream:short)|%(upstream:track)`
+#[derive(Debug, PartialEq)]
+struct BranchLine {
+    name: String,
+    is_current: bool,
+    ahead_count: Option<i32>,
+    behind_count: Option<i32>,
+    upstream_gone: bool,
+}
+
+/// The track field reads like `[ahead 2, behind 1]` or `[gone]`, and is empty both when the
+/// branch is level with its upstream and when it has none, so the upstream name tells them apart
+fn parse_branch_line(line: &str) -> Option<BranchLine> {
+    let parts: Vec<&str> = line.split('|').collect();
+    if parts.len() < 2 {
+        return None;
+    }
+
+    let upstream = parts.get(2).map(|s| s.trim()).unwrap_or_default();
+    let track = parts.get(3).map(|s| s.trim()).unwrap_or_default();
+    let upstream_gone = track == "[gone]";
+    let (ahead_count, behind_count) = if upstream.is_empty() || upstream_gone {
+        (None, None)
+    } else {
+        let mut ahead = 0;
+        let mut behind = 0;
+        for part in track.trim_start_matches('[').trim_end_matches(']').split(',') {
+            match part.trim().split_once(' ') {
+                Some(("ahead", n)) => ahead = n.trim().parse().unwrap_or(0),
+                Some(("behind", n)) => behind = n.trim().parse().unwrap_or(0),
+                _ => {}
+            }
+        }
+        (Some(ahead), Some(behind))
+    };
+
+    Some(BranchLine {
+        name: parts[0].trim().to_string(),
+        is_current: parts[1].trim() == "*",
+        ahead_count,
+        behind_count,
+        upstream_gone,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn counts(line: &str) -> (Option<i32>, Option<i32>, bool) {
+        let branch = parse_branch_line(line).unwrap();
+        (branch.ahead_count, branch.behind_count, branch.upstream_gone)
+    }
+
+    #[test]
+    fn test_parse_branch_line_tracking() {
+        let branch = parse_branch_line("main|*|origin/main|[ahead 2, behind 5]").unwrap();
+        assert_eq!(branch.name, "main");
+        assert!(branch.is_current);
+        assert_eq!((branch.ahead_count, branch.behind_count), (Some(2), Some(5)));
+
+        assert_eq!(counts("feature/x| |origin/feature/x|[ahead 3]"), (Some(3), Some(0), false));
+        assert_eq!(counts("feature/y| |origin/feature/y|[behind 12]"), (Some(0), Some(12), false));
+        assert_eq!(counts("develop| |origin/develop|"), (Some(0), Some(0), false));
+    }
+
+    #[test]
+    fn test_parse_branch_line_without_upstream() {
+        assert_eq!(counts("scratch| ||"), (None, None, false));
+        assert_eq!(counts("origin/main| ||"), (None, None, false));
+        // Output from the older three-field format
+        assert_eq!(counts("main|*|"), (None, None, false));
+        assert!(parse_branch_line("main").is_none());
+    }
+
+    #[test]
+    fn test_parse_branch_line_gone_upstream() {
+        assert_eq!(counts("old-feature| |origin/old-feature|[gone]"), (None, None, true));
+    }
+
+    fn git(dir: &std::path::Path, args: &[&str]) {
+        let status = Command::new("git").current_dir(dir).args(args).output().unwrap().status;
+        assert!(status.success(), "git {:?} failed", args);
+    }
+
+    /// A service on a fresh database, and a repo on `main` with one commit and a `feature` branch
+    async fn service_with_repo(temp_dir: &tempfile::TempDir) -> (GitBranchService, std::path::PathBuf) {
+        let db_path = temp_dir.path().join("postgirl.db");
+        let service = GitBranchService::with_database(DatabaseService::new(db_path.to_str().unwrap()).await.unwrap())
+            .await
+            .unwrap();
+
+        let repo = temp_dir.path().join("repo");
+        std::fs::create_dir(&repo).unwrap();
+        git(&repo, &["init", "-b", "main"]);
+        git(&repo, &["config", "user.name", "Test"]);
+        git(&repo, &["config", "user.email", "test@example.com"]);
+        std::fs::write(repo.join("api.json"), "one").unwrap();
+        git(&repo, &["add", "."]);
+        git(&repo, &["commit", "-m", "initial"]);
+        git(&repo, &["branch", "feature"]);
+        (service, repo)
+    }
+
+    #[tokio::test]
+    async fn test_switch_branch() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let (service, repo) = service_with_repo(&temp_dir).await;
+        let path = repo.to_str().unwrap();
+
+        service.switch_branch(path, "feature").unwrap();
+        assert_eq!(service.get_current_branch(path).unwrap(), "feature");
+        std::fs::write(repo.join("api.json"), "two").unwrap();
+        git(&repo, &["commit", "-am", "change"]);
+
+        service.switch_branch(path, "main").unwrap();
+        assert_eq!(service.get_current_branch(path).unwrap(), "main");
+        assert_eq!(std::fs::read_to_string(repo.join("api.json")).unwrap(), "one");
+
+        // A local edit that checkout would clobber blocks the switch
+        std::fs::write(repo.join("api.json"), "local edit").unwrap();
+        let error = service.switch_branch(path, "feature").unwrap_err().to_string();
+        assert!(error.contains("uncommitted changes to api.json"), "{}", error);
+        assert_eq!(service.get_current_branch(path).unwrap(), "main");
+
+        assert!(service.switch_branch(path, "missing").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_delete_branch() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let (service, repo) = service_with_repo(&temp_dir).await;
+        let path = repo.to_str().unwrap();
+        let history_count = |service: &GitBranchService| {
+            let pool = service.db.get_pool();
+            async move {
+                sqlx::query("SELECT COUNT(*) AS count FROM branch_history")
+                    .fetch_one(&pool)
+                    .await
+                    .unwrap()
+                    .get::<i64, _>("count")
+            }
+        };
+
+        // Merged into main: deleted along with its history
+        let pattern = service.suggest_pattern("api", None);
+        service.save_branch_creation("feature", &pattern).await.unwrap();
+        let result = service.delete_branch(path, "feature", false).await.unwrap();
+        assert!(result.deleted);
+        assert!(!service.branch_exists(path, "feature").unwrap());
+        assert_eq!(history_count(&service).await, 0);
+
+        let result = service.delete_branch(path, "main", true).await.unwrap();
+        assert!(!result.deleted);
+        assert_eq!(result.refusal, Some(BranchDeleteRefusal::CurrentBranch));
+
+        git(&repo, &["checkout", "-b", "experiment"]);
+        std::fs::write(repo.join("api.json"), "two").unwrap();
+        git(&repo, &["commit", "-am", "unmerged work"]);
+        git(&repo, &["checkout", "main"]);
+        let result = service.delete_branch(path, "experiment", false).await.unwrap();
+        assert_eq!(result.refusal, Some(BranchDeleteRefusal::Unmerged));
+        assert!(service.branch_exists(path, "experiment").unwrap());
+
+        assert!(service.delete_branch(path, "experiment", true).await.unwrap().deleted);
+        let result = service.delete_branch(path, "experiment", false).await.unwrap();
+        assert_eq!(result.refusal, Some(BranchDeleteRefusal::NotFound));
+    }
+
+    #[tokio::test]
+    async fn test_rename_branch() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let (service, repo) = service_with_repo(&temp_dir).await;
+        let path = repo.to_str().unwrap();
+
+        let pattern = service.suggest_pattern("api", None);
+        service.save_branch_creation("feature", &pattern).await.unwrap();
+        service.rename_branch(path, "feature", "feature-login").await.unwrap();
+        assert!(!service.branch_exists(path, "feature").unwrap());
+        assert!(service.branch_exists(path, "feature-login").unwrap());
+        let history = service.get_branch_history(None).await.unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].0, "feature-login");
+
+        // The current branch can be renamed too
+        service.rename_branch(path, "main", "trunk").await.unwrap();
+        assert_eq!(service.get_current_branch(path).unwrap(), "trunk");
+    }
+
+    #[tokio::test]
+    async fn test_rename_branch_refusals() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let (service, repo) = service_with_repo(&temp_dir).await;
+        let path = repo.to_str().unwrap();
+
+        let error = service.rename_branch(path, "feature", "main").await.unwrap_err().to_string();
+        assert!(error.contains("already exists"), "{}", error);
+        let error = service.rename_branch(path, "feature", "bad..name").await.unwrap_err().to_string();
+        assert!(error.contains("Invalid branch name"), "{}", error);
+        assert!(service.rename_branch(path, "missing", "other").await.is_err());
+
+        assert!(service.branch_exists(path, "feature").unwrap());
+        assert!(!service.branch_exists(path, "bad..name").unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_branch_config_survives_restart() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let db_path = temp_dir.path().join("postgirl.db");
+        let db_path = db_path.to_str().unwrap();
+
+        let mut service = GitBranchService::with_database(DatabaseService::new(db_path).await.unwrap()).await.unwrap();
+        assert_eq!(service.get_branch_config().branch_prefix_pattern, BranchConfig::default().branch_prefix_pattern);
+
+        let config = BranchConfig {
+            branch_prefix_pattern: "{username}/{feature}".to_string(),
+            allowed_feature_types: vec![FeatureType::Feature, FeatureType::Hotfix],
+            ..BranchConfig::default()
+        };
+        service.update_config(config).await.unwrap();
+
+        let reloaded = GitBranchService::with_database(DatabaseService::new(db_path).await.unwrap()).await.unwrap();
+        let config = reloaded.get_branch_config();
+        assert_eq!(config.branch_prefix_pattern, "{username}/{feature}");
+        assert_eq!(config.allowed_feature_types.len(), 2);
+        assert!(matches!(config.allowed_feature_types[1], FeatureType::Hotfix));
+    }
+}
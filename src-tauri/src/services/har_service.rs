@@ -0,0 +1,277 @@
+use crate::models::har::*;
+use crate::models::http::{HttpMethod, HttpRequest, HttpResponse, RequestBody, ResponseBody};
+use anyhow::{anyhow, Result};
+use chrono::{Duration, SecondsFormat};
+use std::collections::HashMap;
+
+const HAR_VERSION: &str = "1.2";
+
+#[derive(Debug, Clone, Default)]
+pub struct HarService;
+
+impl HarService {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// A HAR log with one entry per request and the response it got. URLs and headers are
+    /// exported as written, so `{{VAR}}` placeholders are kept.
+    pub fn export(&self, exchanges: &[(HttpRequest, HttpResponse)]) -> Har {
+        Har {
+            log: HarLog {
+                version: HAR_VERSION.to_string(),
+                creator: HarCreator {
+                    name: "Postgirl".to_string(),
+                    version: env!("CARGO_PKG_VERSION").to_string(),
+                },
+                entries: exchanges.iter().map(|(request, response)| har_entry(request, response)).collect(),
+            },
+        }
+    }
+
+    /// The requests recorded in a HAR document, in order
+    pub fn import(&self, har_json: &str) -> Result<Vec<HttpRequest>> {
+        let har: Har = serde_json::from_str(har_json).map_err(|e| anyhow!("Invalid HAR file: {}", e))?;
+        Ok(har.log.entries.iter().map(|entry| http_request(&entry.request)).collect())
+    }
+}
+
+fn har_entry(request: &HttpRequest, response: &HttpResponse) -> HarEntry {
+    let timing = &response.timing;
+    let ms = |value: Option<u64>| value.map_or(-1.0, |ms| ms as f64);
+    // The timestamp is taken once the body has been read
+    let started = response.timestamp - Duration::milliseconds(timing.total_time_ms as i64);
+
+    HarEntry {
+        started_date_time: started.to_rfc3339_opts(SecondsFormat::Millis, true),
+        time: timing.total_time_ms as f64,
+        request: har_request(request),
+        response: har_response(response),
+        cache: serde_json::json!({}),
+        timings: HarTimings {
+            blocked: -1.0,
+            dns: ms(timing.dns_lookup_ms),
+            connect: ms(timing.tcp_connect_ms),
+            ssl: ms(timing.tls_handshake_ms),
+            send: 0.0,
+            wait: ms(timing.first_byte_ms),
+            receive: ms(timing.download_ms),
+        },
+    }
+}
+
+fn har_request(request: &HttpRequest) -> HarRequest {
+    let query_string = url::Url::parse(&request.url)
+        .map(|url| url.query_pairs().map(|(name, value)| name_value(&name, &value)).collect())
+        .unwrap_or_default();
+    let post_data = request.body.as_ref().and_then(post_data);
+
+    HarRequest {
+        method: request.method.as_str().to_string(),
+        url: request.url.clone(),
+        http_version: "HTTP/1.1".to_string(),
+        cookies: Vec::new(),
+        headers: sorted_headers(&request.headers),
+        query_string,
+        body_size: post_data.as_ref().map_or(0, |data| data.text.len() as i64),
+        post_data,
+        headers_size: -1,
+    }
+}
+
+fn post_data(body: &RequestBody) -> Option<HarPostData> {
+    let text_only = |mime_type: &str, text: String| HarPostData { mime_type: mime_type.to_string(), text, params: Vec::new() };
+    let params = |fields: &HashMap<String, String>| {
+        let mut params: Vec<HarParam> = fields.iter()
+            .map(|(name, value)| HarParam { name: name.clone(), value: Some(value.clone()), file_name: None, content_type: None })
+            .collect();
+        params.sort_by(|a, b| a.name.cmp(&b.name));
+        params
+    };
+
+    match body {
+        RequestBody::None => None,
+        RequestBody::Raw { content, content_type } => Some(text_only(content_type, content.clone())),
+        RequestBody::Json { data } => Some(text_only("application/json", data.to_string())),
+        RequestBody::GraphQl { query, variables } => {
+            Some(text_only("application/json", RequestBody::graphql_payload(query, variables).to_string()))
+        }
+        RequestBody::Binary { data, content_type } => Some(text_only(content_type, String::from_utf8_lossy(data).to_string())),
+        RequestBody::FormUrlEncoded { fields } => {
+            let params = params(fields);
+            let text = url::form_urlencoded::Serializer::new(String::new())
+                .extend_pairs(params.iter().map(|param| (&param.name, param.value.as_deref().unwrap_or_default())))
+                .finish();
+            Some(HarPostData { mime_type: "application/x-www-form-urlencoded".to_string(), text, params })
+        }
+        RequestBody::FormData { fields, files } => {
+            let mut params = params(fields);
+            params.extend(files.iter().map(|file| HarParam {
+                name: file.field.clone(),
+                value: None,
+                file_name: Some(file.filename.clone().unwrap_or_else(|| file.path.clone())),
+                content_type: file.content_type.clone(),
+            }));
+            Some(HarPostData { mime_type: "multipart/form-data".to_string(), text: String::new(), params })
+        }
+    }
+}
+
+fn har_response(response: &HttpResponse) -> HarResponse {
+    let mime_type = response.headers.iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case("content-type"))
+        .map(|(_, value)| value.clone())
+        .unwrap_or_default();
+    let (text, encoding) = match &response.body {
+        ResponseBody::Text { content, .. } => (Some(content.clone()), None),
+        ResponseBody::Json { data } => (Some(data.to_string()), None),
+        ResponseBody::Binary { data_base64, .. } => (Some(data_base64.clone()), Some("base64".to_string())),
+        ResponseBody::Empty => (None, None),
+    };
+
+    HarResponse {
+        status: response.status,
+        status_text: response.status_text.clone(),
+        http_version: "HTTP/1.1".to_string(),
+        cookies: Vec::new(),
+        headers: sorted_headers(&response.headers),
+        content: HarContent { size: response.full_size as i64, mime_type, text, encoding },
+        redirect_url: response.headers.iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case("location"))
+            .map(|(_, value)| value.clone())
+            .unwrap_or_default(),
+        headers_size: -1,
+        body_size: response.size.transfer_bytes as i64,
+    }
+}
+
+fn http_request(har: &HarRequest) -> HttpRequest {
+    // HTTP/2 captures list pseudo-headers such as :authority alongside the real ones
+    let headers: HashMap<String, String> = har.headers.iter()
+        .filter(|header| !header.name.starts_with(':'))
+        .map(|header| (header.name.clone(), header.value.clone()))
+        .collect();
+
+    HttpRequest {
+        method: HttpMethod::from(har.method.as_str()),
+        url: har.url.clone(),
+        headers,
+        body: har.post_data.as_ref().map(request_body),
+        ..HttpRequest::default()
+    }
+}
+
+fn request_body(post_data: &HarPostData) -> RequestBody {
+    let mime_type = post_data.mime_type.to_lowercase();
+    let fields = || -> HashMap<String, String> {
+        post_data.params.iter()
+            .filter(|param| param.file_name.is_none())
+            .map(|param| (param.name.clone(), param.value.clone().unwrap_or_default()))
+            .collect()
+    };
+
+    if mime_type.contains("json") {
+        if let Ok(data) = serde_json::from_str(&post_data.text) {
+            return RequestBody::Json { data };
+        }
+    }
+    if mime_type.starts_with("application/x-www-form-urlencoded") {
+        let fields = if post_data.params.is_empty() {
+            url::form_urlencoded::parse(post_data.text.as_bytes()).into_owned().collect()
+        } else {
+            fields()
+        };
+        return RequestBody::FormUrlEncoded { fields };
+    }
+    // Uploaded files aren't in the capture, so only the text fields come across
+    if mime_type.starts_with("multipart/form-data") {
+        return RequestBody::FormData { fields: fields(), files: Vec::new() };
+    }
+    RequestBody::Raw { content: post_data.text.clone(), content_type: post_data.mime_type.clone() }
+}
+
+fn sorted_headers(headers: &HashMap<String, String>) -> Vec<HarNameValue> {
+    let mut headers: Vec<HarNameValue> = headers.iter().map(|(name, value)| name_value(name, value)).collect();
+    headers.sort_by(|a, b| a.name.cmp(&b.name));
+    headers
+}
+
+fn name_value(name: &str, value: &str) -> HarNameValue {
+    HarNameValue { name: name.to_string(), value: value.to_string() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::http::{ResponseSize, ResponseTiming, TextSubtype};
+    use chrono::{TimeZone, Utc};
+
+    fn exchange() -> (HttpRequest, HttpResponse) {
+        let request = HttpRequest {
+            method: HttpMethod::Post,
+            url: "https://api.example.com/users?page=2".to_string(),
+            headers: [("Content-Type".to_string(), "application/json".to_string())].into_iter().collect(),
+            body: Some(RequestBody::Json { data: serde_json::json!({"name": "Ann"}) }),
+            ..HttpRequest::default()
+        };
+        let response = HttpResponse {
+            status: 201,
+            status_text: "Created".to_string(),
+            headers: [("content-type".to_string(), "text/plain".to_string())].into_iter().collect(),
+            body: ResponseBody::Text { content: "created".to_string(), content_subtype: TextSubtype::Plain },
+            timing: ResponseTiming {
+                total_time_ms: 250,
+                dns_lookup_ms: None,
+                tcp_connect_ms: None,
+                tls_handshake_ms: None,
+                first_byte_ms: Some(200),
+                download_ms: Some(50),
+                connection_reused: None,
+            },
+            request_id: request.id.clone(),
+            final_url: request.url.clone(),
+            truncated: false,
+            full_size: 7,
+            size: ResponseSize { transfer_bytes: 7, decoded_bytes: 7 },
+            streamed: false,
+            attempts: 1,
+            redirects: Vec::new(),
+            timestamp: Utc.with_ymd_and_hms(2024, 5, 1, 12, 0, 1).unwrap(),
+        };
+        (request, response)
+    }
+
+    #[test]
+    fn test_export_has_required_har_fields() {
+        let har = serde_json::to_value(HarService::new().export(&[exchange()])).unwrap();
+
+        assert_eq!(har["log"]["version"], "1.2");
+        assert_eq!(har["log"]["creator"]["name"], "Postgirl");
+        let entry = &har["log"]["entries"][0];
+        assert_eq!(entry["startedDateTime"], "2024-05-01T12:00:00.750Z");
+        assert_eq!(entry["time"], 250.0);
+        assert_eq!(entry["request"]["method"], "POST");
+        assert_eq!(entry["request"]["queryString"][0]["name"], "page");
+        assert_eq!(entry["request"]["postData"]["text"], r#"{"name":"Ann"}"#);
+        assert_eq!(entry["response"]["status"], 201);
+        assert_eq!(entry["response"]["content"]["text"], "created");
+        assert_eq!(entry["response"]["redirectURL"], "");
+        assert_eq!(entry["timings"]["wait"], 200.0);
+        assert_eq!(entry["timings"]["dns"], -1.0);
+    }
+
+    #[test]
+    fn test_import_reads_requests_back() {
+        let service = HarService::new();
+        let har_json = serde_json::to_string(&service.export(&[exchange()])).unwrap();
+
+        let requests = service.import(&har_json).unwrap();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].method, HttpMethod::Post);
+        assert_eq!(requests[0].url, "https://api.example.com/users?page=2");
+        assert_eq!(requests[0].body, Some(RequestBody::Json { data: serde_json::json!({"name": "Ann"}) }));
+        assert_eq!(requests[0].headers.get("Content-Type").map(String::as_str), Some("application/json"));
+
+        assert!(service.import("{\"entries\": []}").is_err());
+    }
+}
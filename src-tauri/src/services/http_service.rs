@@ -1,83 +1,1016 @@
+use crate::models::environment::Environment;
 use crate::models::http::*;
+use crate::services::body_decoder::{BodyDecoder, ACCEPT_ENCODING};
+use crate::services::cookie_jar_service::CookieJarService;
+use crate::services::file_sync_service::expand_tilde_path;
+use crate::services::redaction::mask_secret;
+use crate::services::sse_parser::SseParser;
+use crate::services::variable_substitution;
 use anyhow::{anyhow, Result};
-use reqwest::{Client, Method, RequestBuilder};
-use std::collections::HashMap;
+use base64::prelude::*;
+use hyper_util::client::legacy::connect::HttpInfo;
+use reqwest::header::{HeaderMap, HeaderValue};
+use reqwest::{multipart, redirect, Certificate, Client, ClientBuilder, Identity, Method, RequestBuilder};
+use std::collections::{HashMap, HashSet};
+use std::net::SocketAddr;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::fmt;
 use std::time::{Duration, Instant};
 use chrono::Utc;
+use rand::Rng;
+use tokio::io::AsyncWriteExt;
+use tokio::sync::oneshot;
+
+/// A response body grew past `ExecuteOptions::max_response_bytes`
+#[derive(Debug)]
+pub struct ResponseTooLarge {
+    pub limit: u64,
+}
+
+impl fmt::Display for ResponseTooLarge {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Response body exceeded the {} byte limit; save it to a file instead", self.limit)
+    }
+}
+
+impl std::error::Error for ResponseTooLarge {}
+
+/// A client certificate or CA bundle from `ExecuteOptions::tls` could not be loaded
+#[derive(Debug)]
+pub struct TlsConfigError {
+    pub message: String,
+}
+
+impl fmt::Display for TlsConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "TLS configuration error: {}", self.message)
+    }
+}
+
+impl std::error::Error for TlsConfigError {}
+
+/// The request was stopped by `cancel_request` before it completed
+#[derive(Debug)]
+pub struct RequestCancelled;
+
+impl fmt::Display for RequestCancelled {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Request was cancelled")
+    }
+}
+
+impl std::error::Error for RequestCancelled {}
+
+/// Sending a request or reading its response failed, classified from the reqwest error
+#[derive(Debug)]
+pub struct RequestFailed {
+    pub error_type: HttpErrorType,
+    pub message: String,
+}
+
+impl fmt::Display for RequestFailed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Request failed: {}", self.message)
+    }
+}
+
+impl std::error::Error for RequestFailed {}
+
+impl From<reqwest::Error> for RequestFailed {
+    fn from(error: reqwest::Error) -> Self {
+        Self { error_type: classify_error(&error), message: error.to_string() }
+    }
+}
+
+/// reqwest reports TLS failures as connect errors, so those are told apart by their causes
+fn classify_error(error: &reqwest::Error) -> HttpErrorType {
+    let causes = || std::iter::successors(std::error::Error::source(error), |cause| cause.source());
+    if error.is_timeout() {
+        HttpErrorType::TimeoutError
+    } else if error.is_builder() && causes().any(|cause| cause.is::<url::ParseError>()) {
+        HttpErrorType::InvalidUrl
+    } else if error.is_builder() {
+        HttpErrorType::InvalidRequest
+    } else if causes().any(|cause| {
+        let cause = cause.to_string().to_lowercase();
+        ["certificate", "tls", "ssl", "handshake"].iter().any(|term| cause.contains(term))
+    }) {
+        HttpErrorType::SslError
+    } else if error.is_connect() || error.is_request() {
+        HttpErrorType::NetworkError
+    } else if error.is_body() || error.is_decode() {
+        HttpErrorType::InvalidResponse
+    } else {
+        HttpErrorType::UnknownError
+    }
+}
+
+/// An `oauth2_client_credentials` auth config with variables substituted. Debug output leaves
+/// out the client secret so it can't end up in logs.
+#[derive(Clone)]
+struct ClientCredentialsGrant {
+    token_url: String,
+    client_id: String,
+    client_secret: String,
+    scopes: Vec<String>,
+}
+
+impl fmt::Debug for ClientCredentialsGrant {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ClientCredentialsGrant")
+            .field("token_url", &self.token_url)
+            .field("client_id", &self.client_id)
+            .field("client_secret", &"***")
+            .field("scopes", &self.scopes)
+            .finish()
+    }
+}
+
+impl ClientCredentialsGrant {
+    /// Scopes may be a list or a space separated string. None when the token URL or client ID
+    /// is missing.
+    fn from_config(config: &Option<serde_json::Value>, variables: &Option<HashMap<String, String>>) -> Option<Self> {
+        let config = config.as_ref()?;
+        let field = |key: &str| {
+            config
+                .get(key)
+                .and_then(|v| v.as_str())
+                .map(|v| HttpService::substitute_variables(v, variables).trim().to_string())
+                .filter(|v| !v.is_empty())
+        };
+        let scopes = match config.get("scopes") {
+            Some(serde_json::Value::Array(scopes)) => scopes
+                .iter()
+                .filter_map(|scope| scope.as_str())
+                .map(|scope| HttpService::substitute_variables(scope, variables))
+                .collect(),
+            Some(serde_json::Value::String(scopes)) => HttpService::substitute_variables(scopes, variables)
+                .split_whitespace()
+                .map(str::to_string)
+                .collect(),
+            _ => Vec::new(),
+        };
+
+        Some(Self {
+            token_url: field("token_url")?,
+            client_id: field("client_id")?,
+            client_secret: field("client_secret").unwrap_or_default(),
+            scopes,
+        })
+    }
+}
+
+struct CachedToken {
+    access_token: String,
+    expires_at: Option<Instant>, // None when the token endpoint gave no expires_in
+}
+
+/// A response whose headers have arrived
+struct Sent {
+    response: reqwest::Response,
+    start_time: Instant,
+    first_byte_ms: u64,
+    attempts: u32, // sends made, including retries
+    redirects: Vec<RedirectHop>, // followed by the last attempt
+}
 
 #[derive(Clone)]
 pub struct HttpService {
     client: Client,
+    // reqwest bakes the redirect policy into the client, so keep a second one for
+    // requests that opt out of following redirects
+    no_redirect_client: Client,
+    // Full payloads of binary and truncated responses, most recent last
+    retained_bodies: RetainedBodies,
+    // Minimum spacing between sends, keyed by workspace id
+    send_throttles: SendThrottles,
+    // Clients built for a proxy, TLS settings or connect timeout, keyed by those and redirect policy
+    custom_clients: CustomClients,
+    // Cancel signals for requests being executed, keyed by request id
+    in_flight: InFlight,
+    // Stop signals for event streams being delivered as events, keyed by request id
+    event_streams: InFlight,
+    // OAuth2 access tokens, keyed by token URL, client ID and scopes
+    oauth_tokens: OAuthTokens,
+    // Session cookies, kept per workspace, including those set by redirect responses
+    cookie_jars: CookieJarService,
+    // Local and remote addresses of connections responses have arrived on
+    seen_connections: SeenConnections,
+}
+
+type RetainedBodies = Arc<Mutex<Vec<(String, Vec<u8>)>>>;
+type SendThrottles = Arc<Mutex<HashMap<String, SendThrottle>>>;
+type InFlight = Arc<Mutex<HashMap<String, oneshot::Sender<()>>>>;
+type EventSink<'a> = &'a (dyn Fn(SseEvent) + Send + Sync);
+type OAuthTokens = Arc<Mutex<HashMap<(String, String, Vec<String>), CachedToken>>>;
+type CustomClients = Arc<Mutex<HashMap<ClientKey, Client>>>;
+type ClientKey = (Option<ProxyConfig>, Option<TlsConfig>, Option<Duration>, bool);
+type SeenConnections = Arc<Mutex<HashSet<(SocketAddr, SocketAddr)>>>;
+
+struct SendThrottle {
+    interval: Duration,
+    next_slot: Option<Instant>,
+}
+
+const MAX_RETAINED_BODIES: usize = 5;
+const MAX_REDIRECTS: usize = 10;
+// Forget seen connections past this many; idle pooled connections are long closed by then
+const MAX_TRACKED_CONNECTIONS: usize = 1024;
+// Used by GET connection tests that don't give a timeout
+const CONNECTION_TEST_GET_TIMEOUT: Duration = Duration::from_secs(5);
+// The origin CORS preflights claim when none is given, that of a typical local dev server
+pub const DEFAULT_CORS_ORIGIN: &str = "http://localhost:3000";
+// Methods a browser sends without asking first
+const CORS_SAFELISTED_METHODS: [&str; 3] = ["GET", "HEAD", "POST"];
+
+/// Stored in a response's extensions: whether its connection carried an earlier response
+#[derive(Clone, Copy)]
+struct ConnectionReused(bool);
+
+/// How a response body is presented, going by its content type
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum BodyKind {
+    Json, // parsed when it's valid, otherwise shown as text
+    Text,
+    Binary,
+}
+
+impl BodyKind {
+    fn from_content_type(content_type: &str) -> Self {
+        let mime = content_type.split(';').next().unwrap_or_default().trim().to_lowercase();
+        let (kind, subtype) = mime.split_once('/').unwrap_or((mime.as_str(), ""));
+        match kind {
+            // e.g. application/ld+json, application/vnd.api+json, application/x-json
+            "application" if subtype.ends_with("json") => BodyKind::Json,
+            _ if subtype.ends_with("+json") => BodyKind::Json,
+            // SVG is XML, so it stays readable despite being an image
+            _ if subtype.ends_with("+xml") => BodyKind::Text,
+            "text" => BodyKind::Text,
+            "application" if subtype.starts_with("xml") || subtype == "html" => BodyKind::Text,
+            "image" | "audio" | "video" => BodyKind::Binary,
+            _ => BodyKind::Binary,
+        }
+    }
 }
 
 impl HttpService {
     pub fn new() -> Self {
-        let client = Client::builder()
+        let client = Self::build_client(redirect::Policy::default());
+        let no_redirect_client = Self::build_client(redirect::Policy::none());
+
+        Self {
+            client,
+            no_redirect_client,
+            retained_bodies: Arc::new(Mutex::new(Vec::new())),
+            custom_clients: Arc::new(Mutex::new(HashMap::new())),
+            in_flight: Arc::new(Mutex::new(HashMap::new())),
+            event_streams: Arc::new(Mutex::new(HashMap::new())),
+            send_throttles: Arc::new(Mutex::new(HashMap::new())),
+            oauth_tokens: Arc::new(Mutex::new(HashMap::new())),
+            cookie_jars: CookieJarService::new(),
+            seen_connections: Arc::new(Mutex::new(HashSet::new())),
+        }
+    }
+
+    /// Set the minimum interval between sends for a workspace; 0 disables throttling
+    pub fn set_min_request_interval(&self, workspace_id: &str, interval_ms: u64) {
+        let mut throttles = self.send_throttles.lock().unwrap();
+        if interval_ms == 0 {
+            throttles.remove(workspace_id);
+            return;
+        }
+        throttles
+            .entry(workspace_id.to_string())
+            .and_modify(|t| t.interval = Duration::from_millis(interval_ms))
+            .or_insert(SendThrottle {
+                interval: Duration::from_millis(interval_ms),
+                next_slot: None,
+            });
+    }
+
+    /// Reserve the workspace's next send slot and wait for it
+    async fn wait_for_send_slot(&self, workspace_id: &str) {
+        let slot = {
+            let mut throttles = self.send_throttles.lock().unwrap();
+            let Some(throttle) = throttles.get_mut(workspace_id) else {
+                return;
+            };
+            let now = Instant::now();
+            let slot = throttle.next_slot.map_or(now, |next| next.max(now));
+            throttle.next_slot = Some(slot + throttle.interval);
+            slot
+        };
+        tokio::time::sleep_until(slot.into()).await;
+    }
+
+    fn build_client(policy: redirect::Policy) -> Client {
+        Self::client_builder(policy)
+            .build()
+            .expect("Failed to create HTTP client")
+    }
+
+    fn client_builder(policy: redirect::Policy) -> ClientBuilder {
+        // Bodies are decompressed by BodyDecoder rather than reqwest so the transfer size stays
+        // visible. A request's own Accept-Encoding header replaces this default.
+        let mut default_headers = HeaderMap::new();
+        default_headers.insert(reqwest::header::ACCEPT_ENCODING, HeaderValue::from_static(ACCEPT_ENCODING));
+        Client::builder()
             .timeout(Duration::from_secs(60)) // Default 60s timeout
             .user_agent("Postgirl/0.1.0")
+            .default_headers(default_headers)
+            .redirect(policy)
+    }
+
+    /// The client for a redirect policy, proxy, TLS settings and connect timeout. These are
+    /// fixed when a client is built, so one client is kept per configuration.
+    pub(crate) fn client_for(
+        &self,
+        follow_redirects: bool,
+        proxy: Option<&ProxyConfig>,
+        tls: Option<&TlsConfig>,
+        connect_timeout: Option<Duration>,
+    ) -> Result<Client> {
+        let proxy = proxy.filter(|proxy| !proxy.url.trim().is_empty());
+        let tls = tls.filter(|tls| **tls != TlsConfig::default());
+        if proxy.is_none() && tls.is_none() && connect_timeout.is_none() {
+            return Ok(if follow_redirects { self.client.clone() } else { self.no_redirect_client.clone() });
+        }
+
+        let mut clients = self.custom_clients.lock().map_err(|e| anyhow!("Lock error: {}", e))?;
+        let key = (proxy.cloned(), tls.cloned(), connect_timeout, follow_redirects);
+        if let Some(client) = clients.get(&key) {
+            return Ok(client.clone());
+        }
+
+        let policy = if follow_redirects { redirect::Policy::default() } else { redirect::Policy::none() };
+        let mut builder = Self::client_builder(policy);
+        if let Some(proxy) = proxy {
+            let mut reqwest_proxy = reqwest::Proxy::all(proxy.url.trim())
+                .map_err(|e| anyhow!("Invalid proxy URL '{}': {}", proxy.url, e))?;
+            if let Some(username) = proxy.username.as_deref().filter(|u| !u.is_empty()) {
+                reqwest_proxy = reqwest_proxy.basic_auth(username, proxy.password.as_deref().unwrap_or_default());
+            }
+            if let Some(no_proxy) = proxy.no_proxy.as_deref() {
+                reqwest_proxy = reqwest_proxy.no_proxy(reqwest::NoProxy::from_string(no_proxy));
+            }
+            builder = builder.proxy(reqwest_proxy);
+        }
+        if let Some(tls) = tls {
+            builder = Self::apply_tls(builder, tls)?;
+        }
+        if let Some(connect_timeout) = connect_timeout {
+            builder = builder.connect_timeout(connect_timeout);
+        }
+
+        let client = builder
             .build()
-            .expect("Failed to create HTTP client");
+            .map_err(|e| TlsConfigError { message: format!("Failed to create HTTP client: {}", e) })?;
+        clients.insert(key, client.clone());
+        Ok(client)
+    }
+
+    /// Attach the client identity and extra trusted CAs. A PEM certificate needs a separate
+    /// PKCS#8 key file; anything else is read as a PKCS#12 bundle.
+    fn apply_tls(mut builder: ClientBuilder, tls: &TlsConfig) -> Result<ClientBuilder, TlsConfigError> {
+        let read = |kind: &str, path: &str| {
+            std::fs::read(expand_tilde_path(path.trim()))
+                .map_err(|e| TlsConfigError { message: format!("Failed to read {} '{}': {}", kind, path, e) })
+        };
+        let non_empty = |path: &Option<String>| path.clone().filter(|path| !path.trim().is_empty());
 
-        Self { client }
+        if let Some(cert_path) = non_empty(&tls.client_cert_path) {
+            let cert = read("client certificate", &cert_path)?;
+            let identity = match non_empty(&tls.client_key_path) {
+                Some(key_path) => Identity::from_pkcs8_pem(&cert, &read("client key", &key_path)?),
+                None => Identity::from_pkcs12_der(&cert, tls.client_cert_password.as_deref().unwrap_or_default()),
+            }
+            .map_err(|e| TlsConfigError { message: format!("Failed to load client certificate '{}': {}", cert_path, e) })?;
+            builder = builder.identity(identity);
+        }
+
+        if let Some(ca_path) = non_empty(&tls.ca_bundle_path) {
+            let bundle = read("CA bundle", &ca_path)?;
+            let certificates = Certificate::from_pem_bundle(&bundle)
+                .ok()
+                .filter(|certificates| !certificates.is_empty())
+                .ok_or_else(|| TlsConfigError { message: format!("No PEM certificates found in CA bundle '{}'", ca_path) })?;
+            for certificate in certificates {
+                builder = builder.add_root_certificate(certificate);
+            }
+        }
+
+        Ok(builder.danger_accept_invalid_certs(tls.accept_invalid_certs))
     }
 
+    /// Execute a request. It can be stopped with `cancel_request` using its id, in which case
+    /// this fails with `RequestCancelled`.
     pub async fn execute_request(
         &self,
         request: HttpRequest,
         environment_variables: Option<HashMap<String, String>>,
+        options: &ExecuteOptions,
     ) -> Result<HttpResponse> {
-        let start_time = Instant::now();
-        
+        self.run(request, environment_variables, options, None).await
+    }
+
+    /// Like `execute_request`, except an event stream response is read until the server ends it
+    /// or `stop_event_stream` is called, with each event passed to `on_event` as it arrives.
+    /// The returned response holds the raw text received, up to the preview limit.
+    pub async fn execute_request_with_events<F>(
+        &self,
+        request: HttpRequest,
+        environment_variables: Option<HashMap<String, String>>,
+        options: &ExecuteOptions,
+        on_event: F,
+    ) -> Result<HttpResponse>
+    where
+        F: Fn(SseEvent) + Send + Sync,
+    {
+        self.run(request, environment_variables, options, Some(&on_event)).await
+    }
+
+    /// Stop reading an event stream; its request completes with the events received so far.
+    /// Returns false when no stream with that request id is open.
+    pub fn stop_event_stream(&self, request_id: &str) -> bool {
+        let stop_tx = self.event_streams.lock().ok().and_then(|mut streams| streams.remove(request_id));
+        stop_tx.is_some_and(|stop_tx| stop_tx.send(()).is_ok())
+    }
+
+    async fn run(
+        &self,
+        request: HttpRequest,
+        environment_variables: Option<HashMap<String, String>>,
+        options: &ExecuteOptions,
+        on_event: Option<EventSink<'_>>,
+    ) -> Result<HttpResponse> {
+        let (cancel_tx, mut cancel_rx) = oneshot::channel();
+        if let Ok(mut in_flight) = self.in_flight.lock() {
+            in_flight.insert(request.id.clone(), cancel_tx);
+        }
+
+        // A dropped sender means a newer send replaced this one in the registry, not a cancel
+        let result = tokio::select! {
+            result = self.execute(request, environment_variables, options, on_event) => result,
+            Ok(()) = &mut cancel_rx => Err(RequestCancelled.into()),
+        };
+
+        drop(cancel_rx);
+        if let Ok(mut in_flight) = self.in_flight.lock() {
+            in_flight.retain(|_, cancel_tx| !cancel_tx.is_closed());
+        }
+        result
+    }
+
+    /// Stop an in-flight request. Returns false when no request with that id is running.
+    pub fn cancel_request(&self, request_id: &str) -> bool {
+        let cancel_tx = self.in_flight.lock().ok().and_then(|mut in_flight| in_flight.remove(request_id));
+        cancel_tx.is_some_and(|cancel_tx| cancel_tx.send(()).is_ok())
+    }
+
+    async fn execute(
+        &self,
+        request: HttpRequest,
+        environment_variables: Option<HashMap<String, String>>,
+        options: &ExecuteOptions,
+        on_event: Option<EventSink<'_>>,
+    ) -> Result<HttpResponse> {
+        let Sent { response, start_time, first_byte_ms, attempts, redirects } =
+            self.send(&request, &environment_variables, options).await?;
+
+        // Event streams may never end, so they're read incrementally rather than buffered whole
+        let is_event_stream = response.headers()
+            .get("content-type")
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|ct| ct.to_lowercase().starts_with("text/event-stream"));
+        let mut response = if is_event_stream && on_event.is_some() {
+            self.process_event_stream(response, request.id, start_time, first_byte_ms, options, on_event).await?
+        } else if request.raw_stream || is_event_stream {
+            self.process_stream(response, request.id, start_time, first_byte_ms, options).await?
+        } else {
+            self.process_response(response, request.id, start_time, first_byte_ms, options).await?
+        };
+        response.attempts = attempts;
+        response.redirects = redirects;
+        Ok(response)
+    }
+
+    /// Send a request and stream its body straight to a file, with no size limit.
+    /// Returns the number of bytes written.
+    pub async fn save_response_to_file(
+        &self,
+        request: HttpRequest,
+        environment_variables: Option<HashMap<String, String>>,
+        options: &ExecuteOptions,
+        path: &str,
+    ) -> Result<u64> {
+        let mut response = self.send(&request, &environment_variables, options).await?.response;
+        let mut decoder = BodyDecoder::new(Self::content_encoding(&response));
+
+        let mut file = tokio::fs::File::create(path).await
+            .map_err(|e| anyhow!("Failed to create {}: {}", path, e))?;
+        while let Some(chunk) = response.chunk().await.map_err(RequestFailed::from)? {
+            file.write_all(&decoder.feed(&chunk)?).await?;
+        }
+        file.write_all(&decoder.finish()?).await?;
+        file.flush().await?;
+        Ok(decoder.decoded_bytes() as u64)
+    }
+
+    /// Build and send a request, retrying per `options.retry`, and return once the final
+    /// response's headers have arrived. Timing covers the last attempt only.
+    async fn send(
+        &self,
+        request: &HttpRequest,
+        environment_variables: &Option<HashMap<String, String>>,
+        options: &ExecuteOptions,
+    ) -> Result<Sent> {
+        let max_attempts = options.retry.as_ref().map_or(1, |retry| retry.max_attempts.max(1));
+        let access_token = self.access_token(request, environment_variables, options).await?;
+        let mut attempts = 0;
+        loop {
+            // Throttled sends wait before timing starts so the delay isn't reported as latency
+            if let Some(workspace_id) = &options.workspace_id {
+                self.wait_for_send_slot(workspace_id).await;
+            }
+
+            let start_time = Instant::now();
+            let mut redirects = Vec::new();
+            let outcome = self
+                .send_following_redirects(request, environment_variables, options, access_token.as_deref(), &mut redirects)
+                .await?;
+            attempts += 1;
+
+            let delay = options.retry.as_ref()
+                .filter(|_| attempts < max_attempts)
+                .and_then(|retry| Self::retry_delay(retry, attempts, &outcome));
+            if let Some(delay) = delay {
+                tokio::time::sleep(delay).await;
+                continue;
+            }
+
+            // send() resolves once the response headers arrive
+            let response = outcome.map_err(RequestFailed::from)?;
+            let first_byte_ms = start_time.elapsed().as_millis() as u64;
+            return Ok(Sent { response, start_time, first_byte_ms, attempts, redirects });
+        }
+    }
+
+    /// Send one attempt. Redirects are followed here rather than by reqwest so each hop can be
+    /// recorded and its cookies stored. The outer error is for requests that can't be built or
+    /// redirect too often; send failures are returned inside for the retry policy to judge.
+    async fn send_following_redirects(
+        &self,
+        request: &HttpRequest,
+        environment_variables: &Option<HashMap<String, String>>,
+        options: &ExecuteOptions,
+        mut access_token: Option<&str>,
+        redirects: &mut Vec<RedirectHop>,
+    ) -> Result<reqwest::Result<reqwest::Response>> {
+        let mut hop = request.clone();
+        loop {
+            let mut response = match self.build_request(&hop, environment_variables, options, access_token)?.send().await {
+                Ok(response) => response,
+                Err(e) => return Ok(Err(e)),
+            };
+            self.record_connection(&mut response);
+
+            if let Some(workspace_id) = &options.workspace_id {
+                let set_cookies: Vec<String> = response.headers()
+                    .get_all(reqwest::header::SET_COOKIE)
+                    .iter()
+                    .filter_map(|v| v.to_str().ok().map(|s| s.to_string()))
+                    .collect();
+                self.cookie_jars.store(workspace_id, response.url(), &set_cookies);
+            }
+
+            let status = response.status().as_u16();
+            let location = response.headers()
+                .get(reqwest::header::LOCATION)
+                .and_then(|v| v.to_str().ok())
+                .filter(|_| request.follow_redirects && matches!(status, 301 | 302 | 303 | 307 | 308));
+            let Some(location) = location else {
+                return Ok(Ok(response));
+            };
+            if redirects.len() >= MAX_REDIRECTS {
+                return Err(anyhow!("Request failed: stopped after {} redirects", MAX_REDIRECTS));
+            }
+            let next_url = response.url()
+                .join(location)
+                .map_err(|e| anyhow!("Invalid redirect location '{}': {}", location, e))?;
+            redirects.push(RedirectHop {
+                status,
+                url: response.url().to_string(),
+                location: location.to_string(),
+            });
+
+            // Like browsers, 303 and a POST answered with 301/302 continue as a body-less GET
+            if (status == 303 && hop.method != HttpMethod::Head)
+                || (matches!(status, 301 | 302) && hop.method == HttpMethod::Post)
+            {
+                hop.method = HttpMethod::Get;
+                hop.body = None;
+                hop.headers.retain(|name, _| !name.to_lowercase().starts_with("content-"));
+            }
+            // Credentials stay with the origin they were meant for
+            if next_url.origin() != response.url().origin() {
+                hop.auth_type = None;
+                hop.headers.retain(|name, _| {
+                    !["authorization", "cookie", "proxy-authorization"].contains(&name.to_lowercase().as_str())
+                });
+                access_token = None;
+            }
+            hop.url = next_url.to_string();
+        }
+    }
+
+    /// How long to wait before resending, or None when the outcome isn't retryable.
+    /// A Retry-After header on 429 and 503 responses replaces the computed backoff.
+    fn retry_delay(
+        retry: &RetryPolicy,
+        attempt: u32,
+        outcome: &reqwest::Result<reqwest::Response>,
+    ) -> Option<Duration> {
+        let max_backoff = Duration::from_millis(retry.max_backoff_ms);
+        match outcome {
+            Ok(response) => {
+                let status = response.status().as_u16();
+                if !retry.retry_on_status.contains(&status) {
+                    return None;
+                }
+                if matches!(status, 429 | 503) {
+                    if let Some(retry_after) = Self::retry_after(response) {
+                        return Some(retry_after.min(max_backoff));
+                    }
+                }
+            }
+            Err(e) if retry.retry_on_network_error && (e.is_connect() || e.is_timeout()) => {}
+            Err(_) => return None,
+        }
+
+        // Exponential backoff with jitter in the upper half so concurrent clients spread out
+        let backoff = retry.initial_backoff_ms
+            .saturating_mul(1u64 << (attempt - 1).min(32))
+            .min(retry.max_backoff_ms);
+        Some(Duration::from_millis(rand::thread_rng().gen_range(backoff / 2..=backoff)))
+    }
+
+    fn retry_after(response: &reqwest::Response) -> Option<Duration> {
+        parse_retry_after(response.headers().get(reqwest::header::RETRY_AFTER)?.to_str().ok()?)
+    }
+
+    /// The access token for an `oauth2_client_credentials` request, reusing a cached one until
+    /// it expires unless `options.force_token_refresh` is set. Other auth types need no token.
+    async fn access_token(
+        &self,
+        request: &HttpRequest,
+        environment_variables: &Option<HashMap<String, String>>,
+        options: &ExecuteOptions,
+    ) -> Result<Option<String>> {
+        if request.auth_type.as_deref() != Some("oauth2_client_credentials") {
+            return Ok(None);
+        }
+        let grant = ClientCredentialsGrant::from_config(&request.auth_config, environment_variables)
+            .ok_or_else(|| anyhow!("OAuth2 client credentials auth requires a token URL and client ID"))?;
+        let key = (grant.token_url.clone(), grant.client_id.clone(), grant.scopes.clone());
+
+        if !options.force_token_refresh {
+            let tokens = self.oauth_tokens.lock().map_err(|e| anyhow!("Lock error: {}", e))?;
+            let cached = tokens
+                .get(&key)
+                .filter(|token| token.expires_at.is_none_or(|expires_at| Instant::now() < expires_at));
+            if let Some(token) = cached {
+                return Ok(Some(token.access_token.clone()));
+            }
+        }
+
+        let token = self.fetch_token(&grant, options).await?;
+        let access_token = token.access_token.clone();
+        self.oauth_tokens
+            .lock()
+            .map_err(|e| anyhow!("Lock error: {}", e))?
+            .insert(key, token);
+        Ok(Some(access_token))
+    }
+
+    /// Request a token from the grant's token endpoint, authenticating the client with HTTP Basic
+    async fn fetch_token(&self, grant: &ClientCredentialsGrant, options: &ExecuteOptions) -> Result<CachedToken> {
+        let mut form = vec![("grant_type", "client_credentials".to_string())];
+        if !grant.scopes.is_empty() {
+            form.push(("scope", grant.scopes.join(" ")));
+        }
+
+        let requested_at = Instant::now();
+        let response = self
+            .client_for(true, options.proxy.as_ref(), options.tls.as_ref(), Self::connect_timeout(None, options))?
+            .post(&grant.token_url)
+            .basic_auth(&grant.client_id, Some(&grant.client_secret))
+            .form(&form)
+            .send()
+            .await
+            .map_err(|e| anyhow!("Token request to {} failed: {}", grant.token_url, e))?;
+
+        let status = response.status();
+        let body: serde_json::Value = if status.is_success() {
+            response
+                .json()
+                .await
+                .map_err(|e| anyhow!("Token endpoint {} returned invalid JSON: {}", grant.token_url, e))?
+        } else {
+            let detail = response.text().await.unwrap_or_default();
+            return Err(anyhow!("Token endpoint {} returned {}: {}", grant.token_url, status, detail.trim()));
+        };
+
+        let access_token = body
+            .get("access_token")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow!("Token endpoint {} returned no access_token", grant.token_url))?
+            .to_string();
+        // Some servers send expires_in as a string
+        let expires_in = body.get("expires_in").and_then(|v| {
+            v.as_u64().or_else(|| v.as_str().and_then(|s| s.trim().parse().ok()))
+        });
+        Ok(CachedToken {
+            access_token,
+            expires_at: expires_in.map(|seconds| requested_at + Duration::from_secs(seconds)),
+        })
+    }
+
+    /// The request's own connect timeout, else the one in `options`; 0 means no separate limit
+    fn connect_timeout(request: Option<&HttpRequest>, options: &ExecuteOptions) -> Option<Duration> {
+        request.and_then(|request| request.connect_timeout_ms)
+            .or(options.connect_timeout_ms)
+            .filter(|ms| *ms > 0)
+            .map(Duration::from_millis)
+    }
+
+    /// Build a request with variables, cookies, auth and body applied
+    fn build_request(
+        &self,
+        request: &HttpRequest,
+        environment_variables: &Option<HashMap<String, String>>,
+        options: &ExecuteOptions,
+        access_token: Option<&str>,
+    ) -> Result<RequestBuilder> {
         // Substitute environment variables in URL
-        let url = self.substitute_variables(&request.url, &environment_variables);
+        let url = Self::substitute_variables(&request.url, environment_variables);
         
         // Convert HttpMethod to reqwest::Method
         let method = self.convert_method(&request.method)?;
         
-        // Create the request builder
-        let mut req_builder = self.client.request(method, &url);
+        // Redirects are followed by send_following_redirects, never by the client
+        let connect_timeout = Self::connect_timeout(Some(request), options);
+        let client = self.client_for(false, options.proxy.as_ref(), options.tls.as_ref(), connect_timeout)?;
+        let mut req_builder = client.request(method, &url);
         
-        // Add headers with variable substitution
-        for (key, value) in &request.headers {
-            let substituted_value = self.substitute_variables(value, &environment_variables);
+        // Cookies from the workspace's jar are merged into any explicit Cookie header
+        let mut jar_cookies = options.workspace_id.as_ref().and_then(|workspace_id| {
+            url::Url::parse(&url).ok()
+                .and_then(|parsed| self.cookie_jars.cookie_header(workspace_id, &parsed))
+        });
+
+        // Add headers with variable substitution, after any defaults the request doesn't override
+        let defaults = options.default_headers.iter()
+            .filter(|(key, _)| !request.headers.keys().any(|own| own.eq_ignore_ascii_case(key)));
+        for (key, value) in defaults.chain(&request.headers) {
+            let mut substituted_value = Self::substitute_variables(value, environment_variables);
+            if key.eq_ignore_ascii_case("cookie") {
+                if let Some(jar) = jar_cookies.take() {
+                    substituted_value = format!("{}; {}", substituted_value, jar);
+                }
+            }
             req_builder = req_builder.header(key, substituted_value);
         }
+        if let Some(jar) = jar_cookies {
+            req_builder = req_builder.header("Cookie", jar);
+        }
+
+        // Apply stored auth with variable substitution
+        if let Some(placement) = Self::resolve_auth(&request.auth_type, &request.auth_config, environment_variables) {
+            req_builder = match placement {
+                AuthPlacement::Header { name, value } => req_builder.header(name, value),
+                AuthPlacement::Query { name, value } => req_builder.query(&[(name, value)]),
+            };
+        }
+        if let Some(access_token) = access_token {
+            req_builder = req_builder.bearer_auth(access_token);
+        }
         
         // Add request body if present
-        req_builder = self.add_request_body(req_builder, &request.body, &environment_variables)?;
+        req_builder = self.add_request_body(req_builder, &request.body, environment_variables)?;
         
         // Set timeout if specified
         if let Some(timeout_ms) = request.timeout_ms {
             req_builder = req_builder.timeout(Duration::from_millis(timeout_ms));
         }
         
-        // Execute the request
-        let response = req_builder.send().await.map_err(|e| {
-            anyhow!("Request failed: {}", e)
-        })?;
-        
-        let end_time = Instant::now();
-        let total_time_ms = end_time.duration_since(start_time).as_millis() as u64;
-        
-        // Process response
-        self.process_response(response, request.id, total_time_ms).await
+        Ok(req_builder)
+    }
+
+    /// Cookies currently held for a workspace
+    pub fn get_cookies(&self, workspace_id: &str) -> Vec<CookieInfo> {
+        self.cookie_jars.list(workspace_id)
+    }
+
+    pub fn clear_cookies(&self, workspace_id: &str) {
+        self.cookie_jars.clear(workspace_id);
+    }
+
+    /// Write the complete payload of a truncated response to disk
+    pub async fn save_response_body(&self, request_id: &str, path: &str) -> Result<usize> {
+        let body = self.response_body_bytes(request_id)?;
+        tokio::fs::write(path, &body).await?;
+        Ok(body.len())
+    }
+
+    /// The complete payload of a recent binary or truncated response
+    pub fn response_body_bytes(&self, request_id: &str) -> Result<Vec<u8>> {
+        let retained = self.retained_bodies.lock().map_err(|e| anyhow!("Lock error: {}", e))?;
+        retained.iter()
+            .rev()
+            .find(|(id, _)| id == request_id)
+            .map(|(_, body)| body.clone())
+            .ok_or_else(|| anyhow!("No retained body for request {}", request_id))
+    }
+
+    fn retain_body(&self, request_id: &str, body: Vec<u8>) {
+        if let Ok(mut retained) = self.retained_bodies.lock() {
+            retained.retain(|(id, _)| id != request_id);
+            retained.push((request_id.to_string(), body));
+            if retained.len() > MAX_RETAINED_BODIES {
+                retained.remove(0);
+            }
+        }
     }
 
     fn substitute_variables(
-        &self,
         text: &str,
         variables: &Option<HashMap<String, String>>,
     ) -> String {
-        if let Some(vars) = variables {
-            let mut result = text.to_string();
-            for (key, value) in vars {
-                let placeholder = format!("{{{{{}}}}}", key);
-                result = result.replace(&placeholder, value);
+        // Dynamic variables like {{$uuid}} resolve even without an environment
+        let none = HashMap::new();
+        variable_substitution::substitute_variables(text, variables.as_ref().unwrap_or(&none))
+    }
+
+    /// Resolve the header or query parameter for a stored auth config.
+    /// Unknown auth types and incomplete configs resolve to `None` rather than erroring.
+    pub fn resolve_auth(
+        auth_type: &Option<String>,
+        auth_config: &Option<serde_json::Value>,
+        variables: &Option<HashMap<String, String>>,
+    ) -> Option<AuthPlacement> {
+        Self::resolve_auth_with(auth_type, auth_config, |value| Self::substitute_variables(value, variables))
+    }
+
+    /// Like `resolve_auth`, passing each config value through `substitute` instead of the
+    /// variables; exports use this to keep `{{VAR}}` placeholders as written.
+    pub fn resolve_auth_with(
+        auth_type: &Option<String>,
+        auth_config: &Option<serde_json::Value>,
+        substitute: impl Fn(&str) -> String,
+    ) -> Option<AuthPlacement> {
+        let auth_type = auth_type.as_deref()?;
+        let config = auth_config.as_ref()?;
+        let field = |key: &str| config.get(key).and_then(|v| v.as_str()).map(&substitute);
+
+        match auth_type {
+            "bearer" => {
+                let token = field("token")?;
+                Some(AuthPlacement::Header {
+                    name: "Authorization".to_string(),
+                    value: format!("Bearer {}", token),
+                })
+            },
+            "basic" => {
+                let username = field("username")?;
+                let password = field("password").unwrap_or_default();
+                let credentials = BASE64_STANDARD.encode(format!("{}:{}", username, password));
+                Some(AuthPlacement::Header {
+                    name: "Authorization".to_string(),
+                    value: format!("Basic {}", credentials),
+                })
+            },
+            "api_key" => {
+                let name = field("key")?;
+                let value = field("value").unwrap_or_default();
+                match field("location").as_deref() {
+                    Some("query") => Some(AuthPlacement::Query { name, value }),
+                    _ => Some(AuthPlacement::Header { name, value }),
+                }
+            },
+            _ => None,
+        }
+    }
+
+    /// The auth header a request would be sent with, its credential masked for display.
+    /// Query parameter placements add no header and resolve to `None`.
+    pub fn preview_auth_header(
+        auth_type: &Option<String>,
+        auth_config: &Option<serde_json::Value>,
+        variables: &Option<HashMap<String, String>>,
+    ) -> Option<(String, String)> {
+        let AuthPlacement::Header { name, value } = Self::resolve_auth(auth_type, auth_config, variables)? else {
+            return None;
+        };
+
+        // Keep the scheme readable and mask only the credential after it
+        let masked = match value.split_once(' ') {
+            Some((scheme, credential)) if scheme == "Bearer" || scheme == "Basic" => {
+                format!("{} {}", scheme, mask_secret(credential))
             }
-            result
-        } else {
-            text.to_string()
+            _ => mask_secret(&value),
+        };
+        Some((name, masked))
+    }
+
+    /// Check a request for problems that would make it fail or send the wrong credentials.
+    /// URLs containing `{{VAR}}` placeholders are only checked once substituted.
+    pub fn validate_request(request: &HttpRequest) -> Vec<ValidationIssue> {
+        let mut issues = Vec::new();
+
+        if request.url.trim().is_empty() {
+            issues.push(ValidationIssue::new("url", "URL is required"));
+        } else if !request.url.contains("{{") {
+            match url::Url::parse(&request.url) {
+                Ok(url) if url.scheme() == "http" || url.scheme() == "https" => {}
+                Ok(url) => issues.push(ValidationIssue::new("url", &format!("Unsupported URL scheme '{}'", url.scheme()))),
+                Err(e) => issues.push(ValidationIssue::new("url", &format!("Invalid URL: {}", e))),
+            }
+        }
+
+        let Some(auth_type) = request.auth_type.as_deref().filter(|t| !t.is_empty() && *t != "none") else {
+            return issues;
+        };
+        let field = |key: &str| {
+            request
+                .auth_config
+                .as_ref()
+                .and_then(|config| config.get(key))
+                .and_then(|v| v.as_str())
+                .filter(|v| !v.trim().is_empty())
+        };
+        let mut require = |key: &str, label: &str| {
+            if field(key).is_none() {
+                issues.push(ValidationIssue::new(
+                    &format!("authConfig.{}", key),
+                    &format!("{} auth requires a {}", auth_type, label),
+                ));
+            }
+        };
+
+        match auth_type {
+            "bearer" => require("token", "token"),
+            "basic" => require("username", "username"),
+            "api_key" => {
+                require("key", "key name");
+                require("location", "location");
+            }
+            "oauth2" => {
+                require("token_url", "token URL");
+                require("client_id", "client ID");
+            }
+            "oauth2_client_credentials" => {
+                require("token_url", "token URL");
+                require("client_id", "client ID");
+                require("client_secret", "client secret");
+            }
+            other => issues.push(ValidationIssue::new("authType", &format!("Unknown auth type '{}'", other))),
         }
+
+        if let Some(location) = field("location").filter(|_| auth_type == "api_key") {
+            if location != "header" && location != "query" {
+                issues.push(ValidationIssue::new(
+                    "authConfig.location",
+                    "api_key location must be 'header' or 'query'",
+                ));
+            }
+        }
+
+        issues
+    }
+
+    /// A multipart part that streams a file from disk rather than reading it into memory
+    fn file_part(file: &FormFile, environment_variables: &Option<HashMap<String, String>>) -> Result<multipart::Part> {
+        let path = expand_tilde_path(Self::substitute_variables(&file.path, environment_variables).trim());
+        let opened = std::fs::File::open(&path)
+            .map_err(|e| anyhow!("Failed to open form file {}: {}", path, e))?;
+        let len = opened.metadata()?.len();
+
+        let filename = file.filename.clone().or_else(|| {
+            Path::new(&path).file_name().map(|name| name.to_string_lossy().to_string())
+        });
+        let content_type = match &file.content_type {
+            Some(content_type) => content_type.clone(),
+            None => mime_guess::from_path(&path).first_or_octet_stream().to_string(),
+        };
+
+        let mut part = multipart::Part::stream_with_length(tokio::fs::File::from_std(opened), len)
+            .mime_str(&content_type)
+            .map_err(|e| anyhow!("Invalid content type for form file {}: {}", file.field, e))?;
+        if let Some(filename) = filename {
+            part = part.file_name(filename);
+        }
+        Ok(part)
     }
 
     fn convert_method(&self, method: &HttpMethod) -> Result<Method> {
@@ -89,6 +1022,8 @@ impl HttpService {
             HttpMethod::Patch => Ok(Method::PATCH),
             HttpMethod::Head => Ok(Method::HEAD),
             HttpMethod::Options => Ok(Method::OPTIONS),
+            HttpMethod::Custom(name) => Method::from_bytes(name.as_bytes())
+                .map_err(|_| anyhow!("Invalid HTTP method '{}'", name)),
         }
     }
 
@@ -102,7 +1037,7 @@ impl HttpService {
             match body {
                 RequestBody::None => {},
                 RequestBody::Raw { content, content_type } => {
-                    let substituted_content = self.substitute_variables(content, environment_variables);
+                    let substituted_content = Self::substitute_variables(content, environment_variables);
                     req_builder = req_builder
                         .header("Content-Type", content_type)
                         .body(substituted_content);
@@ -110,22 +1045,25 @@ impl HttpService {
                 RequestBody::Json { data } => {
                     // For JSON, we need to substitute variables in the serialized string
                     let json_str = serde_json::to_string(data)?;
-                    let substituted_json = self.substitute_variables(&json_str, environment_variables);
+                    let substituted_json = Self::substitute_variables(&json_str, environment_variables);
                     let substituted_data: serde_json::Value = serde_json::from_str(&substituted_json)?;
                     req_builder = req_builder.json(&substituted_data);
                 },
-                RequestBody::FormData { fields } => {
-                    let mut form = reqwest::multipart::Form::new();
+                RequestBody::FormData { fields, files } => {
+                    let mut form = multipart::Form::new();
                     for (key, value) in fields {
-                        let substituted_value = self.substitute_variables(value, environment_variables);
+                        let substituted_value = Self::substitute_variables(value, environment_variables);
                         form = form.text(key.clone(), substituted_value);
                     }
+                    for file in files {
+                        form = form.part(file.field.clone(), Self::file_part(file, environment_variables)?);
+                    }
                     req_builder = req_builder.multipart(form);
                 },
                 RequestBody::FormUrlEncoded { fields } => {
                     let mut params = Vec::new();
                     for (key, value) in fields {
-                        let substituted_value = self.substitute_variables(value, environment_variables);
+                        let substituted_value = Self::substitute_variables(value, environment_variables);
                         params.push((key.clone(), substituted_value));
                     }
                     req_builder = req_builder.form(&params);
@@ -135,6 +1073,15 @@ impl HttpService {
                         .header("Content-Type", content_type)
                         .body(data.clone());
                 },
+                RequestBody::GraphQl { query, variables } => {
+                    let substituted_query = Self::substitute_variables(query, environment_variables);
+                    let variables_str = serde_json::to_string(variables)?;
+                    let substituted_variables: serde_json::Value = serde_json::from_str(
+                        &Self::substitute_variables(&variables_str, environment_variables),
+                    )?;
+                    // .json() also sets Content-Type: application/json
+                    req_builder = req_builder.json(&RequestBody::graphql_payload(&substituted_query, &substituted_variables));
+                },
             }
         }
         Ok(req_builder)
@@ -142,14 +1089,18 @@ impl HttpService {
 
     async fn process_response(
         &self,
-        response: reqwest::Response,
+        mut response: reqwest::Response,
         request_id: String,
-        total_time_ms: u64,
+        start_time: Instant,
+        first_byte_ms: u64,
+        options: &ExecuteOptions,
     ) -> Result<HttpResponse> {
         let status = response.status().as_u16();
+        let final_url = response.url().to_string();
         let status_text = response.status().canonical_reason()
             .unwrap_or("Unknown")
             .to_string();
+        let connection_reused = Self::connection_reused(&response);
 
         // Extract headers
         let mut headers = HashMap::new();
@@ -166,46 +1117,53 @@ impl HttpService {
             .unwrap_or("text/plain")
             .to_lowercase();
 
+        let download_start = Instant::now();
+        let (bytes, size) = Self::read_body(&mut response, options.max_response_bytes).await?;
+        let download_ms = download_start.elapsed().as_millis() as u64;
+        let total_time_ms = start_time.elapsed().as_millis() as u64;
+        let full_size = bytes.len();
+
+        // Hand back only a preview of oversized bodies, keeping the full payload for saving
+        let (bytes, truncated) = match options.preview_bytes {
+            Some(limit) if full_size > limit => {
+                self.retain_body(&request_id, bytes.clone());
+                (bytes[..limit].to_vec(), true)
+            }
+            _ => (bytes, false),
+        };
+
         // Process response body
-        let body = if content_type.contains("application/json") {
-            let text = response.text().await?;
-            if text.is_empty() {
-                ResponseBody::Empty
-            } else {
+        let kind = BodyKind::from_content_type(&content_type);
+        let body = if bytes.is_empty() {
+            ResponseBody::Empty
+        } else if kind == BodyKind::Binary {
+            // Kept whole so it can be fetched again without going through the response
+            if !truncated {
+                self.retain_body(&request_id, bytes.clone());
+            }
+            ResponseBody::binary(&bytes, &content_type)
+        } else {
+            let text = String::from_utf8_lossy(&bytes).to_string();
+            let content_subtype = TextSubtype::from_content_type(&content_type);
+            // A truncated JSON document won't parse, so previews stay as text
+            if kind == BodyKind::Json && !truncated {
                 match serde_json::from_str::<serde_json::Value>(&text) {
                     Ok(json) => ResponseBody::Json { data: json },
-                    Err(_) => ResponseBody::Text { content: text },
+                    Err(_) => ResponseBody::Text { content: text, content_subtype },
                 }
-            }
-        } else if content_type.starts_with("text/") 
-            || content_type.contains("application/xml")
-            || content_type.contains("application/html") {
-            let text = response.text().await?;
-            if text.is_empty() {
-                ResponseBody::Empty
             } else {
-                ResponseBody::Text { content: text }
-            }
-        } else {
-            let bytes = response.bytes().await?;
-            if bytes.is_empty() {
-                ResponseBody::Empty
-            } else {
-                let size = bytes.len();
-                ResponseBody::Binary { 
-                    data: bytes.to_vec(), 
-                    size 
-                }
+                ResponseBody::Text { content: text, content_subtype }
             }
         };
 
         let timing = ResponseTiming {
             total_time_ms,
-            dns_lookup_ms: None, // reqwest doesn't provide detailed timing
+            dns_lookup_ms: None, // reqwest doesn't expose connection-level timing
             tcp_connect_ms: None,
             tls_handshake_ms: None,
-            first_byte_ms: None,
-            download_ms: None,
+            first_byte_ms: Some(first_byte_ms),
+            download_ms: Some(download_ms),
+            connection_reused,
         };
 
         Ok(HttpResponse {
@@ -215,10 +1173,208 @@ impl HttpService {
             body,
             timing,
             request_id,
+            final_url,
+            truncated,
+            full_size,
+            size,
+            streamed: false,
+            attempts: 1,
+            redirects: Vec::new(),
             timestamp: Utc::now(),
         })
     }
 
+    /// Read a body chunk by chunk and return it as raw text. Reading stops at the preview
+    /// limit, or when the request times out with part of the stream received; either way the
+    /// response is marked truncated.
+    async fn process_stream(
+        &self,
+        response: reqwest::Response,
+        request_id: String,
+        start_time: Instant,
+        first_byte_ms: u64,
+        options: &ExecuteOptions,
+    ) -> Result<HttpResponse> {
+        self.process_event_stream(response, request_id, start_time, first_byte_ms, options, None).await
+    }
+
+    /// With `on_event`, parsed events are delivered as they arrive and reading continues past
+    /// the preview limit until the stream ends, times out, or is stopped. Only the text within
+    /// the preview limit is kept for the returned response.
+    async fn process_event_stream(
+        &self,
+        mut response: reqwest::Response,
+        request_id: String,
+        start_time: Instant,
+        first_byte_ms: u64,
+        options: &ExecuteOptions,
+        on_event: Option<EventSink<'_>>,
+    ) -> Result<HttpResponse> {
+        let status = response.status().as_u16();
+        let final_url = response.url().to_string();
+        let status_text = response.status().canonical_reason()
+            .unwrap_or("Unknown")
+            .to_string();
+        let headers: HashMap<String, String> = response.headers().iter()
+            .filter_map(|(name, value)| value.to_str().ok().map(|v| (name.to_string(), v.to_string())))
+            .collect();
+        let connection_reused = Self::connection_reused(&response);
+
+        let (stop_tx, mut stop_rx) = oneshot::channel();
+        if on_event.is_some() {
+            if let Ok(mut streams) = self.event_streams.lock() {
+                streams.insert(request_id.clone(), stop_tx);
+            }
+        }
+        let mut parser = SseParser::new();
+        let mut decoder = BodyDecoder::new(Self::content_encoding(&response));
+
+        let download_start = Instant::now();
+        let mut bytes = Vec::new();
+        let mut truncated = false;
+        let result = loop {
+            let chunk = tokio::select! {
+                chunk = response.chunk() => chunk,
+                Ok(()) = &mut stop_rx => break Ok(()),
+            };
+            let chunk = match chunk {
+                Ok(Some(chunk)) => decoder.feed(&chunk).map(Some),
+                Ok(None) => decoder.finish().map(|rest| Some(rest).filter(|rest| !rest.is_empty())),
+                Err(e) => Err(e.into()),
+            };
+            match chunk {
+                Ok(Some(chunk)) => {
+                    let received = decoder.decoded_bytes();
+                    if let Some(limit) = options.max_response_bytes.filter(|limit| received as u64 > *limit) {
+                        break Err(ResponseTooLarge { limit }.into());
+                    }
+                    if let Some(on_event) = on_event {
+                        for event in parser.feed(&chunk) {
+                            on_event(SseEvent {
+                                request_id: request_id.clone(),
+                                event: event.event,
+                                data: event.data,
+                                id: event.id,
+                            });
+                        }
+                    }
+
+                    let keep = options.preview_bytes.map_or(chunk.len(), |limit| limit.saturating_sub(bytes.len()).min(chunk.len()));
+                    bytes.extend_from_slice(&chunk[..keep]);
+                    if options.preview_bytes.is_some_and(|limit| received >= limit) {
+                        truncated = true;
+                        if on_event.is_none() {
+                            break Ok(());
+                        }
+                    }
+                }
+                Ok(None) => break Ok(()),
+                Err(e) if e.downcast_ref::<reqwest::Error>().is_some_and(|e| e.is_timeout()) && decoder.transfer_bytes() > 0 => {
+                    truncated = true;
+                    break Ok(());
+                }
+                Err(e) => break Err(match e.downcast::<reqwest::Error>() {
+                    Ok(e) => RequestFailed::from(e).into(),
+                    Err(e) => anyhow!("Request failed: {}", e),
+                }),
+            }
+        };
+
+        drop(stop_rx);
+        if let Ok(mut streams) = self.event_streams.lock() {
+            streams.retain(|_, stop_tx| !stop_tx.is_closed());
+        }
+        result?;
+
+        let body = if bytes.is_empty() {
+            ResponseBody::Empty
+        } else {
+            ResponseBody::Text {
+                content: String::from_utf8_lossy(&bytes).to_string(),
+                content_subtype: headers.get("content-type").map_or(TextSubtype::Plain, |v| TextSubtype::from_content_type(v)),
+            }
+        };
+
+        Ok(HttpResponse {
+            status,
+            status_text,
+            headers,
+            body,
+            timing: ResponseTiming {
+                total_time_ms: start_time.elapsed().as_millis() as u64,
+                dns_lookup_ms: None,
+                tcp_connect_ms: None,
+                tls_handshake_ms: None,
+                first_byte_ms: Some(first_byte_ms),
+                download_ms: Some(download_start.elapsed().as_millis() as u64),
+                connection_reused,
+            },
+            request_id,
+            final_url,
+            truncated,
+            full_size: decoder.decoded_bytes(),
+            size: ResponseSize {
+                transfer_bytes: decoder.transfer_bytes(),
+                decoded_bytes: decoder.decoded_bytes(),
+            },
+            streamed: true,
+            attempts: 1,
+            redirects: Vec::new(),
+            timestamp: Utc::now(),
+        })
+    }
+
+    /// Read and decode a whole body chunk by chunk, failing as soon as the decoded payload
+    /// grows past `limit`
+    async fn read_body(response: &mut reqwest::Response, limit: Option<u64>) -> Result<(Vec<u8>, ResponseSize)> {
+        if let Some(limit) = limit.filter(|limit| response.content_length().is_some_and(|len| len > *limit)) {
+            return Err(ResponseTooLarge { limit }.into());
+        }
+
+        let mut decoder = BodyDecoder::new(Self::content_encoding(response));
+        let mut bytes = Vec::new();
+        while let Some(chunk) = response.chunk().await.map_err(RequestFailed::from)? {
+            bytes.extend(decoder.feed(&chunk)?);
+            if let Some(limit) = limit.filter(|limit| bytes.len() as u64 > *limit) {
+                return Err(ResponseTooLarge { limit }.into());
+            }
+        }
+        bytes.extend(decoder.finish()?);
+        if let Some(limit) = limit.filter(|limit| bytes.len() as u64 > *limit) {
+            return Err(ResponseTooLarge { limit }.into());
+        }
+
+        let size = ResponseSize { transfer_bytes: decoder.transfer_bytes(), decoded_bytes: bytes.len() };
+        Ok((bytes, size))
+    }
+
+    /// Mark whether a response arrived on a connection an earlier response used. reqwest
+    /// doesn't report pooling, but a pooled connection keeps its local address, so a repeated
+    /// local and remote address pair means the connection was reused.
+    fn record_connection(&self, response: &mut reqwest::Response) {
+        let Some(info) = response.extensions().get::<HttpInfo>() else {
+            return;
+        };
+        let connection = (info.local_addr(), info.remote_addr());
+        // A poisoned lock leaves reuse unknown rather than failing the request
+        if let Ok(mut seen) = self.seen_connections.lock() {
+            if seen.len() >= MAX_TRACKED_CONNECTIONS && !seen.contains(&connection) {
+                seen.clear();
+            }
+            let reused = !seen.insert(connection);
+            drop(seen);
+            response.extensions_mut().insert(ConnectionReused(reused));
+        }
+    }
+
+    fn connection_reused(response: &reqwest::Response) -> Option<bool> {
+        response.extensions().get::<ConnectionReused>().map(|reused| reused.0)
+    }
+
+    fn content_encoding(response: &reqwest::Response) -> Option<&str> {
+        response.headers().get(reqwest::header::CONTENT_ENCODING).and_then(|v| v.to_str().ok())
+    }
+
     pub fn create_error(
         &self,
         error_type: HttpErrorType,
@@ -233,15 +1389,109 @@ impl HttpService {
         }
     }
 
-    pub async fn test_connection(&self, url: &str) -> Result<bool> {
-        match self.client.head(url).send().await {
+    /// Mutating requests against a protected environment must be confirmed before sending
+    pub fn requires_confirmation(method: &HttpMethod, environment: Option<&Environment>) -> bool {
+        environment.is_some_and(|env| env.is_protected) && method.is_mutating()
+    }
+
+    /// Whether the server at `url` answers at all, whatever the status. HEAD is used unless
+    /// `options.use_get` is set; GET only waits for the response headers.
+    pub async fn test_connection(&self, url: &str, options: &ConnectionTestOptions) -> Result<bool> {
+        let mut request = if options.use_get { self.client.get(url) } else { self.client.head(url) };
+        let timeout = options.timeout_ms.map(Duration::from_millis)
+            .or(options.use_get.then_some(CONNECTION_TEST_GET_TIMEOUT));
+        if let Some(timeout) = timeout {
+            request = request.timeout(timeout);
+        }
+        match request.send().await {
             Ok(_) => Ok(true),
             Err(_) => Ok(false),
         }
     }
 
+    /// Send the OPTIONS preflight a browser would make before `method` with `request_headers`
+    /// from `origin`, and judge the answer the way the browser would. Preflights never follow
+    /// redirects, so a redirect counts against the request. The preflight goes out through
+    /// the options' proxy and TLS settings, like the request it checks.
+    pub async fn inspect_cors(
+        &self,
+        url: &str,
+        method: &str,
+        request_headers: &[String],
+        origin: &str,
+        options: &ExecuteOptions,
+    ) -> Result<CorsInspection> {
+        let method = method.trim().to_uppercase();
+        let request_headers: Vec<String> = request_headers.iter()
+            .map(|header| header.trim().to_lowercase())
+            .filter(|header| !header.is_empty())
+            .collect();
+
+        let client = self.client_for(false, options.proxy.as_ref(), options.tls.as_ref(), Self::connect_timeout(None, options))?;
+        let mut preflight = client.request(Method::OPTIONS, url)
+            .header("Origin", origin)
+            .header("Access-Control-Request-Method", &method);
+        if !request_headers.is_empty() {
+            preflight = preflight.header("Access-Control-Request-Headers", request_headers.join(","));
+        }
+        let response = preflight.send().await.map_err(RequestFailed::from)?;
+
+        let headers = response.headers();
+        let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok()).map(|v| v.trim().to_string());
+        let list = |name: &str| -> Vec<String> {
+            header(name).map(|value| {
+                value.split(',').map(str::trim).filter(|item| !item.is_empty()).map(str::to_string).collect()
+            }).unwrap_or_default()
+        };
+        let status = response.status().as_u16();
+        let allow_origin = header("access-control-allow-origin");
+        let allow_methods = list("access-control-allow-methods");
+        let allow_headers = list("access-control-allow-headers");
+        let allow_credentials = header("access-control-allow-credentials").as_deref() == Some("true");
+
+        let mut problems = Vec::new();
+        if !response.status().is_success() {
+            problems.push(format!("The preflight was answered with status {} rather than a 2xx", status));
+        }
+        match allow_origin.as_deref() {
+            Some("*") => {}
+            Some(allowed) if allowed == origin => {}
+            Some(allowed) => problems.push(format!("Access-Control-Allow-Origin is '{}', not '{}'", allowed, origin)),
+            None => problems.push("The response has no Access-Control-Allow-Origin header".to_string()),
+        }
+        let method_allowed = CORS_SAFELISTED_METHODS.contains(&method.as_str())
+            || allow_methods.iter().any(|allowed| allowed == "*" || allowed.eq_ignore_ascii_case(&method));
+        if !method_allowed {
+            problems.push(format!("{} is not in Access-Control-Allow-Methods", method));
+        }
+        for requested in &request_headers {
+            // A wildcard never covers Authorization
+            let allowed = allow_headers.iter().any(|allowed| {
+                (allowed == "*" && requested != "authorization") || allowed.eq_ignore_ascii_case(requested)
+            });
+            if !allowed {
+                problems.push(format!("Header '{}' is not in Access-Control-Allow-Headers", requested));
+            }
+        }
+
+        Ok(CorsInspection {
+            origin: origin.to_string(),
+            status,
+            allow_origin,
+            allow_methods,
+            allow_headers,
+            allow_credentials,
+            expose_headers: list("access-control-expose-headers"),
+            max_age: header("access-control-max-age").and_then(|v| v.parse().ok()),
+            allowed: problems.is_empty(),
+            problems,
+        })
+    }
+
+    /// Methods offered in the method picker; any other valid method can still be typed in
     pub fn get_supported_methods(&self) -> Vec<HttpMethod> {
-        vec![
+        let webdav = ["PROPFIND", "PROPPATCH", "MKCOL", "COPY", "MOVE", "LOCK", "UNLOCK"];
+        let mut methods = vec![
             HttpMethod::Get,
             HttpMethod::Post,
             HttpMethod::Put,
@@ -249,7 +1499,9 @@ impl HttpService {
             HttpMethod::Patch,
             HttpMethod::Head,
             HttpMethod::Options,
-        ]
+        ];
+        methods.extend(webdav.iter().map(|m| HttpMethod::Custom(m.to_string())));
+        methods
     }
 }
 
@@ -257,4 +1509,14 @@ impl Default for HttpService {
     fn default() -> Self {
         Self::new()
     }
-}
\ No newline at end of file
+}
+
+/// Retry-After as either delay-seconds or an HTTP date
+pub(crate) fn parse_retry_after(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+    let at = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+    Some((at.with_timezone(&Utc) - Utc::now()).to_std().unwrap_or_default())
+}
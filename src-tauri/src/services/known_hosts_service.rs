@@ -0,0 +1,245 @@
+use anyhow::{anyhow, Result};
+use base64::{engine::general_purpose::STANDARD, engine::general_purpose::STANDARD_NO_PAD, Engine as _};
+use hmac::{Hmac, Mac};
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum HostKeyStatus {
+    Trusted,
+    Mismatch, // host is known but presented a different key of the same type
+    Revoked,
+    Unknown,
+}
+
+#[derive(Debug, Clone)]
+struct KnownHostEntry {
+    revoked: bool,
+    patterns: String, // comma-separated host patterns, or a single |1|salt|hash entry
+    key_type: String,
+    key: Vec<u8>,
+}
+
+/// Host key verification against an OpenSSH known_hosts file
+#[derive(Debug, Clone, Default)]
+pub struct KnownHostsService {
+    entries: Vec<KnownHostEntry>,
+}
+
+impl KnownHostsService {
+    pub fn default_path() -> PathBuf {
+        let home_dir = std::env::var("HOME").unwrap_or_default();
+        Path::new(&home_dir).join(".ssh").join("known_hosts")
+    }
+
+    /// Load a known_hosts file; a missing file simply means no host is known yet
+    pub fn load(path: &Path) -> Result<Self> {
+        match std::fs::read_to_string(path) {
+            Ok(contents) => Ok(Self::parse(&contents)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(anyhow!("Failed to read {}: {}", path.display(), e)),
+        }
+    }
+
+    pub fn parse(contents: &str) -> Self {
+        let entries = contents
+            .lines()
+            .filter_map(|line| {
+                let line = line.trim();
+                if line.is_empty() || line.starts_with('#') {
+                    return None;
+                }
+
+                let mut fields = line.split_whitespace();
+                let mut first = fields.next()?;
+                let mut revoked = false;
+                if first.starts_with('@') {
+                    // @cert-authority lines describe CAs, not host keys
+                    if first != "@revoked" {
+                        return None;
+                    }
+                    revoked = true;
+                    first = fields.next()?;
+                }
+
+                Some(KnownHostEntry {
+                    revoked,
+                    patterns: first.to_string(),
+                    key_type: fields.next()?.to_string(),
+                    key: STANDARD.decode(fields.next()?).ok()?,
+                })
+            })
+            .collect();
+
+        Self { entries }
+    }
+
+    /// Check the key a host presented against the known entries
+    pub fn check(&self, host: &str, port: Option<u16>, key_type: &str, key: &[u8]) -> HostKeyStatus {
+        let matching: Vec<&KnownHostEntry> = self
+            .entries
+            .iter()
+            .filter(|entry| host_matches(&entry.patterns, host, port))
+            .collect();
+
+        if matching.iter().any(|entry| entry.revoked && entry.key == key) {
+            return HostKeyStatus::Revoked;
+        }
+        if matching.iter().any(|entry| !entry.revoked && entry.key_type == key_type && entry.key == key) {
+            return HostKeyStatus::Trusted;
+        }
+        if matching.iter().any(|entry| !entry.revoked && entry.key_type == key_type) {
+            return HostKeyStatus::Mismatch;
+        }
+        HostKeyStatus::Unknown
+    }
+
+    /// Append a trusted host key to a known_hosts file, creating it if necessary
+    pub fn add_host_key(path: &Path, host: &str, port: Option<u16>, key_type: &str, key_base64: &str) -> Result<()> {
+        STANDARD
+            .decode(key_base64)
+            .map_err(|e| anyhow!("Invalid host key: {}", e))?;
+
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let mut file = std::fs::OpenOptions::new().create(true).append(true).open(path)?;
+        writeln!(file, "{} {} {}", host_pattern(host, port), key_type, key_base64)?;
+        Ok(())
+    }
+}
+
+/// OpenSSH-style `SHA256:...` fingerprint of a raw host key
+pub fn fingerprint(key: &[u8]) -> String {
+    format!("SHA256:{}", STANDARD_NO_PAD.encode(Sha256::digest(key)))
+}
+
+fn host_pattern(host: &str, port: Option<u16>) -> String {
+    match port {
+        Some(port) if port != 22 => format!("[{}]:{}", host, port),
+        _ => host.to_string(),
+    }
+}
+
+fn host_matches(patterns: &str, host: &str, port: Option<u16>) -> bool {
+    let candidate = host_pattern(host, port);
+
+    if let Some(hashed) = patterns.strip_prefix("|1|") {
+        return hashed_host_matches(hashed, &candidate);
+    }
+
+    let mut matched = false;
+    for pattern in patterns.split(',') {
+        if let Some(negated) = pattern.strip_prefix('!') {
+            if wildcard_match(negated, &candidate) {
+                return false;
+            }
+        } else if wildcard_match(pattern, &candidate) {
+            matched = true;
+        }
+    }
+    matched
+}
+
+/// Hashed entries store base64(salt)|base64(HMAC-SHA1(salt, host))
+fn hashed_host_matches(hashed: &str, candidate: &str) -> bool {
+    let Some((salt, hash)) = hashed.split_once('|') else {
+        return false;
+    };
+    let (Ok(salt), Ok(hash)) = (STANDARD.decode(salt), STANDARD.decode(hash)) else {
+        return false;
+    };
+    let Ok(mut mac) = Hmac::<Sha1>::new_from_slice(&salt) else {
+        return false;
+    };
+    mac.update(candidate.as_bytes());
+    mac.verify_slice(&hash).is_ok()
+}
+
+/// Match `*` and `?` wildcards, case-insensitively like OpenSSH
+fn wildcard_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.to_lowercase().chars().collect();
+    let text: Vec<char> = text.to_lowercase().chars().collect();
+
+    let (mut p, mut t) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            backtrack = Some((p, t));
+            p += 1;
+        } else if let Some((star, matched)) = backtrack {
+            p = star + 1;
+            t = matched + 1;
+            backtrack = Some((star, matched + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GITHUB_ED25519: &str = "AAAAC3NzaC1lZDI1NTE5AAAAIOMqqnkVzrm0SdG6UOoqKLsabgH5C9okWi0dh2l9GKJl";
+
+    fn hashed_entry(host: &str, salt: &[u8]) -> String {
+        let mut mac = Hmac::<Sha1>::new_from_slice(salt).unwrap();
+        mac.update(host.as_bytes());
+        let hash = mac.finalize().into_bytes();
+        format!("|1|{}|{}", STANDARD.encode(salt), STANDARD.encode(hash))
+    }
+
+    #[test]
+    fn test_known_hosts_parsing_and_matching() {
+        let github_key = STANDARD.decode(GITHUB_ED25519).unwrap();
+        let other_key = b"not-the-real-key".to_vec();
+        let contents = format!(
+            "# comment\n\
+             github.com,140.82.121.4 ssh-ed25519 {github}\n\
+             {hashed} ssh-ed25519 {github}\n\
+             [git.internal]:2222 ssh-ed25519 {other}\n\
+             *.example.com,!evil.example.com ssh-ed25519 {other}\n\
+             @cert-authority *.corp ssh-ed25519 {other}\n",
+            github = GITHUB_ED25519,
+            hashed = hashed_entry("gitlab.com", b"0123456789abcdef0123"),
+            other = STANDARD.encode(&other_key),
+        );
+        let known_hosts = KnownHostsService::parse(&contents);
+
+        // Plain and hashed host entries
+        assert_eq!(known_hosts.check("github.com", None, "ssh-ed25519", &github_key), HostKeyStatus::Trusted);
+        assert_eq!(known_hosts.check("gitlab.com", Some(22), "ssh-ed25519", &github_key), HostKeyStatus::Trusted);
+        // A known host presenting a different key is a mismatch, not unknown
+        assert_eq!(known_hosts.check("github.com", None, "ssh-ed25519", &other_key), HostKeyStatus::Mismatch);
+        // Non-standard ports are matched through the [host]:port form
+        assert_eq!(known_hosts.check("git.internal", Some(2222), "ssh-ed25519", &other_key), HostKeyStatus::Trusted);
+        assert_eq!(known_hosts.check("git.internal", None, "ssh-ed25519", &other_key), HostKeyStatus::Unknown);
+        // Wildcards and negation
+        assert_eq!(known_hosts.check("api.example.com", None, "ssh-ed25519", &other_key), HostKeyStatus::Trusted);
+        assert_eq!(known_hosts.check("evil.example.com", None, "ssh-ed25519", &other_key), HostKeyStatus::Unknown);
+        // CA lines are not host keys
+        assert_eq!(known_hosts.check("build.corp", None, "ssh-ed25519", &other_key), HostKeyStatus::Unknown);
+
+        assert_eq!(fingerprint(&github_key), "SHA256:+DiY3wvvV6TuJJhbpZisF/zLDA0zPMSvHdkr4UvCOqU");
+    }
+
+    #[test]
+    fn test_add_host_key() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join(".ssh").join("known_hosts");
+        let key = STANDARD.decode(GITHUB_ED25519).unwrap();
+
+        KnownHostsService::add_host_key(&path, "git.internal", Some(2222), "ssh-ed25519", GITHUB_ED25519).unwrap();
+
+        let known_hosts = KnownHostsService::load(&path).unwrap();
+        assert_eq!(known_hosts.check("git.internal", Some(2222), "ssh-ed25519", &key), HostKeyStatus::Trusted);
+    }
+}
@@ -0,0 +1,90 @@
+use crate::models::http::{HttpResponse, ResponseBody, TextSubtype};
+use anyhow::{anyhow, Result};
+use sqlx::{Row, SqlitePool};
+
+// Larger bodies are stored as a truncated text preview
+const MAX_STORED_BODY_BYTES: usize = 1024 * 1024;
+
+/// Keeps the most recent response of each request so it can be shown again without re-sending
+pub struct LastResponseService {
+    pool: SqlitePool,
+}
+
+impl LastResponseService {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    /// Store a response as the latest for its request, replacing any previous one
+    pub async fn save(&self, response: &HttpResponse) -> Result<()> {
+        let json = serde_json::to_string(&Self::capped(response))?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO last_responses (request_id, response, updated_at)
+            VALUES (?1, ?2, ?3)
+            ON CONFLICT(request_id) DO UPDATE SET response = excluded.response, updated_at = excluded.updated_at
+            "#
+        )
+        .bind(&response.request_id)
+        .bind(&json)
+        .bind(chrono::Utc::now().to_rfc3339())
+        .execute(&self.pool)
+        .await
+        .map_err(|e| anyhow!("Failed to save last response: {}", e))?;
+
+        Ok(())
+    }
+
+    pub async fn get(&self, request_id: &str) -> Result<Option<HttpResponse>> {
+        let row = sqlx::query("SELECT response FROM last_responses WHERE request_id = ?1")
+            .bind(request_id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| anyhow!("Failed to get last response: {}", e))?;
+
+        row.map(|row| serde_json::from_str(&row.get::<String, _>("response")).map_err(Into::into))
+            .transpose()
+    }
+
+    fn capped(response: &HttpResponse) -> HttpResponse {
+        let body = match &response.body {
+            ResponseBody::Text { content, content_subtype } if content.len() > MAX_STORED_BODY_BYTES => ResponseBody::Text {
+                content: truncate_str(content, MAX_STORED_BODY_BYTES).to_string(),
+                content_subtype: *content_subtype,
+            },
+            ResponseBody::Json { data } => {
+                let content = data.to_string();
+                if content.len() <= MAX_STORED_BODY_BYTES {
+                    return response.clone();
+                }
+                ResponseBody::Text {
+                    content: truncate_str(&content, MAX_STORED_BODY_BYTES).to_string(),
+                    content_subtype: TextSubtype::Plain,
+                }
+            }
+            // Cut at a whole number of base64 groups so what's kept still decodes
+            ResponseBody::Binary { data_base64, size, mime_type, preview_hex } if *size > MAX_STORED_BODY_BYTES => ResponseBody::Binary {
+                data_base64: data_base64[..MAX_STORED_BODY_BYTES / 3 * 4].to_string(),
+                size: *size,
+                mime_type: mime_type.clone(),
+                preview_hex: preview_hex.clone(),
+            },
+            _ => return response.clone(),
+        };
+
+        HttpResponse {
+            body,
+            truncated: true,
+            ..response.clone()
+        }
+    }
+}
+
+fn truncate_str(value: &str, max_bytes: usize) -> &str {
+    let mut end = max_bytes.min(value.len());
+    while !value.is_char_boundary(end) {
+        end -= 1;
+    }
+    &value[..end]
+}
@@ -1,11 +1,32 @@
+pub mod assertion_service;
+pub mod backup_service;
+pub mod body_decoder;
+pub mod code_export_service;
+pub mod collection_run_service;
 pub mod collection_service;
+pub mod cookie_jar_service;
+pub mod curl_import_service;
+pub mod database_service;
+pub mod dotenv;
+pub mod extraction_service;
 pub mod git_service;
 pub mod git_branch_service;
+pub mod har_service;
 pub mod credential_service;
 pub mod environment_service;
 pub mod http_service;
+pub mod known_hosts_service;
+pub mod pre_request_service;
+pub mod redaction;
+pub mod last_response_service;
+pub mod request_history_service;
 pub mod file_sync_service;
-pub mod database_service {
-    pub use super::simple_database_service::*;
-}
-mod simple_database_service;
\ No newline at end of file
+pub mod response_diff_service;
+pub mod response_snapshot_service;
+pub mod run_condition;
+pub mod sse_parser;
+pub mod url_components;
+pub mod variable_substitution;
+pub mod websocket_service;
+pub mod workspace_archive_service;
+pub mod xml_formatter;
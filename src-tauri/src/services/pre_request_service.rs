@@ -0,0 +1,115 @@
+use crate::models::collection::PreRequestStep;
+use crate::services::variable_substitution::substitute_variables;
+use anyhow::{anyhow, Result};
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Default)]
+pub struct PreRequestService;
+
+impl PreRequestService {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Run the steps in order, each seeing the variables set by the ones before it. Fails
+    /// without touching `variables` when an HMAC's secret variable isn't defined.
+    pub fn apply(&self, steps: &[PreRequestStep], variables: &mut HashMap<String, String>) -> Result<()> {
+        let mut updated = variables.clone();
+        for step in steps {
+            let (variable, value) = match step {
+                PreRequestStep::SetVariable { variable, value } => (variable, substitute_variables(value, &updated)),
+                PreRequestStep::Base64Encode { variable, value } => {
+                    (variable, STANDARD.encode(substitute_variables(value, &updated)))
+                }
+                PreRequestStep::HmacSha256 { variable, message, secret_variable } => {
+                    let secret = updated
+                        .get(secret_variable)
+                        .ok_or_else(|| anyhow!("HMAC secret variable '{}' is not defined", secret_variable))?;
+                    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+                        .map_err(|e| anyhow!("Invalid HMAC key: {}", e))?;
+                    mac.update(substitute_variables(message, &updated).as_bytes());
+                    let digest = mac.finalize().into_bytes();
+                    (variable, digest.iter().map(|byte| format!("{:02x}", byte)).collect())
+                }
+            };
+            updated.insert(variable.clone(), value);
+        }
+        *variables = updated;
+        Ok(())
+    }
+}
+
+/// Reject steps that could never run
+pub fn validate_pre_request_step(step: &PreRequestStep) -> Result<()> {
+    let variable = match step {
+        PreRequestStep::SetVariable { variable, .. } | PreRequestStep::Base64Encode { variable, .. } => variable,
+        PreRequestStep::HmacSha256 { variable, secret_variable, .. } => {
+            if secret_variable.trim().is_empty() {
+                return Err(anyhow!("HMAC step is missing its secret variable"));
+            }
+            variable
+        }
+    };
+    if variable.trim().is_empty() {
+        return Err(anyhow!("Pre-request step is missing a variable name"));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn variables(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs.iter().map(|(key, value)| (key.to_string(), value.to_string())).collect()
+    }
+
+    #[test]
+    fn test_base64_encode_step() {
+        let mut vars = variables(&[("user", "alice"), ("password", "pa55")]);
+        let steps = vec![PreRequestStep::Base64Encode {
+            variable: "basic".to_string(),
+            value: "{{user}}:{{password}}".to_string(),
+        }];
+
+        PreRequestService::new().apply(&steps, &mut vars).unwrap();
+        assert_eq!(vars["basic"], "YWxpY2U6cGE1NQ==");
+    }
+
+    #[test]
+    fn test_hmac_sha256_step() {
+        let mut vars = variables(&[("api_secret", "s3cret"), ("path", "/orders")]);
+        let steps = vec![
+            PreRequestStep::SetVariable { variable: "timestamp".to_string(), value: "1700000000".to_string() },
+            PreRequestStep::HmacSha256 {
+                variable: "signature".to_string(),
+                message: "POST\n{{path}}\n{{timestamp}}".to_string(),
+                secret_variable: "api_secret".to_string(),
+            },
+        ];
+
+        PreRequestService::new().apply(&steps, &mut vars).unwrap();
+        assert_eq!(vars["timestamp"], "1700000000");
+        assert_eq!(vars["signature"], "cf5e3285242bbfea592468b994e0e1b481eae5928fdcb546e243e528b60f309d");
+    }
+
+    #[test]
+    fn test_missing_hmac_secret_leaves_variables_alone() {
+        let mut vars = variables(&[("path", "/orders")]);
+        let steps = vec![
+            PreRequestStep::SetVariable { variable: "nonce".to_string(), value: "{{$uuid}}".to_string() },
+            PreRequestStep::HmacSha256 {
+                variable: "signature".to_string(),
+                message: "{{path}}".to_string(),
+                secret_variable: "api_secret".to_string(),
+            },
+        ];
+
+        let error = PreRequestService::new().apply(&steps, &mut vars).unwrap_err();
+        assert_eq!(error.to_string(), "HMAC secret variable 'api_secret' is not defined");
+        assert_eq!(vars, variables(&[("path", "/orders")]));
+    }
+}
@@ -0,0 +1,165 @@
+use crate::models::http::{HttpMethod, HttpResponse, RequestHistoryEntry};
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use sqlx::{Row, SqlitePool};
+
+// Older entries beyond this are dropped as new ones are recorded
+const DEFAULT_MAX_ENTRIES_PER_WORKSPACE: i64 = 1000;
+
+/// A log of executed requests per workspace, like a browser's network tab. Only a summary of
+/// each response is kept, never its body.
+pub struct RequestHistoryService {
+    pool: SqlitePool,
+    max_entries: i64,
+}
+
+impl RequestHistoryService {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self {
+            pool,
+            max_entries: DEFAULT_MAX_ENTRIES_PER_WORKSPACE,
+        }
+    }
+
+    #[cfg(test)]
+    pub fn with_max_entries(mut self, max_entries: i64) -> Self {
+        self.max_entries = max_entries;
+        self
+    }
+
+    /// Record an execution, either its response or the error it failed with, then trim the
+    /// workspace's history to the most recent entries. The URL is stored as written, with
+    /// `{{VAR}}` placeholders, so secret values don't end up in the log.
+    pub async fn record(
+        &self,
+        workspace_id: &str,
+        request_id: &str,
+        method: &HttpMethod,
+        url: &str,
+        result: Result<&HttpResponse, &str>,
+    ) -> Result<()> {
+        let (status, total_time_ms, response_size, error) = match result {
+            Ok(response) => (
+                Some(response.status as i64),
+                Some(response.timing.total_time_ms as i64),
+                Some(response.full_size as i64),
+                None,
+            ),
+            Err(error) => (None, None, None, Some(error)),
+        };
+
+        let mut tx = self.pool.begin().await?;
+        sqlx::query(
+            r#"
+            INSERT INTO request_history (workspace_id, request_id, method, url, status, total_time_ms, response_size, error, executed_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
+            "#
+        )
+        .bind(workspace_id)
+        .bind(request_id)
+        .bind(method.as_str())
+        .bind(url)
+        .bind(status)
+        .bind(total_time_ms)
+        .bind(response_size)
+        .bind(error)
+        .bind(Utc::now().to_rfc3339())
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| anyhow!("Failed to record request history: {}", e))?;
+
+        sqlx::query(
+            r#"
+            DELETE FROM request_history
+            WHERE workspace_id = ?1 AND id NOT IN (
+                SELECT id FROM request_history WHERE workspace_id = ?1 ORDER BY id DESC LIMIT ?2
+            )
+            "#
+        )
+        .bind(workspace_id)
+        .bind(self.max_entries)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| anyhow!("Failed to trim request history: {}", e))?;
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// The workspace's most recent executions, newest first
+    pub async fn list(&self, workspace_id: &str, limit: i64) -> Result<Vec<RequestHistoryEntry>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, workspace_id, request_id, method, url, status, total_time_ms, response_size, error, executed_at
+            FROM request_history
+            WHERE workspace_id = ?1
+            ORDER BY id DESC
+            LIMIT ?2
+            "#
+        )
+        .bind(workspace_id)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| anyhow!("Failed to get request history: {}", e))?;
+
+        rows.into_iter()
+            .map(|row| {
+                let executed_at: String = row.get("executed_at");
+                Ok(RequestHistoryEntry {
+                    id: row.get("id"),
+                    workspace_id: row.get("workspace_id"),
+                    request_id: row.get("request_id"),
+                    method: row.get("method"),
+                    url: row.get("url"),
+                    status: row.get::<Option<i64>, _>("status").map(|status| status as u16),
+                    total_time_ms: row.get::<Option<i64>, _>("total_time_ms").map(|ms| ms as u64),
+                    response_size: row.get::<Option<i64>, _>("response_size").map(|size| size as u64),
+                    error: row.get("error"),
+                    executed_at: DateTime::parse_from_rfc3339(&executed_at)?.with_timezone(&Utc),
+                })
+            })
+            .collect()
+    }
+
+    /// Delete the workspace's history, returning how many entries were removed
+    pub async fn clear(&self, workspace_id: &str) -> Result<u64> {
+        let result = sqlx::query("DELETE FROM request_history WHERE workspace_id = ?1")
+            .bind(workspace_id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| anyhow!("Failed to clear request history: {}", e))?;
+        Ok(result.rows_affected())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::test_database::{add_workspace, database_with_workspace};
+
+    #[tokio::test]
+    async fn test_history_keeps_most_recent_entries() {
+        let (database, first) = database_with_workspace("/tmp/First").await;
+        let second = add_workspace(&database, "Second", "/tmp/Second").await;
+        let history = RequestHistoryService::new(database.get_pool()).with_max_entries(3);
+
+        for i in 0..5 {
+            let url = format!("https://api.example.com/items/{}", i);
+            history.record(&first.id, "request-1", &HttpMethod::Get, &url, Err("offline")).await.unwrap();
+        }
+        history.record(&second.id, "request-2", &HttpMethod::Get, "https://api.example.com", Err("offline")).await.unwrap();
+
+        let urls: Vec<String> = history.list(&first.id, 10).await.unwrap().into_iter().map(|e| e.url).collect();
+        assert_eq!(
+            urls,
+            vec![
+                "https://api.example.com/items/4",
+                "https://api.example.com/items/3",
+                "https://api.example.com/items/2",
+            ]
+        );
+        // Trimming one workspace leaves the others alone
+        assert_eq!(history.list(&second.id, 10).await.unwrap().len(), 1);
+    }
+}
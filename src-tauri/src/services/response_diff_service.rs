@@ -0,0 +1,254 @@
+use crate::models::collection::RequestExample;
+use crate::models::http::{HttpResponse, ResponseBody};
+use crate::models::response_diff::{DiffKind, FieldDiff, ResponseDiff, ResponseSnapshot, SnapshotDiff};
+use serde_json::{Map, Value};
+
+#[derive(Debug, Clone, Default)]
+pub struct ResponseDiffService;
+
+impl ResponseDiffService {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Compare a live response against a saved example, skipping the example's volatile fields
+    pub fn compare_to_example(&self, example: &RequestExample, live: &HttpResponse) -> ResponseDiff {
+        let expected = serde_json::from_str(&example.body)
+            .unwrap_or_else(|_| Value::String(example.body.clone()));
+        let actual = body_to_value(&live.body);
+
+        self.diff(example.status, &expected, live.status, &actual, &example.get_volatile_fields())
+    }
+
+    /// Compare a live response against the request's snapshot: status, body and the headers
+    /// the snapshot kept
+    pub fn compare_to_snapshot(&self, snapshot: &ResponseSnapshot, live: &HttpResponse) -> SnapshotDiff {
+        let body = self.diff(snapshot.status, &snapshot.body, live.status, &canonicalize(&body_to_value(&live.body)), &[]);
+
+        let header_differences: Vec<FieldDiff> = snapshot.headers.iter()
+            .filter_map(|(name, expected)| {
+                let actual = live.headers.iter()
+                    .find(|(live_name, _)| live_name.eq_ignore_ascii_case(name))
+                    .map(|(_, value)| value);
+                let kind = match actual {
+                    Some(actual) if actual == expected => return None,
+                    Some(_) => DiffKind::Changed,
+                    None => DiffKind::Removed,
+                };
+                Some(FieldDiff {
+                    path: name.clone(),
+                    kind,
+                    expected: Some(Value::String(expected.clone())),
+                    actual: actual.map(|value| Value::String(value.clone())),
+                })
+            })
+            .collect();
+
+        SnapshotDiff {
+            snapshot_created_at: snapshot.created_at,
+            identical: body.identical && header_differences.is_empty(),
+            body,
+            header_differences,
+        }
+    }
+
+    /// Structural diff of two response bodies. A volatile field matches either a full
+    /// path (`data.meta.requestId`) or a bare key name (`timestamp`) at any depth.
+    pub fn diff(
+        &self,
+        expected_status: u16,
+        expected: &Value,
+        actual_status: u16,
+        actual: &Value,
+        volatile_fields: &[String],
+    ) -> ResponseDiff {
+        let mut differences = Vec::new();
+        diff_values("", expected, actual, volatile_fields, &mut differences);
+
+        ResponseDiff {
+            expected_status,
+            actual_status,
+            identical: expected_status == actual_status && differences.is_empty(),
+            differences,
+            ignored_fields: volatile_fields.to_vec(),
+        }
+    }
+}
+
+/// The response body as JSON; bodies that aren't JSON become a string
+pub fn body_to_value(body: &ResponseBody) -> Value {
+    match body {
+        ResponseBody::Json { data } => data.clone(),
+        ResponseBody::Text { content, .. } => serde_json::from_str(content)
+            .unwrap_or_else(|_| Value::String(content.clone())),
+        ResponseBody::Binary { .. } => {
+            Value::String(String::from_utf8_lossy(&body.binary_bytes().unwrap_or_default()).to_string())
+        }
+        ResponseBody::Empty => Value::Null,
+    }
+}
+
+/// A copy of `value` with every object's keys in sorted order, so key order never shows up
+/// as a difference once stored
+pub fn canonicalize(value: &Value) -> Value {
+    match value {
+        Value::Object(map) => {
+            let mut entries: Vec<(&String, &Value)> = map.iter().collect();
+            entries.sort_by(|a, b| a.0.cmp(b.0));
+            Value::Object(entries.into_iter().map(|(key, value)| (key.clone(), canonicalize(value))).collect::<Map<_, _>>())
+        }
+        Value::Array(items) => Value::Array(items.iter().map(canonicalize).collect()),
+        other => other.clone(),
+    }
+}
+
+fn diff_values(path: &str, expected: &Value, actual: &Value, volatile_fields: &[String], out: &mut Vec<FieldDiff>) {
+    if is_volatile(path, volatile_fields) {
+        return;
+    }
+
+    match (expected, actual) {
+        (Value::Object(expected_map), Value::Object(actual_map)) => {
+            for (key, expected_value) in expected_map {
+                let child = child_key_path(path, key);
+                match actual_map.get(key) {
+                    Some(actual_value) => diff_values(&child, expected_value, actual_value, volatile_fields, out),
+                    None if !is_volatile(&child, volatile_fields) => out.push(FieldDiff {
+                        path: child,
+                        kind: DiffKind::Removed,
+                        expected: Some(expected_value.clone()),
+                        actual: None,
+                    }),
+                    None => {}
+                }
+            }
+            for (key, actual_value) in actual_map {
+                let child = child_key_path(path, key);
+                if !expected_map.contains_key(key) && !is_volatile(&child, volatile_fields) {
+                    out.push(FieldDiff {
+                        path: child,
+                        kind: DiffKind::Added,
+                        expected: None,
+                        actual: Some(actual_value.clone()),
+                    });
+                }
+            }
+        }
+        (Value::Array(expected_items), Value::Array(actual_items)) => {
+            for index in 0..expected_items.len().max(actual_items.len()) {
+                let child = format!("{}[{}]", path, index);
+                match (expected_items.get(index), actual_items.get(index)) {
+                    (Some(e), Some(a)) => diff_values(&child, e, a, volatile_fields, out),
+                    (Some(e), None) => out.push(FieldDiff {
+                        path: child,
+                        kind: DiffKind::Removed,
+                        expected: Some(e.clone()),
+                        actual: None,
+                    }),
+                    (None, Some(a)) => out.push(FieldDiff {
+                        path: child,
+                        kind: DiffKind::Added,
+                        expected: None,
+                        actual: Some(a.clone()),
+                    }),
+                    (None, None) => {}
+                }
+            }
+        }
+        _ if expected != actual => out.push(FieldDiff {
+            path: if path.is_empty() { "$".to_string() } else { path.to_string() },
+            kind: DiffKind::Changed,
+            expected: Some(expected.clone()),
+            actual: Some(actual.clone()),
+        }),
+        _ => {}
+    }
+}
+
+fn child_key_path(parent: &str, key: &str) -> String {
+    if parent.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", parent, key)
+    }
+}
+
+fn is_volatile(path: &str, volatile_fields: &[String]) -> bool {
+    if path.is_empty() {
+        return false;
+    }
+    let leaf = path.rsplit('.').next().unwrap_or(path);
+    let leaf = leaf.split('[').next().unwrap_or(leaf);
+    volatile_fields.iter().any(|field| field == path || field == leaf)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::collection::CreateRequestExampleRequest;
+    use crate::models::http::ResponseTiming;
+    use std::collections::HashMap;
+
+    fn live_response(body: Value) -> HttpResponse {
+        HttpResponse {
+            status: 200,
+            status_text: "OK".to_string(),
+            headers: HashMap::new(),
+            body: ResponseBody::Json { data: body },
+            timing: ResponseTiming {
+                total_time_ms: 10,
+                dns_lookup_ms: None,
+                tcp_connect_ms: None,
+                tls_handshake_ms: None,
+                first_byte_ms: None,
+                download_ms: None,
+                connection_reused: None,
+            },
+            request_id: "request-1".to_string(),
+            final_url: "https://api.example.com/users/1".to_string(),
+            truncated: false,
+            full_size: 0,
+            size: Default::default(),
+            streamed: false,
+            attempts: 1,
+            redirects: Vec::new(),
+            timestamp: chrono::Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_compare_to_example_pinpoints_changed_field() {
+        let example = RequestExample::new(CreateRequestExampleRequest {
+            request_id: "request-1".to_string(),
+            name: "User found".to_string(),
+            status: 200,
+            headers: None,
+            body: r#"{"id": 1, "profile": {"name": "Ada", "role": "admin"}, "updatedAt": "2024-01-01"}"#.to_string(),
+            volatile_fields: Some(vec!["updatedAt".to_string()]),
+        });
+        let live = live_response(serde_json::json!({
+            "id": 1,
+            "profile": {"name": "Ada", "role": "viewer"},
+            "updatedAt": "2025-06-30"
+        }));
+
+        let diff = ResponseDiffService::new().compare_to_example(&example, &live);
+
+        assert!(!diff.identical);
+        assert_eq!(diff.differences.len(), 1);
+        let field = &diff.differences[0];
+        assert_eq!(field.path, "profile.role");
+        assert_eq!(field.kind, DiffKind::Changed);
+        assert_eq!(field.expected, Some(serde_json::json!("admin")));
+        assert_eq!(field.actual, Some(serde_json::json!("viewer")));
+    }
+
+    #[test]
+    fn test_canonicalize_sorts_keys_at_every_depth() {
+        let value: Value = serde_json::from_str(r#"{"b": 1, "a": {"z": [{"y": 1, "x": 2}], "c": null}}"#).unwrap();
+        assert_eq!(
+            canonicalize(&value).to_string(),
+            r#"{"a":{"c":null,"z":[{"x":2,"y":1}]},"b":1}"#
+        );
+    }
+}
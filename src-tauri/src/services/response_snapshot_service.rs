@@ -0,0 +1,202 @@
+use crate::models::http::HttpResponse;
+use crate::models::response_diff::{ResponseSnapshot, SnapshotDiff};
+use crate::services::response_diff_service::{body_to_value, canonicalize, ResponseDiffService};
+use anyhow::{anyhow, Result};
+use sqlx::{Row, SqlitePool};
+use std::collections::BTreeMap;
+
+/// Headers kept in a snapshot when the caller doesn't choose any
+pub const DEFAULT_SNAPSHOT_HEADERS: &[&str] = &["content-type"];
+
+/// Keeps one baseline response per request so later responses can be checked for changes
+pub struct ResponseSnapshotService {
+    pool: SqlitePool,
+}
+
+impl ResponseSnapshotService {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    /// Save `response` as the request's snapshot, replacing any earlier one. Only the named
+    /// headers that the response has are kept.
+    pub async fn save(&self, request_id: &str, response: &HttpResponse, headers: &[String]) -> Result<ResponseSnapshot> {
+        let kept: BTreeMap<String, String> = headers.iter()
+            .filter_map(|name| {
+                let name = name.trim().to_lowercase();
+                response.headers.iter()
+                    .find(|(header, _)| header.eq_ignore_ascii_case(&name))
+                    .map(|(_, value)| (name, value.clone()))
+            })
+            .collect();
+        let snapshot = ResponseSnapshot {
+            request_id: request_id.to_string(),
+            status: response.status,
+            headers: kept,
+            body: canonicalize(&body_to_value(&response.body)),
+            created_at: chrono::Utc::now(),
+        };
+
+        sqlx::query(
+            r#"
+            INSERT INTO response_snapshots (request_id, status, headers, body, created_at)
+            VALUES (?1, ?2, ?3, ?4, ?5)
+            ON CONFLICT(request_id) DO UPDATE SET
+                status = excluded.status, headers = excluded.headers, body = excluded.body, created_at = excluded.created_at
+            "#
+        )
+        .bind(request_id)
+        .bind(snapshot.status as i64)
+        .bind(serde_json::to_string(&snapshot.headers)?)
+        .bind(snapshot.body.to_string())
+        .bind(snapshot.created_at.to_rfc3339())
+        .execute(&self.pool)
+        .await
+        .map_err(|e| anyhow!("Failed to save response snapshot: {}", e))?;
+
+        Ok(snapshot)
+    }
+
+    pub async fn get(&self, request_id: &str) -> Result<Option<ResponseSnapshot>> {
+        let row = sqlx::query("SELECT * FROM response_snapshots WHERE request_id = ?1")
+            .bind(request_id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| anyhow!("Failed to get response snapshot: {}", e))?;
+
+        let Some(row) = row else {
+            return Ok(None);
+        };
+        Ok(Some(ResponseSnapshot {
+            request_id: row.get("request_id"),
+            status: row.get::<i64, _>("status") as u16,
+            headers: serde_json::from_str(&row.get::<String, _>("headers"))?,
+            body: serde_json::from_str(&row.get::<String, _>("body"))?,
+            created_at: chrono::DateTime::parse_from_rfc3339(&row.get::<String, _>("created_at"))?.with_timezone(&chrono::Utc),
+        }))
+    }
+
+    /// Compare a response with the request's snapshot; fails when none has been saved
+    pub async fn diff(&self, request_id: &str, response: &HttpResponse) -> Result<SnapshotDiff> {
+        let snapshot = self.get(request_id).await?
+            .ok_or_else(|| anyhow!("No response snapshot has been saved for this request"))?;
+        Ok(ResponseDiffService::new().compare_to_snapshot(&snapshot, response))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::collection::{CreateCollectionRequest, CreateRequestRequest};
+    use crate::models::http::{ResponseBody, ResponseTiming};
+    use crate::models::response_diff::DiffKind;
+    use crate::services::collection_service::CollectionService;
+    use crate::services::database_service::DatabaseService;
+    use crate::tests::test_database::database_with_workspace;
+    use std::collections::HashMap;
+    use std::sync::Arc;
+
+    fn response(status: u16, content_type: &str, body: &str) -> HttpResponse {
+        HttpResponse {
+            status,
+            status_text: String::new(),
+            headers: HashMap::from([
+                ("Content-Type".to_string(), content_type.to_string()),
+                ("Date".to_string(), chrono::Utc::now().to_rfc2822()),
+            ]),
+            body: ResponseBody::Json { data: serde_json::from_str(body).unwrap() },
+            timing: ResponseTiming {
+                total_time_ms: 10,
+                dns_lookup_ms: None,
+                tcp_connect_ms: None,
+                tls_handshake_ms: None,
+                first_byte_ms: None,
+                download_ms: None,
+                connection_reused: None,
+            },
+            request_id: "request-1".to_string(),
+            final_url: "https://api.example.com/users/1".to_string(),
+            truncated: false,
+            full_size: 0,
+            size: Default::default(),
+            streamed: false,
+            attempts: 1,
+            redirects: Vec::new(),
+            timestamp: chrono::Utc::now(),
+        }
+    }
+
+    async fn saved_request(temp_dir: &tempfile::TempDir) -> (Arc<DatabaseService>, String) {
+        let (database, workspace) = database_with_workspace(temp_dir.path().to_str().unwrap()).await;
+        let collections = CollectionService::new(database.get_pool());
+        let collection = collections.create_collection(CreateCollectionRequest {
+            workspace_id: workspace.id.clone(),
+            name: "Users".to_string(),
+            description: None,
+            folder_path: None,
+            git_branch: None,
+            parent_id: None,
+        }).await.unwrap();
+        let request = collections.create_request(CreateRequestRequest {
+            collection_id: collection.id.clone(),
+            name: "Get user".to_string(),
+            description: None,
+            method: "GET".to_string(),
+            url: "https://api.example.com/users/1".to_string(),
+            headers: None,
+            body: None,
+            body_type: None,
+            auth_type: None,
+            auth_config: None,
+            follow_redirects: None,
+            timeout_ms: None,
+            order_index: None,
+        }).await.unwrap();
+        (database, request.id)
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_diff_reports_only_the_changed_field() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let (database, request_id) = saved_request(&temp_dir).await;
+        let service = ResponseSnapshotService::new(database.get_pool());
+        let headers = vec!["Content-Type".to_string()];
+
+        let baseline = response(200, "application/json", r#"{"id": 1, "profile": {"name": "Ada", "role": "admin"}, "tags": ["a"]}"#);
+        let snapshot = service.save(&request_id, &baseline, &headers).await.unwrap();
+        assert_eq!(snapshot.headers, BTreeMap::from([("content-type".to_string(), "application/json".to_string())]));
+
+        // Reordered keys and an unselected header that changed are not differences
+        let reordered = response(200, "application/json", r#"{"tags": ["a"], "profile": {"role": "admin", "name": "Ada"}, "id": 1}"#);
+        assert!(service.diff(&request_id, &reordered).await.unwrap().identical);
+
+        let changed = response(200, "application/json", r#"{"profile": {"role": "viewer", "name": "Ada"}, "id": 1, "tags": ["a"]}"#);
+        let diff = service.diff(&request_id, &changed).await.unwrap();
+        assert!(!diff.identical);
+        assert!(diff.header_differences.is_empty());
+        assert_eq!(diff.body.differences.len(), 1);
+        assert_eq!(diff.body.differences[0].path, "profile.role");
+        assert_eq!(diff.body.differences[0].kind, DiffKind::Changed);
+        assert_eq!(diff.body.differences[0].actual, Some(serde_json::json!("viewer")));
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_diff_reports_status_and_headers() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let (database, request_id) = saved_request(&temp_dir).await;
+        let service = ResponseSnapshotService::new(database.get_pool());
+        assert!(service.diff(&request_id, &response(200, "application/json", "{}")).await.is_err());
+
+        let headers: Vec<String> = DEFAULT_SNAPSHOT_HEADERS.iter().map(|name| name.to_string()).collect();
+        service.save(&request_id, &response(200, "application/json", r#"{"id": 1}"#), &headers).await.unwrap();
+
+        let diff = service.diff(&request_id, &response(404, "application/problem+json", r#"{"id": 1, "error": "gone"}"#)).await.unwrap();
+        assert!(!diff.identical);
+        assert_eq!((diff.body.expected_status, diff.body.actual_status), (200, 404));
+        assert_eq!(diff.body.differences[0].path, "error");
+        assert_eq!(diff.body.differences[0].kind, DiffKind::Added);
+        assert_eq!(diff.header_differences.len(), 1);
+        assert_eq!(diff.header_differences[0].path, "content-type");
+        assert_eq!(diff.header_differences[0].actual, Some(serde_json::json!("application/problem+json")));
+    }
+}
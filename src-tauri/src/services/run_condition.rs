@@ -0,0 +1,149 @@
+//! Run conditions: predicates over a collection run's variables that decide whether a request
+//! is sent, such as `{{token}} exists` or `{{status}} == 200`
+
+use anyhow::{anyhow, Result};
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RunCondition {
+    Exists(String),
+    Missing(String), // `{{name}} not exists`
+    Compare { variable: String, operator: ComparisonOperator, value: String },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ComparisonOperator {
+    Equals,
+    NotEquals,
+    GreaterThan,
+    GreaterOrEqual,
+    LessThan,
+    LessOrEqual,
+}
+
+// Two-character operators come first so `>=` isn't read as `>`
+const OPERATORS: [(&str, ComparisonOperator); 6] = [
+    ("==", ComparisonOperator::Equals),
+    ("!=", ComparisonOperator::NotEquals),
+    (">=", ComparisonOperator::GreaterOrEqual),
+    ("<=", ComparisonOperator::LessOrEqual),
+    (">", ComparisonOperator::GreaterThan),
+    ("<", ComparisonOperator::LessThan),
+];
+
+/// Parse `{{name}} exists`, `{{name}} not exists` or `{{name}} <op> value`, where the value
+/// may be quoted and `<op>` is one of `==`, `!=`, `>`, `>=`, `<` or `<=`
+pub fn parse_run_condition(condition: &str) -> Result<RunCondition> {
+    let invalid = || anyhow!("Invalid run condition '{}': expected e.g. {{{{token}}}} exists or {{{{status}}}} == 200", condition);
+
+    let rest = condition.trim().strip_prefix("{{").ok_or_else(invalid)?;
+    let (variable, rest) = rest.split_once("}}").ok_or_else(invalid)?;
+    let variable = variable.trim().to_string();
+    if variable.is_empty() {
+        return Err(invalid());
+    }
+
+    let rest = rest.trim();
+    match rest.split_whitespace().collect::<Vec<_>>().as_slice() {
+        ["exists"] => return Ok(RunCondition::Exists(variable)),
+        ["not", "exists"] => return Ok(RunCondition::Missing(variable)),
+        _ => {}
+    }
+
+    let (operator, value) = OPERATORS
+        .iter()
+        .find_map(|(symbol, operator)| rest.strip_prefix(symbol).map(|value| (*operator, value.trim())))
+        .ok_or_else(invalid)?;
+    let value = match unquote(value) {
+        Some(value) => value,
+        None if !value.is_empty() => value,
+        None => return Err(invalid()),
+    };
+
+    Ok(RunCondition::Compare { variable, operator, value: value.to_string() })
+}
+
+fn unquote(value: &str) -> Option<&str> {
+    ['"', '\''].iter().find_map(|quote| {
+        value.strip_prefix(*quote)?.strip_suffix(*quote)
+    })
+}
+
+impl RunCondition {
+    /// Whether the condition holds. Values that both parse as numbers are compared as numbers,
+    /// anything else as text; `>`, `<` and friends are false for non-numbers, and every
+    /// comparison is false when the variable is undefined.
+    pub fn evaluate(&self, variables: &HashMap<String, String>) -> bool {
+        match self {
+            RunCondition::Exists(variable) => variables.contains_key(variable),
+            RunCondition::Missing(variable) => !variables.contains_key(variable),
+            RunCondition::Compare { variable, operator, value } => {
+                let Some(actual) = variables.get(variable) else {
+                    return false;
+                };
+                let numbers = actual.trim().parse::<f64>().ok().zip(value.trim().parse::<f64>().ok());
+                match (operator, numbers) {
+                    (ComparisonOperator::Equals, Some((a, b))) => a == b,
+                    (ComparisonOperator::Equals, None) => actual == value,
+                    (ComparisonOperator::NotEquals, Some((a, b))) => a != b,
+                    (ComparisonOperator::NotEquals, None) => actual != value,
+                    (ComparisonOperator::GreaterThan, Some((a, b))) => a > b,
+                    (ComparisonOperator::GreaterOrEqual, Some((a, b))) => a >= b,
+                    (ComparisonOperator::LessThan, Some((a, b))) => a < b,
+                    (ComparisonOperator::LessOrEqual, Some((a, b))) => a <= b,
+                    (_, None) => false,
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn variables(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs.iter().map(|(key, value)| (key.to_string(), value.to_string())).collect()
+    }
+
+    #[test]
+    fn test_parse_run_condition() {
+        assert_eq!(parse_run_condition("{{token}} exists").unwrap(), RunCondition::Exists("token".to_string()));
+        assert_eq!(parse_run_condition("  {{ token }}  not   exists ").unwrap(), RunCondition::Missing("token".to_string()));
+        assert_eq!(
+            parse_run_condition("{{status}} >= 200").unwrap(),
+            RunCondition::Compare { variable: "status".to_string(), operator: ComparisonOperator::GreaterOrEqual, value: "200".to_string() }
+        );
+        assert_eq!(
+            parse_run_condition("{{role}} == \"admin user\"").unwrap(),
+            RunCondition::Compare { variable: "role".to_string(), operator: ComparisonOperator::Equals, value: "admin user".to_string() }
+        );
+        assert_eq!(
+            parse_run_condition("{{note}} != ''").unwrap(),
+            RunCondition::Compare { variable: "note".to_string(), operator: ComparisonOperator::NotEquals, value: String::new() }
+        );
+
+        for invalid in ["token exists", "{{}} exists", "{{token}}", "{{token}} is set", "{{status}} ==", "{{token exists"] {
+            assert!(parse_run_condition(invalid).is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_evaluate_run_condition() {
+        let vars = variables(&[("token", "abc"), ("status", "200"), ("count", "10")]);
+        let holds = |condition: &str| parse_run_condition(condition).unwrap().evaluate(&vars);
+
+        assert!(holds("{{token}} exists"));
+        assert!(!holds("{{missing}} exists"));
+        assert!(holds("{{missing}} not exists"));
+        assert!(holds("{{status}} == 200"));
+        assert!(holds("{{status}} == 200.0"));
+        assert!(holds("{{token}} == 'abc'"));
+        assert!(holds("{{token}} != abd"));
+        assert!(holds("{{count}} > 9"));
+        assert!(!holds("{{count}} < 9"));
+        assert!(holds("{{count}} <= 10"));
+        assert!(!holds("{{token}} > 1"));
+        assert!(!holds("{{missing}} != 200"));
+    }
+}
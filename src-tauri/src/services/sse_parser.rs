@@ -0,0 +1,70 @@
+//! Incremental parser for `text/event-stream` bodies
+
+/// A dispatched event block
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParsedEvent {
+    pub event: String, // "message" when the block has no event field
+    pub data: String, // data lines joined with newlines
+    pub id: Option<String>,
+}
+
+/// Collects fields across chunks and dispatches an event at each blank line.
+/// Chunks may split lines, or multi-byte characters, anywhere.
+#[derive(Debug, Default)]
+pub struct SseParser {
+    buffer: Vec<u8>,
+    event: Option<String>,
+    data: Vec<String>,
+    id: Option<String>,
+}
+
+impl SseParser {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add bytes from the stream and return any events they complete, in order
+    pub fn feed(&mut self, chunk: &[u8]) -> Vec<ParsedEvent> {
+        self.buffer.extend_from_slice(chunk);
+        let mut events = Vec::new();
+        while let Some(end) = self.buffer.iter().position(|b| *b == b'\n') {
+            let line: Vec<u8> = self.buffer.drain(..=end).collect();
+            let line = String::from_utf8_lossy(&line);
+            let line = line.trim_end_matches('\n').trim_end_matches('\r');
+            if line.is_empty() {
+                events.extend(self.dispatch());
+            } else {
+                self.field(line);
+            }
+        }
+        events
+    }
+
+    fn field(&mut self, line: &str) {
+        if line.starts_with(':') {
+            return; // comment, often used as a keep-alive
+        }
+        let (name, value) = line.split_once(':').unwrap_or((line, ""));
+        let value = value.strip_prefix(' ').unwrap_or(value).to_string();
+        match name {
+            "event" => self.event = Some(value),
+            "data" => self.data.push(value),
+            "id" => self.id = Some(value),
+            _ => {} // retry and unknown fields
+        }
+    }
+
+    /// A block without data lines is discarded, as browsers do
+    fn dispatch(&mut self) -> Option<ParsedEvent> {
+        let event = self.event.take();
+        let id = self.id.take();
+        if self.data.is_empty() {
+            return None;
+        }
+        Some(ParsedEvent {
+            event: event.filter(|event| !event.is_empty()).unwrap_or_else(|| "message".to_string()),
+            data: std::mem::take(&mut self.data).join("\n"),
+            id,
+        })
+    }
+}
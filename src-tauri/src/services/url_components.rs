@@ -0,0 +1,247 @@
+//! Splitting URLs into editable parts and putting them back together
+
+use crate::models::http::{QueryParam, UrlComponents};
+use anyhow::{anyhow, Result};
+
+/// Characters left as they are in query keys and values; anything else is percent-encoded,
+/// apart from spaces, which become `+`
+const QUERY_SAFE: &str = "-._~!$'()*,;:@/?";
+
+/// Split a URL into its parts, keeping query parameters in order with repeats. `{{VAR}}`
+/// placeholders may appear anywhere, including in place of the scheme and host.
+pub fn parse_url_components(url: &str) -> Result<UrlComponents> {
+    let url = url.trim();
+    if url.is_empty() {
+        return Err(anyhow!("URL is empty"));
+    }
+
+    let (rest, fragment) = split_once_outside_placeholders(url, '#');
+    let (rest, query) = split_once_outside_placeholders(rest, '?');
+    let (scheme, rest) = match rest.split_once("://") {
+        Some((scheme, rest)) if !scheme.is_empty() && !scheme.contains(['/', '{']) => (scheme.to_lowercase(), rest),
+        _ => (String::new(), rest),
+    };
+
+    let authority_end = rest.find('/').unwrap_or(rest.len());
+    let (authority, path) = rest.split_at(authority_end);
+    let (userinfo, host_port) = match authority.rsplit_once('@') {
+        Some((userinfo, host_port)) => (Some(userinfo.to_string()), host_port),
+        None => (None, authority),
+    };
+    let (host, port) = split_port(host_port)?;
+    // Placeholder names are case-sensitive, so only plain host names are lowercased
+    let host = if host.contains("{{") { host.to_string() } else { host.to_lowercase() };
+
+    Ok(UrlComponents {
+        scheme,
+        userinfo,
+        host,
+        port,
+        path: path.to_string(),
+        query: query.map(parse_query).unwrap_or_default(),
+        fragment: fragment.map(str::to_string),
+    })
+}
+
+/// Reassemble a URL, percent-encoding query keys and values but leaving placeholders alone
+pub fn build_url_from_components(components: &UrlComponents) -> String {
+    let mut url = String::new();
+    if !components.scheme.is_empty() {
+        url.push_str(&components.scheme);
+        url.push_str("://");
+    }
+    if let Some(userinfo) = &components.userinfo {
+        url.push_str(userinfo);
+        url.push('@');
+    }
+    url.push_str(&components.host);
+    if let Some(port) = &components.port {
+        url.push(':');
+        url.push_str(port);
+    }
+    if !components.path.is_empty() && !components.path.starts_with('/') && !url.is_empty() {
+        url.push('/');
+    }
+    url.push_str(&components.path);
+
+    if !components.query.is_empty() {
+        let pairs: Vec<String> = components.query.iter()
+            .map(|param| match &param.value {
+                Some(value) => format!("{}={}", encode_query_text(&param.key), encode_query_text(value)),
+                None => encode_query_text(&param.key),
+            })
+            .collect();
+        url.push('?');
+        url.push_str(&pairs.join("&"));
+    }
+    if let Some(fragment) = &components.fragment {
+        url.push('#');
+        url.push_str(fragment);
+    }
+    url
+}
+
+fn parse_query(query: &str) -> Vec<QueryParam> {
+    query.split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| match pair.split_once('=') {
+            Some((key, value)) => QueryParam { key: decode_query_text(key), value: Some(decode_query_text(value)) },
+            None => QueryParam { key: decode_query_text(pair), value: None },
+        })
+        .collect()
+}
+
+/// Split at the first `delimiter` that isn't inside a `{{...}}` placeholder
+fn split_once_outside_placeholders(text: &str, delimiter: char) -> (&str, Option<&str>) {
+    let mut depth = 0;
+    let mut chars = text.char_indices().peekable();
+    while let Some((offset, c)) = chars.next() {
+        match c {
+            '{' if chars.peek().is_some_and(|&(_, next)| next == '{') => {
+                chars.next();
+                depth += 1;
+            }
+            '}' if depth > 0 && chars.peek().is_some_and(|&(_, next)| next == '}') => {
+                chars.next();
+                depth -= 1;
+            }
+            c if c == delimiter && depth == 0 => return (&text[..offset], Some(&text[offset + 1..])),
+            _ => {}
+        }
+    }
+    (text, None)
+}
+
+fn split_port(host_port: &str) -> Result<(&str, Option<String>)> {
+    // Skip over the colons of an IPv6 literal like [::1]
+    let search_from = if host_port.starts_with('[') { host_port.find(']').unwrap_or(0) } else { 0 };
+    let Some(colon) = host_port[search_from..].rfind(':').map(|index| index + search_from) else {
+        return Ok((host_port, None));
+    };
+
+    let (host, port) = (&host_port[..colon], &host_port[colon + 1..]);
+    // A host placeholder such as {{API_HOST}} can contain a colon-separated port of its own
+    if host.matches("{{").count() != host.matches("}}").count() {
+        return Ok((host_port, None));
+    }
+    let is_placeholder = port.starts_with("{{") && port.ends_with("}}");
+    if !is_placeholder && port.parse::<u16>().is_err() {
+        return Err(anyhow!("Invalid port '{}'", port));
+    }
+    Ok((host, Some(port.to_string())))
+}
+
+/// Undo `+` for space and `%XX` escapes; malformed escapes are kept as written
+fn decode_query_text(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        let escaped = bytes.get(index + 1..index + 3)
+            .filter(|_| bytes[index] == b'%')
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match (bytes[index], escaped) {
+            (_, Some(byte)) => {
+                decoded.push(byte);
+                index += 3;
+            }
+            (b'+', None) => {
+                decoded.push(b' ');
+                index += 1;
+            }
+            (byte, None) => {
+                decoded.push(byte);
+                index += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+fn encode_query_text(text: &str) -> String {
+    let mut encoded = String::new();
+    let mut rest = text;
+    while !rest.is_empty() {
+        // Placeholders are substituted before sending, so they go out unencoded
+        if let Some(placeholder_end) = rest.strip_prefix("{{").and_then(|after| after.find("}}")) {
+            let end = placeholder_end + 4;
+            encoded.push_str(&rest[..end]);
+            rest = &rest[end..];
+            continue;
+        }
+        let c = rest.chars().next().unwrap();
+        if c.is_ascii_alphanumeric() || QUERY_SAFE.contains(c) {
+            encoded.push(c);
+        } else if c == ' ' {
+            encoded.push('+');
+        } else {
+            let mut buffer = [0; 4];
+            for byte in c.encode_utf8(&mut buffer).bytes() {
+                encoded.push_str(&format!("%{:02X}", byte));
+            }
+        }
+        rest = &rest[c.len_utf8()..];
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn param(key: &str, value: Option<&str>) -> QueryParam {
+        QueryParam { key: key.to_string(), value: value.map(str::to_string) }
+    }
+
+    #[test]
+    fn test_repeated_and_empty_query_keys() {
+        let url = "HTTPS://API.Example.com:8443/v1/items?tag=a&tag=b&empty=&flag&q=hello+world%21#top";
+        let components = parse_url_components(url).unwrap();
+
+        assert_eq!(components.scheme, "https");
+        assert_eq!(components.host, "api.example.com");
+        assert_eq!(components.port.as_deref(), Some("8443"));
+        assert_eq!(components.path, "/v1/items");
+        assert_eq!(components.fragment.as_deref(), Some("top"));
+        assert_eq!(components.query, vec![
+            param("tag", Some("a")),
+            param("tag", Some("b")),
+            param("empty", Some("")),
+            param("flag", None),
+            param("q", Some("hello world!")),
+        ]);
+
+        assert_eq!(
+            build_url_from_components(&components),
+            "https://api.example.com:8443/v1/items?tag=a&tag=b&empty=&flag&q=hello+world!#top"
+        );
+        assert!(parse_url_components("http://host:99999/").is_err());
+    }
+
+    #[test]
+    fn test_template_variables_are_kept() {
+        let url = "{{BASE_URL}}/users/{{userId}}?token={{API Token}}&filter={ \"a\": 1 }";
+        let components = parse_url_components(url).unwrap();
+
+        assert_eq!(components.scheme, "");
+        assert_eq!(components.host, "{{BASE_URL}}");
+        assert_eq!(components.port, None);
+        assert_eq!(components.path, "/users/{{userId}}");
+        assert_eq!(components.query, vec![
+            param("token", Some("{{API Token}}")),
+            param("filter", Some("{ \"a\": 1 }")),
+        ]);
+
+        let mut edited = components.clone();
+        edited.query.push(param("page", Some("{{page}}")));
+        assert_eq!(
+            build_url_from_components(&edited),
+            "{{BASE_URL}}/users/{{userId}}?token={{API Token}}&filter=%7B+%22a%22:+1+%7D&page={{page}}"
+        );
+
+        let components = parse_url_components("http://{{HOST}}:{{PORT}}/health?check=1").unwrap();
+        assert_eq!(components.host, "{{HOST}}");
+        assert_eq!(components.port.as_deref(), Some("{{PORT}}"));
+    }
+}
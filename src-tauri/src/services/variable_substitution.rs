@@ -0,0 +1,268 @@
+use crate::models::environment::{EnvironmentVariable, SubstitutionResult, SubstitutionSpan, VariableType};
+use rand::Rng;
+use regex::Regex;
+use std::collections::HashMap;
+use std::ops::Range;
+use std::sync::OnceLock;
+
+/// How deep variables may be defined in terms of other variables
+const MAX_NESTING_DEPTH: usize = 10;
+
+fn placeholder_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| Regex::new(r"\{\{([^{}]+)\}\}").unwrap())
+}
+
+/// Replace `{{name}}` placeholders with their values. User variables take precedence over
+/// the built-in dynamic ones (`{{$uuid}}` and friends), which are generated afresh for every
+/// placeholder. `{{name|fallback}}` uses the fallback when neither resolves; the fallback runs
+/// to the closing braces and may contain pipes, while a pipe in the name is written `\|`.
+/// Values may reference other variables, which are resolved in turn up to ten levels deep;
+/// a variable that refers back to itself, directly or not, is left as its placeholder.
+/// Unknown placeholders without a fallback are left as they are.
+pub fn substitute_variables(text: &str, variables: &HashMap<String, String>) -> String {
+    substitute(text, |name| variables.get(name).cloned()).0
+}
+
+/// Like `substitute_variables`, also reporting which parts of the output came from which variable
+pub fn substitute_with_spans(text: &str, variables: &HashMap<String, EnvironmentVariable>) -> SubstitutionResult {
+    let (output, substitutions) = substitute(text, |name| variables.get(name).map(|v| v.value.clone()));
+
+    // Byte offsets become UTF-16 offsets; substitutions are in order, so one pass converts them all
+    let mut spans = Vec::with_capacity(substitutions.len());
+    let (mut byte_offset, mut utf16_offset) = (0, 0);
+    let mut to_utf16 = |byte: usize| {
+        utf16_offset += output[byte_offset..byte].encode_utf16().count();
+        byte_offset = byte;
+        utf16_offset
+    };
+    for (name, range) in substitutions {
+        let was_secret = variables
+            .get(&name)
+            .is_some_and(|v| v.is_secret || v.variable_type == VariableType::Secret);
+        let start = to_utf16(range.start);
+        let end = to_utf16(range.end);
+        spans.push(SubstitutionSpan { start, end, variable_name: name, was_secret });
+    }
+
+    SubstitutionResult { output, spans }
+}
+
+/// Substitute every resolvable placeholder, returning the output and the byte range
+/// each top-level substituted variable occupies in it
+fn substitute(text: &str, lookup: impl Fn(&str) -> Option<String>) -> (String, Vec<(String, Range<usize>)>) {
+    substitute_nested(text, &lookup, &mut Vec::new())
+}
+
+/// `resolving` holds the variables whose values are being expanded, outermost first
+fn substitute_nested(
+    text: &str,
+    lookup: &dyn Fn(&str) -> Option<String>,
+    resolving: &mut Vec<String>,
+) -> (String, Vec<(String, Range<usize>)>) {
+    let mut output = String::with_capacity(text.len());
+    let mut substitutions = Vec::new();
+    let mut last = 0;
+
+    for caps in placeholder_pattern().captures_iter(text) {
+        let placeholder = caps.get(0).unwrap();
+        let (name, fallback) = parse_placeholder(&caps[1]);
+        output.push_str(&text[last..placeholder.start()]);
+        let value = if resolving.contains(&name) {
+            None // a cycle; leave the placeholder rather than loop
+        } else {
+            lookup(&name)
+                .map(|value| {
+                    if resolving.len() >= MAX_NESTING_DEPTH {
+                        return value;
+                    }
+                    resolving.push(name.clone());
+                    let (expanded, _) = substitute_nested(&value, lookup, resolving);
+                    resolving.pop();
+                    expanded
+                })
+                .or_else(|| dynamic_value(&name))
+                .or_else(|| fallback.map(str::to_string))
+        };
+        match value {
+            Some(value) => {
+                let start = output.len();
+                output.push_str(&value);
+                substitutions.push((name, start..output.len()));
+            }
+            None => output.push_str(placeholder.as_str()),
+        }
+        last = placeholder.end();
+    }
+    output.push_str(&text[last..]);
+
+    (output, substitutions)
+}
+
+/// Names of the variables referenced in `text`, in order of first use and without fallbacks
+pub fn extract_variables(text: &str) -> Vec<String> {
+    let mut variables: Vec<String> = Vec::new();
+    for caps in placeholder_pattern().captures_iter(text) {
+        let (name, _) = parse_placeholder(&caps[1]);
+        if !variables.contains(&name) {
+            variables.push(name);
+        }
+    }
+    variables
+}
+
+/// Split a placeholder body into the variable name and its fallback at the first unescaped pipe
+pub fn parse_placeholder(body: &str) -> (String, Option<&str>) {
+    let mut name = String::new();
+    let mut chars = body.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '\\' if chars.peek().map(|(_, next)| *next) == Some('|') => {
+                name.push('|');
+                chars.next();
+            }
+            '|' => return (name, Some(&body[i + 1..])),
+            c => name.push(c),
+        }
+    }
+    (name, None)
+}
+
+/// Generate a value for a built-in variable such as `$uuid` or `$randomInt:1:100`
+fn dynamic_value(name: &str) -> Option<String> {
+    let mut parts = name.strip_prefix('$')?.split(':');
+    match (parts.next()?, parts.next(), parts.next(), parts.next()) {
+        ("uuid", None, ..) => Some(uuid::Uuid::new_v4().to_string()),
+        ("timestamp", None, ..) => Some(chrono::Utc::now().timestamp().to_string()),
+        ("isoTimestamp", None, ..) => Some(chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true)),
+        ("randomInt", None, ..) => Some(rand::thread_rng().gen_range(0..=1000).to_string()),
+        ("randomInt", Some(min), Some(max), None) => {
+            let (min, max) = (min.trim().parse::<i64>().ok()?, max.trim().parse::<i64>().ok()?);
+            (min <= max).then(|| rand::thread_rng().gen_range(min..=max).to_string())
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dynamic_variables_regenerate() {
+        let none = HashMap::new();
+
+        let first = substitute_variables("{{$uuid}}", &none);
+        let second = substitute_variables("{{$uuid}}", &none);
+        assert!(uuid::Uuid::parse_str(&first).is_ok());
+        assert_ne!(first, second);
+
+        // Each placeholder gets its own value, even within one text
+        let pair = substitute_variables("{{$uuid}} {{$uuid}}", &none);
+        let (a, b) = pair.split_once(' ').unwrap();
+        assert_ne!(a, b);
+
+        for _ in 0..20 {
+            assert_eq!(substitute_variables("{{$randomInt:5:5}}", &none), "5");
+            let value: i64 = substitute_variables("{{$randomInt}}", &none).parse().unwrap();
+            assert!((0..=1000).contains(&value));
+        }
+
+        let timestamp: i64 = substitute_variables("{{$timestamp}}", &none).parse().unwrap();
+        assert!((timestamp - chrono::Utc::now().timestamp()).abs() < 5);
+        assert!(chrono::DateTime::parse_from_rfc3339(&substitute_variables("{{$isoTimestamp}}", &none)).is_ok());
+    }
+
+    #[test]
+    fn test_user_variables_take_precedence() {
+        let variables = HashMap::from([
+            ("$uuid".to_string(), "fixed-id".to_string()),
+            ("host".to_string(), "api.example.com".to_string()),
+        ]);
+
+        assert_eq!(
+            substitute_variables("https://{{host}}/items/{{$uuid}}", &variables),
+            "https://api.example.com/items/fixed-id"
+        );
+        // Unknown and malformed placeholders are left for the user to notice
+        assert_eq!(substitute_variables("{{missing}} {{$randomInt:9:1}}", &variables), "{{missing}} {{$randomInt:9:1}}");
+    }
+
+    #[test]
+    fn test_spans_locate_substituted_variables() {
+        let variable = |key: &str, value: &str, is_secret: bool| EnvironmentVariable {
+            key: key.to_string(),
+            value: value.to_string(),
+            is_secret,
+            variable_type: VariableType::String,
+        };
+        let variables = HashMap::from([
+            ("host".to_string(), variable("host", "api.example.com", false)),
+            ("token".to_string(), variable("token", "s3cr3t", true)),
+        ]);
+
+        let result = substitute_with_spans("https://{{host}}/users?key={{token}}&q={{missing}}", &variables);
+
+        assert_eq!(result.output, "https://api.example.com/users?key=s3cr3t&q={{missing}}");
+        assert_eq!(result.spans.len(), 2);
+        let host = &result.spans[0];
+        assert_eq!((host.variable_name.as_str(), host.was_secret), ("host", false));
+        assert_eq!(&result.output[host.start..host.end], "api.example.com");
+        let token = &result.spans[1];
+        assert_eq!((token.variable_name.as_str(), token.was_secret), ("token", true));
+        assert_eq!(&result.output[token.start..token.end], "s3cr3t");
+
+        // Offsets count UTF-16 code units, so text before a span shifts it as it would in the editor
+        let result = substitute_with_spans("é🚀/{{host}}", &variables);
+        assert_eq!((result.spans[0].start, result.spans[0].end), (4, 19));
+    }
+
+    #[test]
+    fn test_placeholder_defaults() {
+        let variables = HashMap::from([("host".to_string(), "api.example.com".to_string())]);
+
+        // Present: the variable wins over its fallback
+        assert_eq!(substitute_variables("https://{{host|localhost}}/", &variables), "https://api.example.com/");
+        // Absent with a fallback, which may itself contain pipes or be empty
+        assert_eq!(substitute_variables("{{port|8080}}", &variables), "8080");
+        assert_eq!(substitute_variables("{{sep|a|b}}{{suffix|}}", &variables), "a|b");
+        // Absent without a fallback stays visible
+        assert_eq!(substitute_variables("{{port}}", &variables), "{{port}}");
+        // An escaped pipe belongs to the name
+        let piped = HashMap::from([("a|b".to_string(), "joined".to_string())]);
+        assert_eq!(substitute_variables(r"{{a\|b|fallback}}", &piped), "joined");
+
+        assert_eq!(
+            extract_variables("{{host|localhost}}:{{port|80}}/{{host}}/{{$uuid}}"),
+            vec!["host", "port", "$uuid"]
+        );
+    }
+
+    #[test]
+    fn test_nested_variables() {
+        let variables = HashMap::from([
+            ("scheme".to_string(), "https".to_string()),
+            ("host".to_string(), "{{subdomain}}.example.com".to_string()),
+            ("subdomain".to_string(), "api".to_string()),
+            ("base_url".to_string(), "{{scheme}}://{{host}}".to_string()),
+        ]);
+
+        assert_eq!(substitute_variables("{{base_url}}/users", &variables), "https://api.example.com/users");
+    }
+
+    #[test]
+    fn test_cyclic_variables_terminate() {
+        let variables = HashMap::from([
+            ("self".to_string(), "x{{self}}".to_string()),
+            ("ping".to_string(), "{{pong}}".to_string()),
+            ("pong".to_string(), "{{ping}}".to_string()),
+        ]);
+
+        assert_eq!(substitute_variables("{{self}}", &variables), "x{{self}}");
+        assert_eq!(substitute_variables("{{ping}}", &variables), "{{ping}}");
+
+        // A chain deeper than the limit stops expanding instead of recursing further
+        let chain: HashMap<String, String> = (0..20).map(|i| (format!("v{}", i), format!("{{{{v{}}}}}", i + 1))).collect();
+        assert_eq!(substitute_variables("{{v0}}", &chain), "{{v11}}");
+    }
+}
@@ -0,0 +1,222 @@
+use crate::models::websocket::{WebSocketEvent, WebSocketFrame};
+use crate::services::variable_substitution;
+use anyhow::{anyhow, Result};
+use futures_util::{SinkExt, StreamExt};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::http::{HeaderName, HeaderValue};
+use tokio_tungstenite::tungstenite::protocol::frame::coding::CloseCode;
+use tokio_tungstenite::tungstenite::protocol::CloseFrame;
+use tokio_tungstenite::tungstenite::Message;
+
+/// Tauri event name for frames received on any connection
+pub const WEBSOCKET_EVENT: &str = "websocket-event";
+
+// How long close_all waits for servers to acknowledge before dropping connections
+const CLOSE_TIMEOUT: Duration = Duration::from_secs(2);
+
+#[derive(Clone, Default)]
+pub struct WebSocketService {
+    // Open connections, keyed by connection id
+    connections: Arc<Mutex<HashMap<String, Connection>>>,
+}
+
+struct Connection {
+    outgoing: mpsc::UnboundedSender<Message>,
+    task: JoinHandle<()>,
+}
+
+impl WebSocketService {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Open a connection, substituting variables in the URL and headers. Every frame received
+    /// is passed to `on_event`, ending with a `Closed` frame. Returns the connection id.
+    pub async fn connect<F>(
+        &self,
+        url: &str,
+        headers: &HashMap<String, String>,
+        environment_variables: &HashMap<String, String>,
+        on_event: F,
+    ) -> Result<String>
+    where
+        F: Fn(WebSocketEvent) + Send + 'static,
+    {
+        let url = variable_substitution::substitute_variables(url, environment_variables);
+        let mut request = url
+            .as_str()
+            .into_client_request()
+            .map_err(|e| anyhow!("Invalid WebSocket URL '{}': {}", url, e))?;
+        for (name, value) in headers {
+            let value = variable_substitution::substitute_variables(value, environment_variables);
+            request.headers_mut().insert(
+                HeaderName::from_bytes(name.as_bytes()).map_err(|e| anyhow!("Invalid header name '{}': {}", name, e))?,
+                HeaderValue::from_str(&value).map_err(|e| anyhow!("Invalid value for header '{}': {}", name, e))?,
+            );
+        }
+
+        let (stream, _) = tokio_tungstenite::connect_async(request)
+            .await
+            .map_err(|e| anyhow!("WebSocket connection failed: {}", e))?;
+        let (mut sink, mut source) = stream.split();
+        let (outgoing, mut outgoing_rx) = mpsc::unbounded_channel::<Message>();
+
+        let connection_id = uuid::Uuid::new_v4().to_string();
+        let id = connection_id.clone();
+        let connections = self.connections.clone();
+        let emit = move |frame| on_event(WebSocketEvent { connection_id: id.clone(), frame });
+
+        // Holding the lock until the task is registered keeps it from removing itself first
+        let mut open = self.connections.lock().map_err(|e| anyhow!("Lock error: {}", e))?;
+        let id = connection_id.clone();
+        let task = tokio::spawn(async move {
+            let mut close_frame = None;
+            loop {
+                tokio::select! {
+                    Some(message) = outgoing_rx.recv() => {
+                        if let Err(e) = sink.send(message).await {
+                            emit(WebSocketFrame::Error { message: format!("Send failed: {}", e) });
+                            break;
+                        }
+                    }
+                    incoming = source.next() => match incoming {
+                        Some(Ok(Message::Text(data))) => emit(WebSocketFrame::Text { data }),
+                        Some(Ok(Message::Binary(data))) => emit(WebSocketFrame::Binary { data }),
+                        // The reply is queued automatically; keep reading until the stream ends
+                        Some(Ok(Message::Close(frame))) => close_frame = frame,
+                        Some(Ok(_)) => {} // pings are answered automatically
+                        Some(Err(e)) => {
+                            emit(WebSocketFrame::Error { message: e.to_string() });
+                            break;
+                        }
+                        None => break,
+                    }
+                }
+            }
+
+            emit(WebSocketFrame::Closed {
+                code: close_frame.as_ref().map(|frame| u16::from(frame.code)),
+                reason: close_frame.map(|frame| frame.reason.to_string()).unwrap_or_default(),
+            });
+            if let Ok(mut connections) = connections.lock() {
+                connections.remove(&id);
+            }
+        });
+        open.insert(connection_id.clone(), Connection { outgoing, task });
+
+        Ok(connection_id)
+    }
+
+    /// Send a text frame on an open connection
+    pub fn send(&self, connection_id: &str, message: String) -> Result<()> {
+        self.queue(connection_id, Message::Text(message))
+    }
+
+    /// Start a normal close. The `Closed` event follows once the server acknowledges.
+    pub fn close(&self, connection_id: &str) -> Result<()> {
+        self.queue(connection_id, Message::Close(Some(Self::normal_close())))
+    }
+
+    /// Close every connection, e.g. on app exit, waiting briefly for acknowledgements
+    pub async fn close_all(&self) {
+        let connections: Vec<Connection> = match self.connections.lock() {
+            Ok(mut connections) => connections.drain().map(|(_, connection)| connection).collect(),
+            Err(_) => return,
+        };
+
+        let mut tasks = Vec::new();
+        for connection in connections {
+            let _ = connection.outgoing.send(Message::Close(Some(Self::normal_close())));
+            tasks.push(connection.task);
+        }
+        for task in tasks {
+            let abort = task.abort_handle();
+            if tokio::time::timeout(CLOSE_TIMEOUT, task).await.is_err() {
+                abort.abort();
+            }
+        }
+    }
+
+    fn queue(&self, connection_id: &str, message: Message) -> Result<()> {
+        let connections = self.connections.lock().map_err(|e| anyhow!("Lock error: {}", e))?;
+        let connection = connections
+            .get(connection_id)
+            .ok_or_else(|| anyhow!("WebSocket connection {} not found", connection_id))?;
+        connection
+            .outgoing
+            .send(message)
+            .map_err(|_| anyhow!("WebSocket connection {} is closed", connection_id))
+    }
+
+    fn normal_close() -> CloseFrame<'static> {
+        CloseFrame { code: CloseCode::Normal, reason: "".into() }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::net::TcpListener;
+
+    /// Echo text frames back until the client closes
+    async fn start_echo_server() -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                tokio::spawn(async move {
+                    let mut socket = tokio_tungstenite::accept_async(stream).await.unwrap();
+                    while let Some(Ok(message)) = socket.next().await {
+                        if message.is_text() && socket.send(message).await.is_err() {
+                            break;
+                        }
+                    }
+                });
+            }
+        });
+        format!("ws://{}", addr)
+    }
+
+    #[tokio::test]
+    async fn test_text_round_trip_and_close() {
+        let base_url = start_echo_server().await;
+        let (events_tx, mut events) = mpsc::unbounded_channel();
+        let service = WebSocketService::new();
+
+        let variables = HashMap::from([("host".to_string(), base_url)]);
+        let connection_id = service
+            .connect("{{host}}/echo", &HashMap::new(), &variables, move |event| {
+                let _ = events_tx.send(event);
+            })
+            .await
+            .unwrap();
+
+        service.send(&connection_id, "hello".to_string()).unwrap();
+        let event = tokio::time::timeout(Duration::from_secs(5), events.recv()).await.unwrap().unwrap();
+        assert_eq!(event.connection_id, connection_id);
+        assert_eq!(event.frame, WebSocketFrame::Text { data: "hello".to_string() });
+
+        service.close(&connection_id).unwrap();
+        let event = tokio::time::timeout(Duration::from_secs(5), events.recv()).await.unwrap().unwrap();
+        assert_eq!(event.frame, WebSocketFrame::Closed { code: Some(1000), reason: String::new() });
+
+        // The connection is forgotten once closed
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(service.send(&connection_id, "again".to_string()).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_close_all() {
+        let base_url = start_echo_server().await;
+        let service = WebSocketService::new();
+        let connection_id = service.connect(&base_url, &HashMap::new(), &HashMap::new(), |_| {}).await.unwrap();
+
+        service.close_all().await;
+        assert!(service.send(&connection_id, "hello".to_string()).is_err());
+    }
+}
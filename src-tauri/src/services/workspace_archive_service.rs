@@ -0,0 +1,352 @@
+use crate::models::environment::{Environment, EnvironmentVariable, VariableType};
+use crate::models::workspace::{
+    ArchivedCollection, ArchivedEnvironment, CreateWorkspaceRequest, Workspace, WorkspaceArchive, WorkspaceSettings,
+};
+use crate::models::collection::{CreateCollectionRequest, Request};
+use crate::services::collection_service::CollectionService;
+use crate::services::database_service::DatabaseService;
+use crate::services::environment_service::EnvironmentService;
+use crate::services::file_sync_service::expand_tilde_path;
+use anyhow::{anyhow, Result};
+use chrono::Utc;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::sync::Arc;
+use uuid::Uuid;
+
+const ARCHIVE_FORMAT_VERSION: u32 = 1;
+
+/// Hands a whole workspace to someone without Git access: its collections, environments and
+/// settings in one JSON file, recreated on import through the collection and environment services
+pub struct WorkspaceArchiveService {
+    database: Arc<DatabaseService>,
+}
+
+impl WorkspaceArchiveService {
+    pub fn new(database: Arc<DatabaseService>) -> Self {
+        Self { database }
+    }
+
+    /// Write the workspace to `output_path`. Without `include_secrets`, secret environment
+    /// variables are left out (their keys are listed in the archive), and proxy passwords,
+    /// client certificate passphrases and OAuth2 client secrets are blanked.
+    pub async fn export_workspace(&self, workspace_id: &str, output_path: &Path, include_secrets: bool) -> Result<()> {
+        let workspace = self.database.get_workspace(workspace_id).await?
+            .ok_or_else(|| anyhow!("Workspace not found"))?;
+        let collection_service = CollectionService::new(self.database.get_pool());
+        let environment_service = EnvironmentService::new(self.database.clone());
+
+        let mut collections = Vec::new();
+        for collection in collection_service.list_collections(workspace_id).await? {
+            let mut requests = collection_service.list_requests(&collection.id).await?;
+            if !include_secrets {
+                requests.iter_mut().for_each(strip_client_secret);
+            }
+            collections.push(ArchivedCollection { collection, requests });
+        }
+
+        let mut environments = Vec::new();
+        for mut environment in environment_service.list_environments(workspace_id).await? {
+            let mut omitted_secrets = Vec::new();
+            if !include_secrets {
+                environment.variables.retain(|key, variable| {
+                    let secret = is_secret(variable);
+                    if secret {
+                        omitted_secrets.push(key.clone());
+                    }
+                    !secret
+                });
+                omitted_secrets.sort();
+            }
+            environments.push(ArchivedEnvironment { environment, omitted_secrets });
+        }
+
+        let mut settings = self.database.get_workspace_settings(workspace_id).await?;
+        if let Some(settings) = settings.as_mut().filter(|_| !include_secrets) {
+            settings.proxy_password = None;
+            settings.client_cert_password = None;
+        }
+
+        let archive = WorkspaceArchive {
+            format_version: ARCHIVE_FORMAT_VERSION,
+            exported_at: Utc::now(),
+            name: workspace.name,
+            description: workspace.description,
+            settings,
+            collections,
+            environments,
+        };
+        let contents = serde_json::to_string_pretty(&archive)?;
+        tokio::fs::write(output_path, contents)
+            .await
+            .map_err(|e| anyhow!("Failed to write archive to {}: {}", output_path.display(), e))?;
+        Ok(())
+    }
+
+    /// Recreate an archived workspace at `local_path` under fresh IDs. Existing workspaces are
+    /// never touched: a name already in use gets an "(imported)" suffix, and a directory that
+    /// already belongs to a workspace is refused. A failed import removes the new workspace.
+    pub async fn import_workspace(&self, archive_path: &Path, local_path: &str) -> Result<Workspace> {
+        let contents = tokio::fs::read_to_string(archive_path)
+            .await
+            .map_err(|e| anyhow!("Failed to read archive {}: {}", archive_path.display(), e))?;
+        let archive: WorkspaceArchive = serde_json::from_str(&contents)
+            .map_err(|e| anyhow!("Not a workspace archive: {}", e))?;
+        if archive.format_version > ARCHIVE_FORMAT_VERSION {
+            return Err(anyhow!("Archive format {} is newer than this version of Postgirl supports", archive.format_version));
+        }
+
+        let existing = self.database.get_all_workspaces().await?;
+        let workspace_path = expand_tilde_path(local_path);
+        if let Some(owner) = existing.iter().find(|w| expand_tilde_path(&w.local_path) == workspace_path) {
+            return Err(anyhow!("'{}' is already used by workspace '{}'", local_path, owner.name));
+        }
+        let names: Vec<String> = existing.into_iter().map(|w| w.name).collect();
+
+        let workspace = Workspace::new(CreateWorkspaceRequest {
+            name: import_name(&archive.name, &names),
+            description: archive.description.clone(),
+            git_repository_url: None,
+            local_path: local_path.to_string(),
+            git_username: None,
+            git_email: None,
+        });
+        for dir in ["collections", "environments", ".postgirl"] {
+            tokio::fs::create_dir_all(Path::new(&workspace_path).join(dir))
+                .await
+                .map_err(|e| anyhow!("Failed to create workspace directory '{}': {}", workspace_path, e))?;
+        }
+        self.database.create_workspace(&workspace).await?;
+
+        if let Err(e) = self.recreate_contents(&workspace, archive).await {
+            if let Err(cleanup) = self.database.delete_workspace(&workspace.id).await {
+                eprintln!("Warning: Failed to remove partially imported workspace: {}", cleanup);
+            }
+            return Err(e);
+        }
+        Ok(workspace)
+    }
+
+    async fn recreate_contents(&self, workspace: &Workspace, archive: WorkspaceArchive) -> Result<()> {
+        if let Some(settings) = archive.settings {
+            let now = Utc::now();
+            self.database
+                .create_workspace_settings(&WorkspaceSettings {
+                    id: Uuid::new_v4().to_string(),
+                    workspace_id: workspace.id.clone(),
+                    created_at: now,
+                    updated_at: now,
+                    ..settings
+                })
+                .await?;
+        }
+
+        // Parents are created before their children so the new parent IDs are known
+        let collection_service = CollectionService::new(self.database.get_pool());
+        let archived_ids: HashSet<String> = archive.collections.iter().map(|c| c.collection.id.clone()).collect();
+        let mut collection_ids: HashMap<String, String> = HashMap::new();
+        let mut pending = archive.collections;
+        while !pending.is_empty() {
+            let (ready, waiting): (Vec<_>, Vec<_>) = pending.into_iter().partition(|archived| {
+                archived.collection.parent_id.as_ref()
+                    .filter(|parent_id| archived_ids.contains(*parent_id))
+                    .is_none_or(|parent_id| collection_ids.contains_key(parent_id))
+            });
+            if ready.is_empty() {
+                return Err(anyhow!("Archive contains a cycle of nested collections"));
+            }
+
+            for ArchivedCollection { collection, mut requests } in ready {
+                let created = collection_service
+                    .create_collection(CreateCollectionRequest {
+                        workspace_id: workspace.id.clone(),
+                        name: collection.name,
+                        description: collection.description,
+                        folder_path: collection.folder_path,
+                        git_branch: collection.git_branch,
+                        parent_id: collection.parent_id.and_then(|parent_id| collection_ids.get(&parent_id).cloned()),
+                    })
+                    .await?;
+                requests.sort_by_key(|request| request.order_index);
+                collection_service.copy_requests(&requests, &created.id).await?;
+                collection_ids.insert(collection.id, created.id);
+            }
+            pending = waiting;
+        }
+
+        // Environments are all created first so any of them can be another's parent
+        let environment_service = EnvironmentService::new(self.database.clone());
+        let mut created = Vec::new();
+        let mut environment_ids: HashMap<String, String> = HashMap::new();
+        for ArchivedEnvironment { environment, .. } in archive.environments {
+            let new = environment_service.create_environment(workspace.id.clone(), environment.name.clone()).await?;
+            environment_ids.insert(environment.id.clone(), new.id.clone());
+            created.push((new, environment));
+        }
+        for (new, original) in created {
+            environment_service
+                .update_environment(Environment {
+                    variables: original.variables,
+                    is_active: original.is_active,
+                    is_protected: original.is_protected,
+                    parent_id: original.parent_id.and_then(|parent_id| environment_ids.get(&parent_id).cloned()),
+                    ..new
+                })
+                .await?;
+        }
+
+        Ok(())
+    }
+}
+
+fn is_secret(variable: &EnvironmentVariable) -> bool {
+    variable.is_secret || variable.variable_type == VariableType::Secret
+}
+
+fn strip_client_secret(request: &mut Request) {
+    if request.auth_type.as_deref() != Some("oauth2_client_credentials") {
+        return;
+    }
+    if let Some(mut config) = request.get_auth_config().ok().flatten() {
+        if let Some(secret) = config.get_mut("client_secret") {
+            *secret = serde_json::Value::String(String::new());
+            request.auth_config = Some(config.to_string());
+        }
+    }
+}
+
+/// `name`, or "name (imported)" / "name (imported 2)"... when a workspace already has it
+fn import_name(name: &str, existing: &[String]) -> String {
+    let taken = |candidate: &str| existing.iter().any(|n| n == candidate);
+    if !taken(name) {
+        return name.to_string();
+    }
+    std::iter::once(format!("{} (imported)", name))
+        .chain((2..).map(|n| format!("{} (imported {})", name, n)))
+        .find(|candidate| !taken(candidate))
+        .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::collection::CreateRequestRequest;
+    use crate::models::environment::MergeMode;
+    use crate::tests::test_database::add_workspace;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_workspace_archive_round_trip() {
+        let temp_dir = TempDir::new().unwrap();
+        let database = Arc::new(DatabaseService::new("sqlite::memory:").await.unwrap());
+        let workspace = add_workspace(&database, "Payments", temp_dir.path().join("payments").to_str().unwrap()).await;
+        database
+            .create_workspace_settings(&WorkspaceSettings {
+                workspace_id: workspace.id.clone(),
+                default_timeout: 5000,
+                proxy_password: Some("hunter2".to_string()),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        let collections = CollectionService::new(database.get_pool());
+        let collection_request = |name: &str, parent_id: Option<String>| CreateCollectionRequest {
+            workspace_id: workspace.id.clone(),
+            name: name.to_string(),
+            description: None,
+            folder_path: None,
+            git_branch: None,
+            parent_id,
+        };
+        let api = collections.create_collection(collection_request("API", None)).await.unwrap();
+        let users = collections.create_collection(collection_request("Users", Some(api.id.clone()))).await.unwrap();
+        let request = collections
+            .create_request(CreateRequestRequest {
+                collection_id: users.id.clone(),
+                name: "List users".to_string(),
+                description: None,
+                method: "GET".to_string(),
+                url: "{{BASE_URL}}/users".to_string(),
+                headers: None,
+                body: None,
+                body_type: None,
+                auth_type: None,
+                auth_config: None,
+                follow_redirects: None,
+                timeout_ms: None,
+                order_index: None,
+            })
+            .await
+            .unwrap();
+        collections.set_request_connect_timeout(&request.id, Some(750)).await.unwrap();
+
+        let environments = EnvironmentService::new(database.clone());
+        let dev = environments.create_environment(workspace.id.clone(), "Dev".to_string()).await.unwrap();
+        let variable = |key: &str, value: &str, variable_type: VariableType| EnvironmentVariable {
+            key: key.to_string(),
+            value: value.to_string(),
+            is_secret: variable_type == VariableType::Secret,
+            variable_type,
+        };
+        environments
+            .set_variables(
+                &dev.id,
+                vec![variable("BASE_URL", "https://api.example.com", VariableType::Url), variable("TOKEN", "s3cret", VariableType::Secret)],
+                MergeMode::Replace,
+            )
+            .await
+            .unwrap();
+
+        let service = WorkspaceArchiveService::new(database.clone());
+        let archive_path = temp_dir.path().join("payments.json");
+        service.export_workspace(&workspace.id, &archive_path, false).await.unwrap();
+        let archive = std::fs::read_to_string(&archive_path).unwrap();
+        assert!(!archive.contains("s3cret") && !archive.contains("hunter2"));
+
+        // Importing twice never clobbers: each import is a new workspace under a free name
+        let imported_path = temp_dir.path().join("imported");
+        let imported = service.import_workspace(&archive_path, imported_path.to_str().unwrap()).await.unwrap();
+        assert_eq!(imported.name, "Payments (imported)");
+        assert_ne!(imported.id, workspace.id);
+        assert!(service.import_workspace(&archive_path, imported_path.to_str().unwrap()).await.is_err());
+        let again = service
+            .import_workspace(&archive_path, temp_dir.path().join("again").to_str().unwrap())
+            .await
+            .unwrap();
+        assert_eq!(again.name, "Payments (imported 2)");
+
+        let settings = database.get_workspace_settings(&imported.id).await.unwrap().unwrap();
+        assert_eq!(settings.default_timeout, 5000);
+        assert_eq!(settings.proxy_password, None);
+
+        let imported_collections = collections.list_collections(&imported.id).await.unwrap();
+        assert_eq!(imported_collections.len(), 2);
+        let imported_api = imported_collections.iter().find(|c| c.name == "API").unwrap();
+        let imported_users = imported_collections.iter().find(|c| c.name == "Users").unwrap();
+        assert_ne!(imported_api.id, api.id);
+        assert_eq!(imported_users.parent_id.as_deref(), Some(imported_api.id.as_str()));
+        let imported_requests = collections.list_requests(&imported_users.id).await.unwrap();
+        assert_eq!(imported_requests.len(), 1);
+        assert_eq!(imported_requests[0].url, "{{BASE_URL}}/users");
+        assert_eq!(imported_requests[0].connect_timeout_ms, Some(750));
+
+        let imported_environments = environments.list_environments(&imported.id).await.unwrap();
+        assert_eq!(imported_environments.len(), 1);
+        let imported_dev = &imported_environments[0];
+        assert_ne!(imported_dev.id, dev.id);
+        assert_eq!(imported_dev.variables["BASE_URL"].variable_type, VariableType::Url);
+        assert!(!imported_dev.variables.contains_key("TOKEN"));
+
+        // The original is untouched
+        assert_eq!(database.get_workspace(&workspace.id).await.unwrap().unwrap().name, "Payments");
+        assert_eq!(collections.list_collections(&workspace.id).await.unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_import_name() {
+        let existing = vec!["API".to_string(), "API (imported)".to_string()];
+        assert_eq!(import_name("Web", &existing), "Web");
+        assert_eq!(import_name("API", &existing), "API (imported 2)");
+    }
+}
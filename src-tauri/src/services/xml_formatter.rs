@@ -0,0 +1,20 @@
+//! Reindenting of XML documents for display
+
+use quick_xml::events::Event;
+use quick_xml::{Reader, Writer};
+
+/// Reindent an XML document with two spaces per level. Returns `None` when the text isn't
+/// well-formed, so callers can fall back to showing it as received.
+pub fn pretty_print_xml(xml: &str) -> Option<String> {
+    let mut reader = Reader::from_str(xml);
+    reader.config_mut().trim_text(true);
+    let mut writer = Writer::new_with_indent(Vec::new(), b' ', 2);
+
+    loop {
+        match reader.read_event().ok()? {
+            Event::Eof => break,
+            event => writer.write_event(event).ok()?,
+        }
+    }
+    String::from_utf8(writer.into_inner()).ok()
+}
@@ -0,0 +1,602 @@
+#[cfg(test)]
+mod tests {
+    use crate::models::collection::{
+        Assertion, AssertionOperator, AssertionTarget, CreateRequestRequest, Extraction, ExtractionSource, PreRequestStep,
+        Request,
+    };
+    use crate::commands::collection::record_run_results;
+    use crate::commands::http::apply_workspace_settings;
+    use crate::models::collection_run::{RunPacing, RunStatus};
+    use crate::models::environment::{Environment, EnvironmentVariable, VariableType};
+    use crate::models::http::{ExecuteOptions, ResponseBody};
+    use crate::models::workspace::WorkspaceSettings;
+    use crate::services::collection_run_service::CollectionRunService;
+    use crate::services::environment_service::EnvironmentService;
+    use crate::services::http_service::HttpService;
+    use crate::services::last_response_service::LastResponseService;
+    use crate::services::request_history_service::RequestHistoryService;
+    use crate::tests::mock_server::{self, MockResponse};
+    use crate::tests::test_database::database_with_workspace;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{Arc, Mutex};
+    use std::time::{Duration, Instant};
+
+    /// The options a run in a workspace with these settings is sent with
+    async fn workspace_options(settings: WorkspaceSettings) -> ExecuteOptions {
+        workspace_options_over(settings, ExecuteOptions::default()).await
+    }
+
+    /// Like `workspace_options`, starting from options the caller already filled in
+    async fn workspace_options_over(settings: WorkspaceSettings, caller: ExecuteOptions) -> ExecuteOptions {
+        let (database, workspace) = database_with_workspace("/tmp/runs").await;
+        database
+            .create_workspace_settings(&WorkspaceSettings { workspace_id: workspace.id.clone(), ..settings })
+            .await
+            .unwrap();
+
+        let mut options = ExecuteOptions { workspace_id: Some(workspace.id), ..caller };
+        apply_workspace_settings(&database, &HttpService::new(), &mut options).await;
+        options
+    }
+
+    fn create_request(name: &str, url: String, order_index: i32) -> Request {
+        Request::new(CreateRequestRequest {
+            collection_id: "collection-1".to_string(),
+            name: name.to_string(),
+            description: None,
+            method: "GET".to_string(),
+            url,
+            headers: None,
+            body: None,
+            body_type: None,
+            auth_type: None,
+            auth_config: None,
+            follow_redirects: None,
+            timeout_ms: None,
+            order_index: Some(order_index),
+        })
+    }
+
+    #[tokio::test]
+    async fn test_run_skips_excluded_requests() {
+        let hits = Arc::new(AtomicUsize::new(0));
+        let counter = hits.clone();
+        let base_url = mock_server::start(move |_| {
+            counter.fetch_add(1, Ordering::SeqCst);
+            MockResponse::new(200, "ok").header("Content-Type", "text/plain")
+        }).await;
+
+        let mut cleanup = create_request("Cleanup", format!("{}/cleanup", base_url), 2);
+        cleanup.include_in_run = false;
+        let requests = vec![
+            create_request("List", format!("{}/list", base_url), 0),
+            create_request("Get", format!("{}/get", base_url), 1),
+            cleanup,
+        ];
+
+        let result = CollectionRunService::new(HttpService::new())
+            .run("collection-1", &requests, None, true, &RunPacing::default(), 1, &ExecuteOptions::default())
+            .await;
+
+        assert_eq!(hits.load(Ordering::SeqCst), 2);
+        assert_eq!(result.executed, 2);
+        assert_eq!(result.skipped, 1);
+        assert_eq!(result.results.len(), 3);
+        assert_eq!(result.results[2].name, "Cleanup");
+        assert_eq!(result.results[2].status, RunStatus::Skipped);
+        assert!(result.results[..2].iter().all(|r| r.status == RunStatus::Passed));
+    }
+
+    #[tokio::test]
+    async fn test_run_in_order_with_summary() {
+        let paths = Arc::new(Mutex::new(Vec::new()));
+        let recorded = paths.clone();
+        let base_url = mock_server::start(move |request| {
+            recorded.lock().unwrap().push(request.path.clone());
+            let status = if request.path == "/orders" { 500 } else { 200 };
+            MockResponse::new(status, "{}").header("Content-Type", "application/json")
+        }).await;
+
+        let requests = vec![
+            create_request("Checkout", format!("{}/checkout", base_url), 2),
+            create_request("Login", format!("{}/login", base_url), 0),
+            create_request("Orders", format!("{}/orders", base_url), 1),
+        ];
+
+        let result = CollectionRunService::new(HttpService::new())
+            .run("collection-1", &requests, None, true, &RunPacing::default(), 1, &ExecuteOptions::default())
+            .await;
+
+        assert_eq!(*paths.lock().unwrap(), vec!["/login", "/orders", "/checkout"]);
+        let names: Vec<&str> = result.results.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(names, vec!["Login", "Orders", "Checkout"]);
+        assert_eq!(result.executed, 3);
+        assert_eq!(result.failed, 1);
+        assert_eq!(result.results[1].status, RunStatus::Failed);
+        assert!(result.total_time_ms >= result.results.iter().map(|r| r.duration_ms).max().unwrap());
+
+        // Stopping on error skips whatever comes after the failure
+        paths.lock().unwrap().clear();
+        let result = CollectionRunService::new(HttpService::new())
+            .run("collection-1", &requests, None, false, &RunPacing::default(), 1, &ExecuteOptions::default())
+            .await;
+
+        assert_eq!(*paths.lock().unwrap(), vec!["/login", "/orders"]);
+        assert_eq!(result.executed, 2);
+        assert_eq!(result.failed, 1);
+        assert_eq!(result.skipped, 1);
+        assert_eq!(result.results[2].status, RunStatus::Skipped);
+    }
+
+    #[tokio::test]
+    async fn test_run_chains_extracted_values() {
+        let base_url = mock_server::start(|request| match request.path.as_str() {
+            "/login" => MockResponse::new(200, r#"{"data": {"token": "abc123"}}"#)
+                .header("Content-Type", "application/json")
+                .header("X-Session-Id", "session-9"),
+            _ => {
+                // Echo back what the chained request was sent with
+                let auth = request.headers.get("authorization").cloned().unwrap_or_default();
+                let session = request.headers.get("x-session-id").cloned().unwrap_or_default();
+                MockResponse::new(200, format!("{}|{}", auth, session)).header("Content-Type", "text/plain")
+            }
+        }).await;
+
+        let mut login = create_request("Login", format!("{}/login", base_url), 0);
+        login.extractions = serde_json::to_string(&vec![
+            Extraction { variable: "token".to_string(), source: ExtractionSource::Body, path: "$.data.token".to_string() },
+            Extraction { variable: "session".to_string(), source: ExtractionSource::Header, path: "x-session-id".to_string() },
+        ]).unwrap();
+        let mut profile = create_request("Profile", format!("{}/profile", base_url), 1);
+        profile.headers = serde_json::json!({
+            "Authorization": "Bearer {{token}}",
+            "X-Session-Id": "{{session}}"
+        }).to_string();
+
+        let result = CollectionRunService::new(HttpService::new())
+            .run("collection-1", &[login, profile], None, true, &RunPacing::default(), 1, &ExecuteOptions::default())
+            .await;
+
+        assert_eq!(result.failed, 0);
+        match &result.results[1].response.as_ref().unwrap().body {
+            ResponseBody::Text { content, .. } => assert_eq!(content, "Bearer abc123|session-9"),
+            other => panic!("expected a text body, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_run_condition_on_extracted_value() {
+        let profile_hits = Arc::new(AtomicUsize::new(0));
+        let counter = profile_hits.clone();
+        let base_url = mock_server::start(move |request| match request.path.as_str() {
+            "/login/ok" => MockResponse::new(200, r#"{"token": "abc123", "role": "admin"}"#).header("Content-Type", "application/json"),
+            "/login/denied" => MockResponse::new(401, r#"{"error": "denied"}"#).header("Content-Type", "application/json"),
+            _ => {
+                counter.fetch_add(1, Ordering::SeqCst);
+                MockResponse::new(200, "{}").header("Content-Type", "application/json")
+            }
+        }).await;
+
+        let requests = |login_path: &str| {
+            let mut login = create_request("Login", format!("{}{}", base_url, login_path), 0);
+            login.extractions = serde_json::to_string(&vec![
+                Extraction { variable: "token".to_string(), source: ExtractionSource::Body, path: "$.token".to_string() },
+                Extraction { variable: "role".to_string(), source: ExtractionSource::Body, path: "$.role".to_string() },
+            ]).unwrap();
+            let mut profile = create_request("Profile", format!("{}/profile", base_url), 1);
+            profile.run_condition = Some("{{token}} exists".to_string());
+            let mut admin = create_request("Admin", format!("{}/admin", base_url), 2);
+            admin.run_condition = Some("{{role}} == \"admin\"".to_string());
+            vec![login, profile, admin]
+        };
+        let service = CollectionRunService::new(HttpService::new());
+
+        let result = service.run("collection-1", &requests("/login/ok"), None, true, &RunPacing::default(), 1, &ExecuteOptions::default()).await;
+        assert_eq!(result.executed, 3);
+        assert_eq!(result.skipped, 0);
+        assert_eq!(profile_hits.load(Ordering::SeqCst), 2);
+
+        // Without a token the dependent requests are skipped rather than sent
+        let result = service.run("collection-1", &requests("/login/denied"), None, true, &RunPacing::default(), 1, &ExecuteOptions::default()).await;
+        assert_eq!(result.results[0].status, RunStatus::Failed);
+        assert_eq!(result.executed, 1);
+        assert_eq!(result.skipped, 2);
+        assert_eq!(result.results[1].status, RunStatus::Skipped);
+        assert_eq!(result.results[1].skip_reason.as_deref(), Some("Condition not met: {{token}} exists"));
+        assert_eq!(profile_hits.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_run_applies_pre_request_steps() {
+        let base_url = mock_server::start(|request| {
+            let signature = request.headers.get("x-signature").cloned().unwrap_or_default();
+            MockResponse::new(200, signature).header("Content-Type", "text/plain")
+        }).await;
+
+        let mut signed = create_request("Signed", format!("{}/orders", base_url), 0);
+        signed.headers = serde_json::json!({ "X-Signature": "{{signature}}" }).to_string();
+        signed.pre_request = serde_json::to_string(&vec![
+            PreRequestStep::SetVariable { variable: "timestamp".to_string(), value: "1700000000".to_string() },
+            PreRequestStep::HmacSha256 {
+                variable: "signature".to_string(),
+                message: "POST\n/orders\n{{timestamp}}".to_string(),
+                secret_variable: "api_secret".to_string(),
+            },
+        ]).unwrap();
+        let variables = [("api_secret".to_string(), "s3cret".to_string())].into_iter().collect();
+
+        let result = CollectionRunService::new(HttpService::new())
+            .run("collection-1", &[signed.clone()], Some(variables), true, &RunPacing::default(), 1, &ExecuteOptions::default())
+            .await;
+        match &result.results[0].response.as_ref().unwrap().body {
+            ResponseBody::Text { content, .. } => {
+                assert_eq!(content, "cf5e3285242bbfea592468b994e0e1b481eae5928fdcb546e243e528b60f309d")
+            }
+            other => panic!("expected a text body, got {:?}", other),
+        }
+
+        // Without the secret the request fails before anything is sent
+        let result = CollectionRunService::new(HttpService::new())
+            .run("collection-1", &[signed], None, true, &RunPacing::default(), 1, &ExecuteOptions::default())
+            .await;
+        assert_eq!(result.results[0].status, RunStatus::Failed);
+        assert!(result.results[0].response.is_none());
+        assert_eq!(result.results[0].error.as_deref(), Some("HMAC secret variable 'api_secret' is not defined"));
+    }
+
+    #[tokio::test]
+    async fn test_run_uses_assertions_for_status() {
+        let base_url = mock_server::start(|request| match request.path.as_str() {
+            "/missing" => MockResponse::new(404, r#"{"ok": false}"#).header("Content-Type", "application/json"),
+            _ => MockResponse::new(200, r#"{"ok": false}"#).header("Content-Type", "application/json"),
+        }).await;
+
+        let assertion = |target, property: Option<&str>, operator, expected: &str| Assertion {
+            target,
+            property: property.map(str::to_string),
+            operator,
+            expected: expected.to_string(),
+        };
+        // An expected 404 passes, while a 200 with the wrong body fails
+        let mut missing = create_request("Missing", format!("{}/missing", base_url), 0);
+        missing.assertions = serde_json::to_string(&vec![
+            assertion(AssertionTarget::Status, None, AssertionOperator::Equals, "404"),
+        ]).unwrap();
+        let mut health = create_request("Health", format!("{}/health", base_url), 1);
+        health.assertions = serde_json::to_string(&vec![
+            assertion(AssertionTarget::Header, Some("Content-Type"), AssertionOperator::Contains, "json"),
+            assertion(AssertionTarget::JsonPath, Some("$.ok"), AssertionOperator::Equals, "true"),
+        ]).unwrap();
+
+        let result = CollectionRunService::new(HttpService::new())
+            .run("collection-1", &[missing, health], None, true, &RunPacing::default(), 1, &ExecuteOptions::default())
+            .await;
+
+        assert_eq!(result.results[0].status, RunStatus::Passed);
+        assert_eq!(result.results[1].status, RunStatus::Failed);
+        let checks = &result.results[1].assertions;
+        assert!(checks[0].passed);
+        assert!(!checks[1].passed);
+        assert_eq!(checks[1].message, "Expected $.ok to equal true, got false");
+    }
+
+    /// A server that answers 429 to requests sent within 200ms of the previous one
+    async fn rate_limited_server(statuses: Arc<Mutex<Vec<u16>>>) -> String {
+        let last_request: Arc<Mutex<Option<Instant>>> = Arc::new(Mutex::new(None));
+        mock_server::start(move |_| {
+            let mut last = last_request.lock().unwrap();
+            let too_soon = last.is_some_and(|at| at.elapsed() < Duration::from_millis(200));
+            *last = Some(Instant::now());
+            let status = if too_soon { 429 } else { 200 };
+            statuses.lock().unwrap().push(status);
+            MockResponse::new(status, "{}").header("Content-Type", "application/json").header("Retry-After", "1")
+        }).await
+    }
+
+    #[tokio::test]
+    async fn test_paced_run_stays_under_rate_limit() {
+        let statuses = Arc::new(Mutex::new(Vec::new()));
+        let base_url = rate_limited_server(statuses.clone()).await;
+        let requests: Vec<Request> = (0..4)
+            .map(|i| create_request(&format!("Page {}", i), format!("{}/page/{}", base_url, i), i))
+            .collect();
+
+        let pacing = RunPacing { requests_per_second: Some(4.0), delay_between_requests_ms: Some(100) };
+        assert_eq!(pacing.interval(), Some(Duration::from_millis(250)));
+        let result = CollectionRunService::new(HttpService::new())
+            .run("collection-1", &requests, None, true, &pacing, 1, &ExecuteOptions::default())
+            .await;
+
+        assert_eq!(*statuses.lock().unwrap(), vec![200; 4]);
+        assert_eq!(result.failed, 0);
+        assert!(result.throttle_events.is_empty());
+        assert!(result.total_time_ms >= 750);
+        assert!(result.pacing_wait_ms > 0);
+    }
+
+    #[tokio::test]
+    async fn test_unpaced_run_backs_off_on_429() {
+        let statuses = Arc::new(Mutex::new(Vec::new()));
+        let base_url = rate_limited_server(statuses.clone()).await;
+        let requests = vec![
+            create_request("First", format!("{}/first", base_url), 0),
+            create_request("Second", format!("{}/second", base_url), 1),
+        ];
+
+        let result = CollectionRunService::new(HttpService::new())
+            .run("collection-1", &requests, None, true, &RunPacing::default(), 1, &ExecuteOptions::default())
+            .await;
+
+        // The second request is throttled once, waits out Retry-After and then goes through
+        assert_eq!(*statuses.lock().unwrap(), vec![200, 429, 200]);
+        assert_eq!(result.failed, 0);
+        assert_eq!(result.results.len(), 2);
+        assert_eq!(result.throttle_events.len(), 1);
+        assert_eq!(result.throttle_events[0].name, "Second");
+        assert_eq!(result.throttle_events[0].waited_ms, 1000);
+        assert!(result.total_time_ms >= 1000);
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_run_keeps_order() {
+        let base_url = mock_server::start(|request| {
+            MockResponse::new(200, request.path).delay(Duration::from_millis(200))
+        }).await;
+        let requests: Vec<Request> = (0..10)
+            .map(|i| create_request(&format!("Smoke {}", i), format!("{}/smoke/{}", base_url, i), i))
+            .collect();
+
+        let result = CollectionRunService::new(HttpService::new())
+            .run("collection-1", &requests, None, true, &RunPacing::default(), 5, &ExecuteOptions::default())
+            .await;
+
+        // Two rounds of five rather than ten one after another
+        assert!(result.sequential_reason.is_none());
+        assert_eq!(result.executed, 10);
+        assert_eq!(result.failed, 0);
+        assert!(result.total_time_ms < 1000, "took {}ms", result.total_time_ms);
+        let names: Vec<_> = result.results.iter().map(|r| r.name.clone()).collect();
+        let expected: Vec<_> = (0..10).map(|i| format!("Smoke {}", i)).collect();
+        assert_eq!(names, expected);
+
+        // Extractions chain the requests, so the same run goes one at a time
+        let mut chained = requests.clone();
+        chained[0].extractions = serde_json::to_string(&vec![
+            Extraction { variable: "first".to_string(), source: ExtractionSource::Header, path: "content-length".to_string() },
+        ]).unwrap();
+        let result = CollectionRunService::new(HttpService::new())
+            .run("collection-1", &chained, None, true, &RunPacing::default(), 5, &ExecuteOptions::default())
+            .await;
+        assert!(result.sequential_reason.unwrap().contains("Smoke 0"));
+        assert!(result.total_time_ms >= 2000);
+    }
+
+    #[tokio::test]
+    async fn test_run_keeps_workspace_session_cookies() {
+        let base_url = mock_server::start(|request| match request.path.as_str() {
+            "/login" => MockResponse::new(200, "ok").header("Set-Cookie", "session=abc123; Path=/"),
+            _ if request.headers.get("cookie").is_some_and(|c| c.contains("session=abc123")) => MockResponse::new(200, "profile"),
+            _ => MockResponse::new(401, "no session"),
+        }).await;
+        let requests = vec![
+            create_request("Login", format!("{}/login", base_url), 0),
+            create_request("Profile", format!("{}/profile", base_url), 1),
+        ];
+        let service = CollectionRunService::new(HttpService::new());
+
+        // The workspace's cookie jar carries the session from one request to the next
+        let options = ExecuteOptions { workspace_id: Some("workspace-1".to_string()), ..Default::default() };
+        let result = service.run("collection-1", &requests, None, true, &RunPacing::default(), 1, &options).await;
+        assert_eq!(result.failed, 0);
+
+        // Without a workspace there's no jar to keep it in
+        let service = CollectionRunService::new(HttpService::new());
+        let result = service.run("collection-1", &requests, None, true, &RunPacing::default(), 1, &ExecuteOptions::default()).await;
+        assert_eq!(result.results[1].status, RunStatus::Failed);
+    }
+
+    #[tokio::test]
+    async fn test_run_goes_through_workspace_proxy() {
+        // A proxy receives the absolute URL in the request line
+        let proxy_url = mock_server::start(|request| MockResponse::new(200, request.path)).await;
+        let options = workspace_options(WorkspaceSettings { proxy_url: Some(proxy_url), ..Default::default() }).await;
+        let requests = vec![create_request("Users", "http://api.postgirl.test/users".to_string(), 0)];
+
+        let result = CollectionRunService::new(HttpService::new())
+            .run("collection-1", &requests, None, true, &RunPacing::default(), 1, &options)
+            .await;
+
+        assert_eq!(result.failed, 0);
+        match &result.results[0].response.as_ref().unwrap().body {
+            ResponseBody::Text { content, .. } => assert_eq!(content, "http://api.postgirl.test/users"),
+            other => panic!("Expected text body, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_run_uses_workspace_tls_settings() {
+        let base_url = mock_server::start(|_| MockResponse::new(200, "ok")).await;
+        let options = workspace_options(WorkspaceSettings {
+            verify_ssl: false,
+            ca_bundle_path: Some("/nonexistent/ca.pem".to_string()),
+            ..Default::default()
+        }).await;
+        assert!(options.tls.as_ref().unwrap().accept_invalid_certs);
+
+        // The unreadable CA bundle fails the send, so the run did pick up the workspace's TLS
+        let requests = vec![create_request("Health", format!("{}/health", base_url), 0)];
+        let result = CollectionRunService::new(HttpService::new())
+            .run("collection-1", &requests, None, true, &RunPacing::default(), 1, &options)
+            .await;
+        assert_eq!(result.results[0].status, RunStatus::Failed);
+        assert!(result.results[0].error.as_ref().unwrap().contains("CA bundle"));
+    }
+
+    #[tokio::test]
+    async fn test_run_uses_workspace_connect_timeout() {
+        let options = workspace_options(WorkspaceSettings { connect_timeout_ms: 1500, ..Default::default() }).await;
+        assert_eq!(options.connect_timeout_ms, Some(1500));
+
+        // No separate limit when the workspace leaves it at 0
+        let options = workspace_options(WorkspaceSettings::default()).await;
+        assert_eq!(options.connect_timeout_ms, None);
+    }
+
+    #[tokio::test]
+    async fn test_run_sends_workspace_default_headers() {
+        let base_url = mock_server::start(|request| {
+            let header = |name: &str| request.headers.get(name).cloned().unwrap_or_default();
+            MockResponse::new(200, format!("{} {} {}", header("x-tenant-id"), header("user-agent"), header("accept")))
+        }).await;
+        let settings = || WorkspaceSettings {
+            user_agent: Some("acme-smoke/2.0".to_string()),
+            default_headers: [
+                ("X-Tenant-Id".to_string(), "acme".to_string()),
+                ("Accept".to_string(), "application/json".to_string()),
+            ].into_iter().collect(),
+            ..Default::default()
+        };
+        let requests = vec![create_request("Whoami", format!("{}/whoami", base_url), 0)];
+        let run = |options: ExecuteOptions| {
+            let requests = requests.clone();
+            async move {
+                let result = CollectionRunService::new(HttpService::new())
+                    .run("collection-1", &requests, None, true, &RunPacing::default(), 1, &options)
+                    .await;
+                match &result.results[0].response.as_ref().unwrap().body {
+                    ResponseBody::Text { content, .. } => content.clone(),
+                    other => panic!("Expected text body, got {:?}", other),
+                }
+            }
+        };
+
+        let options = workspace_options(settings()).await;
+        assert_eq!(run(options).await, "acme acme-smoke/2.0 application/json");
+
+        // Defaults the caller passes replace the workspace's header by header, not wholesale
+        let caller = ExecuteOptions {
+            default_headers: [("x-tenant-id".to_string(), "globex".to_string())].into_iter().collect(),
+            ..Default::default()
+        };
+        let options = workspace_options_over(settings(), caller).await;
+        assert_eq!(options.default_headers.len(), 3);
+        assert_eq!(run(options).await, "globex acme-smoke/2.0 application/json");
+    }
+
+    #[tokio::test]
+    async fn test_request_variables_apply_to_their_own_request() {
+        let base_url = mock_server::start(|request| MockResponse::new(200, request.path)).await;
+        let requests = vec![
+            create_request("Ada", format!("{}/users/{{{{USER}}}}", base_url), 0),
+            create_request("Default", format!("{}/users/{{{{USER}}}}", base_url), 1),
+        ];
+        let environment = [("USER".to_string(), "guest".to_string())].into_iter().collect();
+        let own = [("USER".to_string(), "ada".to_string())].into_iter().collect();
+
+        let result = CollectionRunService::new(HttpService::new())
+            .with_request_variables([(requests[0].id.clone(), own)].into_iter().collect())
+            .run("collection-1", &requests, Some(environment), true, &RunPacing::default(), 1, &ExecuteOptions::default())
+            .await;
+
+        let paths: Vec<_> = result.results.iter()
+            .map(|r| match &r.response.as_ref().unwrap().body {
+                ResponseBody::Text { content, .. } => content.clone(),
+                other => panic!("Expected text body, got {:?}", other),
+            })
+            .collect();
+        assert_eq!(paths, vec!["/users/ada", "/users/guest"]);
+    }
+
+    #[tokio::test]
+    async fn test_run_sees_variables_inherited_from_parent_environment() {
+        let base_url = mock_server::start(|request| match request.headers.get("authorization").map(String::as_str) {
+            Some("Bearer parent-token") => MockResponse::new(200, "ok"),
+            _ => MockResponse::new(401, "unauthorized"),
+        }).await;
+
+        let (database, workspace) = database_with_workspace("/tmp/inherited").await;
+        let environments = EnvironmentService::new(database);
+        let parent = environments.create_environment(workspace.id.clone(), "Shared".to_string()).await.unwrap();
+        environments
+            .add_variable(&parent.id, EnvironmentVariable {
+                key: "TOKEN".to_string(),
+                value: "parent-token".to_string(),
+                is_secret: false,
+                variable_type: VariableType::String,
+            })
+            .await
+            .unwrap();
+        let mut child = environments.create_environment(workspace.id.clone(), "Staging".to_string()).await.unwrap();
+        child.parent_id = Some(parent.id.clone());
+        let child = environments.update_environment(child).await.unwrap();
+
+        let mut profile = create_request("Profile", format!("{}/profile", base_url), 0);
+        profile.headers = serde_json::json!({ "Authorization": "Bearer {{TOKEN}}" }).to_string();
+
+        // The child's variables are resolved as a run resolves them, inheritance included
+        let variables = environments
+            .resolve_variables(&workspace.id, Some("collection-1"), None, Some(&child.id))
+            .await
+            .unwrap();
+        let result = CollectionRunService::new(HttpService::new())
+            .run("collection-1", &[profile], Some(variables), true, &RunPacing::default(), 1, &ExecuteOptions::default())
+            .await;
+
+        assert_eq!(result.failed, 0);
+        assert_eq!(result.results[0].response.as_ref().unwrap().status, 200);
+    }
+
+    #[test]
+    fn test_mutating_requests_to_protected_environment_need_confirming() {
+        let mut create = create_request("Create order", "http://localhost/orders".to_string(), 0);
+        create.method = "POST".to_string();
+        let mut cleanup = create_request("Cleanup", "http://localhost/orders".to_string(), 1);
+        cleanup.method = "DELETE".to_string();
+        cleanup.include_in_run = false;
+        let list = create_request("List orders", "http://localhost/orders".to_string(), 2);
+        let requests = vec![create, cleanup, list];
+
+        let mut production = Environment { name: "Production".to_string(), ..Default::default() };
+        production.is_protected = true;
+        assert_eq!(
+            CollectionRunService::requests_needing_confirmation(&requests, Some(&production)),
+            vec!["Create order"]
+        );
+
+        production.is_protected = false;
+        assert!(CollectionRunService::requests_needing_confirmation(&requests, Some(&production)).is_empty());
+        assert!(CollectionRunService::requests_needing_confirmation(&requests, None).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_run_results_are_recorded_in_history() {
+        let base_url = mock_server::start(|_| MockResponse::new(200, "ok").header("Content-Type", "text/plain")).await;
+        let mut skipped = create_request("Cleanup", format!("{}/cleanup", base_url), 2);
+        skipped.include_in_run = false;
+        let requests = vec![
+            create_request("List", format!("{}/list", base_url), 0),
+            create_request("Offline", "http://127.0.0.1:1/offline".to_string(), 1),
+            skipped,
+        ];
+
+        let result = CollectionRunService::new(HttpService::new())
+            .run("collection-1", &requests, None, true, &RunPacing::default(), 1, &ExecuteOptions::default())
+            .await;
+
+        let (database, workspace) = database_with_workspace("/tmp/runs").await;
+        record_run_results(&database, &workspace.id, &requests, &result.results).await;
+
+        // Newest first; the skipped request was never sent
+        let history = RequestHistoryService::new(database.get_pool()).list(&workspace.id, 10).await.unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].request_id, requests[1].id);
+        assert!(history[0].status.is_none());
+        assert!(history[0].error.is_some());
+        assert_eq!(history[1].request_id, requests[0].id);
+        assert_eq!(history[1].status, Some(200));
+
+        let last_responses = LastResponseService::new(database.get_pool());
+        assert_eq!(last_responses.get(&requests[0].id).await.unwrap().map(|r| r.status), Some(200));
+        assert!(last_responses.get(&requests[1].id).await.unwrap().is_none());
+    }
+}
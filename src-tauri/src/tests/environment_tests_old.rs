@@ -2,7 +2,7 @@
 mod tests {
     use crate::models::environment::*;
     use crate::services::environment_service::EnvironmentService;
-    use crate::services::simple_database_service::DatabaseService;
+    use crate::services::database_service::DatabaseService;
     use std::sync::Arc;
     use tempfile::TempDir;
 
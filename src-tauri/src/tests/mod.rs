@@ -1,4 +1,7 @@
+mod collection_run_tests;
 mod git_tests;
 mod http_tests;
+mod mock_server;
 mod simple_unit_tests;
-mod workspace_tests;
\ No newline at end of file
+pub mod test_database;
+mod workspace_tests;
@@ -13,6 +13,8 @@ mod tests {
             description: Some("Test description".to_string()),
             git_repository_url: None,
             local_path: "/tmp/test".to_string(),
+            git_username: None,
+            git_email: None,
         };
         
         let workspace = Workspace::new(request);
@@ -29,6 +31,8 @@ mod tests {
             description: None,
             git_repository_url: None,
             local_path: "/tmp/test".to_string(),
+            git_username: None,
+            git_email: None,
         };
         
         let mut workspace = Workspace::new(request);
@@ -42,6 +46,8 @@ mod tests {
             description: Some("Updated description".to_string()),
             git_repository_url: None,
             local_path: None,
+            git_username: None,
+            git_email: None,
             is_active: Some(true),
         };
         
@@ -59,6 +65,8 @@ mod tests {
             description: None,
             git_repository_url: None,
             local_path: "/tmp/test".to_string(),
+            git_username: None,
+            git_email: None,
         };
         
         let mut workspace = Workspace::new(request);
@@ -101,6 +109,16 @@ mod tests {
         assert!(VariableType::Secret.validate_value("secret value"));
         assert!(!VariableType::Secret.validate_value(""));
         assert!(!VariableType::Secret.validate_value("   "));
+
+        assert!(VariableType::Number.validate_value(" -12.5 "));
+        assert!(!VariableType::Number.validate_value("twelve"));
+        assert!(!VariableType::Number.validate_value("NaN"));
+        assert!(VariableType::Boolean.validate_value("TRUE"));
+        assert!(!VariableType::Boolean.validate_value("yes"));
+        assert!(VariableType::Url.validate_value("https://api.example.com/v1"));
+        assert!(!VariableType::Url.validate_value("api.example.com"));
+        // Placeholders are only checked once substituted
+        assert!(VariableType::Url.validate_value("{{BASE_URL}}/v1"));
     }
 
     #[test]
@@ -110,6 +128,7 @@ mod tests {
         
         assert!(matches!(VariableType::from_str("string"), VariableType::String));
         assert!(matches!(VariableType::from_str("secret"), VariableType::Secret));
+        assert_eq!(VariableType::from_str(VariableType::Url.as_str()), VariableType::Url);
         assert!(matches!(VariableType::from_str("unknown"), VariableType::String));
     }
 
@@ -130,6 +149,7 @@ mod tests {
             description: Some("Collection of API tests".to_string()),
             folder_path: Some("api".to_string()),
             git_branch: Some("main".to_string()),
+            parent_id: None,
         };
         
         let collection = Collection::new(request);
@@ -142,6 +162,34 @@ mod tests {
         assert!(!collection.id.is_empty());
     }
 
+    #[test]
+    fn test_request_written_before_run_settings_deserializes() {
+        let request: Request = serde_json::from_value(serde_json::json!({
+            "id": "request-1",
+            "collection_id": "collection-1",
+            "name": "List users",
+            "description": null,
+            "method": "GET",
+            "url": "https://api.example.com/users",
+            "headers": "{}",
+            "body": null,
+            "body_type": "json",
+            "auth_type": null,
+            "auth_config": null,
+            "follow_redirects": true,
+            "timeout_ms": 30000,
+            "order_index": 0,
+            "created_at": "2024-01-01T00:00:00Z",
+            "updated_at": "2024-01-01T00:00:00Z"
+        })).unwrap();
+
+        assert!(request.include_in_run);
+        assert!(!request.raw_stream);
+        assert_eq!(request.extractions, "[]");
+        assert_eq!(request.assertions, "[]");
+        assert!(request.get_pre_request().is_empty());
+    }
+
     #[test]
     fn test_collection_update() {
         let request = CreateCollectionRequest {
@@ -150,6 +198,7 @@ mod tests {
             description: None,
             folder_path: None,
             git_branch: None,
+            parent_id: None,
         };
         
         let mut collection = Collection::new(request);
@@ -163,6 +212,7 @@ mod tests {
             description: Some("Updated description".to_string()),
             folder_path: Some("updated".to_string()),
             git_branch: Some("feature".to_string()),
+            parent_id: None,
             is_active: Some(true),
         };
         
@@ -0,0 +1,26 @@
+//! Shared setup for tests that need a database holding a workspace
+
+use crate::models::workspace::{CreateWorkspaceRequest, Workspace};
+use crate::services::database_service::DatabaseService;
+use std::sync::Arc;
+
+/// An in-memory database with one workspace, "Test", whose files live under `local_path`
+pub async fn database_with_workspace(local_path: &str) -> (Arc<DatabaseService>, Workspace) {
+    let database = Arc::new(DatabaseService::new("sqlite::memory:").await.unwrap());
+    let workspace = add_workspace(&database, "Test", local_path).await;
+    (database, workspace)
+}
+
+/// Create and store a workspace named `name` whose files live under `local_path`
+pub async fn add_workspace(database: &DatabaseService, name: &str, local_path: &str) -> Workspace {
+    let workspace = Workspace::new(CreateWorkspaceRequest {
+        name: name.to_string(),
+        description: None,
+        git_repository_url: None,
+        local_path: local_path.to_string(),
+        git_username: None,
+        git_email: None,
+    });
+    database.create_workspace(&workspace).await.unwrap();
+    workspace
+}
@@ -154,6 +154,8 @@ Thumbs.db
             name: "Test Workspace".to_string(),
             description: Some("A test workspace".to_string()),
             local_path: "~/Documents/Postgirl/test-workspace".to_string(),
+            git_username: None,
+            git_email: None,
             git_repository_url: Some("https://github.com/user/repo.git".to_string()),
         };
 
@@ -166,6 +168,8 @@ Thumbs.db
             name: "Local Workspace".to_string(),
             description: None,
             local_path: "~/Documents/Postgirl/local-workspace".to_string(),
+            git_username: None,
+            git_email: None,
             git_repository_url: None,
         };
 
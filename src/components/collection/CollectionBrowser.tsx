@@ -163,9 +163,9 @@ export const CollectionBrowser: React.FC<CollectionBrowserProps> = ({
     }
   };
 
-  const handleDuplicateRequest = async (requestId: string, requestName: string) => {
+  const handleDuplicateRequest = async (requestId: string) => {
     try {
-      await CollectionApiService.duplicateRequest(requestId, `${requestName} (Copy)`);
+      await CollectionApiService.duplicateRequest(requestId);
       
       // Reload requests for all collections to update the display
       Object.keys(collectionRequests).forEach(collectionId => {
@@ -337,7 +337,8 @@ export const CollectionBrowser: React.FC<CollectionBrowserProps> = ({
     try {
       const original = await CollectionApiService.getCollection(id);
       if (original) {
-        await CollectionApiService.duplicateCollection(id, `${original.name} (Copy)`, workspaceId);
+        const newName = await CollectionApiService.suggestDuplicateCollectionName(workspaceId, original.name);
+        await CollectionApiService.duplicateCollection(id, newName, workspaceId);
         await loadCollections();
       }
     } catch (err) {
@@ -853,7 +854,7 @@ export const CollectionBrowser: React.FC<CollectionBrowserProps> = ({
                   collectionRequests[collectionId]?.some(r => r.id === openRequestMenuId)
                 ) || '']?.find(r => r.id === openRequestMenuId);
                 if (request) {
-                  handleDuplicateRequest(request.id, request.name);
+                  handleDuplicateRequest(request.id);
                 }
               }}
               className="w-full text-left px-3 py-2 text-sm text-slate-700 dark:text-slate-200 hover:bg-slate-100 dark:hover:bg-slate-700 transition-colors"
@@ -16,10 +16,13 @@ import {
 } from '../../types/environment';
 
 // Simple constants for variable types
-const VARIABLE_TYPES: VariableType[] = ['string', 'secret'];
+const VARIABLE_TYPES: VariableType[] = ['string', 'secret', 'number', 'boolean', 'url'];
 const VARIABLE_TYPE_LABELS: Record<VariableType, string> = {
   string: 'String',
-  secret: 'Secret'
+  secret: 'Secret',
+  number: 'Number',
+  boolean: 'Boolean',
+  url: 'URL'
 };
 
 interface EnvironmentVariableEditorProps {
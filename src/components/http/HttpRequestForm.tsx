@@ -411,7 +411,8 @@ export const HttpRequestForm: React.FC<HttpRequestFormProps> = ({
                       </pre>
                     ) : response.body.type === 'binary' ? (
                       <div className="text-sm text-slate-600 dark:text-slate-400">
-                        Binary data ({response.body.size} bytes)
+                        Binary data ({response.body.mimeType}, {response.body.size} bytes)
+                        <pre className="mt-1 font-mono text-xs">{response.body.previewHex}</pre>
                       </div>
                     ) : (
                       <div className="text-sm text-slate-500 dark:text-slate-400 italic">
@@ -7,7 +7,21 @@ import type {
   CreateRequestRequest,
   UpdateRequestRequest,
   CollectionSummary,
+  CollectionRunResult,
+  RunPacing,
+  RequestRunResult,
+  RequestExample,
+  CreateRequestExampleRequest,
+  ResponseDiff,
+  ResponseSnapshot,
+  SnapshotDiff,
+  CurlImportResult,
+  RequestSearchResult,
+  Extraction,
+  Assertion,
+  PreRequestStep,
 } from '../types/collection';
+import type { HttpResponse } from '../types/http';
 
 export class CollectionApiService {
   // Collection methods
@@ -27,10 +41,48 @@ export class CollectionApiService {
     return await invoke('delete_collection', { id });
   }
 
+  static async restoreCollection(id: string): Promise<Collection> {
+    return await invoke('restore_collection', { id });
+  }
+
+  // Permanently removes collections and requests deleted more than olderThanDays ago
+  static async purgeDeleted(workspaceId: string, olderThanDays: number): Promise<number> {
+    return await invoke('purge_deleted', { workspaceId, olderThanDays });
+  }
+
   static async listCollections(workspaceId: string): Promise<Collection[]> {
     return await invoke('list_collections', { workspaceId });
   }
 
+  // Collections labelled with the branch, plus unlabelled ones
+  static async listCollectionsByBranch(workspaceId: string, branch: string): Promise<Collection[]> {
+    return await invoke('list_collections_by_branch', { workspaceId, branch });
+  }
+
+  // Pass null to clear the branch label
+  static async setCollectionBranch(collectionId: string, branchName: string | null): Promise<Collection> {
+    return await invoke('set_collection_branch', { collectionId, branchName });
+  }
+
+  // Pass null to move the collection back to the top level
+  static async setCollectionParent(collectionId: string, parentId: string | null): Promise<Collection> {
+    return await invoke('set_collection_parent', { collectionId, parentId });
+  }
+
+  static async listChildCollections(parentId: string): Promise<Collection[]> {
+    return await invoke('list_child_collections', { parentId });
+  }
+
+  // Reload collections from the workspace's JSON files, e.g. after a git pull
+  static async syncWorkspaceFromDisk(workspaceId: string): Promise<Collection[]> {
+    return await invoke('sync_workspace_from_disk', { workspaceId });
+  }
+
+  // Collection and environment files still containing merge conflict markers
+  static async listConflictedFiles(workspaceId: string): Promise<string[]> {
+    return await invoke('list_conflicted_files', { workspaceId });
+  }
+
   static async getCollectionSummaries(workspaceId: string): Promise<CollectionSummary[]> {
     return await invoke('get_collection_summaries', { workspaceId });
   }
@@ -52,18 +104,116 @@ export class CollectionApiService {
     return await invoke('delete_request', { id });
   }
 
+  static async restoreRequest(id: string): Promise<Request> {
+    return await invoke('restore_request', { id });
+  }
+
   static async listRequests(collectionId: string): Promise<Request[]> {
     return await invoke('list_requests', { collectionId });
   }
 
-  static async duplicateRequest(id: string, newName: string): Promise<Request> {
+  // Without a name the copy is called "X (copy)", "X (copy 2)", ...
+  static async duplicateRequest(id: string, newName?: string): Promise<Request> {
     return await invoke('duplicate_request', { id, newName });
   }
 
+  static async suggestDuplicateName(collectionId: string, baseName: string): Promise<string> {
+    return await invoke('suggest_duplicate_name', { collectionId, baseName });
+  }
+
+  static async suggestDuplicateCollectionName(workspaceId: string, baseName: string): Promise<string> {
+    return await invoke('suggest_duplicate_collection_name', { workspaceId, baseName });
+  }
+
   static async reorderRequests(collectionId: string, requestOrders: Array<[string, number]>): Promise<void> {
     return await invoke('reorder_requests', { collectionId, requestOrders });
   }
 
+  static async setRequestRunInclusion(requestId: string, included: boolean): Promise<Request> {
+    return await invoke('set_request_run_inclusion', { requestId, included });
+  }
+
+  // Pass undefined to fall back to the workspace's connect timeout
+  static async setRequestConnectTimeout(requestId: string, connectTimeoutMs?: number): Promise<Request> {
+    return await invoke('set_request_connect_timeout', { requestId, connectTimeoutMs });
+  }
+
+  // A predicate such as `{{token}} exists` or `{{status}} == 200`; undefined always runs the request
+  static async setRequestRunCondition(requestId: string, condition?: string): Promise<Request> {
+    return await invoke('set_request_run_condition', { requestId, condition });
+  }
+
+  static async setRequestRawStream(requestId: string, rawStream: boolean): Promise<Request> {
+    return await invoke('set_request_raw_stream', { requestId, rawStream });
+  }
+
+  static async setRequestPreRequest(requestId: string, steps: PreRequestStep[]): Promise<Request> {
+    return await invoke('set_request_pre_request', { requestId, steps });
+  }
+
+  static async setRequestExtractions(requestId: string, extractions: Extraction[]): Promise<Request> {
+    return await invoke('set_request_extractions', { requestId, extractions });
+  }
+
+  static async setRequestAssertions(requestId: string, assertions: Assertion[]): Promise<Request> {
+    return await invoke('set_request_assertions', { requestId, assertions });
+  }
+
+  static async importCurls(collectionId: string, curlCommands: string[]): Promise<CurlImportResult> {
+    return await invoke('import_curls', { collectionId, curlCommands });
+  }
+
+  static async importHar(collectionId: string, harJson: string): Promise<Request[]> {
+    return await invoke('import_har', { collectionId, harJson });
+  }
+
+  // Returns a HAR 1.2 document pairing each request with its last response
+  static async exportHar(requestIds: string[]): Promise<string> {
+    return await invoke('export_har', { requestIds });
+  }
+
+  static async createRequestExample(request: CreateRequestExampleRequest): Promise<RequestExample> {
+    return await invoke('create_request_example', { request });
+  }
+
+  static async compareRequestToExample(requestId: string, exampleId: string, liveResponse: HttpResponse): Promise<ResponseDiff> {
+    return await invoke('compare_request_to_example', { requestId, exampleId, liveResponse });
+  }
+
+  // Without header names only Content-Type is kept in the snapshot
+  static async saveResponseSnapshot(requestId: string, response: HttpResponse, headers?: string[]): Promise<ResponseSnapshot> {
+    return await invoke('save_response_snapshot', { requestId, response, headers });
+  }
+
+  static async diffAgainstSnapshot(requestId: string, response: HttpResponse): Promise<SnapshotDiff> {
+    return await invoke('diff_against_snapshot', { requestId, response });
+  }
+
+  // [header name, value with the credential masked], or null when no header is added
+  static async previewAuthHeader(requestId: string, environmentId?: string): Promise<[string, string] | null> {
+    return await invoke('preview_auth_header', { requestId, environmentId });
+  }
+
+  // Runs with the workspace's active environment when no environment is given
+  static async runCollection(
+    collectionId: string,
+    environmentId?: string,
+    continueOnError = true,
+    pacing?: RunPacing,
+    concurrency?: number, // requests in flight at once; runs that chain variables stay sequential
+    confirm = false // required to send mutating requests to a protected environment
+  ): Promise<CollectionRunResult> {
+    return await invoke('run_collection', { collectionId, environmentId, continueOnError, pacing, concurrency, confirm });
+  }
+
+  static async executeRequestWithAssertions(requestId: string, environmentId?: string): Promise<RequestRunResult> {
+    return await invoke('execute_request_with_assertions', { requestId, environmentId });
+  }
+
+  static async exportCollectionAsCurlScript(collectionId: string, environmentId?: string): Promise<string> {
+    return await invoke('export_collection_as_curl_script', { collectionId, environmentId });
+  }
+
   // Helper methods
   static async getRequestsForWorkspace(workspaceId: string): Promise<Request[]> {
     const collections = await this.listCollections(workspaceId);
@@ -77,66 +227,24 @@ export class CollectionApiService {
     return allRequests;
   }
 
-  static async duplicateCollection(id: string, newName: string, workspaceId: string): Promise<Collection> {
-    const original = await this.getCollection(id);
-    if (!original) {
-      throw new Error('Collection not found');
-    }
-
-    // Create new collection
-    const newCollection = await this.createCollection({
-      workspace_id: workspaceId,
-      name: newName,
-      description: original.description,
-      folder_path: original.folder_path,
-      git_branch: original.git_branch,
-    });
-
-    // Duplicate all requests
-    const requests = await this.listRequests(id);
-    for (const request of requests) {
-      await this.createRequest({
-        collection_id: newCollection.id,
-        name: request.name,
-        description: request.description,
-        method: request.method,
-        url: request.url,
-        headers: JSON.parse(request.headers || '{}'),
-        body: request.body,
-        body_type: request.body_type,
-        auth_type: request.auth_type,
-        auth_config: request.auth_config ? JSON.parse(request.auth_config) : undefined,
-        follow_redirects: request.follow_redirects,
-        timeout_ms: request.timeout_ms,
-        order_index: request.order_index,
-      });
-    }
-
-    return newCollection;
+  static async duplicateCollection(
+    id: string,
+    newName?: string,
+    targetWorkspaceId?: string
+  ): Promise<Collection> {
+    return await invoke('duplicate_collection', { id, newName, targetWorkspaceId });
   }
 
-  static async moveRequestToCollection(requestId: string, targetCollectionId: string): Promise<Request> {
-    const request = await this.getRequest(requestId);
-    if (!request) {
-      throw new Error('Request not found');
-    }
-
-    return await this.updateRequest({
-      id: requestId,
-      collection_id: targetCollectionId,
-    });
+  static async moveRequestToCollection(
+    requestId: string,
+    targetCollectionId: string,
+    newOrderIndex = Number.MAX_SAFE_INTEGER // appends by default
+  ): Promise<Request> {
+    return await invoke('move_request', { requestId, targetCollectionId, newOrderIndex });
   }
 
-  static async searchRequests(workspaceId: string, query: string): Promise<Request[]> {
-    const allRequests = await this.getRequestsForWorkspace(workspaceId);
-    const searchTerm = query.toLowerCase();
-    
-    return allRequests.filter(request => 
-      request.name.toLowerCase().includes(searchTerm) ||
-      request.url.toLowerCase().includes(searchTerm) ||
-      request.method.toLowerCase().includes(searchTerm) ||
-      (request.description && request.description.toLowerCase().includes(searchTerm))
-    );
+  static async searchRequests(workspaceId: string, query: string): Promise<RequestSearchResult[]> {
+    return await invoke('search_requests', { workspaceId, query });
   }
 
   static async exportCollection(id: string): Promise<{ collection: Collection; requests: Request[] }> {
@@ -1,7 +1,12 @@
 import { invoke } from '@tauri-apps/api/core';
 import type {
   Environment,
-  EnvironmentVariable
+  EnvironmentVariable,
+  MergeMode,
+  SubstitutionResult,
+  UndefinedVariable,
+  VariableScope,
+  VariableUsage
 } from '../types/environment';
 
 export class EnvironmentApiService {
@@ -28,8 +33,23 @@ export class EnvironmentApiService {
     return invoke('delete_environment', { environmentId });
   }
 
-  static async listEnvironments(workspaceId: string): Promise<Environment[]> {
-    return invoke('list_environments', { workspaceId });
+  // With resolveInherited, each environment includes the variables it inherits
+  static async listEnvironments(workspaceId: string, resolveInherited?: boolean): Promise<Environment[]> {
+    return invoke('list_environments', { workspaceId, resolveInherited });
+  }
+
+  // Read-only view with inherited variables merged in; don't pass it to updateEnvironment
+  static async getResolvedEnvironment(environmentId: string): Promise<Environment | null> {
+    return invoke('get_resolved_environment', { environmentId });
+  }
+
+  // .env files
+  static async importEnvFile(workspaceId: string, name: string, contents: string): Promise<Environment> {
+    return invoke('import_env_file', { workspaceId, name, contents });
+  }
+
+  static async exportEnvFile(environmentId: string, includeSecrets = false): Promise<string> {
+    return invoke('export_env_file', { environmentId, includeSecrets });
   }
 
   // Environment variable operations
@@ -53,6 +73,18 @@ export class EnvironmentApiService {
     });
   }
 
+  static async setVariables(
+    environmentId: string,
+    variables: EnvironmentVariable[],
+    mode: MergeMode
+  ): Promise<Environment> {
+    return invoke('set_environment_variables', {
+      environmentId,
+      variables,
+      mode
+    });
+  }
+
   static async removeVariable(
     environmentId: string,
     variableKey: string
@@ -63,6 +95,54 @@ export class EnvironmentApiService {
     });
   }
 
+  // Global, collection and request variables; ownerId is the workspace, collection or request id
+  static async getScopedVariables(scope: VariableScope, ownerId: string): Promise<Record<string, string>> {
+    return invoke('get_scoped_variables', { scope, ownerId });
+  }
+
+  static async setScopedVariable(scope: VariableScope, ownerId: string, key: string, value: string): Promise<void> {
+    return invoke('set_scoped_variable', { scope, ownerId, key, value });
+  }
+
+  static async removeScopedVariable(scope: VariableScope, ownerId: string, key: string): Promise<boolean> {
+    return invoke('remove_scoped_variable', { scope, ownerId, key });
+  }
+
+  // The variables to send a request with, merged request > collection > environment > global
+  static async resolveVariables(
+    workspaceId: string,
+    collectionId?: string,
+    requestId?: string,
+    environmentId?: string
+  ): Promise<Record<string, string>> {
+    return invoke('resolve_variables', { workspaceId, collectionId, requestId, environmentId });
+  }
+
+  // Requests in a collection that reference a variable
+  static async findVariableUsages(collectionId: string, variableName: string): Promise<VariableUsage[]> {
+    return invoke('find_variable_usages', { collectionId, variableName });
+  }
+
+  // Placeholders in a collection that nothing defines, checked against an environment
+  static async findUndefinedVariables(collectionId: string, environmentId?: string): Promise<UndefinedVariable[]> {
+    return invoke('find_undefined_variables', { collectionId, environmentId });
+  }
+
+  // Orphaned environment files
+  static async findOrphanedEnvironmentFiles(workspaceId: string): Promise<string[]> {
+    return invoke('find_orphaned_environment_files', { workspaceId });
+  }
+
+  static async importOrphanedEnvironmentFile(
+    workspaceId: string,
+    name: string
+  ): Promise<Environment> {
+    return invoke('import_orphaned_environment_file', {
+      workspaceId,
+      name
+    });
+  }
+
   // Variable substitution
   static async substituteVariables(
     text: string,
@@ -74,6 +154,16 @@ export class EnvironmentApiService {
     });
   }
 
+  static async substituteWithSpans(
+    text: string,
+    variables: Record<string, EnvironmentVariable>
+  ): Promise<SubstitutionResult> {
+    return invoke('substitute_with_spans', {
+      text,
+      variables
+    });
+  }
+
   static async extractVariables(text: string): Promise<string[]> {
     return invoke('extract_environment_variables', { text });
   }
@@ -93,6 +183,10 @@ export class EnvironmentApiService {
     });
   }
 
+  static async clearActiveEnvironment(workspaceId: string): Promise<boolean> {
+    return invoke('clear_active_environment', { workspaceId });
+  }
+
   static async getActiveEnvironment(workspaceId: string): Promise<Environment | null> {
     return invoke('get_active_environment', { workspaceId });
   }
@@ -102,16 +196,7 @@ export class EnvironmentApiService {
     environmentId: string,
     variables: EnvironmentVariable[]
   ): Promise<Environment> {
-    let environment = await this.getEnvironment(environmentId);
-    if (!environment) {
-      throw new Error(`Environment not found: ${environmentId}`);
-    }
-
-    for (const variable of variables) {
-      environment = await this.addVariable(environmentId, variable);
-    }
-
-    return environment;
+    return this.setVariables(environmentId, variables, 'merge');
   }
 
   static async bulkUpdateVariables(
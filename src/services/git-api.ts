@@ -1,5 +1,6 @@
 import { invoke } from '@tauri-apps/api/core';
-import type { GitStatus, GitCredentials, CloneResult } from '../types/git';
+import { listen, type UnlistenFn } from '@tauri-apps/api/event';
+import type { GitStatus, GitCredentials, CredentialHost, CloneProgress, CloneResult, UnknownHostKey, RemoteAccess, Conflict, ConflictResolution, FileDiff, GitCommit, GitRemote, HeadInfo, StashResult } from '../types/git';
 
 export class GitApiService {
   /**
@@ -10,14 +11,23 @@ export class GitApiService {
   }
 
   /**
-   * Clone a Git repository
+   * Clone a Git repository. Pass a clone ID to receive progress through onCloneProgress.
    */
   static async cloneRepository(
     url: string,
     path: string,
-    credentials?: GitCredentials
+    credentials?: GitCredentials,
+    cloneId?: string
   ): Promise<CloneResult> {
-    return await invoke('git_clone_repository', { url, path, credentials });
+    return await invoke('git_clone_repository', { url, path, credentials, cloneId });
+  }
+
+  /**
+   * Receive progress of the clone started with `cloneId`, whether through cloneRepository
+   * or workspace creation. Subscribe before starting the clone so no reports are missed.
+   */
+  static async onCloneProgress(cloneId: string, handler: (progress: CloneProgress) => void): Promise<UnlistenFn> {
+    return await listen<CloneProgress>(`git-clone-progress:${cloneId}`, (event) => handler(event.payload));
   }
 
   /**
@@ -35,31 +45,38 @@ export class GitApiService {
   }
 
   /**
-   * Store Git credentials securely
+   * Store Git credentials securely for a host; each username is kept separately
    */
-  static async storeCredentials(key: string, credentials: GitCredentials): Promise<boolean> {
-    return await invoke('git_store_credentials', { key, credentials });
+  static async storeCredentials(host: string, credentials: GitCredentials): Promise<boolean> {
+    return await invoke('git_store_credentials', { host, credentials });
   }
 
   /**
-   * Get stored Git credentials
+   * Get stored Git credentials for a host (the first saved account when no username is given)
    */
-  static async getCredentials(key: string): Promise<GitCredentials> {
-    return await invoke('git_get_credentials', { key });
+  static async getCredentials(host: string, username?: string): Promise<GitCredentials> {
+    return await invoke('git_get_credentials', { host, username });
   }
 
   /**
    * Delete stored Git credentials
    */
-  static async deleteCredentials(key: string): Promise<boolean> {
-    return await invoke('git_delete_credentials', { key });
+  static async deleteCredentials(host: string, username?: string): Promise<boolean> {
+    return await invoke('git_delete_credentials', { host, username });
   }
 
   /**
-   * Check if credentials exist for a key
+   * Check if credentials exist for a host
    */
-  static async credentialsExist(key: string): Promise<boolean> {
-    return await invoke('git_credentials_exist', { key });
+  static async credentialsExist(host: string, username?: string): Promise<boolean> {
+    return await invoke('git_credentials_exist', { host, username });
+  }
+
+  /**
+   * List hosts with saved credentials (never the secrets)
+   */
+  static async listCredentialHosts(): Promise<CredentialHost[]> {
+    return await invoke('git_list_credential_hosts');
   }
 
   /**
@@ -68,6 +85,146 @@ export class GitApiService {
   static async getBranches(repoPath: string): Promise<Array<{ name: string; is_current: boolean }>> {
     return await invoke('git_get_branches', { repoPath });
   }
+
+  /**
+   * Fetch from a remote
+   */
+  static async fetch(repoPath: string, remote: string, credentials?: GitCredentials): Promise<CloneResult> {
+    return await invoke('git_fetch', { repoPath, remote, credentials });
+  }
+
+  /**
+   * Check that a remote is reachable with the given credentials, without fetching
+   */
+  static async checkRemoteAccess(repoPath: string, remote: string, credentials?: GitCredentials): Promise<RemoteAccess> {
+    return await invoke('git_check_remote_access', { repoPath, remote, credentials });
+  }
+
+  /**
+   * Fast-forward a branch to its remote counterpart
+   */
+  static async pull(repoPath: string, remote: string, branch: string, credentials?: GitCredentials): Promise<CloneResult> {
+    return await invoke('git_pull', { repoPath, remote, branch, credentials });
+  }
+
+  /**
+   * Get recent commits on a branch or ref (HEAD by default), newest first
+   */
+  static async getLog(repoPath: string, reference?: string, limit?: number): Promise<GitCommit[]> {
+    return await invoke('git_get_log', { repoPath, reference, limit });
+  }
+
+  /**
+   * Get the commit HEAD is on and the tags pointing at it
+   */
+  static async getHeadInfo(repoPath: string): Promise<HeadInfo> {
+    return await invoke('git_get_head_info', { repoPath });
+  }
+
+  /**
+   * Get staged and unstaged changes as unified diffs, one entry per file and side
+   */
+  static async getDiff(repoPath: string): Promise<FileDiff[]> {
+    return await invoke('git_get_diff', { repoPath });
+  }
+
+  /**
+   * Stage every change in the working tree, including untracked files
+   */
+  static async stageAll(repoPath: string): Promise<CloneResult> {
+    return await invoke('git_stage_all', { repoPath });
+  }
+
+  /**
+   * Stage only the given paths, relative to the repository root
+   */
+  static async stageFiles(repoPath: string, paths: string[]): Promise<CloneResult> {
+    return await invoke('git_stage_files', { repoPath, paths });
+  }
+
+  /**
+   * Move the given paths back out of the staging area, keeping their changes
+   */
+  static async unstageFiles(repoPath: string, paths: string[]): Promise<CloneResult> {
+    return await invoke('git_unstage_files', { repoPath, paths });
+  }
+
+  /**
+   * List files left conflicted by a merge
+   */
+  static async listConflicts(repoPath: string): Promise<Conflict[]> {
+    return await invoke('git_list_conflicts', { repoPath });
+  }
+
+  /**
+   * Resolve a conflicted file and stage the result
+   */
+  static async resolveConflict(repoPath: string, path: string, resolution: ConflictResolution): Promise<CloneResult> {
+    return await invoke('git_resolve_conflict', { repoPath, path, resolution });
+  }
+
+  /**
+   * Abandon an in-progress merge
+   */
+  static async abortMerge(repoPath: string): Promise<CloneResult> {
+    return await invoke('git_abort_merge', { repoPath });
+  }
+
+  /**
+   * List the repository's remotes
+   */
+  static async listRemotes(repoPath: string): Promise<GitRemote[]> {
+    return await invoke('git_list_remotes', { repoPath });
+  }
+
+  /**
+   * Add a remote, failing if the name is already taken
+   */
+  static async addRemote(repoPath: string, name: string, url: string): Promise<GitRemote> {
+    return await invoke('git_add_remote', { repoPath, name, url });
+  }
+
+  /**
+   * Remove a remote and its remote-tracking branches
+   */
+  static async removeRemote(repoPath: string, name: string): Promise<CloneResult> {
+    return await invoke('git_remove_remote', { repoPath, name });
+  }
+
+  /**
+   * Stash uncommitted changes, including untracked files
+   */
+  static async stashSave(repoPath: string, message?: string): Promise<StashResult> {
+    return await invoke('git_stash_save', { repoPath, message });
+  }
+
+  /**
+   * Restore and drop the most recent stash
+   */
+  static async stashPop(repoPath: string): Promise<StashResult> {
+    return await invoke('git_stash_pop', { repoPath });
+  }
+
+  /**
+   * Push a branch to a remote
+   */
+  static async push(repoPath: string, remote: string, branch: string, credentials?: GitCredentials): Promise<CloneResult> {
+    return await invoke('git_push', { repoPath, remote, branch, credentials });
+  }
+
+  /**
+   * Add a host key the user chose to trust to known_hosts
+   */
+  static async trustHostKey(hostKey: UnknownHostKey): Promise<void> {
+    return await invoke('git_trust_host_key', { hostKey });
+  }
+
+  /**
+   * Accept any SSH host key (local testing only)
+   */
+  static async setSkipHostKeyVerification(skip: boolean): Promise<void> {
+    return await invoke('git_set_skip_host_key_verification', { skip });
+  }
 }
 
 // Workspace-specific Git operations
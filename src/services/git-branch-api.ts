@@ -3,6 +3,7 @@ import type {
   BranchConfig,
   BranchCreateRequest,
   BranchCreateResult,
+  BranchDeleteResult,
   BranchPattern,
   BranchHistoryEntry,
   FeatureType,
@@ -84,6 +85,30 @@ class GitBranchApiService {
     return await invoke('list_branches', { workspacePath });
   }
 
+  /**
+   * Check out an existing branch. Rejects when uncommitted changes would be overwritten.
+   */
+  async switchBranch(workspacePath: string, branchName: string): Promise<void> {
+    await this.initializeService();
+    return await invoke('git_switch_branch', { workspacePath, branchName });
+  }
+
+  /**
+   * Delete a branch. The current branch is never deleted, and unmerged ones only with force.
+   */
+  async deleteBranch(workspacePath: string, branchName: string, force = false): Promise<BranchDeleteResult> {
+    await this.initializeService();
+    return await invoke('git_delete_branch', { workspacePath, branchName, force });
+  }
+
+  /**
+   * Rename a local branch. Fails when the new name is invalid or already taken.
+   */
+  async renameBranch(workspacePath: string, oldName: string, newName: string): Promise<void> {
+    await this.initializeService();
+    return await invoke('git_rename_branch', { workspacePath, oldName, newName });
+  }
+
   async getBranchHistory(limit?: number): Promise<BranchHistoryEntry[]> {
     await this.initializeService();
     const history = await invoke('get_branch_history', { limit });
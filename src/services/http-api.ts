@@ -1,9 +1,20 @@
 import { invoke } from '@tauri-apps/api/core';
+import { listen, type UnlistenFn } from '@tauri-apps/api/event';
 import type { 
   HttpRequest, 
   HttpMethod, 
+  CodeTarget,
+  ValidationIssue,
   ExecuteRequestResponse,
-  ExecuteRequestRequest 
+  ExecuteRequestRequest,
+  ExecuteOptions,
+  HttpResponse,
+  RequestHistoryEntry,
+  CookieInfo,
+  SseEvent,
+  UrlComponents,
+  ConnectionTestOptions,
+  CorsInspection
 } from '../types/http';
 
 export class HttpApiService {
@@ -13,24 +24,127 @@ export class HttpApiService {
    */
   static async executeRequest(
     request: HttpRequest, 
-    environmentVariables?: Record<string, string>
+    environmentVariables?: Record<string, string>,
+    options?: ExecuteOptions,
+    confirm?: boolean
   ): Promise<ExecuteRequestResponse> {
     const payload: ExecuteRequestRequest = {
       request,
-      environmentVariables
+      environmentVariables,
+      options,
+      confirm
     };
     
     return await invoke('execute_http_request', {
       request: payload.request,
-      environmentVariables: payload.environmentVariables
+      environmentVariables: payload.environmentVariables,
+      options: payload.options,
+      confirm: payload.confirm
     });
   }
 
   /**
-   * Test if a URL is reachable
+   * Stop an in-flight request; resolves to false when it had already finished
+   */
+  static async cancelRequest(requestId: string): Promise<boolean> {
+    return await invoke('cancel_request', { requestId });
+  }
+
+  /**
+   * Receive the server-sent events of a request whose response is an event stream.
+   * Subscribe before executing the request so no events are missed.
+   */
+  static async onSseEvent(requestId: string, handler: (event: SseEvent) => void): Promise<UnlistenFn> {
+    return await listen<SseEvent>(`sse-event:${requestId}`, (event) => handler(event.payload));
+  }
+
+  /**
+   * Stop reading an event stream; the request completes with what was received so far
    */
-  static async testConnection(url: string): Promise<boolean> {
-    return await invoke('test_http_connection', { url });
+  static async stopSse(requestId: string): Promise<boolean> {
+    return await invoke('stop_sse', { requestId });
+  }
+
+  /**
+   * Get the most recent response received for a request, if any
+   */
+  static async getLastResponse(requestId: string): Promise<HttpResponse | null> {
+    return await invoke('get_last_response', { requestId });
+  }
+
+  /**
+   * Get a workspace's executed requests, newest first
+   */
+  static async getRequestHistory(workspaceId: string, limit?: number): Promise<RequestHistoryEntry[]> {
+    return await invoke('get_request_history', { workspaceId, limit });
+  }
+
+  /**
+   * Delete a workspace's request history, returning how many entries were removed
+   */
+  static async clearRequestHistory(workspaceId: string): Promise<number> {
+    return await invoke('clear_request_history', { workspaceId });
+  }
+
+  /**
+   * Get the cookies stored for a workspace
+   */
+  static async getCookies(workspaceId: string): Promise<CookieInfo[]> {
+    return await invoke('get_cookies', { workspaceId });
+  }
+
+  /**
+   * Remove all cookies stored for a workspace
+   */
+  static async clearCookies(workspaceId: string): Promise<void> {
+    return await invoke('clear_cookies', { workspaceId });
+  }
+
+  /**
+   * Save the complete body of a truncated response to disk
+   */
+  static async saveResponseBody(requestId: string, path: string): Promise<number> {
+    return await invoke('save_response_body', { requestId, path });
+  }
+
+  /**
+   * Fetch the complete body of a recent binary or truncated response, base64-encoded
+   */
+  static async getResponseBodyBytes(requestId: string): Promise<string> {
+    return await invoke('get_response_body_bytes', { requestId });
+  }
+
+  /**
+   * Send a request and stream its response straight to disk, for bodies over the size limit
+   */
+  static async saveResponseToFile(
+    request: HttpRequest,
+    path: string,
+    environmentVariables?: Record<string, string>,
+    options?: ExecuteOptions
+  ): Promise<number> {
+    return await invoke('save_response_to_file', { request, path, environmentVariables, options });
+  }
+
+  /**
+   * Test if a URL is reachable. Uses HEAD unless `options.useGet` is set.
+   */
+  static async testConnection(url: string, options?: ConnectionTestOptions): Promise<boolean> {
+    return await invoke('test_http_connection', { url, options });
+  }
+
+  /**
+   * Send the CORS preflight a browser would make for `method` with `requestHeaders` and report
+   * whether the request would be allowed. `origin` defaults to http://localhost:3000.
+   */
+  static async inspectCors(
+    url: string,
+    method: string,
+    requestHeaders?: string[],
+    origin?: string,
+    options?: ExecuteOptions
+  ): Promise<CorsInspection> {
+    return await invoke('inspect_cors', { url, method, requestHeaders, origin, options });
   }
 
   /**
@@ -47,6 +161,17 @@ export class HttpApiService {
     return await invoke('create_default_http_request');
   }
 
+  /**
+   * Create a POST request with a GraphQL body
+   */
+  static async createGraphqlRequest(
+    url: string,
+    query: string,
+    variables?: Record<string, unknown>
+  ): Promise<HttpRequest> {
+    return await invoke('create_graphql_request', { url, query, variables });
+  }
+
   /**
    * Validate if a URL is properly formatted for HTTP requests
    */
@@ -54,6 +179,21 @@ export class HttpApiService {
     return await invoke('validate_http_url', { url });
   }
 
+  static async parseUrlComponents(url: string): Promise<UrlComponents> {
+    return await invoke('parse_url_components', { url });
+  }
+
+  static async buildUrlFromComponents(components: UrlComponents): Promise<string> {
+    return await invoke('build_url_from_components', { components });
+  }
+
+  /**
+   * Check a request's URL and auth configuration before sending it
+   */
+  static async validateRequest(request: HttpRequest): Promise<ValidationIssue[]> {
+    return await invoke('validate_request', { request });
+  }
+
   /**
    * Parse a curl command into an HTTP request
    */
@@ -62,10 +202,29 @@ export class HttpApiService {
   }
 
   /**
-   * Format an HTTP response for debugging purposes
+   * Export an HTTP request as a curl command
+   */
+  static async exportAsCurl(request: HttpRequest): Promise<string> {
+    return await invoke('export_request_as_curl', { request });
+  }
+
+  /**
+   * Render an HTTP request as a curl, fetch or Python requests snippet
+   */
+  static async generateCodeSnippet(request: HttpRequest, target: CodeTarget): Promise<string> {
+    return await invoke('generate_code_snippet', { request, target });
+  }
+
+  /**
+   * Format an HTTP response for debugging purposes. Sensitive headers (Authorization,
+   * Cookie, Set-Cookie, X-Api-Key unless a list is given) are masked unless includeSecrets is set
    */
-  static async formatResponseDebug(response: any): Promise<string> {
-    return await invoke('format_http_response_debug', { response });
+  static async formatResponseDebug(
+    response: any,
+    includeSecrets?: boolean,
+    sensitiveHeaders?: string[]
+  ): Promise<string> {
+    return await invoke('format_http_response_debug', { response, includeSecrets, sensitiveHeaders });
   }
 
   /**
@@ -0,0 +1,40 @@
+import { invoke } from '@tauri-apps/api/core';
+import { listen, type UnlistenFn } from '@tauri-apps/api/event';
+import type { WebSocketEvent } from '../types/http';
+
+export class WebSocketApiService {
+  /**
+   * Open a connection and return its id. Variables resolve from global, environment and collection scopes.
+   */
+  static async connect(
+    url: string,
+    headers?: Record<string, string>,
+    workspaceId?: string,
+    collectionId?: string
+  ): Promise<string> {
+    return await invoke('ws_connect', { url, headers, workspaceId, collectionId });
+  }
+
+  /**
+   * Send a text frame on an open connection
+   */
+  static async send(connectionId: string, message: string): Promise<void> {
+    return await invoke('ws_send', { connectionId, message });
+  }
+
+  /**
+   * Start closing a connection; a closed event with the close code follows
+   */
+  static async close(connectionId: string): Promise<void> {
+    return await invoke('ws_close', { connectionId });
+  }
+
+  /**
+   * Receive frames and close notifications from every connection
+   */
+  static async onEvent(handler: (event: WebSocketEvent) => void): Promise<UnlistenFn> {
+    return await listen<WebSocketEvent>('websocket-event', (event) => handler(event.payload));
+  }
+}
+
+export default WebSocketApiService;
@@ -34,8 +34,9 @@ export async function runDatabaseMigrations(): Promise<string> {
 }
 
 // Workspace CRUD operations
-export async function createWorkspace(request: CreateWorkspaceRequest): Promise<Workspace> {
-  return await safeInvoke('workspace_create', { request });
+// Pass a clone ID to follow a repository clone with GitApiService.onCloneProgress
+export async function createWorkspace(request: CreateWorkspaceRequest, cloneId?: string): Promise<Workspace> {
+  return await safeInvoke('workspace_create', { request, cloneId });
 }
 
 export async function getWorkspace(id: string): Promise<Workspace | null> {
@@ -66,6 +67,11 @@ export async function getWorkspaceSummaries(): Promise<WorkspaceSummary[]> {
   return await safeInvoke('workspace_get_summaries');
 }
 
+// Slower: inspects each workspace directory to fill in git_status
+export async function getWorkspaceSummariesWithGit(): Promise<WorkspaceSummary[]> {
+  return await safeInvoke('workspace_get_summaries_with_git');
+}
+
 export async function accessWorkspace(id: string): Promise<boolean> {
   return await safeInvoke('workspace_access', { id });
 }
@@ -83,6 +89,28 @@ export async function updateWorkspaceSettings(settings: WorkspaceSettings): Prom
   return await safeInvoke('workspace_settings_update', { settings });
 }
 
+export async function setMinRequestInterval(workspaceId: string, intervalMs: number): Promise<WorkspaceSettings> {
+  return await safeInvoke('workspace_set_min_request_interval', { workspaceId, intervalMs });
+}
+
+// Encrypted backups
+export async function exportEncryptedBackup(destPath: string, passphrase: string, includeSecrets = false): Promise<boolean> {
+  return await safeInvoke('export_encrypted_backup', { destPath, passphrase, includeSecrets });
+}
+
+export async function importEncryptedBackup(srcPath: string, passphrase: string): Promise<boolean> {
+  return await safeInvoke('import_encrypted_backup', { srcPath, passphrase });
+}
+
+// Workspace archives, for sharing a workspace without Git
+export async function exportWorkspace(workspaceId: string, outputPath: string, includeSecrets = false): Promise<boolean> {
+  return await safeInvoke('export_workspace', { workspaceId, outputPath, includeSecrets });
+}
+
+export async function importWorkspace(archivePath: string, localPath: string): Promise<Workspace> {
+  return await safeInvoke('import_workspace', { archivePath, localPath });
+}
+
 // Git operations
 export async function getGitStatus(repoPath: string) {
   return await safeInvoke('git_get_status', { repoPath });
@@ -211,6 +211,10 @@ describe('Store Integration Tests', () => {
         follow_redirects: true,
         timeout_ms: 30000,
         order_index: 0,
+        include_in_run: true,
+        raw_stream: false,
+        extractions: '[]',
+        assertions: '[]',
         created_at: '2023-01-01T00:00:00Z',
         updated_at: '2023-01-01T00:00:00Z',
       },
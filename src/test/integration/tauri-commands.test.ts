@@ -192,6 +192,8 @@ describe('Tauri Command Integration Tests', () => {
       default_timeout: 30000,
       follow_redirects: true,
       verify_ssl: true,
+      min_request_interval_ms: 0,
+      max_response_bytes: 0,
       created_at: '2023-01-01T00:00:00Z',
       updated_at: '2023-01-01T00:00:00Z',
     };
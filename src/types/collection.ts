@@ -1,3 +1,5 @@
+import type { HttpResponse } from './http';
+
 export interface Collection {
   id: string;
   workspace_id: string;
@@ -5,6 +7,7 @@ export interface Collection {
   description?: string;
   folder_path?: string;
   git_branch?: string;
+  parent_id?: string;
   is_active: boolean;
   created_at: string;
   updated_at: string;
@@ -25,16 +28,58 @@ export interface Request {
   follow_redirects: boolean;
   timeout_ms: number;
   order_index: number;
+  include_in_run: boolean; // skipped by collection runs when false
+  raw_stream: boolean; // responses returned as raw streamed text
+  connect_timeout_ms?: number; // overrides the workspace's connect timeout
+  run_condition?: string; // collection runs skip the request unless this holds, e.g. `{{token}} exists`
+  pre_request: string; // JSON array of PreRequestStep
+  extractions: string; // JSON array of Extraction rules
+  assertions: string; // JSON array of Assertion rules
   created_at: string;
   updated_at: string;
 }
 
+// Sets a variable just before a request is sent; templates may use {{variables}} and built-ins
+export type PreRequestStep =
+  | { operation: 'set_variable'; variable: string; value: string }
+  | { operation: 'base64_encode'; variable: string; value: string }
+  | { operation: 'hmac_sha256'; variable: string; message: string; secret_variable: string }; // lowercase hex digest
+
+export type ExtractionSource = 'body' | 'header';
+
+// Captures a response value into a variable for later requests in a collection run
+export interface Extraction {
+  variable: string;
+  source: ExtractionSource;
+  path: string; // JSONPath like $.data.token for bodies, the header name for headers
+}
+
+export type AssertionTarget = 'status' | 'header' | 'json_path' | 'response_time';
+
+export type AssertionOperator = 'equals' | 'not_equals' | 'contains' | 'less_than' | 'greater_than' | 'exists';
+
+// A check on a response, e.g. "header Content-Type contains json"
+export interface Assertion {
+  target: AssertionTarget;
+  property?: string; // header name or JSONPath; unused for status and response time
+  operator: AssertionOperator;
+  expected: string; // ignored by exists
+}
+
+export interface AssertionResult {
+  assertion: Assertion;
+  passed: boolean;
+  actual?: string; // missing when the header or JSONPath was not found
+  message: string;
+}
+
 export interface CreateCollectionRequest {
   workspace_id: string;
   name: string;
   description?: string;
   folder_path?: string;
   git_branch?: string;
+  parent_id?: string;
 }
 
 export interface UpdateCollectionRequest {
@@ -43,6 +88,7 @@ export interface UpdateCollectionRequest {
   description?: string;
   folder_path?: string;
   git_branch?: string;
+  parent_id?: string;
   is_active?: boolean;
 }
 
@@ -86,25 +132,139 @@ export interface CollectionSummary {
   description?: string;
   folder_path?: string;
   git_branch?: string;
+  parent_id?: string;
   is_active: boolean;
   request_count: number;
   created_at: string;
   updated_at: string;
 }
 
+export type RunStatus = 'passed' | 'failed' | 'skipped';
+
+export interface RequestRunResult {
+  requestId: string;
+  name: string;
+  status: RunStatus;
+  response?: HttpResponse;
+  error?: string;
+  skipReason?: string;
+  durationMs: number; // zero for skipped requests
+  assertions: AssertionResult[];
+}
+
+export interface CollectionRunResult {
+  collectionId: string;
+  results: RequestRunResult[]; // in execution order
+  executed: number;
+  skipped: number;
+  failed: number;
+  totalTimeMs: number; // wall time, waits included
+  pacingWaitMs: number; // spent holding requests back to keep to the pacing
+  throttleEvents: ThrottleEvent[]; // 429s that were waited out and resent
+  sequentialReason?: string; // why a run asked to go in parallel went one at a time
+}
+
+// Spacing between sends in a collection run; when both are set the longer spacing wins
+export interface RunPacing {
+  requestsPerSecond?: number;
+  delayBetweenRequestsMs?: number;
+}
+
+export interface ThrottleEvent {
+  requestId: string;
+  name: string;
+  waitedMs: number;
+}
+
+export interface RequestSearchResult {
+  request_id: string;
+  name: string;
+  method: string;
+  url: string;
+  collection_id: string;
+  collection_name: string;
+  matched_field: 'name' | 'url' | 'description' | 'headers' | 'body';
+  snippet: string;
+}
+
+export interface CurlImportError {
+  index: number; // position in the submitted list
+  command: string;
+  message: string;
+}
+
+export interface CurlImportResult {
+  requests: Request[];
+  errors: CurlImportError[];
+}
+
+export interface RequestExample {
+  id: string;
+  request_id: string;
+  name: string;
+  status: number;
+  headers: string; // JSON string
+  body: string;
+  volatile_fields: string; // JSON array of ignored field paths/names
+  created_at: string;
+}
+
+export interface CreateRequestExampleRequest {
+  request_id: string;
+  name: string;
+  status: number;
+  headers?: Record<string, string>;
+  body: string;
+  volatile_fields?: string[];
+}
+
+export type DiffKind = 'added' | 'removed' | 'changed';
+
+export interface FieldDiff {
+  path: string;
+  kind: DiffKind;
+  expected?: any;
+  actual?: any;
+}
+
+export interface ResponseDiff {
+  expectedStatus: number;
+  actualStatus: number;
+  differences: FieldDiff[];
+  ignoredFields: string[];
+  identical: boolean;
+}
+
+// A request's baseline response, kept for spotting regressions
+export interface ResponseSnapshot {
+  requestId: string;
+  status: number;
+  headers: Record<string, string>; // only the headers chosen when saving, names lowercased
+  body: any; // object keys sorted
+  createdAt: string;
+}
+
+export interface SnapshotDiff {
+  snapshotCreatedAt: string;
+  body: ResponseDiff; // status and body
+  headerDifferences: FieldDiff[]; // paths are the lowercased header names
+  identical: boolean;
+}
+
 // Helper types for frontend use
 export interface RequestWithHeaders extends Omit<Request, 'headers' | 'auth_config'> {
   headers: Record<string, string>;
   auth_config?: Record<string, any>;
 }
 
-export type RequestBodyType = 'json' | 'form' | 'raw' | 'none';
-export type AuthType = 'none' | 'bearer' | 'basic' | 'api_key';
+export type RequestBodyType = 'json' | 'form' | 'raw' | 'graphql' | 'none';
+export type AuthType = 'none' | 'bearer' | 'basic' | 'api_key' | 'oauth2_client_credentials';
 
 export const REQUEST_BODY_TYPES: Record<RequestBodyType, string> = {
   json: 'JSON',
   form: 'Form Data',
   raw: 'Raw Text',
+  graphql: 'GraphQL',
   none: 'No Body'
 };
 
@@ -112,7 +272,8 @@ export const AUTH_TYPES: Record<AuthType, string> = {
   none: 'No Auth',
   bearer: 'Bearer Token',
   basic: 'Basic Auth',
-  api_key: 'API Key'
+  api_key: 'API Key',
+  oauth2_client_credentials: 'OAuth 2.0 Client Credentials'
 };
 
 // Utility functions
@@ -3,6 +3,8 @@ export interface Environment {
   name: string;
   variables: Record<string, EnvironmentVariable>;
   isActive: boolean;
+  isProtected: boolean;
+  parentId?: string; // inherits the parent's variables, overriding any it redefines
   createdAt: string;
   updatedAt: string;
 }
@@ -14,7 +16,41 @@ export interface EnvironmentVariable {
   variableType: VariableType;
 }
 
-export type VariableType = 'string' | 'secret';
+export type VariableType = 'string' | 'secret' | 'number' | 'boolean' | 'url';
+
+// How setVariables combines the given variables with the environment's existing ones
+export type MergeMode = 'replace' | 'merge';
+
+// Levels besides environments; on clashes request > collection > environment > global
+export type VariableScope = 'global' | 'collection' | 'request';
+
+// A substituted region of the output; offsets are string indices (UTF-16 code units)
+export interface SubstitutionSpan {
+  start: number;
+  end: number; // exclusive
+  variableName: string;
+  wasSecret: boolean;
+}
+
+export interface SubstitutionResult {
+  output: string;
+  spans: SubstitutionSpan[];
+}
+
+export type RequestField = 'url' | 'headers' | 'body' | 'auth';
+
+// A request that references a variable, and where
+export interface VariableUsage {
+  requestId: string;
+  requestName: string;
+  fields: RequestField[];
+}
+
+// A placeholder that no variable in scope defines
+export interface UndefinedVariable {
+  name: string;
+  usages: VariableUsage[];
+}
 
 // Form data interfaces for UI components
 export interface EnvironmentFormData {
@@ -30,6 +66,7 @@ export function createDefaultEnvironment(): Environment {
     name: 'New Environment',
     variables: {},
     isActive: false,
+    isProtected: false,
     createdAt: now,
     updatedAt: now,
   };
@@ -13,10 +13,40 @@ export interface GitCredentials {
   ssh_key_path?: string;
 }
 
+export interface CredentialHost {
+  host: string;
+  username: string;
+}
+
+// Payload of the `git-clone-progress:<cloneId>` event emitted while a clone runs
+export interface CloneProgress {
+  received_objects: number;
+  indexed_objects: number;
+  total_objects: number;
+  received_bytes: number;
+}
+
 export interface CloneResult {
   success: boolean;
   path: string;
   message: string;
+  unknown_host?: UnknownHostKey; // set when the remote's SSH host key isn't in known_hosts
+}
+
+export interface UnknownHostKey {
+  host: string;
+  port?: number;
+  key_type: string;
+  key: string; // base64
+  fingerprint: string; // SHA256:...
+}
+
+export interface RemoteAccess {
+  reachable: boolean;
+  authenticated: boolean;
+  default_branch?: string;
+  message: string;
+  unknown_host?: UnknownHostKey;
 }
 
 export interface GitStatus {
@@ -27,6 +57,7 @@ export interface GitStatus {
   untracked_files: string[];
   ahead: number;
   behind: number;
+  has_upstream: boolean; // false when the branch doesn't track a remote branch
 }
 
 export interface Branch {
@@ -48,8 +79,52 @@ export interface GitCommit {
 export interface GitRemote {
   name: string;
   url: string;
+  fetch_url: string;
+  push_url: string; // the fetch URL unless a separate push URL is configured
 }
 
+export interface GitCommit {
+  hash: string;
+  message: string;
+  author: string;
+  date: string;
+  files_changed: number; // compared with the first parent
+}
+
+// The commit fields are missing on a branch with no commits yet
+export interface HeadInfo {
+  hash?: string;
+  short_hash?: string;
+  message?: string;
+  author?: string;
+  tags: string[]; // lightweight and annotated tags pointing at the commit
+}
+
+export interface FileDiff {
+  path: string;
+  status: 'added' | 'deleted' | 'modified' | 'renamed';
+  staged: boolean; // a file with both kinds of change is listed once for each
+  hunks: string[]; // unified diff text, each starting with its @@ header; empty for binary files
+}
+
+export interface Conflict {
+  path: string;
+  ours: string | null; // null when that side deleted the file
+  theirs: string | null;
+  base: string | null;
+}
+
+export interface StashResult {
+  success: boolean;
+  stash_oid?: string; // the stash commit that was created or applied
+  message: string;
+}
+
+export type ConflictResolution =
+  | { type: 'ours' }
+  | { type: 'theirs' }
+  | { type: 'content'; content: string };
+
 export interface BranchPattern {
   workspace: string;
   username: string;
@@ -79,8 +154,9 @@ export interface GitBranch {
   last_commit_hash?: string;
   last_commit_message?: string;
   last_commit_date?: string;
-  ahead_count?: number;
+  ahead_count?: number; // unset when there's no upstream to compare with
   behind_count?: number;
+  upstream_gone: boolean; // the tracked upstream branch was deleted
 }
 
 export interface BranchCreateRequest {
@@ -96,6 +172,15 @@ export interface BranchCreateResult {
   message: string;
 }
 
+export interface BranchDeleteResult {
+  branch_name: string;
+  deleted: boolean;
+  refusal?: BranchDeleteRefusal; // why the branch was kept, when it was
+  message: string;
+}
+
+export type BranchDeleteRefusal = 'not_found' | 'current_branch' | 'unmerged';
+
 export interface BranchHistoryEntry {
   branch_name: string;
   pattern: BranchPattern;
@@ -1,4 +1,7 @@
-export type HttpMethod = 'GET' | 'POST' | 'PUT' | 'DELETE' | 'PATCH' | 'HEAD' | 'OPTIONS';
+export type StandardHttpMethod = 'GET' | 'POST' | 'PUT' | 'DELETE' | 'PATCH' | 'HEAD' | 'OPTIONS';
+
+// Extension methods such as WebDAV's PROPFIND are sent as-is
+export type HttpMethod = StandardHttpMethod | (string & {});
 
 export interface HttpRequest {
   id: string;
@@ -9,17 +12,30 @@ export interface HttpRequest {
   body?: RequestBody;
   timeoutMs?: number;
   followRedirects: boolean;
+  authType?: string; // bearer, basic, api_key, oauth2_client_credentials
+  authConfig?: Record<string, unknown>;
+  rawStream?: boolean; // return the body as raw streamed text, never parsed
+  connectTimeoutMs?: number; // overrides ExecuteOptions.connectTimeoutMs
   createdAt: string;
   updatedAt: string;
 }
 
+// A file sent as one part of a multipart form; the path may contain {{VAR}} placeholders
+export interface FormFile {
+  field: string;
+  path: string;
+  filename?: string; // defaults to the path's file name
+  contentType?: string; // defaults to a guess from the extension
+}
+
 export type RequestBody = 
   | { type: 'none' }
   | { type: 'raw'; content: string; contentType: string }
   | { type: 'json'; data: any; content: string }
-  | { type: 'formData'; fields: Record<string, string> }
+  | { type: 'formData'; fields: Record<string, string>; files?: FormFile[] }
   | { type: 'formUrlEncoded'; fields: Record<string, string> }
-  | { type: 'binary'; data: number[]; contentType: string };
+  | { type: 'binary'; data: number[]; contentType: string }
+  | { type: 'graphql'; query: string; variables: Record<string, unknown> };
 
 export interface HttpResponse {
   status: number;
@@ -28,13 +44,49 @@ export interface HttpResponse {
   body: ResponseBody;
   timing: ResponseTiming;
   requestId: string;
+  finalUrl: string; // URL after following any redirects
+  truncated: boolean; // body holds only a preview of the payload
+  fullSize: number; // size of the complete payload in bytes
+  size: ResponseSize; // bytes on the wire and after undoing Content-Encoding
+  streamed: boolean; // body was read as a stream (event streams, raw stream requests)
+  attempts: number; // sends made, including retries
+  redirects: RedirectHop[]; // redirects followed to reach finalUrl, in order
   timestamp: string;
 }
 
+// One execution in a workspace's request history; status, timing and size are missing on failure
+export interface RequestHistoryEntry {
+  id: number;
+  workspaceId: string;
+  requestId: string;
+  method: string;
+  url: string; // as written, with {{VAR}} placeholders
+  status?: number;
+  totalTimeMs?: number;
+  responseSize?: number; // bytes in the complete payload
+  error?: string;
+  executedAt: string;
+}
+
+// The two differ only when the server compressed the body
+export interface ResponseSize {
+  transferBytes: number;
+  decodedBytes: number;
+}
+
+export interface RedirectHop {
+  status: number;
+  url: string; // the URL that answered with the redirect
+  location: string; // its Location header, as sent
+}
+
+// What kind of text a body holds, for highlighting
+export type TextSubtype = 'xml' | 'html' | 'csv' | 'plain';
+
 export type ResponseBody = 
-  | { type: 'text'; content: string }
+  | { type: 'text'; content: string; contentSubtype: TextSubtype }
   | { type: 'json'; data: any }
-  | { type: 'binary'; data: number[]; size: number }
+  | { type: 'binary'; dataBase64: string; size: number; mimeType: string; previewHex: string } // previewHex: first bytes, space separated
   | { type: 'empty' };
 
 export interface ResponseTiming {
@@ -44,6 +96,27 @@ export interface ResponseTiming {
   tlsHandshakeMs?: number;
   firstByteMs?: number;
   downloadMs?: number;
+  connectionReused?: boolean; // absent when the connection couldn't be identified
+}
+
+// How testConnection probes a URL
+export interface ConnectionTestOptions {
+  useGet?: boolean; // for servers that reject or hang on HEAD
+  timeoutMs?: number;
+}
+
+// A server's answer to a CORS preflight and whether the browser would send the request
+export interface CorsInspection {
+  origin: string;
+  status: number;
+  allowOrigin?: string;
+  allowMethods: string[];
+  allowHeaders: string[];
+  allowCredentials: boolean;
+  exposeHeaders: string[];
+  maxAge?: number; // seconds the preflight may be cached
+  allowed: boolean;
+  problems: string[]; // why the request would be blocked; empty when allowed
 }
 
 export interface HttpError {
@@ -60,17 +133,88 @@ export type HttpErrorType =
   | 'invalidUrl'
   | 'invalidRequest'
   | 'invalidResponse'
+  | 'responseTooLarge'
+  | 'cancelled'
   | 'unknownError';
 
+export interface ExecuteOptions {
+  previewBytes?: number; // bodies larger than this are truncated
+  workspaceId?: string; // selects the cookie jar and send throttle
+  environmentId?: string; // where the variables come from; the workspace's active one when unset
+  maxResponseBytes?: number; // bodies larger than this fail with responseTooLarge
+  proxy?: ProxyConfig; // send through this proxy instead of connecting directly
+  tls?: TlsConfig; // client certificate and trusted CAs
+  connectTimeoutMs?: number; // limit on establishing a connection, within the total timeout
+  retry?: RetryPolicy; // resend on flaky statuses or network errors
+  forceTokenRefresh?: boolean; // fetch a new OAuth2 token even when the cached one is still valid
+  defaultHeaders?: Record<string, string>; // sent unless the request sets the same header
+}
+
+export interface RetryPolicy {
+  maxAttempts: number; // including the first send
+  retryOnStatus: number[];
+  retryOnNetworkError: boolean; // connection failures and timeouts
+  initialBackoffMs: number; // doubled after each attempt, with jitter
+  maxBackoffMs: number; // also caps Retry-After
+}
+
+export interface ProxyConfig {
+  url: string;
+  username?: string;
+  password?: string;
+  noProxy?: string; // comma-separated hosts, domains or CIDR ranges
+}
+
+export interface TlsConfig {
+  clientCertPath?: string; // PKCS#12 bundle, or a PEM certificate with clientKeyPath
+  clientKeyPath?: string; // PKCS#8 PEM key for a PEM certificate
+  clientCertPassword?: string; // PKCS#12 passphrase
+  caBundlePath?: string; // PEM certificates trusted alongside the system roots
+  acceptInvalidCerts?: boolean;
+}
+
+export interface ValidationIssue {
+  field: string; // e.g. "url" or "authConfig.token"
+  message: string;
+}
+
+// A URL split up for editing; parts keep {{VAR}} placeholders as written
+export interface UrlComponents {
+  scheme: string; // empty when the URL starts with a placeholder such as {{BASE_URL}}
+  userinfo?: string;
+  host: string;
+  port?: string; // a number or a placeholder
+  path: string;
+  query: QueryParam[]; // in order, repeats included
+  fragment?: string;
+}
+
+export interface QueryParam {
+  key: string;
+  value: string | null; // null for a bare `key` without `=`
+}
+
+export type CodeTarget = 'curl' | 'javaScriptFetch' | 'pythonRequests';
+
+export interface CookieInfo {
+  name: string;
+  value: string;
+  domain: string;
+  path: string;
+}
+
 export interface ExecuteRequestRequest {
   request: HttpRequest;
   environmentVariables?: Record<string, string>;
+  options?: ExecuteOptions;
+  confirm?: boolean;
 }
 
 export interface ExecuteRequestResponse {
   response?: HttpResponse;
   error?: HttpError;
   requestId: string;
+  requiresConfirmation: boolean;
 }
 
 // Helper types for UI
@@ -111,7 +255,14 @@ export const HTTP_METHODS: HttpMethod[] = [
   'DELETE',
   'PATCH',
   'HEAD',
-  'OPTIONS'
+  'OPTIONS',
+  'PROPFIND',
+  'PROPPATCH',
+  'MKCOL',
+  'COPY',
+  'MOVE',
+  'LOCK',
+  'UNLOCK'
 ];
 
 export const CONTENT_TYPES = [
@@ -161,7 +312,7 @@ export function getStatusColor(status: number): string {
 }
 
 export function getMethodColor(method: HttpMethod): string {
-  const colors: Record<HttpMethod, string> = {
+  const colors: Record<StandardHttpMethod, string> = {
     GET: 'bg-blue-100 text-blue-800 dark:bg-blue-900/30 dark:text-blue-300',
     POST: 'bg-green-100 text-green-800 dark:bg-green-900/30 dark:text-green-300',
     PUT: 'bg-orange-100 text-orange-800 dark:bg-orange-900/30 dark:text-orange-300',
@@ -170,7 +321,7 @@ export function getMethodColor(method: HttpMethod): string {
     HEAD: 'bg-gray-100 text-gray-800 dark:bg-gray-900/30 dark:text-gray-300',
     OPTIONS: 'bg-yellow-100 text-yellow-800 dark:bg-yellow-900/30 dark:text-yellow-300',
   };
-  return colors[method] || 'bg-gray-100 text-gray-800 dark:bg-gray-900/30 dark:text-gray-300';
+  return colors[method as StandardHttpMethod] || 'bg-gray-100 text-gray-800 dark:bg-gray-900/30 dark:text-gray-300';
 }
 
 export function createDefaultRequest(): HttpRequest {
@@ -257,6 +408,7 @@ export function validateJsonBody(content: string): { isValid: boolean; error?: s
 export function getContentTypeForBody(body: RequestBody): string {
   switch (body.type) {
     case 'json':
+    case 'graphql':
       return 'application/json';
     case 'formData':
       return 'multipart/form-data';
@@ -287,7 +439,27 @@ export function bodyToString(body: RequestBody): string {
         .join('&');
     case 'binary':
       return '[Binary Data]';
+    case 'graphql':
+      return JSON.stringify({ query: body.query, variables: body.variables });
     default:
       return '';
   }
-}
\ No newline at end of file
+}
+// Payload of the `sse-event:<requestId>` event emitted for each server-sent event
+export interface SseEvent {
+  requestId: string;
+  event: string; // "message" when the server didn't name it
+  data: string; // multi-line data is joined with newlines
+  id: string | null;
+}
+
+export interface WebSocketEvent {
+  connectionId: string;
+  frame: WebSocketFrame;
+}
+
+export type WebSocketFrame =
+  | { type: 'text'; data: string }
+  | { type: 'binary'; data: number[] }
+  | { type: 'closed'; code: number | null; reason: string } // always the last event for a connection
+  | { type: 'error'; message: string };
@@ -6,6 +6,8 @@ export interface Workspace {
   description?: string;
   git_repository_url?: string;
   local_path: string;
+  git_username?: string;
+  git_email?: string;
   is_active: boolean;
   created_at: string;
   updated_at: string;
@@ -17,6 +19,8 @@ export interface CreateWorkspaceRequest {
   description?: string;
   git_repository_url?: string;
   local_path: string;
+  git_username?: string;
+  git_email?: string;
 }
 
 export interface UpdateWorkspaceRequest {
@@ -25,6 +29,8 @@ export interface UpdateWorkspaceRequest {
   description?: string;
   git_repository_url?: string;
   local_path?: string;
+  git_username?: string;
+  git_email?: string;
   is_active?: boolean;
 }
 
@@ -36,6 +42,19 @@ export interface WorkspaceSettings {
   default_timeout: number;
   follow_redirects: boolean;
   verify_ssl: boolean;
+  min_request_interval_ms: number; // minimum spacing between sends, 0 disables throttling
+  max_response_bytes: number; // largest body a request may download, 0 for no limit
+  connect_timeout_ms?: number; // time allowed to establish a connection, 0 for no separate limit
+  proxy_url?: string; // requests connect directly when unset
+  proxy_username?: string;
+  proxy_password?: string;
+  no_proxy?: string; // comma-separated hosts that bypass the proxy
+  client_cert_path?: string; // PKCS#12 bundle, or a PEM certificate with client_key_path
+  client_key_path?: string;
+  client_cert_password?: string;
+  ca_bundle_path?: string; // PEM certificates trusted alongside the system roots
+  user_agent?: string; // sent instead of Postgirl's own when set
+  default_headers?: Record<string, string>; // added to every request that doesn't set them itself
   created_at: string;
   updated_at: string;
 }
@@ -60,4 +79,5 @@ export interface GitStatus {
   untracked_files: string[];
   ahead: number;
   behind: number;
+  has_upstream: boolean; // false when the branch doesn't track a remote branch
 }
\ No newline at end of file